@@ -0,0 +1,262 @@
+# /lib/asm_block.py, created 2026-10-15 08:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+SECTION_REGEX = re.compile(r"^[ \t]*(?:\.section[ \t]+(?P<name>[^\s,]+)|(?P<short>\.(?:text|data|bss|rodata|code|const))\b"
+                           r"|(?i:section|segment)[ \t]+(?P<nasm>[^\s,]+)|(?P<masm>[A-Za-z_]\w*)[ \t]+(?i:segment)\b)")
+LABEL_REGEX = re.compile(r"^[ \t]*(?P<name>[A-Za-z_$?@][\w.$?@]*)(?P<colons>::?)(?!=)")
+PROC_REGEX = re.compile(r"^[ \t]*(?P<name>[A-Za-z_$?@][\w$?@]*)[ \t]+(?i:(?P<kind>proc|endp|macro))\b")
+GLOBAL_REGEX = re.compile(r"^[ \t]*(?:\.globl|\.global|(?i:global|public))[ \t]+(?P<names>[^\n]+)")
+TYPE_REGEX = re.compile(r"^[ \t]*\.type[ \t]+(?P<name>[\w.$]+)[ \t]*,[ \t]*[@%#]?(?P<kind>function|object|STT_FUNC|STT_OBJECT)")
+MACRO_REGEX = re.compile(r"^[ \t]*(?:\.macro|%(?:i?macro))[ \t]+(?P<name>[A-Za-z_$.?][\w$.?]*)")
+MACRO_END_REGEX = re.compile(r"^[ \t]*(?:\.endm\b|%endmacro\b|(?i:endm)\b)")
+INCLUDE_REGEX = re.compile(r"^[ \t]*(?:\.include|%include|#[ \t]*include|(?i:include))[ \t]+(?:\"(?P<quoted>[^\"\n]+)\"|'(?P<single>[^'\n]+)'"
+                           r"|<(?P<system>[^>\n]+)>|(?P<bare>[\w./\\-]+))")
+PREPROCESSOR_REGEX = re.compile(r"#[ \t]*(?:include|define|undef|if|ifdef|ifndef|elif|else|endif|error)\b")
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def strip_asm_literals(text: str, warn=None) -> str:
+    """Blanks strings, character constants and comments (`;`, `//`, `/* */`, `#` outside preprocessor directives) with spaces.
+
+    Delimiters are kept and positions are preserved, so include paths are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    line_start = 0
+    while i < n:
+        ch = text[i]
+        if ch == "\n":
+            line_start = i + 1
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            if end < 0 and warn:
+                warn(f"Unterminated block comment at line {text.count(chr(10), 0, i) + 1}")
+            end = n if end < 0 else end + 2
+            out.append(blank(text[i:end]))
+            i = end
+            continue
+        if ch == "#" and not text[line_start:i].strip() and PREPROCESSOR_REGEX.match(text, i):
+            out.append(ch)   # C preprocessor in .S sources
+            i += 1
+            continue
+        if ch == ";" or text.startswith("//", i) or (ch == "#" and (i == line_start or text[i - 1] in " \t")):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch not in "\"'":
+            out.append(ch)
+            i += 1
+            continue
+        end = i + 1
+        while end < n and text[end] not in (ch, "\n"):
+            end += 2 if text[end] == "\\" else 1
+        if end >= n or text[end] != ch:
+            if ch == "'":
+                out.append(text[i:i + 2])   # GAS character constant like 'a
+                i += 2
+                continue
+            if warn:
+                warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+            out.append(ch + blank(text[i + 1:end]))
+            i = end
+            continue
+        out.append(ch + blank(text[i + 1:end]) + ch)
+        i = end + 1
+    return "".join(out)
+
+
+class SymbolParser(EntityParser):
+    """Parser for sections, macros and labels; a label lasts until the next non-local label, section or macro.
+
+    Labels declared with `.globl` / `global` / `PUBLIC` (or `name::`) are public, `.type name, @function`, NASM
+    `global name:function` and MASM `PROC` mark functions. Local labels (`.L1`, `.loop`, `1:`) and macro bodies are skipped.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="private")
+
+    def parse(self):
+        lines = self.owner.clean_lines
+        globals_set = set()
+        functions = set()
+        for line in lines[1:]:
+            if not isinstance(line, str):
+                continue
+            if match := GLOBAL_REGEX.match(line):
+                for item in match.group('names').split(","):
+                    name, _, kind = item.strip().partition(":")
+                    if name:
+                        globals_set.add(name.strip())
+                        if kind.strip().lower() == "function":
+                            functions.add(name.strip())
+            elif (match := TYPE_REGEX.match(line)) and match.group('kind') in ("function", "STT_FUNC"):
+                functions.add(match.group('name'))
+
+        events = []   # sections, macros and labels in source order
+        section = None
+        in_macro = None
+        for line_num in range(1, len(lines)):
+            line = lines[line_num] if isinstance(lines[line_num], str) else ""
+            if in_macro:
+                if MACRO_END_REGEX.match(line):
+                    in_macro["last_line"] = line_num
+                    in_macro = None
+                continue
+            if match := MACRO_REGEX.match(line):
+                in_macro = {"line": line_num, "kind": "macro", "name": match.group('name'), "last_line": line_num}
+                events.append(in_macro)
+                continue
+            if match := SECTION_REGEX.match(line):
+                section = match.group('name') or match.group('short') or match.group('nasm') or match.group('masm')
+                events.append({"line": line_num, "kind": "section", "name": section})
+                continue
+            if (match := PROC_REGEX.match(line)) and match.group('kind').lower() == "endp":
+                procs = [e for e in events if e["kind"] == "function" and e["name"] == match.group('name') and "last_line" not in e]
+                if procs:
+                    procs[-1]["last_line"] = line_num
+                continue
+            if match:
+                kind = "function" if match.group('kind').lower() == "proc" else "macro"
+                frame = {"line": line_num, "kind": kind, "name": match.group('name'), "section": section}
+                events.append(frame)
+                if kind == "macro":
+                    in_macro = frame
+                continue
+            match = LABEL_REGEX.match(line)
+            if not match or match.group('name').startswith("."):
+                continue
+            name = match.group('name')
+            if match.group('colons') == "::":
+                globals_set.add(name)
+            events.append({"line": line_num, "kind": "function" if name in functions else "label", "name": name, "section": section})
+
+        for index, event in enumerate(events):
+            if "last_line" in event:
+                last_line = event["last_line"]
+            else:
+                following = [e["line"] for e in events[index + 1:] if event["kind"] != "section" or e["kind"] == "section"]
+                last_line = (following[0] if following else len(lines)) - 1
+                while last_line > event["line"] and not (isinstance(lines[last_line], str) and lines[last_line].strip()):
+                    last_line -= 1
+            extra = {"section": event["section"]} if event.get("section") else {}
+            visibility = "private" if event["kind"] in ("label", "function") and event["name"] not in globals_set else "public"
+            if self.owner.add_definition(event["kind"], event["name"], event["line"], last_line, extra, visibility):
+                self.new_entities_lines.append(event["line"])
+        return True
+
+
+class DepsParserAsm(DepsParser):
+    """Parser for `.include`, NASM `%include`, MASM `INCLUDE` and `#include` directives; quoted paths are resolved to the source."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        lines = self.owner.content_text.split("\n")
+        for line_num in range(1, len(self.owner.clean_lines)):
+            clean = self.owner.clean_lines[line_num]
+            match = INCLUDE_REGEX.match(clean) if isinstance(clean, str) else None
+            if not match:
+                continue
+            raw = INCLUDE_REGEX.match(lines[line_num - 1])
+            path = raw.group('quoted') or raw.group('single') or raw.group('bare')
+            if path:
+                path = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), path)).replace("\\", "/")
+            self.add_module(path or raw.group('system'))
+        return True
+
+
+class ContentCodeAsm(ContentBlock):
+    """Parser for assembly sources (.s, .S, .asm) in GAS, NASM and MASM syntax."""
+    supported_types = [".s", ".asm"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "asm"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodeAsm with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips strings and comments with strip_asm_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_asm_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses assembly content to extract sections, labels, macros and includes."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserAsm(self),
+            SymbolParser("symbol", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeAsm)
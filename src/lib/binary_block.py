@@ -0,0 +1,47 @@
+# /lib/binary_block.py, created 2026-10-16 05:00 EEST
+# Binary files in pack: listed by size and hash, or embedded as base64 under size threshold.
+import base64
+import hashlib
+import logging
+import textwrap
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+
+BINARY_POLICIES = ("skip", "list", "embed")   # embed falls back to list over size threshold
+
+
+class BinaryBlock(ContentBlock):
+    supported_types = [":binary"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "binary"
+        self.size = kwargs.get("size", 0)
+        self.sha256 = kwargs.get("sha256")
+        self.encoding = kwargs.get("encoding")   # "base64" when embedded, None - listed only
+        logging.debug(f"Initialized BinaryBlock for {file_name}, size={self.size}, encoding={self.encoding}")
+
+    @classmethod
+    def from_bytes(cls, data: bytes, file_name: str, timestamp: str, policy: str = "list", embed_limit: int = 65536, **kwargs):
+        """Block for binary file by policy, None for `skip`."""
+        if policy not in BINARY_POLICIES:
+            raise ValueError(f"Unsupported binary policy `{policy}`, expected one of {', '.join(BINARY_POLICIES)}")
+        if policy == "skip":
+            return None
+        embed = policy == "embed" and len(data) <= embed_limit
+        text = "\n".join(textwrap.wrap(base64.b64encode(data).decode("ascii"), 76)) if embed else ""
+        return cls(text, ":binary", file_name, timestamp, size=len(data), sha256=hashlib.sha256(data).hexdigest(),
+                   encoding="base64" if embed else None, **kwargs)
+
+    def decoded(self) -> bytes:
+        return base64.b64decode(self.content_text) if self.encoding == "base64" else b""
+
+    def to_sandwich_block(self):
+        attrs = [f'file_id="{self.file_id}"'] if self.file_id is not None else []
+        attrs += [f'mod_time="{self.timestamp}"', f'size="{self.size}"', f'sha256="{self.sha256}"']
+        if self.encoding:
+            return f"<{self.tag} {' '.join(attrs)} encoding=\"{self.encoding}\">\n{self.content_text}\n</{self.tag}>"
+        return f"<{self.tag} {' '.join(attrs)} />"
+
+
+SandwichPack.register_block_class(BinaryBlock)
@@ -1,4 +1,4 @@
-# /lib/content_block.py, updated 2025-08-06 14:30 EEST
+# /lib/content_block.py, updated 2026-10-16 16:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 # Proposed: 2025-08-06
 # Changes: Added module_map parameter to compress for imported class name compression (e.g., SandwichPack), added log separator (CLA Rule 2: Ensure code correctness, CLA Rule 12: Minimize changes).
@@ -23,6 +23,10 @@ logging.basicConfig(
 
 class ContentBlock:
     supported_types = [':document', ':post']
+    doc_comment_prefixes = ("///", "//!", "/**", "/*!")   # comments kept by strip_comment_text unless docs are stripped too
+    # decision points counted by measure_entities: branch and loop keywords, handlers, boolean operators, ternary / `?`
+    branch_regex = re.compile(r"\b(?:if|elif|elsif|unless|for|foreach|while|until|case|when|catch|except|rescue|guard|and|or)\b" +
+                              r"|&&|\|\||\?(?![.?:])")
 
     def __init__(self, content_text, content_type, file_name=None, timestamp=None, **kwargs):
         self.content_text = content_text
@@ -39,6 +43,10 @@ class ContentBlock:
         self.file_id = kwargs.get('file_id')
         # Unix time from БД (пост/файл) — для инкрементальных правок в контексте
         self.revision_ts = kwargs.get('revision_ts')
+        self.source_encoding = kwargs.get('source_encoding')   # original encoding of transcoded file, None - UTF-8
+        self.line_endings = kwargs.get('line_endings')   # original crlf / cr line endings of text normalized to LF, None - as is
+        self.file_meta = kwargs.get('file_meta')   # size, mode (octal) and git blob hash of file on disk, None - not recorded
+        self.line_map = None   # original line numbers of content lines after truncation or body elision, None - unchanged
         self.tokens = estimate_tokens(content_text)
         self.clean_lines = ["Line №0"] + self.content_text.splitlines()
         self.strip_log = []
@@ -53,12 +61,18 @@ class ContentBlock:
         self.open_ml_comment = [r"/\*"]
         self.close_ml_comment = [r"\*/"]
         self.escape_char = "\\"
+        self.nested_comments = False   # block comments nest like Rust `/* /* */ */`
         self.module_prefix = ""
         self.line_offsets = []
+        self.reexports = []   # re-exports like Rust `pub use`: {"path": public path, "source": source path, "line": line}
+        self.import_paths = []   # resolved imports like Rust `use`: {"path": imported path, "alias": local name, "line": line}
+        self.module_decls = []   # file modules like Rust `mod net;`: {"name": relative module path, "line": line, "path_attr": explicit file}
         logging.debug(f"Initialized base of {type(self).__name__} with content_type={content_type}, tag={self.tag}, file_name={file_name}")
 
     def parse_warn(self, msg):
-        """Logs a warning and adds it to self.warnings."""
+        """Logs a warning and adds it to self.warnings, repeated stripping of the same block doesn't duplicate it."""
+        if msg in self.warnings:
+            return
         self.warnings.append(msg)
         logging.warning(msg)
 
@@ -227,7 +241,7 @@ class ContentBlock:
             logging.warning(f"Entity {entity['name']} placement check failed at line {line_num}, line: '{self.clean_lines[line_num]}'")
             return False
         entity["first_line"] = line_num
-        if entity["type"] != "abstract method" or "last_line" not in entity:
+        if "abstract" not in entity["type"] or "last_line" not in entity:   # also `async abstract method`
             entity["last_line"] = self.detect_bounds(line_num, self.clean_lines)[1]
         self.entity_map[line_num] = entity
         logging.debug(f"Added entity {entity['name']} at first_line={line_num}, last_line={entity['last_line']}")
@@ -424,6 +438,267 @@ class ContentBlock:
     def parse_content(self, clean_lines=None, depth=0):
         return {"entities": [], "dependencies": self.dependencies}
 
+    def entity_path(self, entity: dict):
+        """Import path of entity matching re-export sources, None if block has no module paths."""
+        return None
+
+    def module_path(self):
+        """Module path of block for import resolution, None if block has no module paths."""
+        return None
+
+    def module_files(self) -> dict:
+        """Files of declared modules mapped to their module paths, empty if block has no file modules."""
+        return {}
+
+    def rebase_module(self, module_path: str, role=None) -> bool:
+        """Moves block to module path (and build target role) of declaring file, returns True if entities need reparsing."""
+        return False
+
+    def target_role(self):
+        """Build target role of block file (Cargo: library, binary, build script, example, bench, integration test), None if unknown."""
+        return None
+
+    def crate_manifest(self):
+        """Crate described by manifest block: {"name": package or None, "members" / "exclude": workspace patterns}, None if not a manifest."""
+        return None
+
+    def rebase_crate(self, crate_name: str) -> bool:
+        """Moves block to named crate of workspace, returns True if entities need reparsing."""
+        return False
+
+    def entity_public(self, entity: dict) -> bool:
+        """Checks entity is externally visible, blocks with nested scopes can check enclosing ones."""
+        return entity.get("visibility") == "public"
+
+    def entity_enabled(self, entity: dict, cfg_test=None, cfg_features=None) -> bool:
+        """Checks entity against pack build options (`cfg_test`, `cfg_features`), only conditionally compiled items can be disabled."""
+        return True
+
+    def body_range(self, entity: dict):
+        """Lines (first, last) of function body inside braces, None for one-liners and bodies not found."""
+        last_line = entity["last_line"]
+        depth = 0
+        for num in range(entity["first_line"], min(last_line, len(self.clean_lines))):
+            for c in self.clean_lines[num]:
+                if c in "([":
+                    depth += 1
+                elif c in ")]":
+                    depth -= 1
+                elif c == "{" and depth == 0:
+                    closing = last_line < len(self.clean_lines) and self.clean_lines[last_line].strip().startswith("}")
+                    last = last_line - 1 if closing else last_line
+                    return (num + 1, last) if num < last else None
+        return None
+
+    def function_bodies(self, entities: list, min_lines: int = 1) -> list:
+        """Sorted (first, last, entity) body ranges of functions with at least min_lines body lines, nested ones skipped."""
+        bodies = sorted((r[0], r[1], e) for e in entities
+                        if ("function" in e["type"] or "method" in e["type"]) and "abstract" not in e["type"]
+                        and (r := self.body_range(e)) and r[1] - r[0] + 1 >= min_lines)
+        result = []
+        for first, last, ent in bodies:
+            if not result or first > result[-1][1]:
+                result.append((first, last, ent))
+        return result
+
+    def measure_entities(self, entities: list):
+        """Sets rough size metrics of entities from stripped code: `sloc` non-blank lines and `complexity` 1 + decision points."""
+        for ent in entities:
+            lines = self.clean_lines[ent["first_line"]:ent["last_line"] + 1]
+            ent["sloc"] = sum(1 for line in lines if line.strip())
+            ent["complexity"] = 1 + sum(len(self.branch_regex.findall(line)) for line in lines)
+
+    def original_lines(self) -> list:
+        """Original line number of every content line, None for inserted lines like truncation markers."""
+        return self.line_map if self.line_map is not None else list(range(1, self.content_text.count("\n") + 2))
+
+    def comment_markers(self) -> tuple:
+        """(single line, block open, block close) comment markers of block language, None where absent."""
+        def literal(markers):   # markers are regex patterns
+            return markers[0].replace("\\", "") if markers else None
+        return literal(self.open_sl_comment), literal(self.open_ml_comment), literal(self.close_ml_comment)
+
+    def line_comment(self, text: str):
+        """Comment line with text in block language, None if language has no comments."""
+        single, opening, closing = self.comment_markers()
+        if single:
+            return f"{single} {text}"
+        return f"{opening} {text} {closing}" if opening else None
+
+    @staticmethod
+    def _align(stripped: str, line: str, escape_char: str):
+        """Position in line after stripped prefix, where string contents were removed; None if prefix doesn't fit."""
+        i = 0
+        for ch in stripped:
+            while i < len(line) and line[i] != ch:
+                i += 2 if line[i] == escape_char else 1   # escaped quote of removed string content is skipped whole
+            if i >= len(line):
+                return None
+            i += 1
+        return i
+
+    def _block_close(self, line: str, pos: int, depth: int, opening: str, closing: str) -> tuple:
+        """(end position of block comment or -1, nesting depth left) scanning line from pos."""
+        while True:
+            close = re.compile(closing).search(line, pos)
+            nested = re.compile(opening).search(line, pos) if self.nested_comments else None
+            if nested is not None and (close is None or nested.start() < close.start()):
+                depth, pos = depth + 1, nested.end()
+                continue
+            if close is None:
+                return -1, depth
+            depth, pos = depth - 1, close.end()
+            if depth == 0:
+                return pos, 0
+
+    def strip_comment_text(self, keep_docs=True) -> int:
+        """Removes comments from content, doc comments are kept unless keep_docs is False, shebang always.
+        Comment start is taken from lines after strip_strings of block language, so markers in strings are untouched,
+        lines of comments only are dropped, line_map keeps original numbers. Returns count of removed comments."""
+        if not self.open_sl_comment and not self.open_ml_comment:
+            return 0
+        saved = (self.clean_lines, self.strip_log, self.warnings)
+        self.clean_lines = ["Line №0"] + self.content_text.split("\n")
+        self.strip_log, self.warnings = [], []
+        stripped = self.strip_strings()
+        self.clean_lines, self.strip_log, self.warnings = saved
+        lines = self.content_text.split("\n")
+        numbers = self.original_lines()
+        out = []
+        out_numbers = []
+        removed = 0
+        block = None   # (opening, closing, kept, depth) of block comment continued from previous line
+
+        def is_doc(comment):
+            return comment.startswith(self.doc_comment_prefixes) and not comment.startswith(("////", "/**/"))
+
+        for n, (num, line) in enumerate(zip(numbers, lines)):
+            if block is not None:
+                opening, closing, kept, depth = block
+                end, depth = self._block_close(line, 0, depth, opening, closing)
+                block = None if end >= 0 else (opening, closing, kept, depth)
+                if kept or (end >= 0 and line[end:].strip()):
+                    out.append(line if kept else line[end:])
+                    out_numbers.append(num)
+                continue
+            s = stripped[n + 1] if n + 1 < len(stripped) and isinstance(stripped[n + 1], str) else ""
+            starts = [(s.find(marker), marker, None) for marker in self.open_sl_comment if marker in s]
+            starts += [(m.start(), m.group(), (opening, closing)) for opening, closing in zip(self.open_ml_comment, self.close_ml_comment)
+                       if (m := re.search(opening, s))]
+            k, marker, pair = min(starts, key=lambda start: start[0]) if starts else (0, None, None)
+            i = self._align(s[:k], line, self.escape_char) if starts and not (n == 0 and line.startswith("#!")) else None
+            if i is None:
+                out.append(line)
+                out_numbers.append(num)
+                continue
+            j = i + len(line[i:]) - len(line[i:].lstrip())
+            if not line.startswith(marker, j):
+                out.append(line)   # stripped line doesn't match original, kept as is
+                out_numbers.append(num)
+                continue
+            kept = keep_docs and is_doc(line[j:])
+            rest = ""
+            if pair is not None:
+                end, depth = self._block_close(line, j + len(marker), 1, *pair)
+                if end < 0:
+                    block = (pair[0], pair[1], kept, depth)
+                else:
+                    rest = line[end:]
+            if kept:
+                out.append(line)
+                out_numbers.append(num)
+                continue
+            removed += 1
+            text = (line[:i] + rest).rstrip()
+            if text.strip():
+                out.append(text)
+                out_numbers.append(num)
+        if removed:
+            self.content_text = "\n".join(out)
+            self.line_map = out_numbers
+            self.tokens = estimate_tokens(self.content_text)
+        return removed
+
+    def replace_bodies(self, bodies: list, note=None):
+        """Replaces body lines by `...` line, or by comment from note(entity, lines count) in block language.
+        Braces around commented body are joined into one line: `fn f() { /* note */ }`. Body ranges are original
+        line numbers, bodies cut by truncation are kept, lines dropped by comment stripping may be absent."""
+        lines = self.content_text.split("\n")
+        numbers = self.original_lines()
+        out = []
+        out_numbers = []
+        pos = 0
+        for first, last, ent in bodies:
+            inside = [i for i, n in enumerate(numbers) if n is not None and first <= n <= last]
+            if not inside or inside[0] < pos:
+                continue
+            start, end = inside[0], inside[-1]
+            lo = start if numbers[start] == first else max(start - 1, 0)   # absent edge line may be truncated
+            hi = end if numbers[end] == last else end + 1
+            if None in numbers[lo:hi + 1]:
+                continue
+            out += lines[pos:start]
+            out_numbers += numbers[pos:start]
+            pos = end + 1
+            body = [line for line in lines[start:end + 1] if line.strip()]
+            indent = body[0][:min(len(line) - len(line.lstrip()) for line in body)] if body else ""
+            single, opening, closing = self.comment_markers()
+            if note is None:
+                comment = "..."
+            elif opening:
+                comment = f"{opening} {note(ent, last - first + 1)} {closing}"
+            else:
+                comment = f"...  {single} {note(ent, last - first + 1)}" if single else "..."
+            if note is not None and opening and out and out[-1].rstrip().endswith("{") \
+                    and pos < len(lines) and lines[pos].strip() == "}":
+                out[-1] = f"{out[-1].rstrip()} {comment} }}"
+                pos += 1
+            else:
+                out.append(indent + comment)
+                out_numbers.append(None)
+        out += lines[pos:]
+        out_numbers += numbers[pos:]
+        self.content_text = "\n".join(out)
+        self.line_map = out_numbers
+        self.tokens = estimate_tokens(self.content_text)
+
+    def annotate_lines(self):
+        """Prefixes every line by original line number: `  12| code`, inserted lines get blank number."""
+        lines = self.content_text.split("\n")
+        numbers = self.original_lines()
+        tail = lines and lines[-1] == ""   # text ending with newline has no last line to annotate
+        width = len(str(max((n for n in numbers if n is not None), default=1)))
+        out = [f"{'' if n is None else n:>{width}}|" + (f" {line}" if line else "") for n, line in zip(numbers, lines[:-1] if tail else lines)]
+        self.content_text = "\n".join(out + ([""] if tail else []))
+        self.tokens = estimate_tokens(self.content_text)
+
+    def anchor_entities(self, anchors: list):
+        """Inserts comment line before first line of entities, anchors are (first_line, text) by original lines."""
+        lines = self.content_text.split("\n")
+        numbers = self.original_lines()
+        by_line = {}
+        for first_line, text in anchors:
+            if (comment := self.line_comment(text)) is not None:
+                by_line.setdefault(first_line, []).append(comment)
+        out = []
+        out_numbers = []
+        for n, line in zip(numbers, lines):
+            for comment in by_line.get(n, []) if n is not None else []:
+                out.append(line[:len(line) - len(line.lstrip())] + comment)
+                out_numbers.append(None)
+            out.append(line)
+            out_numbers.append(n)
+        self.content_text = "\n".join(out)
+        self.line_map = out_numbers
+        self.tokens = estimate_tokens(self.content_text)
+
+    def outline(self, entities: list) -> list:
+        """Replaces function bodies by `...` line, signatures, doc comments and type definitions with fields are kept.
+        Returns entities outside of stripped bodies, their line numbers refer to original file."""
+        bodies = self.function_bodies(entities)
+        self.replace_bodies(bodies)
+        return [e for e in entities if not any(first <= e["first_line"] and e["last_line"] <= last for first, last, _ in bodies)]
+
 
 class ContextPatchBlock(ContentBlock):
     """Дополнение контекста: правка поста/файла без повторного разбора сущностей (как :post)."""
@@ -499,3 +774,19 @@ class SpanBlock(ContentBlock):
         return f'<{self.tag} file_id="{self.file_id}" start="{start_line}" end="{end_line}" hash="{self.block_hash}" timestamp="{timestamp}">\n ' + \
                f'{self.content_text}\n</{self.tag}>'
 
+
+
+class SummaryBlock(ContentBlock):
+    """Leading pack manifest: project orientation map before file bodies, not parsed."""
+    supported_types = [':summary']
+
+    def __init__(self, content_text: str, project_name: str):
+        super().__init__(content_text, ":summary", file_name=None, timestamp=None)
+        self.tag = "summary"
+        self.project_name = project_name
+
+    def to_sandwich_block(self):
+        return f'<{self.tag} project="{self.project_name}">\n{self.content_text}\n</{self.tag}>'
+
+    def parse_content(self, clean_lines=None, depth=0):
+        return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
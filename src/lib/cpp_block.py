@@ -0,0 +1,307 @@
+# /lib/cpp_block.py, updated 2026-10-16 16:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?:template\s*<[^;{]*?>\s*)?"
+QUALS_REGEX_PATTERN = r"(?P<quals>(?:(?:static|inline|virtual|constexpr|consteval|extern|explicit|friend)\s+)*)"
+RET_REGEX_PATTERN = r"(?P<return>(?:(?!(?:return|else|new|delete|throw|case|goto)\b)[\w:]*\w(?:<[^;{()]*>)?[ \t*&]+)*?)"
+NAME_REGEX_PATTERN = r"(?P<name>(?!(?:if|for|while|switch|return|sizeof|catch|do|else|case|new|delete|throw|alignof|decltype|static_assert|defined|operator)\b)~?[A-Za-z_]\w*(?:::~?[A-Za-z_]\w*)*)"
+ARGS_REGEX_PATTERN = r"\s*\((?P<args>[^;{]*?)\)"
+TAIL_REGEX_PATTERN = r"(?P<tail>(?:\s*(?:const|noexcept|override|final|mutable|volatile|&&?))*(?:\s*->\s*[^{;]+?)?(?:\s*:\s*[^;{]*?)?)"
+
+
+def _skip_directive(text: str, i: int) -> int:
+    """Returns end of preprocessor directive at text[i], honoring line continuations."""
+    while True:
+        end = text.find("\n", i)
+        if end < 0:
+            return len(text)
+        if not text[:end].rstrip("\r").endswith("\\"):
+            return end
+        i = end + 1
+
+
+def _skip_disabled(text: str, i: int) -> int:
+    """Returns offset of the directive closing `#if 0` group that starts after text[i] (its #else/#elif/#endif)."""
+    depth = 0
+    for match in re.finditer(r"^[ \t]*#[ \t]*(?P<kw>if|ifdef|ifndef|elif|else|endif)\b", text[i:], re.MULTILINE):
+        kw = match.group('kw')
+        if kw.startswith("if"):
+            depth += 1
+        elif kw == "endif" and depth > 0:
+            depth -= 1
+        elif depth == 0:
+            return i + match.start()
+    return len(text)
+
+
+def template_params(line: str) -> tuple:
+    """Splits `template <...> rest` line into (params, rest) with balanced angle brackets, or (None, line)."""
+    head = re.match(r"\s*template\s*<", line) if isinstance(line, str) else None
+    if not head:
+        return None, line
+    depth = 0
+    for i in range(head.end() - 1, len(line)):
+        depth += {"<": 1, ">": -1}.get(line[i], 0)
+        if depth == 0:
+            return line[head.end() - 1:i + 1], line[i + 1:].strip()
+    return None, line
+
+
+def strip_cpp_literals(text: str, warn=None) -> str:
+    """Blanks C/C++ string, raw string and char literals plus preprocessor directives, keeping line count.
+
+    Quotes of blanked literals are kept, `#if 0` groups are blanked up to their #else/#elif/#endif,
+    other directive lines become empty. Comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    line_start = True
+    while i < n:
+        ch = text[i]
+        if line_start and ch == "#":
+            end = _skip_directive(text, i)
+            directive = text[i:end]
+            if re.match(r"#\s*if\s+(?:0|false)\b", directive):
+                end = _skip_disabled(text, end)   # closing directive is blanked on next iteration
+            out.append("\n" * text.count("\n", i, end))
+            i = end
+            continue
+        if ch == "\n":
+            line_start = True
+            out.append(ch)
+            i += 1
+            continue
+        if not ch.isspace():
+            line_start = False
+        if text.startswith("//", i):
+            end = _skip_directive(text, i)   # line comments are continued by backslash too
+            out.append("//" + "\n" * text.count("\n", i, end))
+            i = end
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if ch == '"' and re.search(r"(?:^|[^\w])(?:u8|u|U|L)?R$", text[max(0, i - 3):i]):
+            delim_end = text.find("(", i)
+            end = text.find(")" + text[i + 1:delim_end] + '"', delim_end) if delim_end > 0 else -1
+            closed = end >= 0
+            end = end + delim_end - i + 1 if closed else n
+        elif ch == "'" and re.search(r"\b\d[\w']*$", text[max(0, i - 40):i]):
+            out.append(ch)   # digit separator: 1'000'000
+            i += 1
+            continue
+        elif ch in "\"'":
+            j = i + 1
+            while j < n and text[j] not in (ch, "\n"):
+                j += 2 if text[j] == "\\" else 1
+            closed = j < n and text[j] == ch
+            end = j + 1 if closed else min(j, n)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append(ch + "\n" * text.count("\n", i, end) + (ch if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class NamespaceParser(EntityParser):
+    """Parser for named namespaces, including nested `a::b` form."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)(?:inline\s+)?namespace\s+(?P<name>[\w:]+)", ["indent", "name"], 1)\
+            .add_token(r"\s*{", ["head_end"], 2)\
+            .add_token(r"(?P<nested>(?:\s*(?:inline\s+)?namespace\s+[\w:]+\s*{)*)", ["nested"], 0)
+        super().__init__(entity_type, owner, outer_regex, r"\bnamespace\b", default_visibility="public")
+
+    def _format_entity_name(self, match):
+        """Same-line nesting `namespace a { namespace b {` is registered as one `a::b` namespace."""
+        nested = re.findall(r"namespace\s+([\w:]+)", match_value(match, 'nested', ''))
+        return "::".join([super()._format_entity_name(match)] + nested)
+
+
+class ClassParser(EntityParser):
+    """Parser for classes and structs with their inline methods; access labels define visibility."""
+    def __init__(self, entity_type, owner, keyword="class"):
+        self.keyword = keyword
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + keyword + r"\s+(?:alignas\s*\([^)]*\)\s*)?(?P<name>\w+)(?:\s+final)?", ["indent", "name"], 1)\
+            .add_token(r"\s*(?::\s*(?P<parent>[^{;]+?))?", ["parent"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)   # forward declarations must not pass
+        inner_regex = IterativeRegex()
+        inner_regex\
+            .add_token(BASE_REGEX_PATTERN + QUALS_REGEX_PATTERN + RET_REGEX_PATTERN + NAME_REGEX_PATTERN + r"(?=\s*\()",
+                       ["indent", "quals", "return", "name"], 1)\
+            .add_token(ARGS_REGEX_PATTERN + TAIL_REGEX_PATTERN, ["args", "tail"], 1)\
+            .add_token(r"\s*(?P<ending>{|=\s*0\s*;)", ["ending"], 4)
+        super().__init__(entity_type, owner, outer_regex, rf"\b{keyword}\b", inner_regex,
+                         default_visibility="public")
+
+    def detect_abstract(self, match):
+        return "=" in match_value(match, 'ending', '')
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        start_line = self.owner.find_line(base_match.start('name'))
+        entity = self.owner.entity_map.get(start_line)
+        if entity and entity.get("parent"):
+            entity["parent"] = re.sub(r"\b(?:public|protected|private|virtual)\s+", "", entity["parent"]).strip()
+        self._apply_access(entity)
+        return True
+
+    def _apply_access(self, entity):
+        """Sets methods visibility by `public:`/`protected:`/`private:` labels of the class body."""
+        if not entity:
+            return
+        access = "private" if self.keyword == "class" else "public"
+        for line_num in range(entity["first_line"], entity["last_line"] + 1):
+            line = self.owner.clean_lines[line_num]
+            label = re.match(r"^\s*(public|protected|private)\s*:(?!:)", line) if isinstance(line, str) else None
+            if label:
+                access = label.group(1)
+            method = self.owner.entity_map.get(line_num)
+            if method and "method" in method["type"] and method.get("parent") == entity["name"]:
+                method["visibility"] = "private" if access == "private" else "public"
+                if access == "protected":
+                    method["access"] = access
+
+
+class FunctionParser(EntityParser):
+    """Parser for free functions and out-of-line `Class::method` definitions."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + QUALS_REGEX_PATTERN + RET_REGEX_PATTERN + NAME_REGEX_PATTERN + r"(?=\s*\()",
+                       ["indent", "quals", "return", "name"], 1)\
+            .add_token(ARGS_REGEX_PATTERN + TAIL_REGEX_PATTERN, ["args", "tail"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)   # calls and prototypes must not pass
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def detect_visibility(self, match):
+        return "private" if "static" in match_value(match, 'quals', '') else self.default_visibility
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        start_line = self.owner.find_line(base_match.start('name'))
+        entity = self.owner.entity_map.get(start_line)
+        if entity and "::" in entity["name"]:
+            scope, _, name = entity["name"].rpartition("::")
+            entity.update({"type": "method", "name": name, "parent": scope})
+        return True
+
+
+class DepsParserCpp(DepsParser):
+    """Parser for #include directives; directives are removed from clean_lines, so original text is used."""
+    INCLUDE_REGEX = re.compile(r"^[ \t]*#[ \t]*include\s*[<\"](?P<module>[^>\"]+)[>\"]", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.INCLUDE_REGEX.finditer(self.owner.content_text):
+            self.add_module(match.group('module'))
+        return True
+
+
+class ContentCodeCpp(ContentBlock):
+    """Parser for C and C++ content blocks."""
+    supported_types = [".c", ".h", ".cc", ".cpp", ".cxx", ".hh", ".hpp", ".hxx"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "cpp"
+        self.entity_map = {}
+        self.call_method_sep = ['.', '->', '::']
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeCpp with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips literals and preprocessor directives with strip_cpp_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_cpp_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def apply_scopes(self):
+        """Prefixes entity names with enclosing namespaces and stores template parameters."""
+        namespaces = [(e["first_line"], e["last_line"], e["name"]) for e in self.sorted_entities() if e["type"] == "namespace"]
+        renamed = {}
+        for line_num, entity in self.entity_map.items():
+            chain = [name for first, last, name in namespaces if first < line_num <= last]
+            if chain and "method" not in entity["type"]:
+                renamed[entity["name"]] = "::".join(chain + [entity["name"]])
+                entity["name"] = renamed[entity["name"]]
+            params, rest = template_params(self.clean_lines[line_num])
+            if not params and line_num > 1:
+                params, rest = template_params(self.clean_lines[line_num - 1])
+                params = None if rest else params
+            if params:
+                entity["template"] = params
+        for entity in self.entity_map.values():
+            if "method" in entity["type"] and entity.get("parent") in renamed:
+                entity["parent"] = renamed[entity["parent"]]
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses C/C++ content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserCpp(self),
+            NamespaceParser("namespace", self),
+            ClassParser("class", self),
+            ClassParser("structure", self, keyword="struct"),
+            FunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeCpp)
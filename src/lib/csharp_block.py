@@ -0,0 +1,337 @@
+# /lib/csharp_block.py, created 2026-10-14 14:45 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+ATTRIBUTES_PATTERN = r"(?:\[[^\]\n]*\][ \t]*)*"   # same line only, upper lines are collected separately
+MODIFIERS_PATTERN = r"(?P<mods>(?:(?:public|private|protected|internal|static|virtual|override|abstract|sealed|async|extern|unsafe|new|partial|readonly|ref|required|file|const|volatile)\s+)*)"
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)" + ATTRIBUTES_PATTERN + MODIFIERS_PATTERN
+TYPE_PATTERN = r"[\w.]+(?:<[^;{()=]*>)?(?:\[[,\s]*\])*\??"
+STATEMENT_KEYWORDS = r"(?!(?:if|for|foreach|while|switch|catch|using|lock|fixed|return|new|throw|else|case|await|yield|nameof|typeof|sizeof|default)\b)"
+ARGS_REGEX_PATTERN = r"\s*\((?P<args>[^;{]*?)\)"
+TYPE_KINDS = {"class": "class", "struct": "structure", "interface": "interface", "enum": "enum", "record": "record"}
+
+
+def strip_csharp_literals(text: str, warn=None) -> str:
+    """Blanks regular, verbatim (@""), interpolated ($"") and raw (\"\"\"...\"\"\") strings and chars, keeping line count.
+
+    Quotes of blanked literals are kept with newlines in place, interpolation holes are dropped with the text.
+    Preprocessor lines (#region, #if...) become empty. Comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    line_start = True
+    while i < n:
+        ch = text[i]
+        if ch == "\n":
+            line_start = True
+            out.append(ch)
+            i += 1
+            continue
+        if line_start and ch == "#":
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            continue
+        if not ch.isspace():
+            line_start = False
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        prefix = re.match(r"\$*@?\$*(?=\")", text[i:i + 5]) if ch in "$@" else None
+        start = i
+        if prefix:
+            i += len(prefix.group(0))
+            ch = '"'
+        if text.startswith('"""', i):
+            quotes = len(re.match(r'"+', text[i:]).group(0))
+            end = text.find('"' * quotes, i + quotes)
+            closed = end >= 0
+            end = end + quotes if closed else n
+        elif ch == '"' and prefix and "@" in prefix.group(0):
+            j = i + 1
+            while j < n and (text[j] != '"' or text.startswith('""', j)):
+                j += 2 if text.startswith('""', j) else 1
+            closed = j < n
+            end = j + 1 if closed else n
+        elif ch in "\"'":
+            j = i + 1
+            while j < n and text[j] not in (ch, "\n"):
+                j += 2 if text[j] == "\\" else 1
+            closed = j < n and text[j] == ch
+            end = j + 1 if closed else min(j, n)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, start) + 1}")
+        out.append(text[start:i] + ch + "\n" * text.count("\n", i, end) + (ch if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def collect_attributes(clean_lines: list, line_num: int, head: str = "") -> list:
+    """Returns attribute names from `[Attr(...)]` lines above line_num and from the declaration head."""
+    block = [head]
+    i = line_num - 1
+    while i > 0 and isinstance(clean_lines[i], str) and clean_lines[i].strip().startswith('['):
+        block.insert(0, clean_lines[i])
+        i -= 1
+    names = []
+    for group in re.findall(r"\[([^\]]*)\]", " ".join(block)):
+        group = re.sub(r"\([^)]*\)", "", group)
+        names.extend(re.sub(r"^\w+\s*:\s*", "", item.strip()) for item in group.split(',') if item.strip())
+    return names
+
+
+class CSharpEntityParser(EntityParser):
+    """Base for C# parsers: visibility from modifiers, partial flag and attributes as extra fields."""
+    def detect_visibility(self, match):
+        mods = match_value(match, 'mods', '')
+        if re.search(r"\b(?:public|protected|internal)\b", mods):
+            return "public"
+        if 'private' in mods:
+            return "private"
+        return self.default_visibility
+
+    def make_entity(self, e_type, name, vis, first_line, full_text, extra_fields=None):
+        entity = super().make_entity(e_type, name, vis, first_line, full_text, extra_fields)
+        line = self.owner.clean_lines[first_line]
+        short = name.split('.')[-1]
+        head = line[:line.find(short)] if isinstance(line, str) else ""
+        if attributes := collect_attributes(self.owner.clean_lines, first_line, head):
+            entity["attributes"] = attributes
+        if re.search(r"\bpartial\b", head):
+            entity["partial"] = True
+        if re.search(r"\basync\b", head) and "method" in e_type:
+            entity["type"] = "async " + entity["type"]
+        return entity
+
+
+class NamespaceParser(EntityParser):
+    """Parser for block and file-scoped (`namespace A.B;`) namespaces."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)namespace\s+(?P<name>[\w.]+)", ["indent", "name"], 1)\
+            .add_token(r"\s*[{;]", ["head_end"], 2)
+        super().__init__(entity_type, owner, outer_regex, r"\bnamespace\b", default_visibility="public")
+
+
+class TypeParser(CSharpEntityParser):
+    """Parser for class, struct, interface, enum and record declarations."""
+    def __init__(self, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + keyword + r"(?:\s+(?:class|struct))?\s+(?P<name>\w+)", ["indent", "mods", "name"], 1)\
+            .add_token(r"(?:\s*<[^{;]*?>)?(?:\s*\((?P<args>[^;{]*?)\))?(?:\s*:\s*(?P<parent>[^{;]+?))?"
+                       r"(?:\s*where\s+[^{;]+?)*", ["args", "parent"], 1)\
+            .add_token(r"\s*[{;]", ["head_end"], 3)
+        super().__init__(TYPE_KINDS[keyword], owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and entity.get("parent"):
+            entity["parent"] = re.sub(r"\s+", " ", re.sub(r"\([^)]*\)", "", entity["parent"])).strip()
+        return True
+
+
+class MethodParser(CSharpEntityParser):
+    """Parser for methods, constructors and local functions; parent is assigned by enclosing type."""
+    def __init__(self, entity_type, owner):
+        # return type, or constructor lookahead: calls like `Foo(x);` must not pass as declarations
+        ret = (r"(?:(?P<return>" + STATEMENT_KEYWORDS + TYPE_PATTERN + r"[ \t]+)"
+               r"|(?=\w+" + ARGS_REGEX_PATTERN.replace("?P<args>", "") + r"\s*(?::\s*(?:base|this)\s*\([^)]*\)\s*)?(?:{|=>)))")
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + ret + r"(?:[\w.]+\.)?" + STATEMENT_KEYWORDS + r"(?P<name>\w+)(?:<[^;{()]*>)?(?=\s*\()",
+                       ["indent", "mods", "return", "name"], 1)\
+            .add_token(ARGS_REGEX_PATTERN + r"(?:\s*:\s*(?:base|this)\s*\([^)]*\))?(?:\s*where\s+[^{;=]+?)*", ["args"], 1)\
+            .add_token(r"\s*(?P<ending>{|=>|;)", ["ending"], 3)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="private")
+
+    def _process_match(self, base_match):
+        validation = self.outer_regex.validate_match(self.content, base_match.start())
+        if validation['hit_rate'] < 1.0:
+            return False
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and validation['match'].group('ending') == ';':
+            entity["type"] = entity["type"].replace("method", "abstract method")
+            entity["last_line"] = entity["first_line"]
+        return True
+
+
+class PropertyParser(CSharpEntityParser):
+    """Parser for properties with accessors `{ get; set; }` or expression body `=> value;`."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"(?P<return>" + STATEMENT_KEYWORDS + TYPE_PATTERN + r")[ \t]+(?:[\w.]+\.)?(?P<name>\w+)",
+                       ["indent", "mods", "return", "name"], 1)\
+            .add_token(r"\s*(?:{\s*(?:\[[^\]]*\]\s*)?(?:(?:private|protected|internal|public)\s+)*(?:get|set|init)\b|=>)", ["head_end"], 3)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="private")
+
+
+class DepsParserCSharp(DepsParser):
+    """Parser for `using` directives, including static and alias forms."""
+    USING_REGEX = re.compile(r"^[ \t]*(?:global\s+)?using\s+(?P<static>static\s+)?(?:(?P<alias>\w+)\s*=\s*)?(?P<path>[\w.]+)\s*;", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.USING_REGEX.finditer(self.owner.get_clean_content()):
+            path = match.group('path')
+            if match.group('alias') or match.group('static'):
+                module, _, name = path.rpartition('.')
+                self.add_module(module or path)
+                self.add_import(module or path, match.group('alias') or name)
+            else:
+                self.add_module(path)
+        return True
+
+
+class ContentCodeCSharp(ContentBlock):
+    """Parser for C# content blocks."""
+    supported_types = [".cs"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "csharp"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeCSharp with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips all C# string forms with strip_csharp_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_csharp_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Expression bodies (`=> ...;`) and body-less declarations end with `;`, block bodies use brace counting."""
+        depth = 0
+        arrow = False
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for pos, ch in enumerate(line):
+                if ch in "([":
+                    depth += 1
+                elif ch in ")]":
+                    depth -= 1
+                elif ch == "{" and depth == 0 and not arrow:
+                    return super().detect_bounds(start_line, clean_lines)
+                elif ch == "{":
+                    depth += 1
+                elif ch == "}":
+                    depth -= 1
+                elif ch == ";" and depth == 0:
+                    return start_line, line_num
+                elif line.startswith("=>", pos) and depth == 0:
+                    arrow = True
+            if not arrow and line_num - start_line >= 8:
+                break
+        return super().detect_bounds(start_line, clean_lines)
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line members and declarations are regular in C#, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def apply_scopes(self):
+        """Prefixes types with namespaces, sets parent of members to the enclosing type."""
+        entities = self.sorted_entities()
+        last_line = len(self.clean_lines) - 1
+        for entity in entities:
+            if entity["type"] == "namespace" and entity["first_line"] == entity["last_line"]:
+                entity["last_line"] = last_line   # file-scoped namespace
+        namespaces = [(e["first_line"], e["last_line"], e["name"]) for e in entities if e["type"] == "namespace"]
+        types = [e for e in entities if e["type"] in TYPE_KINDS.values()]
+        for entity in types:   # outer types come first and are already qualified
+            line_num = entity["first_line"]
+            outer = [t for t in types if t is not entity and t["first_line"] < line_num <= t["last_line"]]
+            prefix = outer[-1]["name"] if outer else ".".join(name for first, last, name in namespaces if first < line_num <= last)
+            if prefix:
+                entity["name"] = f"{prefix}.{entity['name']}"
+        for entity in entities:
+            if "method" in entity["type"] or entity["type"] == "property":
+                owners = [t for t in types if t["first_line"] < entity["first_line"] <= t["last_line"]]
+                if owners:
+                    entity["parent"] = owners[-1]["name"]
+                    if owners[-1]["type"] == "interface":
+                        entity["visibility"] = "public"   # interface members are public
+                elif "method" in entity["type"]:
+                    entity["type"] = entity["type"].replace("method", "function")   # top-level statements
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses C# content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserCSharp(self),
+            NamespaceParser("namespace", self),
+            TypeParser(self, "record"),
+            TypeParser(self, "interface"),
+            TypeParser(self, "enum"),
+            TypeParser(self, "struct"),
+            TypeParser(self, "class"),
+            PropertyParser("property", self),
+            MethodParser("method", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeCSharp)
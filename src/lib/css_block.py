@@ -0,0 +1,300 @@
+# /lib/css_block.py, updated 2026-10-16 16:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+AT_RULE_REGEX = re.compile(r"@(?P<keyword>[\w-]+)\s*(?P<rest>.*)", re.DOTALL)
+CALLABLE_REGEX = re.compile(r"(?P<name>[\w-]+)\s*(?:\((?P<args>.*)\))?", re.DOTALL)
+DECLARATION_REGEX = re.compile(r"\s*(?P<name>--[\w-]+|\$[\w-]+)\s*:")
+
+
+def strip_css_literals(text: str, scss: bool = False, warn=None) -> str:
+    """Blanks quoted strings and unquoted `url(...)` values, drops `/* */` comments and for SCSS `//` comments, preserving line count."""
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("\n" * text.count("\n", i, end))
+            i = end
+            continue
+        if scss and text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            continue
+        if ch in "\"'":
+            end = i + 1
+            while end < n and text[end] not in (ch, "\n"):
+                end += 2 if text[end] == "\\" else 1   # backslash-newline continues the string
+            closed = end < n and text[end] == ch
+            end = end + 1 if closed else end
+            if not closed and warn:
+                warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+            out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+            i = end
+            continue
+        match = re.match(r"url\(\s*(?![\"'])", text[i:i + 64], re.IGNORECASE)
+        if match and (i == 0 or not (text[i - 1].isalnum() or text[i - 1] in "-_")):
+            end = text.find(")", i)
+            end = n if end < 0 else end
+            out.append("url(" + "\n" * text.count("\n", i, end))
+            i = end
+            continue
+        out.append(ch)
+        i += 1
+    return "".join(out)
+
+
+def split_selectors(header: str) -> list:
+    """Splits selector list by top-level commas, `:is(a, b)` arguments are kept together."""
+    parts = []
+    depth = 0
+    last = 0
+    for i, ch in enumerate(header):
+        if ch in "([":
+            depth += 1
+        elif ch in ")]":
+            depth -= 1
+        elif ch == "," and depth == 0:
+            parts.append(header[last:i])
+            last = i + 1
+    parts.append(header[last:])
+    return [" ".join(part.split()) for part in parts if part.strip()]
+
+
+def resolve_selectors(parents: list, children: list) -> list:
+    """Combines nested selectors with the enclosing ones, `&` refers to parent selector."""
+    if not parents:
+        return children
+    result = []
+    for child in children:
+        for parent in parents:
+            result.append(child.replace("&", parent) if "&" in child else f"{parent} {child}")
+    return result
+
+
+class RuleParser(EntityParser):
+    """Parser for rule sets, `@mixin`, `@function`, `@media`, `@keyframes` and custom property declarations, nesting is tracked by braces."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        stack = []
+        seen = set()
+        stmt_start = 0
+        pos = 0
+        for match in re.finditer(r"#\{|[{};]", content):
+            if match.start() < pos:
+                continue   # inside skipped interpolation
+            pos = match.end()
+            token = match.group(0)
+            if token == "#{":
+                end = content.find("}", pos)
+                pos = len(content) if end < 0 else end + 1
+                continue
+            if token == "{":
+                stack.append(self._open_frame(content, stmt_start, match.start(), stack))
+            else:
+                self._declaration(content, stmt_start, match.start(), stack, seen)
+                if token == "}" and stack:
+                    frame = stack.pop()
+                    if frame["line"] in self.owner.entity_map:
+                        self.owner.entity_map[frame["line"]]["last_line"] = self.owner.find_line(match.start())
+            stmt_start = pos
+        for frame in stack:
+            if frame["line"]:
+                self.owner.parse_warn(f"Incomplete entity at line {frame['line']} in file {self.owner.file_name}")
+        return True
+
+    def _open_frame(self, content, start, end, stack):
+        """Classifies block header and stores entity for it, returns nesting frame."""
+        header = content[start:end]
+        offset = start + len(header) - len(header.lstrip())
+        header = header.strip()
+        parent = next((f for f in reversed(stack) if f["name"]), None)
+        selectors = next((f["selectors"] for f in reversed(stack) if f["selectors"]), [])
+        frame = {"name": None, "selectors": selectors, "line": 0, "opaque": any(f["opaque"] for f in stack)}
+        if frame["opaque"] or not header or header.endswith(":"):
+            return frame   # keyframe steps or SCSS nested properties
+        line_num = self.owner.find_line(offset)
+        extra = {"parent": parent["name"]} if parent else {}
+        at_rule = AT_RULE_REGEX.match(header)
+        if at_rule:
+            keyword = at_rule.group('keyword').lower()
+            rest = " ".join(at_rule.group('rest').split())
+            if keyword in ("mixin", "function"):
+                sign = CALLABLE_REGEX.match(rest)
+                if not sign:
+                    return frame
+                name = sign.group('name')
+                extra["params"] = re.findall(r"\$[\w-]+", sign.group('args') or "")
+                frame["selectors"] = []
+                e_type = keyword
+            elif keyword == "media":
+                name = rest
+                e_type = "media"
+            elif keyword.endswith("keyframes"):
+                name = rest
+                e_type = "keyframes"
+                frame["opaque"] = True
+            else:
+                return frame   # @supports, @include, @if, @font-face... are transparent for nesting
+        else:
+            frame["selectors"] = resolve_selectors(selectors, split_selectors(header))
+            name = ", ".join(frame["selectors"])
+            e_type = self.entity_type
+        visibility = "private" if name[:1] in "-_%" else "public"
+        if self.owner.add_rule(e_type, name, line_num, line_num, visibility, extra):
+            self.new_entities_lines.append(line_num)
+            frame["line"] = line_num
+        frame["name"] = name
+        return frame
+
+    def _declaration(self, content, start, end, stack, seen):
+        """Stores first declaration of custom property, or SCSS variable on top level."""
+        match = DECLARATION_REGEX.match(content, start, end)
+        if not match or stack and stack[-1]["opaque"]:
+            return
+        name = match.group('name')
+        if name in seen or name.startswith("$") and stack:
+            return
+        seen.add(name)
+        parent = next((f["name"] for f in reversed(stack) if f["name"]), None)
+        line_num = self.owner.find_line(match.start('name'))
+        e_type = "custom property" if name.startswith("--") else "variable"
+        visibility = "private" if name[1:2] in "-_" and e_type == "variable" else "public"
+        key = self.owner.add_rule(e_type, name, line_num, self.owner.find_line(max(end - 1, start)), visibility,
+                                  {"parent": parent} if parent else {}, shared_line=True)
+        if key == line_num:
+            self.new_entities_lines.append(line_num)
+
+
+class DepsParserCSS(DepsParser):
+    """Parser for `@import`, `@use` and `@forward` rules, SCSS `@use` namespace is stored as import."""
+    IMPORT_REGEX = re.compile(r"@(?P<rule>import|use|forward)\s+(?:url\(\s*)?[\"']?(?P<path>[^\"'\s);]+)[\"']?(?:\s*\))?(?:\s+as\s+(?P<alias>[\w-]+|\*))?")
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        clean_lines = self.owner.clean_lines
+        for match in self.IMPORT_REGEX.finditer(self.owner.content_text):
+            line_num = self.owner.content_text.count("\n", 0, match.start()) + 1
+            line = clean_lines[line_num] if line_num < len(clean_lines) else ""
+            if not isinstance(line, str) or f"@{match.group('rule')}" not in line:
+                continue   # commented out
+            path = match.group('path')
+            if re.match(r"(?i)^(?:[a-z][\w+.-]*:|//)", path):
+                continue   # remote stylesheet or Sass built-in module
+            if not Path(path).suffix:
+                path += self.owner.content_type
+            if not path.startswith("/"):
+                path = os.path.normpath(f"{Path(self.owner.file_name).parent}/{path}").replace("\\", "/")
+            module = path if path.startswith("/") else f"/{path}"
+            self.add_module(module)
+            alias = match.group('alias') or (Path(match.group('path')).stem.lstrip("_") if match.group('rule') == "use" else None)
+            if alias and alias != "*":
+                self.add_import(module, alias)
+        return True
+
+
+class ContentCodeCSS(ContentBlock):
+    """Parser for CSS and SCSS stylesheets (.css, .scss)."""
+    supported_types = [".css", ".scss"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "scss" if content_type == ".scss" else "css"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are dropped by strip_css_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodeCSS with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips quoted strings, url() values and comments, using strip_css_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_css_literals(
+            "\n".join(self.clean_lines[1:]),
+            scss=self.content_type == ".scss",
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_rule(self, e_type: str, name: str, first_line: int, last_line: int, visibility: str, extra_fields: dict,
+                 shared_line=False):
+        """Stores entity without placement check, selectors are not identifiers; one rule per line. Declarations with
+        shared_line may follow rule on its line (`:root { --a: 1px; }`), they get fractional keys to keep order.
+        Returns entity_map key, None if line is taken."""
+        key = first_line
+        while key in self.entity_map and shared_line:
+            key = round(key + 0.001, 3)
+        if key in self.entity_map:
+            return None
+        self.entity_map[key] = {
+            "type": e_type,
+            "name": name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": 0,
+            **extra_fields
+        }
+        return key
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses CSS/SCSS content to extract rules, mixins, functions, media queries and custom properties."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserCSS(self),
+            RuleParser("selector", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        for entity in self.entity_map.values():
+            lines = self.clean_lines[entity["first_line"]:entity["last_line"] + 1]
+            entity["tokens"] = estimate_tokens("\n".join(line for line in lines if isinstance(line, str)))
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeCSS)
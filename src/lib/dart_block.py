@@ -0,0 +1,333 @@
+# /lib/dart_block.py, created 2026-10-15 05:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+ANNOTATIONS_PATTERN = r"(?:@[\w.]+(?:\([^)\n]*\))?[ \t]+)*"   # same line only
+TYPE_MODIFIERS_PATTERN = r"(?P<mods>(?:(?:abstract|sealed|base|interface|final|mixin)\s+)*)"
+MEMBER_MODIFIERS_PATTERN = r"(?P<mods>(?:(?:static|external|factory|const|abstract|covariant|late|final)\s+)*)"
+TYPE_REF_PATTERN = r"(?!(?:return|new|throw|else|case|await|yield|get|set|operator)\b)[\w$.]+(?:<[^;{}()]*>)?\??" \
+                   r"(?:\s+Function\s*\([^;{}]*?\)\??)?"
+STATEMENT_KEYWORDS = r"(?!(?:if|for|while|switch|catch|try|do|else|return|new|throw|await|yield|assert|super|this|case|rethrow)\b)"
+ARGS_PATTERN = r"(?:\s*<[^;(){}]*>)?\s*\((?P<args>(?:[^;(){}]|\([^;(){}]*\)|{[^;{}]*})*)\)"
+BODY_PATTERN = r"\s*(?P<async>async\*?|sync\*)?\s*"
+KIND_TYPES = {"class": "class", "mixin": "mixin", "enum": "enum", "extension": "extension"}
+WIDGET_BASES = {"StatelessWidget": "stateless", "StatefulWidget": "stateful", "State": "state"}
+
+
+def strip_dart_literals(text: str, warn=None) -> str:
+    """Blanks string literals (single, triple-quoted, raw `r'...'`, with `${...}` interpolation), preserving line count.
+
+    Quotes of blanked literals are kept, multi-line strings collapse to `''` with their newlines in place.
+    Comment bodies are dropped (block comments nest in Dart), the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("/*", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("*/", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            out.append("/*" + "\n" * text.count("\n", i, j) + "*/")
+            i = j
+            continue
+        raw = ch in "rR" and i + 1 < n and text[i + 1] in "\"'" and (i == 0 or not (text[i - 1].isalnum() or text[i - 1] in "_$"))
+        if ch not in "\"'" and not raw:
+            out.append(ch)
+            i += 1
+            continue
+        start = i
+        if raw:
+            i += 1
+        end, closed = skip_dart_string(text, i, raw)
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, start) + 1}")
+        quote = text[i]
+        out.append(quote + "\n" * text.count("\n", start, end) + (quote if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def skip_dart_string(text: str, i: int, raw: bool = False):
+    """End offset of string literal opened at text[i] and closed flag, interpolated expressions may contain strings."""
+    n = len(text)
+    quote = text[i] * 3 if text.startswith(text[i] * 3, i) else text[i]
+    j = i + len(quote)
+    while j < n:
+        if text.startswith(quote, j):
+            return j + len(quote), True
+        if len(quote) == 1 and text[j] == "\n":
+            return j, False
+        if raw:
+            j += 1
+        elif text[j] == "\\":
+            j += 2
+        elif text.startswith("${", j):
+            depth = 0
+            while j < n:
+                if text[j] in "\"'":
+                    j = skip_dart_string(text, j)[0]
+                    continue
+                depth += {"{": 1, "}": -1}.get(text[j], 0)
+                j += 1
+                if depth == 0:
+                    break
+        else:
+            j += 1
+    return n, False
+
+
+def blank_nested_bodies(text: str) -> str:
+    """Blanks contents of braces nested in the outer body, so only member declarations remain visible."""
+    out = []
+    depth = 0
+    for ch in text:
+        if ch == "}":
+            depth -= 1
+        out.append(ch if depth < 2 or ch in "{}\n" else " ")
+        if ch == "{":
+            depth += 1
+    return "".join(out)
+
+
+class DartEntityParser(EntityParser):
+    """Base for Dart parsers: library-private names start with underscore."""
+    def detect_visibility(self, match):
+        name = match_value(match, 'name', '')
+        return "private" if name.split(".")[-1].startswith("_") else "public"
+
+
+class TypeParser(DartEntityParser):
+    """Parser for class-like declarations (class, mixin, enum, extension) and their members.
+
+    Members are searched at class body depth only, constructors are recognized by the class name,
+    classes extending StatelessWidget / StatefulWidget / State are flagged as widgets.
+    """
+    def __init__(self, owner, keyword="class"):
+        self.keyword = keyword
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)" + ANNOTATIONS_PATTERN + TYPE_MODIFIERS_PATTERN + keyword + r"\s+(?!class\b|on\b)(?P<name>\w+)",
+                       ["indent", "mods", "name"], 1)\
+            .add_token(r"(?:\s*<[^{]*?>)?(?:\s+extends\s+(?P<parent>[^{]+?))?(?:\s+with\s+(?P<mixins>[^{]+?))?"
+                       r"(?:\s+on\s+(?P<on>[^{]+?))?(?:\s+implements\s+(?P<implements>[^{]+?))?", ["parent", "mixins", "on", "implements"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)
+        super().__init__(KIND_TYPES[keyword], owner, outer_regex, rf"\b{keyword}\b", IterativeRegex(),   # members regex is built per class
+                         default_visibility="public")
+
+    def member_regex(self, class_name: str) -> IterativeRegex:
+        """Member declarations: return type is required, except for accessors, constructors and bodies (legacy dynamic return)."""
+        ctor = re.escape(class_name)
+        no_return = rf"(?=(?:get|set)\s)|(?={ctor}\b)|(?=[\w$]+\s*\([^;{{}}]*\)" + r"\s*(?:async\*?|sync\*)?\s*(?:{|=>))"
+        inner_regex = IterativeRegex()
+        inner_regex\
+            .add_token(r"^(?P<indent>[ \t]*)" + ANNOTATIONS_PATTERN + MEMBER_MODIFIERS_PATTERN + rf"(?:(?P<return>{TYPE_REF_PATTERN})[ \t]+|{no_return})"
+                       r"(?:(?P<accessor>get|set)\s+)?" + STATEMENT_KEYWORDS + r"(?P<name>[A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*)?)"
+                       r"(?=\s*(?:[(<]|=>|{))", ["indent", "mods", "return", "accessor", "name"], 1)\
+            .add_token(r"(?:" + ARGS_PATTERN + r")?", ["args"], 1)\
+            .add_token(BODY_PATTERN + r"(?P<ending>{|=>|;|:)", ["async", "ending"], 4)
+        return inner_regex
+
+    def detect_abstract(self, match):
+        return match_value(match, 'ending', '') == ';' and bool(match_value(match, 'return') or match_value(match, 'accessor'))
+
+    def parse_inner(self, content: str, offset: int, parent_name: str):
+        class_name = parent_name[len(self.owner.module_prefix):]
+        self.inner_regex = self.member_regex(class_name)
+        known = len(self.new_entities_lines)
+        super().parse_inner(blank_nested_bodies(content), offset, parent_name)
+        for line_num in self.new_entities_lines[known:]:
+            entity = self.owner.entity_map[line_num]
+            line = self.owner.clean_lines[line_num]
+            if re.search(rf"\b(?:get|set)\s+{re.escape(entity['name'])}\b", line):
+                entity["type"] = entity["type"].replace("method", "property")
+            elif re.search(r"\bfactory\s", line):
+                entity["type"] = "factory"
+            elif entity["name"].split(".")[0] == class_name:
+                entity["type"] = "constructor"
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if not entity:
+            return True
+        validation = self.outer_regex.validate_match(self.content, base_match.start())
+        for field in ("mixins", "on", "implements"):
+            if value := match_value(validation['match'], field):
+                entity[field] = re.sub(r"\s+", " ", value.strip())
+        if entity.get("parent"):
+            entity["parent"] = re.sub(r"\s+", " ", entity["parent"])
+            base = entity["parent"].split("<")[0].strip()
+            if base in WIDGET_BASES:
+                entity["widget"] = WIDGET_BASES[base]
+        return True
+
+
+class FunctionParser(DartEntityParser):
+    """Parser for top-level functions and accessors, with block (`{`) or expression (`=>`) bodies."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^" + ANNOTATIONS_PATTERN + r"(?P<mods>(?:external\s+)*)" + rf"(?:(?P<return>{TYPE_REF_PATTERN})[ \t]+)?"
+                       r"(?:(?P<accessor>get|set)\s+)?" + STATEMENT_KEYWORDS +
+                       r"(?!(?:class|mixin|enum|extension|typedef|import|export|part|library|var|const|final|late)\b)"
+                       r"(?P<name>[A-Za-z_$][\w$]*)(?=\s*(?:[(<]|=>|{))", ["mods", "return", "accessor", "name"], 1)\
+            .add_token(r"(?:" + ARGS_PATTERN + r")?", ["args"], 1)\
+            .add_token(BODY_PATTERN + r"(?:{|=>)", ["async"], 3)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and match_value(base_match, 'accessor'):
+            entity["type"] = entity["type"].replace("function", "property")
+        return True
+
+
+class DepsParserDart(DepsParser):
+    """Parser for `import` / `export` / `part` directives: `dart:` and `package:` URIs kept as is, relative paths resolved.
+
+    Prefixed imports (`as name`) and `show` lists are stored as imports.
+    """
+    DIRECTIVE_REGEX = re.compile(r"^[ \t]*(?P<kind>import|export|part)\s+(?P<quote>['\"])(?P<uri>[^'\"\n]+)(?P=quote)"
+                                 r"(?P<tail>[^;]*);", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = re.sub(r"//[^\n]*", "", self.owner.content_text)   # URIs are strings, clean_lines lost them
+        for match in self.DIRECTIVE_REGEX.finditer(content):
+            uri = match.group('uri')
+            if not re.match(r"^\w+:", uri):
+                uri = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), uri)).replace("\\", "/")
+            self.add_module(uri)
+            tail = match.group('tail')
+            if prefix := re.search(r"\bas\s+(\w+)", tail):
+                self.add_import(uri, prefix.group(1))
+            if shown := re.search(r"\bshow\s+([\w\s,]+?)(?=\bhide\b|$)", tail):
+                for name in re.findall(r"\w+", shown.group(1)):
+                    self.add_import(uri, name)
+        return True
+
+
+class ContentCodeDart(ContentBlock):
+    """Parser for Dart / Flutter content blocks."""
+    supported_types = [".dart"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "dart"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeDart with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string literals with strip_dart_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_dart_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Block bodies end with the matching brace, expression bodies (`=> expr;`) and declarations without body with `;`."""
+        depth = 0
+        arrow = False
+        body = False
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for i, ch in enumerate(line):
+                if ch == "{" and depth == 0 and not arrow:
+                    body = True
+                if ch in "([{":
+                    depth += 1
+                elif ch in ")]}":
+                    depth = max(depth - 1, 0)
+                    if body and depth == 0:
+                        return start_line, line_num
+                elif ch == ";" and depth == 0 and not body:
+                    return start_line, line_num
+                elif line.startswith("=>", i) and depth == 0:
+                    arrow = True
+        return start_line, start_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations (`=>` bodies) are regular in Dart, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Dart content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserDart(self),
+            TypeParser(self, "mixin"),
+            TypeParser(self, "extension"),
+            TypeParser(self, "enum"),
+            TypeParser(self, "class"),
+            FunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeDart)
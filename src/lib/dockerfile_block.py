@@ -0,0 +1,265 @@
+# /lib/dockerfile_block.py, created 2026-10-15 03:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import json
+import shlex
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+INSTRUCTION_REGEX = re.compile(r"^[ \t]*(?P<keyword>[A-Za-z]+)(?:[ \t]+(?P<args>.*))?$")
+DIRECTIVE_REGEX = re.compile(r"^#\s*(?P<name>[a-z]+)\s*=\s*(?P<value>\S+)\s*$", re.IGNORECASE)
+HEREDOC_REGEX = re.compile(r"<<-?[\"']?(?P<word>\w+)[\"']?")
+
+
+def split_args(args: str) -> list:
+    """Instruction arguments: JSON exec form (`["a", "b"]`) or shell words, flags (`--from=x`) are kept."""
+    if args.lstrip().startswith("["):
+        try:
+            value = json.loads(args)
+            if isinstance(value, list):
+                return [str(item) for item in value]
+        except ValueError:
+            pass
+    try:
+        return shlex.split(args, comments=False, posix=True)
+    except ValueError:
+        return args.split()
+
+
+def split_flags(words: list):
+    """Splits leading `--name=value` flags from positional arguments."""
+    flags = {}
+    while words and words[0].startswith("--"):
+        name, _, value = words.pop(0)[2:].partition("=")
+        flags[name] = value
+    return flags, words
+
+
+class StageParser(EntityParser):
+    """Parser for build stages: `FROM image [AS name]` up to the next FROM, stages of multi-stage builds refer to each other by name."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        stages = [ins for ins in self.owner.instructions if ins["keyword"] == "FROM"]
+        names = []
+        for index, ins in enumerate(stages):
+            flags, words = split_flags(split_args(ins["args"]))
+            if not words:
+                continue
+            image = words[0]
+            alias = words[2] if len(words) >= 3 and words[1].lower() == "as" else None
+            following = [s["first_line"] for s in stages[index + 1:]]
+            last_line = self.owner.last_instruction_line(following[0] - 1 if following else len(self.owner.clean_lines) - 1)
+            extra = {"image": image, "index": index}
+            if image in names:
+                extra["base"] = image   # built on top of previous stage
+            if flags.get("platform"):
+                extra["platform"] = flags["platform"]
+            name = alias or image
+            if self.owner.add_instruction(self.entity_type, name, ins["first_line"], max(last_line, ins["last_line"]), extra):
+                self.new_entities_lines.append(ins["first_line"])
+                self.owner.stages.append((ins["first_line"], name))
+                names.append(alias or str(index))
+        return True
+
+
+class InstructionParser(EntityParser):
+    """Parser for instructions describing the container: EXPOSE ports, ENTRYPOINT / CMD and COPY / ADD paths."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        for ins in self.owner.instructions:
+            keyword = ins["keyword"]
+            if keyword not in ("EXPOSE", "ENTRYPOINT", "CMD", "COPY", "ADD"):
+                continue
+            words = split_args(ins["args"])
+            extra = {}
+            stage = self.owner.stage_at(ins["first_line"])
+            if stage:
+                extra["parent"] = stage
+            if keyword == "EXPOSE":
+                if not words:
+                    continue
+                e_type, name = "port", " ".join(words)
+                extra["ports"] = words
+            elif keyword in ("ENTRYPOINT", "CMD"):
+                if not words:
+                    continue
+                e_type, name = "entrypoint" if keyword == "ENTRYPOINT" else "command", Path(words[0]).name
+                extra["command"] = words if ins["args"].lstrip().startswith("[") else ins["args"].strip()
+            else:
+                flags, words = split_flags(words)
+                if HEREDOC_REGEX.match(words[0] if words else ""):
+                    words = [w for w in words if not HEREDOC_REGEX.match(w)]
+                    words = ["<<heredoc"] + words
+                if len(words) < 2:
+                    continue
+                e_type, name = "copy", words[-1]
+                extra["sources"] = words[:-1]
+                if keyword == "ADD":
+                    extra["add"] = True
+                if flags.get("from"):
+                    extra["from"] = flags["from"]
+            if self.owner.add_instruction(e_type, name, ins["first_line"], ins["last_line"], extra):
+                self.new_entities_lines.append(ins["first_line"])
+        return True
+
+
+class DepsParserDockerfile(DepsParser):
+    """Parser for local build context files copied into image (COPY / ADD without --from), globs, directories and URLs are skipped."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for ins in self.owner.instructions:
+            if ins["keyword"] not in ("COPY", "ADD"):
+                continue
+            flags, words = split_flags(split_args(ins["args"]))
+            if "from" in flags or len(words) < 2:
+                continue
+            for source in words[:-1]:
+                if re.search(r"[*?\[]|^<<|://|^git@", source) or source.endswith("/") or not Path(source).suffix:
+                    continue
+                module = os.path.normpath(f"{Path(self.owner.file_name).parent}/{source}").replace("\\", "/")
+                self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class ContentDockerfile(ContentBlock):
+    """Parser for Dockerfiles (Dockerfile, Containerfile, *.dockerfile)."""
+    supported_types = ["Dockerfile", "Containerfile", ".dockerfile"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "dockerfile"
+        self.entity_map = {}
+        self.instructions = []
+        self.stages = []
+        self.open_sl_comment = []   # comment lines are blanked by strip_strings
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentDockerfile with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks comment lines and heredoc bodies, joins continued lines into instructions (escape directive respected)."""
+        lines = self.content_text.split("\n")
+        escape = "\\"
+        for line in lines:
+            match = DIRECTIVE_REGEX.match(line.strip())
+            if not match:
+                break   # parser directives precede any other line
+            if match.group('name').lower() == "escape":
+                escape = match.group('value')[:1] or escape
+        self.instructions = []
+        current = None
+        heredocs = []
+        clean = []
+        for line_num, line in enumerate(lines, 1):
+            if heredocs:
+                clean.append("")
+                if line.strip() == heredocs[0]:
+                    heredocs.pop(0)
+                current["last_line"] = line_num
+                if not heredocs:
+                    current = None
+                continue
+            if line.lstrip().startswith("#") or (not line.strip() and current is None):
+                clean.append("")
+                continue
+            clean.append(line)
+            text = line.rstrip()
+            continued = text.endswith(escape)
+            if continued:
+                text = text[:-1]
+            if current is None:
+                match = INSTRUCTION_REGEX.match(text)
+                if not match:
+                    continue
+                current = {"keyword": match.group('keyword').upper(), "args": match.group('args') or "", "first_line": line_num, "last_line": line_num}
+                self.instructions.append(current)
+            else:
+                current["args"] += " " + text.strip()
+                current["last_line"] = line_num
+            heredocs = HEREDOC_REGEX.findall(text) if not continued else []
+            if not continued and not heredocs:
+                current["args"] = current["args"].strip()
+                current = None
+        if heredocs:
+            self.parse_warn(f"Unterminated heredoc {heredocs[0]} in file {self.file_name}")
+        self.clean_lines = [""] + clean
+        self.get_clean_content()
+        return self.clean_lines
+
+    def last_instruction_line(self, line_num: int) -> int:
+        """Last line of instruction at or before line_num, trailing blank and comment lines do not belong to stage."""
+        lines = [ins["last_line"] for ins in self.instructions if ins["last_line"] <= line_num]
+        return max(lines) if lines else line_num
+
+    def stage_at(self, line_num: int):
+        """Name of the stage containing line_num."""
+        names = [name for first_line, name in self.stages if first_line <= line_num]
+        return names[-1] if names else None
+
+    def add_instruction(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Dockerfile content to extract stages, ports, entrypoints and copied paths."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.stages = []
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            StageParser("stage", self),
+            InstructionParser("instruction", self),
+            DepsParserDockerfile(self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentDockerfile)
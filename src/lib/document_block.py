@@ -10,7 +10,7 @@ logging.basicConfig(level=logging.DEBUG, format='%(asctime)s #%(levelname)s: %(m
 
 # Под документами подразумеваются текстовые файлы, для которых не требуется парсинг кода
 class DocumentBlock(ContentBlock):
-    supported_types = list(DOCUMENT_EXTENSIONS)
+    supported_types = [ext for ext in DOCUMENT_EXTENSIONS if ext not in (".md", ".toml")]   # own parsers in markdown_block, toml_block
 
     def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
         super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
@@ -27,7 +27,7 @@ SandwichPack.register_block_class(DocumentBlock)
 
 
 class TextDataBlock(ContentBlock):
-    supported_types = list(TEXT_FILE_EXTENSIONS)
+    supported_types = [ext for ext in TEXT_FILE_EXTENSIONS if ext not in (".yml", ".yaml", ".json")]   # own parsers in yaml_block, json_block
 
     def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
         super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
@@ -0,0 +1,501 @@
+# /lib/elixir_block.py, created 2026-10-15 04:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+EX_DEF_REGEX = re.compile(r"^[ \t]*(?P<kind>defmodule|defprotocol|defimpl|defmacrop|defmacro|defguardp|defguard|defdelegate|defp|def)\b[ \t]*"
+                          r"(?P<name>[A-Za-z_][\w.]*[?!]?)?", re.MULTILINE)
+EX_TOKEN_REGEX = re.compile(r"(?P<do_kw>\bdo:)|(?<![.:])\b(?P<open>do|fn)\b(?![?!:])|(?<![.:])\b(?P<end>end)\b(?![?!:])|"
+                            r"^[ \t]*@(?P<attr>doc|moduledoc|spec)\b", re.MULTILINE)
+EX_KINDS = {"defmodule": "module", "defprotocol": "protocol", "defimpl": "implementation", "def": "function", "defp": "function",
+            "defmacro": "macro", "defmacrop": "macro", "defguard": "guard", "defguardp": "guard", "defdelegate": "delegate"}
+EX_SCOPES = ("defmodule", "defprotocol", "defimpl")
+EX_SIGIL_PAIRS = {"(": ")", "[": "]", "{": "}", "<": ">"}
+EX_DEPS_REGEX = re.compile(r"^[ \t]*(?P<kind>alias|import|require|use)\s+(?P<module>[A-Z][\w.]*?)(?:\.\{(?P<multi>[^}]*)\})?"
+                           r"(?:\s*,\s*as:\s*(?P<alias>[A-Z]\w*))?(?=\s|,|$)", re.MULTILINE)
+ERL_ATTR_REGEX = re.compile(r"^-(?P<attr>[a-z_]\w*)\b")
+ERL_FUNCTION_REGEX = re.compile(r"^(?P<name>[a-z]\w*|'[^'\n]*')\s*\(")
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def count_args(text: str, start: int) -> int:
+    """Arity of call/head with argument list opened at text[start] == '(', nested brackets respected."""
+    depth = 0
+    count = 0
+    has_arg = False
+    for ch in text[start:]:
+        if ch in "([{":
+            depth += 1
+            if depth == 1:
+                continue
+        elif ch in ")]}":
+            depth -= 1
+            if depth == 0:
+                break
+        elif ch == "," and depth == 1:
+            count += 1
+            continue
+        if depth >= 1 and not ch.isspace():
+            has_arg = True
+    return count + 1 if has_arg else 0
+
+
+def strip_elixir_literals(text: str, warn=None) -> str:
+    """Blanks Elixir strings, charlists, heredocs, sigils, character literals (`?a`) and `#` comments with spaces.
+
+    Delimiters are kept and positions are preserved, so attribute values (`@doc`) are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch == "?" and i + 1 < n and (i == 0 or not (text[i - 1].isalnum() or text[i - 1] in "_?!")):
+            size = 3 if text[i + 1] == "\\" and i + 2 < n else 2
+            out.append("?" + " " * (size - 1))
+            i += size
+            continue
+        sigil = re.match(r"~[a-zA-Z]+(?=[/|\"'(\[{<])", text[i:i + 12]) if ch == "~" else None
+        if sigil:
+            out.append(sigil.group(0))
+            i += len(sigil.group(0))
+            ch = text[i]
+            if ch in "\"'" and text.startswith(ch * 3, i):
+                close = ch * 3
+            else:
+                close = EX_SIGIL_PAIRS.get(ch, ch)
+        elif ch in "\"'":
+            close = ch * 3 if text.startswith(ch * 3, i) else ch
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        opener = close if len(close) == 3 else ch
+        j = i + len(opener)
+        while j < n and not text.startswith(close, j):
+            j += 2 if text[j] == "\\" else 1
+        closed = j < n
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        end = j + len(close) if closed else n
+        out.append(opener + blank(text[i + len(opener):end - (len(close) if closed else 0)]) + (close if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def strip_erlang_literals(text: str, warn=None) -> str:
+    """Blanks Erlang strings, quoted atoms, character literals (`$a`) and `%` comments with spaces, positions are preserved."""
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "%":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch == "$" and i + 1 < n:
+            size = 3 if text[i + 1] == "\\" and i + 2 < n else 2
+            out.append("$" + " " * (size - 1))
+            i += size
+            continue
+        if ch not in "\"'":
+            out.append(ch)
+            i += 1
+            continue
+        j = i + 1
+        while j < n and text[j] != ch:
+            j += 2 if text[j] == "\\" else 1
+        closed = j < n
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        end = j + 1 if closed else n
+        out.append(ch + blank(text[i + 1:end - (1 if closed else 0)]) + (ch if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class DefinitionParser(EntityParser):
+    """Parser for Elixir definitions: modules, protocols and implementations scope functions, macros, guards and delegates.
+
+    Bodies are matched by `do` / `fn` ... `end` pairs, `do:` one-liners end with their statement. Consecutive clauses
+    of the same function are merged into one entity, `@doc` / `@moduledoc` / `@spec` preceding definition are stored in extras.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        events = [(m.start(), "def", m) for m in EX_DEF_REGEX.finditer(content)]
+        events += [(m.start(), "token", m) for m in EX_TOKEN_REGEX.finditer(content)]
+        events.sort(key=lambda e: (e[0], e[1] != "def"))
+        stack = []
+        pending = None
+        attrs = {}
+        definitions = []
+        for pos, event, match in events:
+            if event == "def":
+                if pending:
+                    self.finish(pending, pending["line"], definitions)   # head without body (default arguments clause)
+                pending = self.open_definition(match, stack, attrs, content)
+                attrs = {}
+                continue
+            if match.group('attr') == "moduledoc":
+                scopes = [f for f in stack if f and f["kind"] in EX_SCOPES]
+                if scopes:
+                    scopes[-1]["doc"] = self.attribute_value(match)   # placed inside the module body
+            elif match.group('attr'):
+                attrs[match.group('attr')] = self.attribute_value(match)
+            elif match.group('do_kw') and pending and pending["pos"] < pos:
+                self.finish(pending, self.owner.statement_end(self.owner.find_line(pos)), definitions)
+                pending = None
+            elif match.group('open'):
+                if match.group('open') == "do" and pending:
+                    stack.append(pending)
+                    pending = None
+                else:
+                    stack.append(None)
+            elif match.group('end') and stack:
+                frame = stack.pop()
+                if frame:
+                    self.finish(frame, self.owner.find_line(pos), definitions)
+        if pending:
+            self.finish(pending, self.owner.statement_end(pending["line"]), definitions)
+
+        previous = None
+        for frame in sorted(definitions, key=lambda f: f["line"]):
+            key = (frame["kind"], frame["name"], frame.get("arity"), frame["parent"])
+            if previous and previous[0] == key and previous[1]["last_line"] < frame["line"]:
+                entity = previous[1]
+                entity["last_line"] = frame["last_line"]
+                entity["clauses"] = entity.get("clauses", 1) + 1
+                continue
+            extra = {"parent": frame["parent"]}
+            for field in ("arity", "doc", "spec", "for", "to"):
+                if field in frame:
+                    extra[field] = frame[field]
+            visibility = "private" if frame["kind"] in ("defp", "defmacrop", "defguardp") else "public"
+            if self.owner.add_definition(EX_KINDS[frame["kind"]], frame["name"], frame["line"], frame["last_line"], extra, visibility):
+                self.new_entities_lines.append(frame["line"])
+                previous = (key, self.owner.entity_map[frame["line"]])
+        return True
+
+    def open_definition(self, match, stack, attrs, content):
+        kind = match.group('kind')
+        scopes = [f for f in stack if f and f["kind"] in EX_SCOPES]
+        parent = scopes[-1]["name"] if scopes else ""
+        name = match.group('name') or kind
+        if kind == "defmodule" and parent:
+            name = f"{parent}.{name}"   # nested module is named by the enclosing one
+        frame = {"kind": kind, "name": name, "parent": parent, "line": self.owner.find_line(match.start('kind')), "pos": match.end()}
+        tail = content[match.end():].split("\n", 1)[0]
+        if kind in EX_SCOPES:
+            if kind == "defimpl" and (target := re.search(r"\bfor:\s*(?P<type>[\w.]+)", tail)):
+                frame["for"] = target.group('type')
+        else:
+            if "doc" in attrs:
+                frame["doc"] = attrs["doc"]
+            if "spec" in attrs:
+                frame["spec"] = attrs["spec"]
+            frame["arity"] = count_args(content, match.end()) if content[match.end():match.end() + 1] == "(" else 0
+            if kind == "defdelegate" and (target := re.search(r"\bto:\s*(?P<module>[\w.]+)", content[match.end():match.end() + 400])):
+                frame["to"] = target.group('module')
+        return frame
+
+    def attribute_value(self, match):
+        """Value of `@doc` / `@moduledoc` (first line of text or False), `@spec` as compact text read from the source."""
+        raw = self.owner.content_text[match.end():]
+        if match.group('attr') == "spec":
+            end_line = self.owner.statement_end(self.owner.find_line(match.start('attr')))
+            lines = self.owner.content_text.split("\n")[self.owner.find_line(match.start('attr')) - 1:end_line]
+            return " ".join(" ".join(lines).split())[len("@spec "):]
+        value = re.match(r"\s*(?:(?P<false>false|nil)\b|~[sS]?(?P<q1>\"\"\"|''')|(?P<q2>\"\"\"|''')|\"(?P<text>(?:[^\"\\\\]|\\\\.)*)\")", raw)
+        if not value:
+            return None
+        if value.group('false'):
+            return False
+        if value.group('text') is not None:
+            return value.group('text')
+        quote = value.group('q1') or value.group('q2')
+        body = raw[value.end():raw.find(quote, value.end())]
+        return next((line.strip() for line in body.split("\n") if line.strip()), "")
+
+    def finish(self, frame, last_line, definitions):
+        frame["last_line"] = max(last_line, frame["line"])
+        definitions.append(frame)
+
+
+class DepsParserElixir(DepsParser):
+    """Parser for `alias` / `import` / `require` / `use` directives, including multi-alias `alias Foo.{A, B}` and `as:` names."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in EX_DEPS_REGEX.finditer(self.owner.get_clean_content()):
+            modules = [match.group('module')]
+            if match.group('multi') is not None:
+                modules = [f"{match.group('module')}.{item.strip()}" for item in match.group('multi').split(",") if item.strip()]
+            for module in modules:
+                self.add_module(module)
+                if match.group('kind') == "alias":
+                    self.add_import(module, match.group('alias') or module.split(".")[-1])
+        return True
+
+
+class FormParser(EntityParser):
+    """Parser for Erlang forms: `-module`, `-record`, `-type`, `-define` attributes and functions, ended by a dot.
+
+    Functions listed in `-export` (or all with `export_all`) are public, `-spec` is stored in the following function.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="private")
+        self.module_line = None
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        forms = []
+        pos = 0
+        for stop in list(re.finditer(r"\.(?=\s|$)", content)) + [None]:
+            end = stop.start() if stop else len(content)
+            text = content[pos:end]
+            offset = pos + len(text) - len(text.lstrip())
+            if text.strip():
+                forms.append((offset, end, text.strip()))
+            pos = end + 1
+        exports = set()
+        export_all = False
+        for offset, end, text in forms:
+            attr = ERL_ATTR_REGEX.match(text)
+            if attr and attr.group('attr') == "export":
+                exports.update(re.findall(r"([a-z]\w*|'[^']*')\s*/\s*(\d+)", self.owner.content_text[offset:end]))
+            elif attr and attr.group('attr') == "compile" and "export_all" in text:
+                export_all = True
+
+        module = None
+        spec = None
+        for offset, end, text in forms:
+            first_line, last_line = self.owner.find_line(offset), self.owner.find_line(max(end - 1, offset))
+            raw = self.owner.content_text[offset:end]
+            attr = ERL_ATTR_REGEX.match(text)
+            if attr:
+                name = attr.group('attr')
+                args = re.match(r"\(\s*(?P<first>[a-z]\w*|\?\w+|[A-Z]\w*)", raw[len(name) + 1:].lstrip())
+                first = args.group('first') if args else None
+                if name == "module" and first:
+                    module = first
+                    self.add(first_line, last_line, "module", first, {}, "public")
+                elif name in ("behaviour", "behavior") and first and self.module_line:
+                    self.owner.entity_map[self.module_line].setdefault("behaviours", []).append(first)
+                elif name == "record" and first:
+                    fields = re.findall(r"[{,]\s*([a-z]\w*)", text[text.find("{"):]) if "{" in text else []
+                    self.add(first_line, last_line, "record", first, {"fields": fields}, "public")
+                elif name in ("type", "opaque") and (type_name := re.match(r"-\w+\s*\(?\s*(?P<name>[a-z]\w*)\s*\(", text)):
+                    self.add(first_line, last_line, "type", type_name.group('name'), {"opaque": True} if name == "opaque" else {}, "public")
+                elif name == "define" and first:
+                    self.add(first_line, last_line, "macro", first.lstrip("?"), {}, "public")
+                elif name == "spec":
+                    spec = " ".join(raw[len("-spec"):].split())
+                continue
+            function = ERL_FUNCTION_REGEX.match(text)
+            if not function:
+                continue
+            fname = function.group('name').strip("'")
+            arity = count_args(text, function.end() - 1)
+            extra = {"arity": arity, "parent": module or ""}
+            clauses = len(re.findall(r";\s*\n\s*" + re.escape(function.group('name')) + r"\s*\(", text)) + 1
+            if clauses > 1:
+                extra["clauses"] = clauses
+            if spec and spec.startswith(fname):
+                extra["spec"] = spec
+            spec = None
+            public = export_all or (fname, str(arity)) in exports or (f"'{fname}'", str(arity)) in exports
+            self.add(first_line, last_line, "function", fname, extra, "public" if public else "private")
+        return True
+
+    def add(self, first_line, last_line, e_type, name, extra, visibility):
+        if self.owner.add_definition(e_type, name, first_line, last_line, extra, visibility):
+            self.new_entities_lines.append(first_line)
+            if e_type == "module":
+                self.module_line = first_line
+
+
+class DepsParserErlang(DepsParser):
+    """Parser for `-include("file.hrl")` (relative to the source) and `-include_lib("app/include/file.hrl")` attributes."""
+    INCLUDE_REGEX = re.compile(r"^-(?P<kind>include|include_lib)\s*\(\s*\"(?P<path>[^\"\n]+)\"\s*\)", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.INCLUDE_REGEX.finditer(self.owner.content_text):
+            path = match.group('path')
+            if "$" in path:
+                continue
+            if match.group('kind') == "include":
+                path = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), path)).replace("\\", "/")
+            self.add_module(path)
+        return True
+
+
+class ContentCodeElixir(ContentBlock):
+    """Parser for Elixir content blocks (.ex, .exs)."""
+    supported_types = [".ex", ".exs"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "elixir"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeElixir with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_literals(self, text: str) -> str:
+        return strip_elixir_literals(text, warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}"))
+
+    def strip_strings(self):
+        """Strips literals and comments, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = self.strip_literals("\n".join(self.clean_lines[1:]))
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def statement_end(self, start_line: int) -> int:
+        """Last line of statement started at start_line: brackets are balanced and line does not end with operator or comma."""
+        depth = 0
+        for line_num in range(start_line, len(self.clean_lines)):
+            line = self.clean_lines[line_num] if isinstance(self.clean_lines[line_num], str) else ""
+            depth += sum(line.count(c) for c in "([{") - sum(line.count(c) for c in ")]}")
+            if depth <= 0 and not re.search(r"(?:,|\||->|=|\bdo:|::)\s*$", line):
+                return line_num
+        return start_line
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": self.module_prefix + name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def update_tokens(self):
+        """Recounts tokens of entities extended by merged clauses."""
+        lines = self.content_text.split("\n")
+        for entity in self.entity_map.values():
+            entity["tokens"] = estimate_tokens("\n".join(lines[entity["first_line"] - 1:entity["last_line"]]))
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Elixir content to extract modules, functions, macros and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserElixir(self),
+            DefinitionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.update_tokens()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+class ContentCodeErlang(ContentCodeElixir):
+    """Parser for Erlang content blocks (.erl, .hrl)."""
+    supported_types = [".erl", ".hrl"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "erlang"
+        logging.debug(f"Initialized ContentCodeErlang with tag={self.tag}, file_name={file_name}")
+
+    def strip_literals(self, text: str) -> str:
+        return strip_erlang_literals(text, warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}"))
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Erlang content to extract module, records, types, macros, functions and includes."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserErlang(self),
+            FormParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeElixir)
+SandwichPack.register_block_class(ContentCodeErlang)
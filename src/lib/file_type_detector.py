@@ -382,6 +382,46 @@ def bytes_txt(sample: bytes, min_ratio: float = 0.88) -> bool:
     return (ok / n) >= min_ratio
 
 
+def binary_extension(name: str) -> bool:
+    """Расширение имени файла из BL (двоичные форматы)."""
+    return bool(name) and _norm_ext(Path(name)) in bl_exts()
+
+
+def is_binary_data(sample: bytes, name: str = "") -> bool:
+    """Бинарный файл для упаковки: расширение из BL либо нулевой байт в первых 8 КБ."""
+    return binary_extension(name) or b"\x00" in sample[:8192]
+
+
+# Сгенерированные файлы: lock-файлы, суффиксы генераторов, маркер в шапке файла, минифицированные JS/CSS.
+LOCK_FILES = frozenset({
+    "Cargo.lock", "package-lock.json", "npm-shrinkwrap.json", "yarn.lock", "pnpm-lock.yaml", "bun.lock", "poetry.lock",
+    "Pipfile.lock", "uv.lock", "Gemfile.lock", "composer.lock", "go.sum", "flake.lock", "packages.lock.json",
+})
+GENERATED_SUFFIXES = (
+    ".pb.rs", ".pb.go", ".pb.cc", ".pb.h", ".pb.ts", "_pb2.py", "_pb2_grpc.py", "_pb2.pyi", ".g.dart", ".freezed.dart",
+    ".designer.cs", ".g.cs", ".generated.cs", ".min.js", ".min.mjs", ".min.css", ".bundle.js",
+)
+_GENERATED_MARKER_RE = re.compile(r"@generated\b|\bDO NOT EDIT\b|\bauto-?generated\b|\bautomatically generated\b", re.IGNORECASE)
+_MINIFIED_EXTS = frozenset({".js", ".mjs", ".cjs", ".css"})
+
+
+def generated_reason(name: str, text: str) -> str | None:
+    """Почему файл считается сгенерированным: lockfile, name (суффикс генератора), marker (в первых 10 строках),
+    minified (JS/CSS из длинных строк); None — обычный исходник."""
+    base = Path(name or "").name
+    if base in LOCK_FILES:
+        return "lockfile"
+    if base.lower().endswith(GENERATED_SUFFIXES):
+        return "name"
+    if _GENERATED_MARKER_RE.search("\n".join(text.split("\n", 10)[:10])):
+        return "marker"
+    if _norm_ext(Path(base)) in _MINIFIED_EXTS and len(text) > 2000:
+        lines = text.split("\n")
+        if max(len(line) for line in lines) > 1000 and len(text) / len(lines) > 300:
+            return "minified"
+    return None
+
+
 def bhead(path: Path, max_bytes: int = 32768) -> bytes | None:
     """Первые max_bytes файла (binary read)."""
     try:
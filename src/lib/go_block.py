@@ -0,0 +1,238 @@
+# /lib/go_block.py, created 2026-10-14 12:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)"
+TYPE_PARAMS_PATTERN = r"(?:\[[^\]\n]*\])?"
+ARGS_REGEX_PATTERN = r"\s*\((?P<args>[^{;]*?)\)"
+RET_REGEX_PATTERN = r"(?P<return>[^{;\n]*)"
+
+
+def strip_go_literals(text: str, warn=None) -> str:
+    """Blanks Go interpreted, raw and rune literals, preserving line count and positions of code.
+
+    Quotes of blanked literals are kept, newlines inside raw (backtick) strings stay in place.
+    Comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if ch == "`":
+            end = text.find("`", i + 1)
+            closed = end >= 0
+            end = end + 1 if closed else n
+        elif ch in "\"'":
+            j = i + 1
+            while j < n and text[j] not in (ch, "\n"):
+                j += 2 if text[j] == "\\" else 1
+            closed = j < n and text[j] == ch
+            end = j + 1 if closed else min(j, n)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append(ch + "\n" * text.count("\n", i, end) + (ch if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class GoEntityParser(EntityParser):
+    """Base for Go parsers: visibility follows identifier case (exported names are capitalized)."""
+    def detect_visibility(self, match):
+        name = match_value(match, 'name', '')
+        return "public" if name[:1].isupper() else "private"
+
+
+class PackageParser(EntityParser):
+    """Parser for the `package` clause, stored as single-line entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bpackage\b", default_visibility="public")
+
+    def parse(self):
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*package\s+(?P<name>\w+)", line)
+            if not match:
+                continue
+            self.owner.package = match.group('name')
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": line_num,
+                "tokens": estimate_tokens(line),
+                "parent": "",
+            }
+            self.new_entities_lines.append(line_num)
+            return True
+        return False
+
+
+class StructParser(GoEntityParser):
+    """Parser for `type Name struct {...}` declarations."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"type\s+(?P<name>\w+)" + TYPE_PARAMS_PATTERN + r"\s+struct\b", ["indent", "name"], 2)\
+            .add_token(r"\s*{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\btype\b", default_visibility="private")
+
+
+class InterfaceParser(GoEntityParser):
+    """Parser for `type Name interface {...}` declarations and their method sets."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"type\s+(?P<name>\w+)" + TYPE_PARAMS_PATTERN + r"\s+interface\b", ["indent", "name"], 2)\
+            .add_token(r"\s*{", ["head_end"], 1)
+        inner_regex = IterativeRegex()
+        inner_regex\
+            .add_token(r"^(?P<indent>[ \t]+)(?P<name>[A-Za-z_]\w*)", ["indent", "name"], 2)\
+            .add_token(ARGS_REGEX_PATTERN, ["args"], 1)\
+            .add_token(r"[^\n{}]*$", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\btype\b", inner_regex, default_visibility="private")
+
+    def detect_abstract(self, match):
+        return True  # interface methods never have a body
+
+
+class MethodParser(GoEntityParser):
+    """Parser for methods with receivers: `func (r *Type) Name(...) ... {`."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"func\s*\(\s*(?:\w+\s+)?\*?\s*(?P<parent>\w+)" + TYPE_PARAMS_PATTERN + r"\s*\)",
+                       ["indent", "parent"], 2)\
+            .add_token(r"\s*(?P<name>\w+)", ["name"], 2)\
+            .add_token(ARGS_REGEX_PATTERN, ["args"], 1)\
+            .add_token(RET_REGEX_PATTERN, ["return"], 1)\
+            .add_token(r"{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bfunc\b", default_visibility="private")
+
+
+class FunctionParser(GoEntityParser):
+    """Parser for top-level Go functions, including generic ones."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"func\s+(?P<name>\w+)" + TYPE_PARAMS_PATTERN, ["indent", "name"], 2)\
+            .add_token(ARGS_REGEX_PATTERN, ["args"], 1)\
+            .add_token(RET_REGEX_PATTERN, ["return"], 1)\
+            .add_token(r"{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bfunc\b", default_visibility="private")
+
+
+class DepsParserGo(DepsParser):
+    """Parser for Go imports, single and grouped; import paths are read from the original text."""
+    SINGLE_REGEX = re.compile(r"^[ \t]*import\s+(?P<alias>[\w.]+\s+)?\"(?P<module>[^\"]+)\"", re.MULTILINE)
+    GROUP_REGEX = re.compile(r"^[ \t]*import\s*\((?P<items>[^)]*)\)", re.MULTILINE)
+    ITEM_REGEX = re.compile(r"^\s*(?P<alias>[\w.]+\s+)?\"(?P<module>[^\"]+)\"", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def _add_spec(self, match):
+        module = match.group('module')
+        alias = (match.group('alias') or "").strip()
+        self.add_module(module)
+        if alias not in ("_", "."):
+            self.add_import(module, alias or module.split('/')[-1])
+
+    def parse(self):
+        content = re.sub(r"//[^\n]*", "", self.owner.content_text)   # import paths are strings, clean_lines lost them
+        for match in self.SINGLE_REGEX.finditer(content):
+            self._add_spec(match)
+        for group in self.GROUP_REGEX.finditer(content):
+            for match in self.ITEM_REGEX.finditer(group.group('items')):
+                self._add_spec(match)
+        return True
+
+
+class ContentCodeGo(ContentBlock):
+    """Parser for Go content blocks."""
+    supported_types = [".go"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "go"
+        self.entity_map = {}
+        self.package = ""
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeGo with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string, raw string and rune literals with strip_go_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_go_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Go content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserGo(self),
+            PackageParser("package", self),
+            StructParser("structure", self),
+            InterfaceParser("interface", self),
+            MethodParser("method", self),
+            FunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeGo)
@@ -0,0 +1,241 @@
+# /lib/graphql_block.py, created 2026-10-15 02:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+DEF_REGEX = re.compile(r"^[ \t]*(?P<extend>extend[ \t]+)?(?P<kind>type|interface|input|enum|union|scalar|schema|directive|query|mutation|subscription|fragment)\b",
+                       re.MULTILINE)
+HEAD_REGEX = re.compile(r"\s*(?P<name>@?\w+)?\s*(?P<vars>\((?:[^()]|\([^()]*\))*\))?")
+FIELD_REGEX = re.compile(r"(?P<name>\w+)\s*(?P<args>\((?:[^()]|\([^()]*\))*\))?\s*:\s*(?P<type>(?:\[\s*)*\w+(?:\s*[!\]])*)")
+DIRECTIVE_REGEX = re.compile(r"@\w+(?:\s*\((?:[^()]|\([^()]*\))*\))?")
+ROOT_TYPES = {"Query": "query", "Mutation": "mutation", "Subscription": "subscription"}
+OPERATIONS = ("query", "mutation", "subscription")
+
+
+def strip_graphql_literals(text: str, warn=None) -> str:
+    """Blanks strings, block strings (descriptions) and `#` comments with spaces, line breaks and quotes are kept."""
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch != "\"":
+            out.append(ch)
+            i += 1
+            continue
+        if text.startswith("\"\"\"", i):
+            end = text.find("\"\"\"", i + 3)
+            closed = end >= 0
+            end = end + 3 if closed else n
+            quote = "\"\"\""
+        else:
+            end = i + 1
+            while end < n and text[end] not in "\"\n":
+                end += 2 if text[end] == "\\" else 1
+            closed = end < n and text[end] == "\""
+            end = end + 1 if closed else min(end, n)
+            quote = "\""
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        body = re.sub(r"[^\n]", " ", text[i + len(quote):end - (len(quote) if closed else 0)])
+        out.append(quote + body + (quote if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def compact(text: str) -> str:
+    return " ".join(text.split())
+
+
+class DefinitionParser(EntityParser):
+    """Parser for top level definitions: schema types with field signatures, root operation fields and executable operations."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        depth = [0] * (len(content) + 1)
+        level = 0
+        for i, ch in enumerate(content):
+            depth[i] = level
+            level += 1 if ch == "{" else -1 if ch == "}" else 0
+        depth[len(content)] = level
+        starts = [m for m in DEF_REGEX.finditer(content) if depth[m.start('kind')] == 0]
+        root_types = dict(ROOT_TYPES)
+        definitions = []
+        for i, match in enumerate(starts):
+            limit = starts[i + 1].start() if i + 1 < len(starts) else len(content)
+            body_start = content.find("{", match.end(), limit)
+            body_end = -1
+            if body_start >= 0:
+                body_end = next((j for j in range(body_start + 1, limit) if content[j] == "}" and depth[j] == depth[body_start] + 1), -1)
+                if body_end < 0:
+                    self.owner.parse_warn(f"Unclosed block at line {self.owner.find_line(body_start)} in file {self.owner.file_name}")
+            end = body_end + 1 if body_end >= 0 else len(content[:limit].rstrip())
+            definitions.append((match, body_start if body_start >= 0 and body_end >= 0 else -1, end))
+            if match.group('kind') == "schema" and body_start >= 0:
+                for item in re.finditer(r"\b(?P<op>query|mutation|subscription)\s*:\s*(?P<type>\w+)", content[body_start:end]):
+                    root_types = {k: v for k, v in root_types.items() if v != item.group('op')}
+                    root_types[item.group('type')] = item.group('op')
+
+        for match, body_start, end in definitions:
+            kind = match.group('kind')
+            if kind == "schema":
+                continue
+            head_end = body_start if body_start >= 0 else end
+            head = DIRECTIVE_REGEX.sub(lambda m: " " * len(m.group(0)), content[match.end():head_end]) if kind != "directive" else content[match.end():head_end]
+            head_match = HEAD_REGEX.match(head)
+            name = head_match.group('name') if head_match and head_match.group('name') else None
+            first_line = self.owner.find_line(match.start('kind'))
+            last_line = self.owner.find_line(max(end - 1, match.start('kind')))
+            extra = {}
+            if match.group('extend'):
+                extra["extend"] = True
+            if kind in OPERATIONS or kind == "fragment":
+                if head_match and head_match.group('vars'):
+                    extra["variables"] = compact(head_match.group('vars')[1:-1])
+                if on := re.search(r"\bon\s+(?P<type>\w+)", head):
+                    extra["on"] = on.group('type')
+                name = name or f"{kind}:{first_line}"
+            elif not name:
+                continue
+            if implements := re.search(r"\bimplements\b(?P<types>[^=]*)", head):
+                extra["implements"] = [t for t in re.split(r"[\s&,]+", implements.group('types')) if t]
+            if kind == "union" and "=" in head:
+                extra["members"] = [t for t in re.split(r"[\s|]+", head.split("=", 1)[1]) if t]
+            if kind == "directive" and head_match and head_match.group('vars'):
+                extra["args"] = compact(head_match.group('vars')[1:-1])
+            fields = []
+            if body_start >= 0 and kind in ("type", "interface", "input", "enum"):
+                body = DIRECTIVE_REGEX.sub(lambda m: " " * len(m.group(0)), content[body_start + 1:end - 1])
+                if kind == "enum":
+                    extra["values"] = re.findall(r"\b[A-Za-z_]\w*\b", body)
+                else:
+                    fields = [(body_start + 1 + f.start(), body_start + 1 + f.end(), f) for f in FIELD_REGEX.finditer(body)]
+                    extra["fields"] = [self.signature(f) for _, _, f in fields]
+            if self.owner.add_definition(self.entity_type if kind == "type" else kind, name, first_line, last_line, extra):
+                self.new_entities_lines.append(first_line)
+            if kind == "type" and name in root_types:
+                for start, stop, field in fields:
+                    line_num = self.owner.find_line(start)
+                    if self.owner.add_definition(root_types[name], field.group('name'), line_num, self.owner.find_line(stop - 1),
+                                                 {"parent": name, "signature": self.signature(field)}):
+                        self.new_entities_lines.append(line_num)
+        return True
+
+    @staticmethod
+    def signature(field) -> str:
+        """Field signature without descriptions and directives: `user(id: ID!): User`."""
+        args = f"({compact(field.group('args')[1:-1])})" if field.group('args') else ""
+        return f"{field.group('name')}{args}: {compact(field.group('type'))}"
+
+
+class DepsParserGraphQL(DepsParser):
+    """Parser for `#import "./fragments.graphql"` comments (graphql-tag loader, graphql-import); paths are read from the original text."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*#\s*import\s+(?:[\w*,{}\s]+\s+from\s+)?[\"'](?P<module>[^\"'\n]+)[\"']", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.content_text):
+            module = match.group('module')
+            if module.startswith("."):
+                module = os.path.normpath(f"{Path(self.owner.file_name).parent}/{module}").replace("\\", "/")
+            self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class ContentGraphQL(ContentBlock):
+    """Parser for GraphQL schemas and documents (.graphql, .gql)."""
+    supported_types = [".graphql", ".gql"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "graphql"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by strip_graphql_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentGraphQL with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks strings, descriptions and comments with strip_graphql_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_graphql_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses GraphQL content to extract types, inputs, enums, root operation fields and operations."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserGraphQL(self),
+            DefinitionParser("type", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentGraphQL)
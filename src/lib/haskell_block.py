@@ -0,0 +1,348 @@
+# /lib/haskell_block.py, created 2026-10-14 18:05 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+KEYWORDS = ("module", "import", "data", "newtype", "type", "class", "instance", "where", "let", "in", "do", "case", "of",
+            "if", "then", "else", "deriving", "default", "foreign", "infix", "infixl", "infixr", "pattern")
+NOT_KEYWORD = r"(?!(?:" + "|".join(KEYWORDS) + r")\b)"
+IDENT = r"[a-z_][\w']*"
+SYMBOL_CHARS = "!#$%&*+./<=>?@\\^|~:-"
+CHAR_LITERAL = re.compile(r"'(?:\\'|\\[^'\n]+|[^\\'\n])'")
+SCOPE_TYPES = ("class", "instance")
+
+
+def _blank_lines(segment: str) -> str:
+    """Line breaks of blanked segment, with indentation of continued lines kept for the layout rule."""
+    return "".join("\n" + re.match(r"[ \t]*", line).group(0) for line in segment.split("\n")[1:])
+
+
+def strip_haskell_literals(text: str, warn=None) -> str:
+    """Blanks Haskell string and char literals, drops `--` and nested `{- -}` comments (pragmas too), preserving line count.
+
+    Quotes after identifier chars are primes (foldl', x'), `-->` and similar are operators, not comments.
+    String gaps (backslash, newline, backslash) continue the literal on the next line.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("--", i) and (i == 0 or text[i - 1] not in SYMBOL_CHARS):
+            j = i
+            while j < n and text[j] == "-":
+                j += 1
+            if j >= n or text[j] not in SYMBOL_CHARS:
+                end = text.find("\n", i)
+                i = n if end < 0 else end
+                continue
+        if text.startswith("{-", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("{-", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("-}", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            out.append(_blank_lines(text[i:j]))
+            i = j
+            continue
+        if ch == "'" and not (i > 0 and (text[i - 1].isalnum() or text[i - 1] in "_'")) and (match := CHAR_LITERAL.match(text, i)):
+            out.append("''")
+            i = match.end()
+            continue
+        if ch != '"':
+            out.append(ch)
+            i += 1
+            continue
+        j = i + 1
+        while j < n and text[j] != '"':
+            if text[j] == "\\" and j + 1 < n and text[j + 1].isspace():
+                gap_end = text.find("\\", j + 1)   # string gap
+                j = n if gap_end < 0 else gap_end + 1
+            elif text[j] == "\\":
+                j += 2
+            elif text[j] == "\n":
+                break
+            else:
+                j += 1
+        closed = j < n and text[j] == '"'
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + _blank_lines(text[i:j]) + ('"' if closed else ""))
+        i = j + 1 if closed else j
+    return "".join(out)
+
+
+class HaskellEntityParser(EntityParser):
+    """Base for Haskell parsers: top-level names missing in the module export list are private."""
+    def detect_visibility(self, match):
+        exports = self.owner.exports
+        return "public" if exports is None or match.group('name') in exports else "private"
+
+    def inside_entity(self, line_num):
+        return any(e["first_line"] < line_num <= e["last_line"] for e in self.owner.entity_map.values())
+
+
+class TypeParser(HaskellEntityParser):
+    """Parser for `data`, `newtype` and `class` declarations; class context is stored as parent."""
+    def __init__(self, owner, keyword="data"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^{keyword}\s+(?:(?:instance|family)\s+)?(?:(?P<context>\([^)\n]*\)|[^=(\n]*?)\s*=>\s*)?(?P<name>[A-Z][\w']*)",
+                       ["context", "name"], 1)
+        super().__init__(keyword, owner, outer_regex, rf"^{keyword}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and (context := match_value(base_match, 'context')):
+            entity["parent"] = ", ".join(re.findall(r"\b[A-Z][\w.']*", context))
+        return True
+
+
+class InstanceParser(HaskellEntityParser):
+    """Parser for `instance [Context =>] Class Type`, named as "Class Type" by the head type constructor."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^instance\s+(?:(?:\([^)\n]*\)|[^=(\n]*?)\s*=>\s*)?(?P<name>[A-Z][\w.']*)\s+(?P<target>[^\n]*?)\s*(?:\bwhere\b|$)",
+                       ["name", "target"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"^instance\b", default_visibility="public")
+
+    def detect_visibility(self, match):
+        return "public"   # instances are always exported
+
+    def _format_entity_name(self, match):
+        target = match.group('target')
+        head = re.search(r"[A-Z][\w.']*", target)
+        return f"{match.group('name')} {head.group(0) if head else target.strip('()[] ') or target}"
+
+
+class SignatureParser(HaskellEntityParser):
+    """Parser for top-level functions with type signature `name :: Type`, equations below belong to the same entity."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^{NOT_KEYWORD}(?P<name>{IDENT})(?:\s*,\s*{IDENT})*\s*::", ["name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+
+class FunctionParser(HaskellEntityParser):
+    """Parser for top-level functions defined by equations only, without signature."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^{NOT_KEYWORD}(?P<name>{IDENT})(?![\w'])[^\n=|:]*?(?:=(?![=>])|\||\n[ \t]+\|)", ["name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if self.inside_entity(self.owner.find_line(base_match.start())):
+            return False   # next equation of already indexed function
+        return super()._process_match(base_match)
+
+
+class MemberParser(HaskellEntityParser):
+    """Parser for class and instance members; class members without default equation are abstract."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^(?P<indent>[ \t]+){NOT_KEYWORD}(?P<name>{IDENT})(?![\w'])[^\n=|]*?(?:::|=(?![=>])|\|)", ["indent", "name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def detect_visibility(self, match):
+        return "public"
+
+    def _process_match(self, base_match):
+        line_num = self.owner.find_line(base_match.start('name'))
+        scopes = [e for e in self.owner.entity_map.values() if e["type"] in SCOPE_TYPES and e["first_line"] < line_num <= e["last_line"]]
+        if not scopes or len(base_match.group('indent')) != self.owner.body_indent(scopes[0]):
+            return False   # local definitions in where/let clauses
+        if any(e["type"] not in SCOPE_TYPES and e["first_line"] < line_num <= e["last_line"] for e in self.owner.entity_map.values()):
+            return False
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(line_num)
+        if entity:
+            entity["parent"] = scopes[0]["name"]
+            if scopes[0]["type"] == "class" and entity["first_line"] == entity["last_line"] and "::" in self.owner.clean_lines[line_num]:
+                entity["type"] = "abstract method"
+        return True
+
+
+class ModuleParser(EntityParser):
+    """Parser for the `module Name (exports) where` header, stored as entity; export list defines visibility."""
+    HEADER_REGEX = re.compile(r"^module\s+(?P<name>[\w.']+)\s*(?P<exports>\((?:[^()]|\((?:[^()]|\([^()]*\))*\))*\))?\s*where\b", re.MULTILINE)
+
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"^module\b", default_visibility="public")
+
+    def parse(self):
+        match = self.HEADER_REGEX.search(self.owner.get_clean_content())
+        if not match:
+            return False
+        if exports := match.group('exports'):
+            self.owner.exports = set(re.findall(r"[\w']+", re.sub(r"\bmodule\s+[\w.]+", "", exports)))
+        line_num = self.owner.find_line(match.start('name'))
+        last_line = self.owner.find_line(match.end())
+        self.owner.entity_map[line_num] = {
+            "type": self.entity_type,
+            "name": match.group('name'),
+            "visibility": self.default_visibility,
+            "file_id": self.owner.file_id,
+            "first_line": line_num,
+            "last_line": last_line,
+            "tokens": estimate_tokens(match.group(0)),
+            "parent": "",
+        }
+        self.new_entities_lines.append(line_num)
+        return True
+
+
+class DepsParserHaskell(DepsParser):
+    """Parser for Haskell imports: qualified (pre or post), `as` aliases, explicit import lists and `hiding`."""
+    IMPORT_REGEX = re.compile(r"^import\s+(?:\{-# SOURCE #-\}\s*)?(?:safe\s+)?(?:qualified\s+)?(?:\"[^\"]*\"\s+)?(?P<module>[A-Z][\w.']*)"
+                              r"(?:\s+qualified)?(?:\s+as\s+(?P<alias>[A-Z][\w.']*))?(?P<hiding>\s+hiding)?"
+                              r"(?:\s*(?P<names>\((?:[^()]|\([^()]*\))*\)))?", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.get_clean_content()):
+            module = match.group('module')
+            self.add_module(module)
+            if alias := match.group('alias'):
+                self.add_import(module, alias)
+            if match.group('names') and not match.group('hiding'):
+                names = re.sub(r"\([^()]*\)", "", match.group('names')[1:-1])   # Type(..) and operators
+                for name in re.findall(r"(?<![\w'])[A-Za-z_][\w']*", names):
+                    if name not in ("type", "pattern"):
+                        self.add_import(module, name)
+        return True
+
+
+class ContentCodeHaskell(ContentBlock):
+    """Parser for Haskell content blocks (.hs), layout based."""
+    supported_types = [".hs"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "haskell"
+        self.entity_map = {}
+        self.exports = None
+        self.open_sl_comment = []   # comments are dropped by strip_haskell_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodeHaskell with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips string and char literals with comments, using strip_haskell_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_haskell_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def body_indent(self, scope):
+        """Indent of the first member line inside class/instance body."""
+        for line in self.clean_lines[scope["first_line"] + 1:scope["last_line"] + 1]:
+            if isinstance(line, str) and line.strip():
+                return len(line) - len(line.lstrip())
+        return -1
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Layout bounds: deeper indented lines belong to the declaration, as next equations of the same function do."""
+        line = clean_lines[start_line] if 0 < start_line < len(clean_lines) else None
+        if not isinstance(line, str) or not line.strip():
+            return start_line, start_line
+        indent = len(line) - len(line.lstrip())
+        word = re.match(rf"{NOT_KEYWORD}({IDENT})", line.lstrip())
+        last_line = start_line
+        for line_num in range(start_line + 1, len(clean_lines)):
+            line = clean_lines[line_num]
+            if not isinstance(line, str) or not line.strip():
+                continue
+            line_indent = len(line) - len(line.lstrip())
+            if line_indent > indent or (line_indent == indent and word and re.match(rf"{word.group(1)}(?![\w'])(?!\s*::)", line.lstrip())):
+                last_line = line_num
+                continue
+            break
+        return start_line, last_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations are regular in Haskell, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_start), self.clean_lines)
+        lines = self.clean_lines[start_line:end_line + 1]
+        return "\n".join(line for line in lines if isinstance(line, str))
+
+    def check_entity_placement(self, line_num: int, name: str):
+        """Names may contain primes (foldl'), instance names "Class Type" are checked by the class name."""
+        line = self.clean_lines[line_num] if 0 < line_num < len(self.clean_lines) else None
+        if not isinstance(line, str) or not line.strip():
+            logging.warning(f" check_entity_placement failed: outbound {line_num} or void line")
+            return False
+        return bool(re.search(rf"(?<![\w']){re.escape(name.split(' ')[0])}(?![\w'])", line))
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Haskell content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.exports = None
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserHaskell(self),
+            ModuleParser("module", self),
+            TypeParser(self, "data"),
+            TypeParser(self, "newtype"),
+            TypeParser(self, "class"),
+            InstanceParser("instance", self),
+            SignatureParser("function", self),
+            FunctionParser("function", self),
+            MemberParser("method", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeHaskell)
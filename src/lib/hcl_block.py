@@ -0,0 +1,257 @@
+# /lib/hcl_block.py, created 2026-10-15 06:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+BLOCK_REGEX = re.compile(r"^[ \t]*(?P<kind>[A-Za-z_][\w-]*)(?P<labels>(?:[ \t]+(?:\"[^\"\n]*\"|[A-Za-z_][\w-]*))*)[ \t]*{", re.MULTILINE)
+LABEL_REGEX = re.compile(r"\"(?P<quoted>[^\"\n]*)\"|(?P<ident>[A-Za-z_][\w-]*)")
+ATTR_REGEX = re.compile(r"^[ \t]*(?P<key>[A-Za-z_][\w-]*)[ \t]*=(?!=)[ \t]*(?P<value>[^\n]*)", re.MULTILINE)
+HEREDOC_REGEX = re.compile(r"<<-?(?P<marker>[A-Za-z_]\w*)[ \t]*$", re.MULTILINE)
+# block kind: (entity type, stored attributes)
+BLOCK_KINDS = {
+    "resource": ("resource", ("provider", "count", "for_each")),
+    "data": ("data source", ("provider", "count", "for_each")),
+    "module": ("module", ("source", "version")),
+    "variable": ("variable", ("type", "default", "description", "sensitive")),
+    "output": ("output", ("value", "description", "sensitive")),
+    "provider": ("provider", ("alias", "region", "version")),
+}
+RESERVED_KEYS = {"type": "value_type"}   # attribute names clashing with entity fields
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def skip_template(text: str, i: int) -> int:
+    """End offset of quoted template opened at text[i], `${...}` / `%{...}` sequences may contain nested strings."""
+    n = len(text)
+    j = i + 1
+    while j < n and text[j] not in "\"\n":
+        if text[j] == "\\":
+            j += 2
+            continue
+        if text.startswith(("${", "%{"), j):
+            depth = 0
+            while j < n:
+                if text[j] == "\"":
+                    j = skip_template(text, j)
+                    continue
+                depth += {"{": 1, "}": -1}.get(text[j], 0)
+                j += 1
+                if depth == 0:
+                    break
+            continue
+        j += 1
+    return j
+
+
+def strip_hcl_literals(text: str, warn=None) -> str:
+    """Blanks strings (with template interpolations), heredoc bodies and `#`, `//`, `/* */` comments with spaces.
+
+    Positions are preserved, quotes and heredoc openers are kept, so labels and attribute values are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#" or text.startswith("//", i):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append(blank(text[i:end]))
+            i = end
+            continue
+        heredoc = HEREDOC_REGEX.match(text, i) if ch == "<" else None
+        if heredoc:
+            out.append(heredoc.group(0))
+            marker = heredoc.group('marker')
+            closing = re.compile(rf"^[ \t]*{re.escape(marker)}[ \t]*$", re.MULTILINE).search(text, heredoc.end())
+            if not closing and warn:
+                warn(f"Unterminated heredoc {marker} at line {text.count(chr(10), 0, i) + 1}")
+            end = closing.end() if closing else n
+            out.append(blank(text[heredoc.end():end]))
+            i = end
+            continue
+        if ch != "\"":
+            out.append(ch)
+            i += 1
+            continue
+        end = skip_template(text, i)
+        closed = end < n and text[end] == "\""
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append("\"" + blank(text[i + 1:end]) + ("\"" if closed else ""))
+        i = end + 1 if closed else end
+    return "".join(out)
+
+
+def attribute_value(raw: str) -> str:
+    """Attribute value from source line: quoted string unwrapped when it is the whole value, expressions compacted."""
+    value = raw.strip()
+    quoted = re.fullmatch(r"\"((?:[^\"\\]|\\.)*)\"", value)
+    if quoted:
+        return quoted.group(1)
+    return " ".join(value.split())
+
+
+class BlockParser(EntityParser):
+    """Parser for top level blocks: resources and data sources named by type and label, modules, variables, outputs, providers.
+
+    Attributes listed in BLOCK_KINDS are stored as extras, other blocks (terraform, locals) are skipped.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw = self.owner.content_text
+        depth = [0] * (len(content) + 1)
+        level = 0
+        for i, ch in enumerate(content):
+            depth[i] = level
+            level += 1 if ch == "{" else -1 if ch == "}" else 0
+        depth[len(content)] = level
+        for match in BLOCK_REGEX.finditer(content):
+            kind = match.group('kind')
+            if depth[match.start('kind')] != 0 or kind not in BLOCK_KINDS:
+                continue
+            labels = [m.group('quoted') if m.group('quoted') is not None else m.group('ident')
+                      for m in LABEL_REGEX.finditer(raw[match.start('labels'):match.end('labels')])]
+            body_start = match.end() - 1
+            body_end = next((j for j in range(body_start + 1, len(content)) if content[j] == "}" and depth[j] == 1), -1)
+            if body_end < 0:
+                self.owner.parse_warn(f"Unclosed block at line {self.owner.find_line(body_start)} in file {self.owner.file_name}")
+                body_end = len(content) - 1
+            e_type, fields = BLOCK_KINDS[kind]
+            attrs = {}
+            for attr in ATTR_REGEX.finditer(content, body_start + 1, body_end):
+                if depth[attr.start('key')] == 1 and attr.group('key') in fields:
+                    attrs[RESERVED_KEYS.get(attr.group('key'), attr.group('key'))] = attribute_value(raw[attr.start('value'):attr.end('value')])
+            if kind in ("resource", "data"):
+                if len(labels) < 2:
+                    continue
+                name = ".".join(labels[:2]) if kind == "resource" else ".".join(["data"] + labels[:2])
+                extra = {"resource_type": labels[0], **attrs}
+            elif not labels:
+                continue
+            else:
+                name = labels[0] + (f".{attrs['alias']}" if kind == "provider" and attrs.get("alias") else "")
+                extra = attrs
+            first_line = self.owner.find_line(match.start('kind'))
+            if self.owner.add_block(e_type, name, first_line, self.owner.find_line(body_end), extra):
+                self.new_entities_lines.append(first_line)
+                if kind == "module" and attrs.get("source"):
+                    self.owner.module_sources.append(attrs["source"])
+        return True
+
+
+class DepsParserHCL(DepsParser):
+    """Parser for module sources: local paths (`./modules/vpc`) are resolved to directories, registry and remote sources kept as is."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for source in self.owner.module_sources:
+            if source.startswith(("./", "../")):
+                source = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), source)).replace("\\", "/")
+            self.add_module(source)
+        return True
+
+
+class ContentHCL(ContentBlock):
+    """Parser for Terraform / HCL configuration blocks (.tf, .hcl)."""
+    supported_types = [".tf", ".hcl"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "hcl"
+        self.entity_map = {}
+        self.module_sources = []
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentHCL with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips strings, heredocs and comments with strip_hcl_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_hcl_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_block(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses HCL content to extract resources, modules, variables, outputs and providers."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.module_sources = []
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            BlockParser("block", self),
+            DepsParserHCL(self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentHCL)
@@ -0,0 +1,233 @@
+# /lib/html_block.py, created 2026-10-14 21:20 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+from lib.js_block import ContentCodeJs, ContentCodeTypeScript
+from lib.css_block import ContentCodeCSS
+
+
+RAW_TEXT_REGEX = re.compile(r"<(?P<tag>script|style)\b(?P<attrs>[^>]*)>(?P<body>.*?)</(?P=tag)\s*>", re.IGNORECASE | re.DOTALL)
+VOID_TAGS = ("area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr")
+NON_JS_TYPES = ("application/json", "application/ld+json", "importmap", "text/template", "text/x-template", "text/html")
+
+
+def attr_value(attrs: str, name: str, default=None):
+    """Value of attribute `name` in tag attributes text, quoted or bare."""
+    match = re.search(rf"(?:^|\s){name}\s*=\s*(?:\"(?P<dq>[^\"]*)\"|'(?P<sq>[^']*)'|(?P<bare>[^\s>]+))", attrs, re.IGNORECASE)
+    if not match:
+        return default
+    return next(v for v in (match.group('dq'), match.group('sq'), match.group('bare')) if v is not None)
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def is_local_ref(ref: str) -> bool:
+    return bool(ref) and not re.match(r"(?i)^(?:[a-z][\w+.-]*:|//|#|\{\{|\$\{)", ref)
+
+
+class ElementParser(EntityParser):
+    """Parser for elements with `id` attribute, bounded by the matching closing tag; `<template>` gets own entity type."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex.add_token(r"<(?P<tag>[A-Za-z][\w-]*)\b(?P<attrs>[^>]*?)\sid\s*=\s*[\"']?(?P<name>[^\"'\s>]+)", ["tag", "attrs", "name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in self.outer_regex.all_matches(content):
+            tag = match.group('tag').lower()
+            if tag == "style":
+                continue   # indexed with its content by RawTextParser
+            line_num = self.owner.find_line(match.start('name'))
+            last_line = self.owner.element_end(tag, match.start())
+            self.owner.add_element("template" if tag == "template" else self.entity_type, match.group('name'), line_num, last_line, {"tag": tag})
+            self.new_entities_lines.append(line_num)
+        for match in re.finditer(r"<template\b(?P<attrs>[^>]*)>", content, re.IGNORECASE):
+            if attr_value(match.group('attrs'), "id"):
+                continue   # indexed above
+            line_num = self.owner.find_line(match.start())
+            name = attr_value(match.group('attrs'), "name") or "template"
+            self.owner.add_element("template", name, line_num, self.owner.element_end("template", match.start()), {"tag": "template"})
+            self.new_entities_lines.append(line_num)
+        return True
+
+
+class RawTextParser(EntityParser):
+    """Parser for inline `<script>` and `<style>` contents, delegated to the JS/TS and CSS blocks."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        text = self.owner.content_text
+        for match in RAW_TEXT_REGEX.finditer(text):
+            tag = match.group('tag').lower()
+            attrs = match.group('attrs')
+            if not match.group('body').strip() or attr_value(attrs, "src"):
+                continue
+            start = match.start('body')
+            first_line = text.count("\n", 0, start) + 1
+            last_line = text.count("\n", 0, match.end('body')) + 1
+            masked = blank(text[:start]) + match.group('body') + blank(text[match.end('body'):])
+            if tag == "style":
+                tag_line = text.count("\n", 0, match.start()) + 1
+                self.owner.add_element("style", attr_value(attrs, "id") or "style", tag_line, last_line, {"tag": "style"})
+                self.new_entities_lines.append(tag_line)
+                scss = (attr_value(attrs, "lang") or "").lower() == "scss"
+                self.parse_sub_block(ContentCodeCSS, masked, ".scss" if scss else ".css", f"style{first_line}")
+                continue
+            script_type = (attr_value(attrs, "type") or "").lower()
+            if script_type in NON_JS_TYPES:
+                continue
+            typescript = (attr_value(attrs, "lang") or "").lower() in ("ts", "typescript") or script_type == "text/typescript"
+            block_class = ContentCodeTypeScript if typescript else ContentCodeJs
+            self.parse_sub_block(block_class, masked, ".ts" if typescript else ".js", f"script{first_line}")
+        return True
+
+    def parse_sub_block(self, block_class, masked: str, content_type: str, suffix: str):
+        """Parses masked copy of the page with another block class, entities keep page line numbers."""
+        sub_block = block_class(masked, content_type, f"{self.owner.file_name}&{suffix}", self.owner.timestamp,
+                                module_prefix=self.owner.module_prefix)
+        sub_result = sub_block.parse_content(depth=self.owner.depth + 1)
+        for sub_entity in sub_result["entities"]:
+            line_num = sub_entity["first_line"]
+            if line_num in self.owner.entity_map:
+                logging.error(f"Already exists entity {self.owner.entity_map[line_num]}, can't add {sub_entity}")
+                continue
+            self.owner.entity_map[line_num] = sub_entity
+            self.new_entities_lines.append(line_num)
+        self.modules.extend(m for m in sub_result["dependencies"]["modules"] if m not in self.modules)
+        self.imports.update(sub_result["dependencies"]["imports"])
+
+
+class DepsParserHTML(DepsParser):
+    """Parser for local resources: `<script src>`, stylesheets and module preloads `<link href>`."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        refs = [attr_value(m.group('attrs'), "src") for m in re.finditer(r"<script\b(?P<attrs>[^>]*)>", content, re.IGNORECASE)]
+        refs += [attr_value(m.group('attrs'), "href") for m in re.finditer(r"<link\b(?P<attrs>[^>]*)>", content, re.IGNORECASE)
+                 if re.search(r"(?i)\b(?:stylesheet|modulepreload|import)\b", attr_value(m.group('attrs'), "rel", ""))]
+        for ref in refs:
+            if not is_local_ref(ref):
+                continue
+            ref = ref.split("?")[0].split("#")[0]
+            if not ref.startswith("/"):
+                ref = os.path.normpath(f"{Path(self.owner.file_name).parent}/{ref}").replace("\\", "/")
+            self.add_module(ref if ref.startswith("/") else f"/{ref}")
+        return True
+
+
+class ContentCodeHTML(ContentBlock):
+    """Parser for HTML pages and templates (.html, .htm)."""
+    supported_types = [".html", ".htm"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "html"
+        self.entity_map = {}
+        self.depth = 0
+        self.open_sl_comment = []   # comments are blanked by strip_strings
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeHTML with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks `<!-- -->` comments and raw text of script/style elements, attribute values are kept for ids."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = "\n".join(self.clean_lines[1:])
+        clean_text = re.sub(r"<!--.*?(?:-->|$)", lambda m: blank(m.group(0)), clean_text, flags=re.DOTALL)
+        clean_text = RAW_TEXT_REGEX.sub(lambda m: m.group(0)[:m.start('body') - m.start()] + blank(m.group('body')) +
+                                        m.group(0)[m.end('body') - m.start():], clean_text)
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def element_end(self, tag: str, start: int) -> int:
+        """Line of the closing tag matching element opened at offset start, same line for void and self-closed elements."""
+        content = self.get_clean_content()
+        first_line = self.find_line(start)
+        if tag in VOID_TAGS:
+            return first_line
+        depth = 0
+        for match in re.finditer(rf"<(?P<close>/)?{re.escape(tag)}\b[^>]*?(?P<self>/)?>", content[start:], re.IGNORECASE):
+            if match.group('self'):
+                if depth == 0:
+                    return first_line
+                continue
+            depth += -1 if match.group('close') else 1
+            if depth == 0:
+                return self.find_line(start + match.end() - 1)
+        return first_line
+
+    def add_element(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, repeated names get line suffix (template:12)."""
+        if first_line in self.entity_map:
+            return False
+        if any(e["name"] == name and e["type"] == e_type for e in self.entity_map.values()):
+            name = f"{name}:{first_line}"
+        lines = self.clean_lines[first_line:last_line + 1]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(line for line in lines if isinstance(line, str))),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses HTML content to extract element ids, templates, inline scripts and styles."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.depth = depth
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserHTML(self),
+            ElementParser("element", self),
+            RawTextParser("script", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeHTML)
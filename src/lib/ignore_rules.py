@@ -0,0 +1,186 @@
+# /lib/ignore_rules.py, updated 2026-10-16 06:30 EEST
+# Scanner ignore rules: .gitignore / .ignore files, .git/info/exclude and global git excludes, gitignore pattern semantics.
+# Symlinks policy of walker: skip, follow with cycle detection or yield as links.
+
+import os
+import re
+import logging
+import subprocess
+from pathlib import Path
+
+IGNORE_FILES = (".gitignore", ".ignore")   # .ignore rules are applied after .gitignore, like ripgrep does
+SYMLINK_POLICIES = ("skip", "follow", "link")   # link - yielded as is without following, recorded as symlink
+
+
+def glob_regex(pattern: str) -> str:
+    """Translates gitignore glob to regex body: `**` spans directories, `*` and `?` stay within one path segment."""
+    out = []
+    i = 0
+    while i < len(pattern):
+        c = pattern[i]
+        if pattern.startswith("**/", i):
+            out.append("(?:.*/)?")
+            i += 3
+        elif pattern.startswith("/**", i) and i + 3 == len(pattern):
+            out.append("/.*")
+            i += 3
+        elif pattern.startswith("**", i):
+            out.append(".*")
+            i += 2
+        elif c == "*":
+            out.append("[^/]*")
+            i += 1
+        elif c == "?":
+            out.append("[^/]")
+            i += 1
+        elif c == "[" and (close := pattern.find("]", i + 2)) > 0:
+            body = pattern[i + 1:close]
+            out.append("[" + ("^" + body[1:] if body[0] in "!^" else body).replace("\\", "\\\\") + "]")
+            i = close + 1
+        elif c == "\\" and i + 1 < len(pattern):
+            out.append(re.escape(pattern[i + 1]))
+            i += 2
+        else:
+            out.append(re.escape(c))
+            i += 1
+    return "".join(out)
+
+
+def parse_rule(line: str, base: str):
+    """Rule tuple (base dir, regex, negate, dir_only) for gitignore line, None for blanks and comments."""
+    line = line.rstrip("\n").rstrip("\r")
+    while line.endswith(" ") and not line.endswith("\\ "):
+        line = line[:-1]
+    if not line or line.startswith("#"):
+        return None
+    negate = line.startswith("!")
+    if negate:
+        line = line[1:]
+    elif line.startswith("\\#") or line.startswith("\\!"):
+        line = line[1:]
+    dir_only = line.endswith("/")
+    line = line.rstrip("/")
+    if not line:
+        return None
+    anchored = "/" in line
+    body = glob_regex(line.lstrip("/"))
+    return base, re.compile(("^" if anchored else "^(?:.*/)?") + body + "$"), negate, dir_only
+
+
+def path_regex(pattern: str):
+    """Compiled regex for include / exclude glob: matches relative path itself or anything below matched directory."""
+    pattern = pattern.rstrip("/")
+    anchored = "/" in pattern
+    return re.compile(("^" if anchored else "^(?:.*/)?") + glob_regex(pattern.lstrip("/")) + "(?:/.*)?$")
+
+
+class IgnoreRules:
+    """Ordered ignore rules, last matching rule wins. Paths are absolute posix, rule bases are directories of ignore files."""
+
+    def __init__(self, rules=None):
+        self.rules = rules or []
+
+    @staticmethod
+    def read_rules(file_path: Path, base: str) -> list:
+        try:
+            with open(file_path, "r", encoding="utf-8", errors="replace") as f:
+                return [rule for line in f if (rule := parse_rule(line, base)) is not None]
+        except OSError as e:
+            logging.debug(f"Ignore file {file_path} not readable: {e}")
+            return []
+
+    @staticmethod
+    def global_excludes_file():
+        """core.excludesFile from git config, else $XDG_CONFIG_HOME/git/ignore."""
+        try:
+            out = subprocess.run(["git", "config", "--get", "core.excludesFile"], capture_output=True, text=True, timeout=5)
+            if out.returncode == 0 and out.stdout.strip():
+                return Path(os.path.expanduser(out.stdout.strip()))
+        except (OSError, subprocess.TimeoutExpired):
+            pass
+        config_home = os.environ.get("XDG_CONFIG_HOME") or os.path.join(os.path.expanduser("~"), ".config")
+        return Path(config_home) / "git" / "ignore"
+
+    @classmethod
+    def for_root(cls, root_dir, global_excludes=True) -> "IgnoreRules":
+        """Rules effective at scan root: global excludes, .git/info/exclude and ignore files from repository root down."""
+        root = Path(root_dir).absolute()
+        repo = next((d for d in (root, *root.parents) if (d / ".git").exists()), None)
+        top = repo or root
+        rules = []
+        if global_excludes and (excludes := cls.global_excludes_file()).is_file():
+            rules += cls.read_rules(excludes, top.as_posix())
+        if repo and (repo / ".git" / "info" / "exclude").is_file():
+            rules += cls.read_rules(repo / ".git" / "info" / "exclude", top.as_posix())
+        if repo:
+            for directory in reversed(root.parents[:len(root.parents) - len(repo.parents)]):
+                rules += cls.directory_rules(directory)
+        return cls(rules)
+
+    @classmethod
+    def directory_rules(cls, directory: Path) -> list:
+        rules = []
+        for name in IGNORE_FILES:
+            if (directory / name).is_file():
+                rules += cls.read_rules(directory / name, directory.as_posix())
+        return rules
+
+    def extended(self, directory: Path) -> "IgnoreRules":
+        rules = self.directory_rules(directory)
+        return IgnoreRules(self.rules + rules) if rules else self
+
+    def ignored(self, path: Path, is_dir: bool) -> bool:
+        posix = path.as_posix()
+        result = False
+        for base, regex, negate, dir_only in self.rules:
+            if dir_only and not is_dir:
+                continue
+            if not posix.startswith(base.rstrip("/") + "/"):
+                continue
+            if regex.match(posix[len(base.rstrip("/")) + 1:]):
+                result = not negate
+        return result
+
+
+def walk_files(root_dir, use_ignore=True, global_excludes=True, symlinks="skip"):
+    """Yields files under root (as root_dir / relative path) in stable sorted order, skipping ignored files
+    and not descending into ignored directories and .git. Symlinks are skipped, followed or yielded by policy,
+    followed directories are scanned once, so looping symlink can't hang walker."""
+    if symlinks not in SYMLINK_POLICIES:
+        raise ValueError(f"Unsupported symlink policy `{symlinks}`, expected one of {', '.join(SYMLINK_POLICIES)}")
+    root = Path(root_dir).absolute()
+    rules = IgnoreRules.for_root(root, global_excludes).extended(root) if use_ignore else None
+    visited = {os.path.realpath(root)}
+    stack = [(root, rules)]
+    while stack:
+        directory, rules = stack.pop()
+        try:
+            entries = sorted(directory.iterdir(), key=lambda p: p.name)
+        except OSError as e:
+            logging.warning(f"Directory {directory} not readable: {e}")
+            continue
+        subdirs = []
+        for entry in entries:
+            link = entry.is_symlink()
+            is_dir = entry.is_dir() and (not link or symlinks == "follow")
+            if entry.name == ".git" or (rules is not None and rules.ignored(entry, is_dir)):
+                logging.debug(f"Ignored by rules: {entry}")
+                continue
+            if link and symlinks == "skip":
+                logging.debug(f"Skipped symlink: {entry}")
+                continue
+            if link and symlinks == "link":
+                yield Path(root_dir) / entry.relative_to(root)
+            elif is_dir:
+                real = os.path.realpath(entry)
+                if link and real in visited:
+                    logging.warning(f"Symlink {entry} leads to already scanned directory {real}, skipped to break cycle")
+                    continue
+                visited.add(real)
+                subdirs.append(entry)
+            elif entry.is_file():
+                yield Path(root_dir) / entry.relative_to(root)
+            elif link:
+                logging.warning(f"Broken symlink {entry} skipped")
+        for sub in reversed(subdirs):
+            stack.append((sub, rules.extended(sub) if rules is not None else None))
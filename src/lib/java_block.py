@@ -0,0 +1,253 @@
+# /lib/java_block.py, created 2026-10-14 13:40 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+ANNOTATIONS_PATTERN = r"(?P<annotations>(?:@(?!interface\b)[\w.]+(?:\([^)\n]*\))?[ \t]+)*)"   # same line only
+MODIFIERS_PATTERN = r"(?P<mods>(?:(?:public|protected|private|static|final|abstract|sealed|non-sealed|strictfp|default|synchronized|native)\s+)*)"
+STATEMENT_KEYWORDS = r"(?!(?:if|for|while|switch|catch|try|do|else|return|new|throw|synchronized)\b)"
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)" + ANNOTATIONS_PATTERN + MODIFIERS_PATTERN
+ARGS_REGEX_PATTERN = r"\s*\((?P<args>[^;{]*?)\)(?:\s*throws\s+[\w.,\s]+?)?"
+# return type, or lookahead for constructor with body: calls like `foo(x);` must not pass as declarations
+RET_REGEX_PATTERN = r"(?:<[^;{()]*>\s+)?(?:(?P<return>(?!(?:return|new|throw|else|case|yield)\b)[\w.$]+(?:<[^;{()]*>)?(?:\s*\[\])*[ \t]+)" \
+                    r"|(?=[\w$]+" + ARGS_REGEX_PATTERN.replace("?P<args>", "") + r"\s*{))"
+KIND_TYPES = {"class": "class", "interface": "interface", "enum": "enum", "record": "record", "@interface": "annotation"}
+
+
+def strip_java_literals(text: str, warn=None, raw_triple=False) -> str:
+    """Blanks string, text block (\"\"\"...\"\"\") and char literals, preserving line count and positions of code.
+
+    Quotes of blanked literals are kept, text blocks collapse to `""` with their newlines in place.
+    With raw_triple (Kotlin) backslash is not an escape inside triple-quoted strings.
+    Comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if text.startswith('"""', i):
+            j = i + 3
+            while j < n and not text.startswith('"""', j):
+                j += 2 if text[j] == "\\" and not raw_triple else 1
+            closed = j < n
+            end = j + 3 if closed else n
+            while closed and end < n and text[end] == '"':   # """a "quoted"""" ends on the last quotes
+                end += 1
+        elif ch in "\"'":
+            j = i + 1
+            while j < n and text[j] not in (ch, "\n"):
+                j += 2 if text[j] == "\\" else 1
+            closed = j < n and text[j] == ch
+            end = j + 1 if closed else min(j, n)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        quote = '"' if ch == '"' else ch
+        out.append(quote + "\n" * text.count("\n", i, end) + (quote if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def collect_annotations(clean_lines: list, line_num: int, head: str = "") -> list:
+    """Returns annotation names placed on lines above line_num (with multi-line arguments) and in head."""
+    block = [head]
+    i = line_num - 1
+    depth = 0
+    while i > 0 and isinstance(clean_lines[i], str):
+        line = clean_lines[i].strip()
+        depth += line.count(')') - line.count('(')
+        if not line or (depth <= 0 and not line.startswith('@')) or line.endswith((';', '{', '}')) and depth <= 0:
+            break
+        block.insert(0, line)
+        i -= 1
+    return re.findall(r"@(?!interface\b)([A-Za-z_][\w.]*)", " ".join(block))
+
+
+class JavaEntityParser(EntityParser):
+    """Base for Java parsers: visibility from modifiers, annotations stored as extra field."""
+    def detect_visibility(self, match):
+        mods = match_value(match, 'mods', '')
+        if 'private' in mods:
+            return "private"
+        if 'public' in mods or 'protected' in mods:
+            return "public"
+        return self.default_visibility
+
+    def make_entity(self, e_type, name, vis, first_line, full_text, extra_fields=None):
+        entity = super().make_entity(e_type, name, vis, first_line, full_text, extra_fields)
+        line = self.owner.clean_lines[first_line]
+        head = line[:line.find(name.split('.')[-1])] if isinstance(line, str) else ""
+        annotations = collect_annotations(self.owner.clean_lines, first_line, head)
+        if annotations:
+            entity["annotations"] = annotations
+        return entity
+
+
+class TypeParser(JavaEntityParser):
+    """Parser for class-like declarations (class, interface, enum, record, @interface) and their methods."""
+    def __init__(self, owner, keyword="class"):
+        self.keyword = keyword
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + re.escape(keyword) + r"\s+(?P<name>\w+)", ["indent", "annotations", "mods", "name"], 1)\
+            .add_token(r"(?:\s*<[^{]*?>)?(?:\s*\([^)]*\))?(?:\s+extends\s+(?P<parent>[^{]+?))?"
+                       r"(?:\s+implements\s+(?P<implements>[^{]+?))?(?:\s+permits\s+[^{]+?)?", ["parent", "implements"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)
+        inner_regex = IterativeRegex()
+        inner_regex\
+            .add_token(BASE_REGEX_PATTERN + RET_REGEX_PATTERN + STATEMENT_KEYWORDS + r"(?P<name>[A-Za-z_$][\w$]*)(?=\s*\()",
+                       ["indent", "annotations", "mods", "return", "name"], 1)\
+            .add_token(ARGS_REGEX_PATTERN, ["args"], 1)\
+            .add_token(r"\s*(?P<ending>[{;])", ["ending"], 4)
+        super().__init__(KIND_TYPES[keyword], owner, outer_regex, rf"(?<![\w@]){re.escape(keyword)}\b", inner_regex,
+                         default_visibility="public" if "interface" in keyword else "private")   # interface members are public
+
+    def detect_abstract(self, match):
+        return match_value(match, 'ending', '') == ';'
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if not entity:
+            return True
+        validation = self.outer_regex.validate_match(self.content, base_match.start())
+        if implements := match_value(validation['match'], 'implements'):
+            entity["implements"] = re.sub(r"\s+", " ", implements.strip())
+        if entity.get("parent"):
+            entity["parent"] = re.sub(r"\s+", " ", entity["parent"])
+        return True
+
+
+class PackageParser(EntityParser):
+    """Parser for the `package` declaration, stored as single-line entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bpackage\b", default_visibility="public")
+
+    def parse(self):
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*package\s+(?P<name>[\w.]+)\s*;", line) if isinstance(line, str) else None
+            if not match:
+                continue
+            self.owner.package = match.group('name')
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": line_num,
+                "tokens": estimate_tokens(line),
+                "parent": "",
+            }
+            self.new_entities_lines.append(line_num)
+            return True
+        return False
+
+
+class DepsParserJava(DepsParser):
+    """Parser for Java imports, including static and on-demand (`.*`) forms."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*import\s+(?P<static>static\s+)?(?P<path>[\w.]+?)(?P<wildcard>\.\*)?\s*;", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.get_clean_content()):
+            path = match.group('path')
+            if match.group('wildcard'):
+                self.add_module(path)
+                continue
+            module, _, name = path.rpartition('.')
+            self.add_module(module)
+            self.add_import(module, name)
+        return True
+
+
+class ContentCodeJava(ContentBlock):
+    """Parser for Java content blocks."""
+    supported_types = [".java"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "java"
+        self.entity_map = {}
+        self.package = ""
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeJava with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string, text block and char literals with strip_java_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_java_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Java content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserJava(self),
+            PackageParser("package", self),
+            TypeParser(self, "@interface"),
+            TypeParser(self, "interface"),
+            TypeParser(self, "enum"),
+            TypeParser(self, "record"),
+            TypeParser(self, "class")
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeJava)
@@ -1,4 +1,4 @@
-# /lib/js_block.py, updated 2025-08-08 18:45 EEST
+# /lib/js_block.py, updated 2026-10-16 16:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
@@ -22,16 +22,154 @@ FN_VARIANTS = [r"(?:function\s+(?P<name>\w+)\s*\((?P<args>[^\)]*)\)",
 FN_REGEX_PATTERN = r"(?:const\s+(?P<name>\w+)\s*=\s*)?(?P<async>async\s+)?(?:function\s*(?P<name2>\w+)?)?\("
 CLASS_REGEX_PATTERN = r"class\s+(?P<name>\w+)"
 INTERFACE_REGEX_PATTERN = r"interface\s+(?P<name>\w+)"
-OBJECT_REGEX_PATTERN = r"const\s+(?P<name>\w+)\s*="
+OBJECT_REGEX_PATTERN = r"(?:(?:export\s+)?const\s+(?P<name>\w+)\s*=|export\s+default\b)"
+TYPE_REGEX_PATTERN = r"(?:declare\s+)?type\s+(?P<name>\w+)"
+ENUM_REGEX_PATTERN = r"(?:declare\s+)?(?:const\s+)?enum\s+(?P<name>\w+)"
+TYPE_LINE_REGEX = re.compile(r"^[ \t]*(?:export\s+)?(?:declare\s+)?type\s+\w+")
 METHODS_REGEX_PATTERN = r"(?:methods|computed|watch)\s*:"
 # Negative lookahead to avoid matching JS/TS keywords as method names
 TS_KEYWORD_EXCLUSION = r'(?!(?:if|for|while|switch|return|throw|catch|else|try|do|typeof|instanceof|void|await|delete|of|in)\b)'
+# Tokens after which `/` starts a regex literal instead of a division
+REGEX_PREFIX_CHARS = "(,=:[!&|?{};+-*%<>~^"
+REGEX_PREFIX_WORDS = {"return", "typeof", "instanceof", "case", "do", "else", "in", "of", "new", "delete", "void", "throw", "yield", "await"}
+# String literals directly after these keep their text: module specifiers for DepsParserJs
+SPECIFIER_PREFIX_REGEX = re.compile(r"(?:\bfrom|\bimport|\brequire\s*\(|\bimport\s*\()\s*$")
+
+
+def _skip_quoted(text: str, i: int) -> tuple:
+    """Skips string literal text[i], returning (end, closed); unclosed literal ends at the line end."""
+    quote = text[i]
+    j = i + 1
+    while j < len(text):
+        if text[j] == "\\":
+            j += 2
+            continue
+        if text[j] == quote:
+            return j + 1, True
+        if text[j] == "\n":
+            break
+        j += 1
+    return min(j, len(text)), False
+
+
+def _skip_template(text: str, i: int) -> tuple:
+    """Skips template literal text[i] honoring ${...} nesting, returning (end, closed)."""
+    j = i + 1
+    while j < len(text):
+        ch = text[j]
+        if ch == "\\":
+            j += 2
+            continue
+        if ch == "`":
+            return j + 1, True
+        if text.startswith("${", j):
+            depth = 1
+            j += 2
+            while j < len(text) and depth > 0:
+                ch = text[j]
+                if ch in "\"'":
+                    j = _skip_quoted(text, j)[0]
+                    continue
+                if ch == "`":
+                    j = _skip_template(text, j)[0]
+                    continue
+                depth += 1 if ch == "{" else -1 if ch == "}" else 0
+                j += 1
+            continue
+        j += 1
+    return len(text), False
+
+
+def _skip_regex(text: str, i: int) -> int:
+    """Returns index after the flags of regex literal text[i], or -1 when it is not a regex."""
+    j = i + 1
+    in_class = False
+    while j < len(text):
+        ch = text[j]
+        if ch == "\n":
+            return -1
+        if ch == "\\":
+            j += 2
+            continue
+        if ch == "[":
+            in_class = True
+        elif ch == "]":
+            in_class = False
+        elif ch == "/" and not in_class:
+            return j + 1
+        j += 1
+    return -1
+
+
+def strip_js_literals(text: str, warn=None) -> str:
+    """Blanks JS/TS string, template and regex literals, preserving line count and positions of code.
+
+    Quotes of blanked literals are kept, newlines inside template literals stay in place, regex
+    literals become `/_/` with their flags. Module specifiers (import/export from, require, import())
+    keep their text. Comment bodies are dropped, the markers stay for the comment stripper.
+
+    Args:
+        text (str): Source text.
+        warn (callable, optional): Receives a message for each unclosed literal.
+
+    Returns:
+        str: Cleaned text.
+    """
+    out = []
+    tail = ""   # recent significant code, for specifier and regex context detection
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if ch == "/":
+            prev = tail.rstrip()
+            word = re.search(r"[A-Za-z_$][\w$]*$", prev)
+            allowed = not prev or prev[-1] in REGEX_PREFIX_CHARS or (word is not None and word.group(0) in REGEX_PREFIX_WORDS)
+            end = _skip_regex(text, i) if allowed else -1
+            if end > 0:
+                flags = re.match(r"[a-z]*", text[end:]).group(0)
+                out.append("/_/" + flags)
+                tail = (tail + "_")[-40:]
+                i = end + len(flags)
+                continue
+        elif ch in "\"'`":
+            end, closed = _skip_template(text, i) if ch == "`" else _skip_quoted(text, i)
+            literal = text[i:end]
+            if not closed and warn:
+                kind = "template" if ch == "`" else "string"
+                warn(f"Incomplete {kind} literal at line {text.count(chr(10), 0, i) + 1}")
+            if ch != "`" and closed and SPECIFIER_PREFIX_REGEX.search(tail):
+                out.append(literal)
+            else:
+                out.append(ch + "\n" * literal.count("\n") + (ch if closed else ""))
+            tail = (tail + ch + ch)[-40:]
+            i = end
+            continue
+        out.append(ch)
+        if not ch.isspace():
+            tail = (tail + ch)[-40:]
+        elif not tail.endswith(" "):
+            tail = (tail + " ")[-40:]
+        i += 1
+    return "".join(out)
+
 
 class ObjectParser(EntityParser):
     """Parser for JavaScript object declarations."""
     def __init__(self, entity_type, owner):
         outer_regex = IterativeRegex()
-        outer_regex.add_token(BASE_REGEX_PATTERN + OBJECT_REGEX_PATTERN, ["indent", "name"], 2)
+        outer_regex.add_token(INDENT_PART + OBJECT_REGEX_PATTERN, ["indent", "name"], 2)
         outer_regex.add_token(r"\s*{", ["head_end"], 1)
         super().__init__(entity_type, owner, outer_regex, r"\bconst\b|\bexport\b", default_visibility="public")
 
@@ -45,7 +183,7 @@ class ObjectParser(EntityParser):
             start_pos = base_match.start()
             start_line = self.owner.find_line(start_pos)
             validation = self.outer_regex.validate_match(content, start_pos)
-            if validation['hit_rate'] < 0.5:
+            if validation['hit_rate'] < 1.0:   # plain `const x = value` is not an object
                 logging.debug(f"Skipping low hit_rate {validation['hit_rate']} for match at {start_line}")
                 continue
             match = validation['match']
@@ -117,12 +255,12 @@ class FunctionParser(EntityParser):
         outer_regex.add_token(
             BASE_REGEX_PATTERN + FN_REGEX_PATTERN, ["indent", "async", "name", "name2"], 2
         ).add_token(
-            r"(?P<args>[^\)]*)\)(?:\s*=>)?\s*{", ["args"], 1
+            r"(?P<args>[^\)]*)\)(?:\s*:\s*[^={;]+?)?(?:\s*=>)?\s*{", ["args"], 1
         ).add_token(r"\s*{", ["head_end"], 1)
         super().__init__(entity_type, owner, outer_regex, r"\bfunction\b|\bconst\b", default_visibility="public")
 
     def _format_entity_name(self, match):
-        return match.group('name') or match.group('name2') or match.group('name3')
+        return match_value(match, 'name') or match_value(match, 'name2') or match_value(match, 'name3')
 
     def parse(self):
         content = self.owner.get_clean_content()
@@ -137,6 +275,8 @@ class FunctionParser(EntityParser):
             if not match:
                 continue
             name = self._format_entity_name(match)
+            if not name:   # anonymous call like `(function () {...})()`
+                continue
             full_text = self.owner.extract_entity_text(match.start(), match.end())
             extra_fields = {"parent": ""}
             self.make_add_entity(self.entity_type, self.owner.module_prefix + name, self.default_visibility, start_line, full_text, extra_fields)
@@ -179,31 +319,89 @@ class ClassParser(EntityParser):
         super().__init__(entity_type, owner, outer_regex, r"\bclass\b", inner_regex, default_visibility="public")
 
 
+class TypeAliasParser(EntityParser):
+    """Parser for TypeScript type aliases; bounds detected by ContentCodeJs.detect_bounds as statement."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex.add_token(BASE_REGEX_PATTERN + TYPE_REGEX_PATTERN, ["indent", "name"], 2)
+        outer_regex.add_token(r"\s*(?:<[^=]*>)?\s*=", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\btype\b", default_visibility="public")
+
+
+class EnumParser(EntityParser):
+    """Parser for TypeScript enums, including const and declare forms."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex.add_token(BASE_REGEX_PATTERN + ENUM_REGEX_PATTERN, ["indent", "name"], 2)
+        outer_regex.add_token(r"\s*{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\benum\b", default_visibility="public")
+
+
+class ExportParser(EntityParser):
+    """Marks exported entities with `export` = named/default, using declarations and export lists."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        by_name = {}
+        for line_num, entity in self.owner.entity_map.items():
+            line = self.owner.clean_lines[line_num]
+            if "method" not in entity["type"] and isinstance(line, str):   # class parent is the base class
+                by_name.setdefault(entity["name"], entity)
+                head = re.match(r"^[ \t]*export\s+(default\b)?", line)
+                if head:
+                    entity["export"] = "default" if head.group(1) else "named"
+        for match in re.finditer(r"^[ \t]*export\s+{(?P<items>[^}]*)}(?!\s*from\b)", content, re.MULTILINE):
+            for item in match.group('items').split(','):
+                name = item.strip().split()[0] if item.strip() else ""
+                if name in by_name:
+                    by_name[name]["export"] = "named"
+        for match in re.finditer(r"^[ \t]*export\s+default\s+(?P<name>[\w$]+)\s*;?\s*$", content, re.MULTILINE):
+            if match.group('name') in by_name:
+                by_name[match.group('name')]["export"] = "default"
+        return True
+
+
 class DepsParserJs(DepsParser):
-    """Parser for JavaScript/TypeScript imports."""
+    """Parser for JavaScript/TypeScript imports: ES import/export-from, require() and dynamic import()."""
+    IMPORT_REGEX = re.compile(
+        r"^[ \t]*import\s+(?:type\s+)?(?P<clause>[\w$*{}\s,]+?)\s+from\s+['\"](?P<module>[^'\"]+)['\"]", re.MULTILINE)
+    EXPORT_FROM_REGEX = re.compile(
+        r"^[ \t]*export\s+(?:type\s+)?(?P<clause>\*(?:\s+as\s+[\w$]+)?|{[^}]*})\s*from\s+['\"](?P<module>[^'\"]+)['\"]", re.MULTILINE)
+    SIDE_EFFECT_REGEX = re.compile(r"^[ \t]*import\s+['\"](?P<module>[^'\"]+)['\"]", re.MULTILINE)
+    REQUIRE_REGEX = re.compile(
+        r"(?:\b(?:const|let|var)\s+(?P<clause>[\w$]+|{[^}]*})\s*=\s*)?\b(?:require|import)\s*\(\s*['\"](?P<module>[^'\"]+)['\"]\s*\)")
+
     def __init__(self, owner):
-        outer_regex = IterativeRegex()
-        outer_regex.add_token(r"^(?P<indent>[ \t]*)import\s+{?(?P<items>[\w,\s]+)}?\s+from\s+['\"](?P<module>[^'\"]+)['\"]", ["indent", "items", "module"], 2)
-        inner_regex = IterativeRegex()
-        inner_regex.add_token(r"(?P<name>\w+)\s*(?:,|$)", ["name"], 1)
-        super().__init__(owner, outer_regex)
-        self.inner_regex = inner_regex
-
-    def _process_match(self, match):
-        module = match.group('module')
-        if module:
-            self.add_module(module)
-        items = match.group('items')
-        if items:
-            content = items.strip()
-            for inner_match in self.inner_regex.all_matches(content):
-                validation = self.inner_regex.validate_match(content, inner_match.start())
-                if validation['hit_rate'] < 0.5:
-                    continue
-                inner_match = validation['match']
-                if inner_match:
-                    item = inner_match.group('name')
-                    self.add_import(module, item)
+        super().__init__(owner, None)
+
+    def _add_clause(self, module: str, clause: str):
+        """Adds imported names from `Default, { a, b as c, type T }` like clause; namespaces are skipped."""
+        clause = clause or ""
+        named = re.search(r"{(?P<items>[^}]*)}", clause)
+        rest = clause[:named.start()] + clause[named.end():] if named else clause
+        for item in rest.split(','):
+            item = item.strip()
+            if re.fullmatch(r"[\w$]+", item):
+                self.add_import(module, item)   # default import, local name
+        if named:
+            for item in named.group('items').split(','):
+                words = item.split()
+                if words and words[0] == "type":
+                    words = words[1:]
+                if words and re.fullmatch(r"[\w$]+", words[0]):
+                    self.add_import(module, words[0])
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for regex in (self.IMPORT_REGEX, self.EXPORT_FROM_REGEX, self.SIDE_EFFECT_REGEX, self.REQUIRE_REGEX):
+            for match in regex.finditer(content):
+                module = match.group('module')
+                self.add_module(module)
+                if 'clause' in regex.groupindex:
+                    self._add_clause(module, match.group('clause'))
+        return True
 
 
 class ContentCodeJs(ContentBlock):
@@ -219,6 +417,36 @@ class ContentCodeJs(ContentBlock):
         self.module_prefix = kwargs.get("module_prefix", "")
         logging.debug(f"Initialized ContentCodeJs with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
 
+    def strip_strings(self):
+        """Strips string, template and regex literals with strip_js_literals, keeping module specifiers."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_js_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Type aliases end with their statement, other entities use brace counting."""
+        line = clean_lines[start_line] if 0 < start_line < len(clean_lines) else None
+        if not isinstance(line, str) or not TYPE_LINE_REGEX.match(line):
+            return super().detect_bounds(start_line, clean_lines)
+        depth = 0
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num].rstrip() if isinstance(clean_lines[line_num], str) else ""
+            depth += sum(line.count(c) for c in "{[(") - sum(line.count(c) for c in "}])")
+            if depth > 0 or not line:
+                continue
+            following = next((ln.strip() for ln in clean_lines[line_num + 1:] if isinstance(ln, str) and ln.strip()), "")
+            if line.endswith(';') or not (line.endswith(('=', '|', '&', ',')) or following.startswith(('|', '&'))):
+                return start_line, line_num
+        return start_line, start_line
+
     def parse_content(self, clean_lines=None, depth=0):
         """Parses JavaScript content to extract entities and dependencies."""
         logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
@@ -235,7 +463,8 @@ class ContentCodeJs(ContentBlock):
             FunctionParser("function", self),
             ObjectParser("object", self),
             MethodParser("method", self),
-            DepsParserJs(self)
+            DepsParserJs(self),
+            ExportParser("export", self)
         ]
 
         original_clean_lines = self.clean_lines.copy()
@@ -284,7 +513,10 @@ class ContentCodeTypeScript(ContentCodeJs):
             MethodParser("method", self),
             InterfaceParser("interface", self),
             ClassParser("class", self),
-            DepsParserJs(self)
+            TypeAliasParser("type", self),
+            EnumParser("enum", self),
+            DepsParserJs(self),
+            ExportParser("export", self)
         ]
 
         original_clean_lines = self.clean_lines.copy()
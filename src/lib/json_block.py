@@ -0,0 +1,281 @@
+# /lib/json_block.py, created 2026-10-15 01:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import json
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+NPM_DEPENDENCIES = {"dependencies": "dependency", "devDependencies": "dev dependency",
+                    "peerDependencies": "peer dependency", "optionalDependencies": "optional dependency"}
+COMPOSER_DEPENDENCIES = {"require": "dependency", "require-dev": "dev dependency"}
+
+
+def strip_json_literals(text: str, warn=None):
+    """Blanks `//` and `/* */` comments (JSONC), returns (clean, relaxed): clean has string contents blanked too,
+       relaxed keeps strings and has trailing commas blanked, so it can be loaded by json module. Positions match source."""
+    clean = []
+    relaxed = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "\"":
+            end = i + 1
+            while end < n and text[end] not in "\"\n":
+                end += 2 if text[end] == "\\" else 1
+            if end >= n or text[end] != "\"":
+                if warn:
+                    warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+                end = min(end, n) - 1
+            clean.append("\"" + " " * (end - i - 1) + "\"")
+            relaxed.append(text[i:end + 1])
+            i = end + 1
+            continue
+        if text.startswith("//", i) or text.startswith("/*", i):
+            if text[i + 1] == "/":
+                end = text.find("\n", i)
+                end = n if end < 0 else end
+            else:
+                end = text.find("*/", i + 2)
+                end = n if end < 0 else end + 2
+            comment = re.sub(r"[^\n]", " ", text[i:end])
+            clean.append(comment)
+            relaxed.append(comment)
+            i = end
+            continue
+        clean.append(ch)
+        relaxed.append(ch)
+        i += 1
+    clean = "".join(clean)
+    relaxed = list("".join(relaxed))
+    for match in re.finditer(r",(?=\s*[}\]])", clean):
+        relaxed[match.start()] = " "
+    return clean, "".join(relaxed)
+
+
+def scan_members(clean: str, relaxed: str, max_depth: int = 3) -> list:
+    """Object members up to max_depth as dicts: path (list of keys), key offset, value span; arrays are not entered."""
+    members = []
+    stack = []
+    i = 0
+    n = len(clean)
+    while i < n:
+        ch = clean[i]
+        top = stack[-1] if stack else None
+        if ch == "\"":
+            end = clean.find("\"", i + 1)
+            end = n - 1 if end < 0 else end
+            if top and top["type"] == "{" and top["expect_key"]:
+                try:
+                    key = json.loads(relaxed[i:end + 1])
+                except ValueError:
+                    key = relaxed[i + 1:end]
+                member = {"path": (top["path"] or []) + [key], "start": i, "value_start": None, "end": None}
+                if top["path"] is not None and len(member["path"]) <= max_depth:
+                    members.append(member)
+                top["member"] = member
+                top["expect_key"] = False
+            i = end + 1
+            continue
+        if ch == ":" and top and top["member"] and top["member"]["value_start"] is None:
+            top["member"]["value_start"] = i + 1
+        elif ch in "{[":
+            path = top["member"]["path"] if top and top["type"] == "{" and top["member"] else (None if top else [])
+            stack.append({"type": ch, "path": path if ch == "{" else None, "expect_key": ch == "{", "member": None})
+        elif ch in "}]" and stack:
+            closing = stack.pop()
+            if closing["member"] and closing["member"]["end"] is None:
+                closing["member"]["end"] = i
+        elif ch == "," and top:
+            if top["member"] and top["member"]["end"] is None:
+                top["member"]["end"] = i
+            top["expect_key"] = top["type"] == "{"
+            top["member"] = None if top["type"] == "{" else top["member"]
+        i += 1
+    for member in members:
+        end = member["end"] if member["end"] is not None else n
+        value_text = relaxed[member["value_start"]:end] if member["value_start"] is not None else ""
+        member["end"] = member["start"] + len(clean[member["start"]:end].rstrip())
+        try:
+            member["value"] = json.loads(value_text)
+        except ValueError:
+            member["value"] = None
+    return members
+
+
+class KeyParser(EntityParser):
+    """Parser for top level keys of root object, members described by manifest entities are skipped."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        for member in self.owner.members:
+            if len(member["path"]) == 1 and self.owner.add_member(self.entity_type, member["path"][0], member, {}):
+                self.new_entities_lines.append(self.owner.find_line(member["start"]))
+        return True
+
+
+class ManifestParser(EntityParser):
+    """Parser for known manifests: package.json and composer.json (package, scripts, dependencies, autoload namespaces), tsconfig.json options."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        file_name = Path(self.owner.file_name or "").name
+        if file_name in ("package.json", "composer.json"):
+            root = {m["path"][0]: m["value"] for m in self.owner.members if len(m["path"]) == 1}
+            if isinstance(root.get("name"), str) and self.owner.members:
+                line_num = self.owner.find_line(self.owner.root_start)
+                extra = {k: root[k] for k in ("version", "type", "main", "description") if isinstance(root.get(k), str)}
+                if self.owner.add_item(self.entity_type, root["name"], line_num, self.owner.find_line(len(self.owner.content_text)), extra):
+                    self.new_entities_lines.append(line_num)
+            kinds = NPM_DEPENDENCIES if file_name == "package.json" else COMPOSER_DEPENDENCIES
+            for member in self.owner.members:
+                path, value = member["path"], member["value"]
+                if len(path) != 2:
+                    if file_name == "composer.json" and path[:2] == ["autoload", "psr-4"] and len(path) == 3:
+                        self.add(member, "namespace", path[2].rstrip("\\"), {"path": value})
+                    continue
+                if path[0] in kinds:
+                    self.add(member, kinds[path[0]], path[1], {"version": value} if isinstance(value, str) else {})
+                elif path[0] == "scripts":
+                    self.add(member, "script", path[1], {"command": value} if isinstance(value, (str, list)) else {})
+        elif re.match(r"^[tj]sconfig(?:\.[\w.-]+)?\.json$", file_name):
+            for member in self.owner.members:
+                path = member["path"]
+                if len(path) == 2 and path[0] == "compilerOptions":
+                    self.add(member, "compiler option", path[1], {"value": member["value"]} if not isinstance(member["value"], dict) else {})
+                elif len(path) == 3 and path[:2] == ["compilerOptions", "paths"]:
+                    self.add(member, "path alias", path[2], {"targets": member["value"]})
+        else:
+            return False
+        return True
+
+    def add(self, member, e_type, name, extra):
+        extra["parent"] = member["path"][-2]
+        if self.owner.add_member(e_type, name, member, extra):
+            self.new_entities_lines.append(self.owner.find_line(member["start"]))
+
+
+class DepsParserJSON(DepsParser):
+    """Parser for local references: tsconfig `extends` and project `references`, npm `file:` dependencies and workspaces."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        file_name = Path(self.owner.file_name or "").name
+        refs = []
+        for member in self.owner.members:
+            path, value = member["path"], member["value"]
+            if path == ["extends"] and isinstance(value, str) and value.startswith("."):
+                refs.append(value if value.endswith(".json") else f"{value}.json")
+            elif path == ["references"] and isinstance(value, list):
+                refs += [r["path"] if r["path"].endswith(".json") else f"{r['path']}/tsconfig.json"
+                         for r in value if isinstance(r, dict) and isinstance(r.get("path"), str)]
+            elif file_name == "package.json" and len(path) == 2 and path[0] in NPM_DEPENDENCIES and \
+                    isinstance(value, str) and value.startswith("file:"):
+                refs.append(f"{value[5:]}/package.json")
+            elif file_name == "package.json" and path == ["workspaces"] and isinstance(value, list):
+                refs += [f"{w}/package.json" for w in value if isinstance(w, str) and not re.search(r"[*?\[]", w)]
+        for ref in refs:
+            module = os.path.normpath(f"{Path(self.owner.file_name).parent}/{ref}").replace("\\", "/")
+            self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class ContentJSON(ContentBlock):
+    """Parser for JSON and JSONC files (.json): top level keys, package.json / composer.json / tsconfig.json entities."""
+    supported_types = [".json"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "json"
+        self.entity_map = {}
+        self.members = []
+        self.root_start = 0
+        self.relaxed_text = ""
+        self.open_sl_comment = []   # comments are blanked by strip_json_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentJSON with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks strings and JSONC comments with strip_json_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text, self.relaxed_text = strip_json_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_member(self, e_type: str, name: str, member: dict, extra_fields: dict):
+        return self.add_item(e_type, name, self.find_line(member["start"]), self.find_line(max(member["end"] - 1, member["start"])), extra_fields)
+
+    def add_item(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses JSON content to extract top level keys and manifest entities."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+        clean_text = self.get_clean_content()
+        self.root_start = max(clean_text.find("{"), 0)
+        self.members = scan_members(clean_text, self.relaxed_text) if self.relaxed_text else []
+
+        parsers = [
+            ManifestParser("package", self),
+            DepsParserJSON(self),
+            KeyParser("key", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentJSON)
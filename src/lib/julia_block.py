@@ -0,0 +1,361 @@
+# /lib/julia_block.py, created 2026-10-15 07:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+NAME_PATTERN = r"(?:[A-Za-z_][\w!]*\.)*(?::?[A-Za-z_][\w!]*|:?[^\s\w(){}\[\],;\"'`#@$]+)"
+# block keywords closed by `end`; inside brackets `end` / `begin` are indices and blocks are balanced, so they are skipped
+KEYWORD_REGEX = re.compile(r"(?<![\w.:@!])(?P<kw>function|macro|baremodule|module|mutable[ \t]+struct|struct|abstract[ \t]+type|primitive[ \t]+type"
+                           r"|begin|let|quote|if|for|while|try|do|end)\b(?!!)")
+DEFINITION_REGEX = re.compile(r"(?P<kw>function|macro|baremodule|module|mutable[ \t]+struct|struct|abstract[ \t]+type|primitive[ \t]+type)"
+                              r"[ \t]+(?P<name>" + NAME_PATTERN + r")(?:\{(?P<params>[^}\n]*)\})?(?:[ \t]*<:[ \t]*(?P<parent>[\w.]+(?:\{[^}\n]*\})?))?")
+SHORT_FUNCTION_REGEX = re.compile(r"^[ \t]*(?P<name>" + NAME_PATTERN + r")(?:\{[^}\n]*\})?\((?P<args>[^()]*(?:\([^()]*\)[^()]*)*)\)"
+                                  r"(?:[ \t]*::[ \t]*[^=\n]+?)?(?:[ \t]+where[ \t]+[^=\n]+?)?[ \t]*=(?![=>])", re.MULTILINE)
+DOCSTRING_REGEX = re.compile(r"^[ \t]*(?:@doc[ \t]+)?(?:raw)?(?P<quote>\"\"\"|\")", re.MULTILINE)
+USING_REGEX = re.compile(r"^[ \t]*(?P<kind>using|import)[ \t]+(?P<body>[^\n]+)", re.MULTILINE)
+INCLUDE_REGEX = re.compile(r"\binclude\s*\(\s*\"(?P<path>[^\"\n$]+)\"\s*\)")
+KINDS = {"function": "function", "macro": "macro", "module": "module", "baremodule": "module", "struct": "structure",
+         "mutable struct": "structure", "abstract type": "abstract type", "primitive type": "primitive type"}
+SCOPES = ("module", "baremodule")
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def skip_julia_string(text: str, i: int, quote: str):
+    """End offset after string / command literal opened by quote at text[i] and closed flag, `$(...)` may contain nested strings."""
+    n = len(text)
+    j = i + len(quote)
+    while j < n:
+        if text[j] == "\\":
+            j += 2
+            continue
+        if text.startswith(quote, j):
+            return j + len(quote), True
+        if len(quote) == 1 and text[j] == "\n" and quote == "'":
+            break
+        if text.startswith("$(", j):
+            depth = 0
+            while j < n:
+                if text[j] in "\"`":
+                    inner = text[j] * 3 if text.startswith(text[j] * 3, j) else text[j]
+                    j, _ = skip_julia_string(text, j, inner)
+                    continue
+                depth += {"(": 1, ")": -1}.get(text[j], 0)
+                j += 1
+                if depth == 0:
+                    break
+            continue
+        j += 1
+    return n, False
+
+
+def strip_julia_literals(text: str, warn=None) -> str:
+    """Blanks Julia strings (triple-quoted, prefixed like raw"..."), commands, character literals and `#`, `#= =#` comments with spaces.
+
+    Block comments nest, `'` after an operand is the transpose operator. Delimiters are kept and positions are preserved,
+    so docstrings are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("#=", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("#=", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("=#", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            if depth and warn:
+                warn(f"Unterminated block comment at line {text.count(chr(10), 0, i) + 1}")
+            out.append(blank(text[i:j]))
+            i = j
+            continue
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch == "'":
+            prev = text[i - 1] if i > 0 else "\n"
+            if prev.isalnum() or prev in "_)]}'.!":
+                out.append(ch)   # transpose operator
+                i += 1
+                continue
+            literal = re.match(r"'(?:\\[^'\n]{1,9}|[^\\'\n]{1,2})'", text[i:i + 12])
+            if not literal:
+                out.append(ch)
+                i += 1
+                continue
+            out.append("'" + " " * (literal.end() - 2) + "'")
+            i += literal.end()
+            continue
+        if ch not in "\"`":
+            out.append(ch)
+            i += 1
+            continue
+        quote = ch * 3 if text.startswith(ch * 3, i) else ch
+        end, closed = skip_julia_string(text, i, quote)
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        body_end = end - len(quote) if closed else end
+        out.append(quote + blank(text[i + len(quote):body_end]) + (quote if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class DefinitionParser(EntityParser):
+    """Parser for Julia definitions: modules scope functions, macros, structs and abstract / primitive types.
+
+    Blocks are matched by keyword ... `end` pairs at bracket level, one-line `f(x) = ...` functions end with their statement.
+    Functions inside structs are inner constructors, nested functions (closures) are skipped. Docstring directly preceding
+    definition is stored as doc (first line of text), names starting with `_` are private by convention.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw = self.owner.content_text
+        brackets = [0] * (len(content) + 1)
+        level = 0
+        for i, ch in enumerate(content):
+            brackets[i] = level
+            level = max(level + (1 if ch in "([{" else -1 if ch in ")]}" else 0), 0)
+        docs = self.find_docstrings(content, raw)
+        events = [(m.start(), "keyword", m) for m in KEYWORD_REGEX.finditer(content) if brackets[m.start()] == 0]
+        events += [(m.start('name'), "short", m) for m in SHORT_FUNCTION_REGEX.finditer(content) if brackets[m.start('name')] == 0]
+        events.sort(key=lambda e: (e[0], e[1] != "short"))
+        stack = []
+        for pos, event, match in events:
+            if event == "short":
+                if not any(f["kind"] in ("function", "macro") for f in stack):
+                    first_line = self.owner.find_line(pos)
+                    self.add(self.open_frame("function", match.group('name'), None, stack, first_line, docs),
+                             self.owner.statement_end(first_line))
+                continue
+            kw = " ".join(match.group('kw').split())
+            if kw == "end":
+                if stack:
+                    frame = stack.pop()
+                    if frame.get("entity"):
+                        self.add(frame, self.owner.find_line(pos))
+                else:
+                    self.owner.parse_warn(f"Unexpected end at line {self.owner.find_line(pos)} in file {self.owner.file_name}")
+                continue
+            definition = DEFINITION_REGEX.match(content, pos) if kw in KINDS else None
+            if not definition or any(f["kind"] in ("function", "macro") for f in stack):
+                stack.append({"kind": kw})
+                continue
+            frame = self.open_frame(kw, definition.group('name'), definition, stack, self.owner.find_line(pos), docs)
+            stack.append(frame)
+        for frame in stack:
+            if frame.get("entity"):
+                self.owner.parse_warn(f"Unclosed {frame['kind']} at line {frame['line']} in file {self.owner.file_name}")
+        return True
+
+    def open_frame(self, kw, name, definition, stack, line, docs):
+        scopes = [f for f in stack if f.get("entity") and f["kind"] in SCOPES]
+        structs = [f for f in stack if f.get("entity") and f["kind"] in ("struct", "mutable struct")]
+        parent = scopes[-1]["name"] if scopes else ""
+        if kw in SCOPES and parent:
+            name = f"{parent}.{name}"   # nested module is named by the enclosing one
+        frame = {"kind": kw, "name": name, "line": line, "entity": True, "type": KINDS[kw], "extra": {"parent": parent}}
+        if kw == "function" and structs:
+            frame["type"] = "constructor"
+            frame["extra"]["parent"] = structs[-1]["name"]
+        if definition and definition.group('parent'):
+            frame["extra"]["supertype"] = definition.group('parent')
+        if kw == "mutable struct":
+            frame["extra"]["mutable"] = True
+        if line in docs:
+            frame["extra"]["doc"] = docs[line]
+        return frame
+
+    def find_docstrings(self, content, raw):
+        """Maps definition line to docstring text: string literal at line start followed by the definition on the next line."""
+        docs = {}
+        for match in DOCSTRING_REGEX.finditer(content):
+            quote = match.group('quote')
+            closer = content.find(quote, match.end())
+            if closer < 0:
+                continue
+            follow = re.match(r"[ \t]*\n\s*(?=\S)", content[closer + len(quote):])
+            if not follow:
+                continue
+            body = raw[match.end():closer]
+            docs[self.owner.find_line(closer + len(quote) + follow.end())] = next((line.strip() for line in body.split("\n") if line.strip()), "")
+        return docs
+
+    def add(self, frame, last_line):
+        last_line = max(last_line, frame["line"])
+        extra = dict(frame["extra"])
+        if frame["kind"] in ("struct", "mutable struct"):
+            extra["fields"] = self.struct_fields(frame["line"], last_line)
+        visibility = "private" if frame["name"].split(".")[-1].startswith("_") else "public"
+        if self.owner.add_definition(frame["type"], frame["name"], frame["line"], last_line, extra, visibility):
+            self.new_entities_lines.append(frame["line"])
+
+    def struct_fields(self, first_line, last_line):
+        """Field names declared directly in struct body, inner constructors are skipped."""
+        fields = []
+        inner = [(e["first_line"], e["last_line"]) for e in self.owner.entity_map.values() if first_line < e["first_line"] <= last_line]
+        for line_num in range(first_line + 1, last_line):
+            line = self.owner.clean_lines[line_num] if isinstance(self.owner.clean_lines[line_num], str) else ""
+            if any(first <= line_num <= last for first, last in inner):
+                continue
+            field = re.match(r"^\s*(?:const\s+)?(?P<name>[A-Za-z_]\w*)\s*(?:::|=(?!=)|$)", line)
+            if field and field.group('name') not in ("end", "new"):
+                fields.append(field.group('name'))
+        return fields
+
+
+class DepsParserJulia(DepsParser):
+    """Parser for `using` / `import` statements (`A.B: x, y as z` names stored as imports) and `include("file.jl")`."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in USING_REGEX.finditer(content):
+            body = match.group('body')
+            line_num = self.owner.find_line(match.start('kind'))
+            while body.rstrip().endswith(",") and line_num + 1 < len(self.owner.clean_lines):
+                line_num += 1   # list continued on the next line
+                body += " " + self.owner.clean_lines[line_num]
+            module, _, names = body.partition(":")
+            if names:
+                module = module.strip()
+                self.add_module(module)
+                for item in names.split(","):
+                    words = item.split()
+                    if words:
+                        self.add_import(module, words[-1])   # `x as y` imports y
+                continue
+            for item in body.split(","):
+                words = item.split()
+                if not words:
+                    continue
+                self.add_module(words[0])
+                if len(words) == 3 and words[1] == "as":
+                    self.add_import(words[0], words[2])
+        for match in INCLUDE_REGEX.finditer(self.owner.content_text):
+            if content[match.start():match.start() + 7] != "include":
+                continue   # inside comment or string
+            path = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), match.group('path'))).replace("\\", "/")
+            self.add_module(path)
+        return True
+
+
+class ContentCodeJulia(ContentBlock):
+    """Parser for Julia content blocks (.jl)."""
+    supported_types = [".jl"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "julia"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeJulia with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips strings, commands, character literals and comments with strip_julia_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_julia_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def statement_end(self, start_line: int) -> int:
+        """Last line of statement started at start_line: brackets and keyword blocks are balanced, line does not end with operator or comma."""
+        depth = 0
+        for line_num in range(start_line, len(self.clean_lines)):
+            line = self.clean_lines[line_num] if isinstance(self.clean_lines[line_num], str) else ""
+            depth += sum(line.count(c) for c in "([{") - sum(line.count(c) for c in ")]}")
+            for match in KEYWORD_REGEX.finditer(line):
+                depth += -1 if match.group('kw') == "end" else 1
+            if depth <= 0 and not re.search(r"(?:,|[=+\-*/|&]|->|::)\s*$", line):
+                return line_num
+        return start_line
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": self.module_prefix + name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Julia content to extract modules, functions, macros, structs and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserJulia(self),
+            DefinitionParser("definition", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeJulia)
@@ -0,0 +1,243 @@
+# /lib/kotlin_block.py, created 2026-10-14 14:10 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+from lib.java_block import strip_java_literals
+
+
+MODIFIERS_PATTERN = r"(?P<mods>(?:(?:public|private|protected|internal|open|abstract|sealed|data|enum|annotation|inner|value|inline|final|override|expect|actual|suspend|operator|infix|tailrec|external|const|lateinit)\s+)*)"
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?:@[\w.]+(?:\([^)\n]*\))?[ \t]+)*" + MODIFIERS_PATTERN
+PARENS_PATTERN = r"\((?:[^()]|\([^()]*\))*\)"
+CLASS_KINDS = {"class": "class", "interface": "interface", "object": "object"}
+
+
+class KotlinEntityParser(EntityParser):
+    """Base for Kotlin parsers: public unless declared private/protected."""
+    def detect_visibility(self, match):
+        return "private" if 'private' in match_value(match, 'mods', '') else "public"
+
+
+class ClassParser(KotlinEntityParser):
+    """Parser for class, interface and object declarations, with or without body."""
+    def __init__(self, entity_type, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + rf"(?:fun\s+)?{keyword}\s+(?P<name>\w+)", ["indent", "mods", "name"], 2)\
+            .add_token(r"(?:\s*<[^{=]*?>)?(?:\s*(?:(?:public|private|protected|internal)\s+)?(?:@\w+\s*)*constructor)?"
+                       r"(?:\s*" + PARENS_PATTERN + r")?", ["args"], 1)\
+            .add_token(r"(?:\s*:\s*(?P<parent>[^{\n]+?))?(?:\s+where\s+[^{\n]+?)?\s*(?:{|$)", ["parent"], 1)
+        super().__init__(entity_type, owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and entity.get("parent"):
+            parents = [re.sub(r"\(.*", "", p).strip() for p in re.split(r",(?![^<(]*[>)])", entity["parent"])]
+            entity["parent"] = ", ".join(p for p in parents if p)
+        return True
+
+
+class CompanionParser(KotlinEntityParser):
+    """Parser for `companion object [Name]`, named Companion unless specified."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"companion\s+object\b(?:\s+(?P<name>\w+))?", ["indent", "mods", "name"], 2)\
+            .add_token(r"(?:\s*:\s*(?P<parent>[^{\n]+?))?\s*{", ["parent"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bcompanion\s+object\b", default_visibility="public")
+
+    def _format_entity_name(self, match):
+        return self.owner.module_prefix + (match.group('name') or "Companion")
+
+
+class FunctionParser(KotlinEntityParser):
+    """Parser for functions, including extension functions `fun Receiver.name()` and expression bodies."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"fun\s+(?:<[^>(]*>\s*)?(?:(?P<receiver>[\w.]+(?:<[^>(]*>)?\??)\.)?(?P<name>\w+)",
+                       ["indent", "mods", "receiver", "name"], 2)\
+            .add_token(r"\s*" + PARENS_PATTERN, ["args"], 1)\
+            .add_token(r"(?:\s*:\s*[^{=\n]+?)?(?:\s+where\s+[^{=\n]+?)?\s*(?:{|=)", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bfun\b", default_visibility="public")
+
+    def _format_entity_name(self, match):
+        receiver = match_value(match, 'receiver')
+        name = match.group('name')
+        return self.owner.module_prefix + (f"{receiver}.{name}" if receiver else name)
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and match_value(base_match, 'receiver'):
+            entity["receiver"] = base_match.group('receiver')
+        if entity and entity.get("hit_rate", 1.0) < 1.0:   # no body: interface or abstract member
+            entity["type"] = "abstract " + entity["type"]
+        if entity and 'suspend' in match_value(base_match, 'mods', ''):
+            entity["type"] = "suspend " + entity["type"]
+        return True
+
+
+class PackageParser(EntityParser):
+    """Parser for the `package` header, stored as single-line entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bpackage\b", default_visibility="public")
+
+    def parse(self):
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*package\s+(?P<name>[\w.]+)", line) if isinstance(line, str) else None
+            if not match:
+                continue
+            self.owner.package = match.group('name')
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": line_num,
+                "tokens": estimate_tokens(line),
+                "parent": "",
+            }
+            self.new_entities_lines.append(line_num)
+            return True
+        return False
+
+
+class DepsParserKotlin(DepsParser):
+    """Parser for Kotlin imports, including `.*` and `as` aliases."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*import\s+(?P<path>[\w.`]+?)(?P<wildcard>\.\*)?(?:\s+as\s+(?P<alias>\w+))?\s*;?\s*$", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.get_clean_content()):
+            path = match.group('path').replace('`', '')
+            if match.group('wildcard'):
+                self.add_module(path)
+                continue
+            module, _, name = path.rpartition('.')
+            self.add_module(module)
+            self.add_import(module, match.group('alias') or name)
+        return True
+
+
+class ContentCodeKotlin(ContentBlock):
+    """Parser for Kotlin content blocks (.kt, .kts)."""
+    supported_types = [".kt", ".kts"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "kotlin"
+        self.entity_map = {}
+        self.package = ""
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeKotlin with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string and raw string (\"\"\"...\"\"\") literals, backslash is literal inside raw strings."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_java_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}"),
+            raw_triple=True
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Declarations without body (data classes, expression functions) end with their statement."""
+        depth = 0
+        for line_num in range(start_line, min(len(clean_lines), start_line + 8)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for ch in line:
+                if ch == '{' and depth == 0:
+                    return super().detect_bounds(start_line, clean_lines)
+                depth += {'(': 1, ')': -1}.get(ch, 0)
+            stripped = line.rstrip()
+            if depth > 0 or stripped.endswith(('=', ',', ':', '(')):
+                continue
+            following = next((ln.strip() for ln in clean_lines[line_num + 1:] if isinstance(ln, str) and ln.strip()), "")
+            if following.startswith(('{', ':', '.', '?', 'where')):
+                continue
+            return start_line, line_num
+        return super().detect_bounds(start_line, clean_lines)
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations are regular in Kotlin, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def check_entity_placement(self, line_num: int, name: str):
+        """Unnamed companion object gets implicit name Companion, absent in the source line."""
+        line = self.clean_lines[line_num] if 0 < line_num < len(self.clean_lines) else None
+        if name.endswith("Companion") and isinstance(line, str) and re.search(r"\bcompanion\s+object\b", line):
+            return True
+        return super().check_entity_placement(line_num, name)
+
+    def apply_scopes(self):
+        """Sets parent of functions and companions to the enclosing class/interface/object chain (functions become methods)."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in CLASS_KINDS.values() or e["type"] == "companion object"]
+        for line_num, entity in self.entity_map.items():
+            if "function" not in entity["type"] and entity["type"] != "companion object":
+                continue   # for classes parent holds supertypes
+            owners = [s for s in scopes if s is not entity and s["first_line"] < line_num <= s["last_line"]]
+            if owners:
+                entity["parent"] = ".".join(s["name"] for s in owners)
+                entity["type"] = entity["type"].replace("function", "method")
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Kotlin content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserKotlin(self),
+            PackageParser("package", self),
+            CompanionParser("companion object", self),
+            ClassParser("interface", self, "interface"),
+            ClassParser("object", self, "object"),
+            ClassParser("class", self, "class"),
+            FunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeKotlin)
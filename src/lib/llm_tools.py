@@ -1,13 +1,53 @@
-
+# /lib/llm_tools.py, updated 2026-10-16 01:00 EEST
 import logging
+import os
 import re
 import math
 
+TOKENIZERS = ("estimate", "tiktoken", "huggingface")
+_token_counter = None   # callable(text) -> int, None - words and spaces estimation
+_tokenizer_name = "estimate"
+
+
+def set_tokenizer(name="estimate", model=None):
+    """Selects token counter used by estimate_tokens: `estimate` heuristic, `tiktoken` BPE encoding (model - encoding name,
+    cl100k_base by default), `huggingface` tokenizer (model - hub name or tokenizer.json path) or callable(text) -> int."""
+    global _token_counter, _tokenizer_name
+    if callable(name):
+        _token_counter, _tokenizer_name = name, getattr(name, "__name__", "custom")
+        return
+    if name not in TOKENIZERS:
+        raise ValueError(f"Unknown tokenizer `{name}`, expected one of {', '.join(TOKENIZERS)}")
+    if name == "estimate":
+        _token_counter, _tokenizer_name = None, name
+        return
+    try:
+        if name == "tiktoken":
+            import tiktoken
+            encoding = tiktoken.get_encoding(model or "cl100k_base")
+            counter = lambda text: len(encoding.encode(text, disallowed_special=()))
+        else:
+            from tokenizers import Tokenizer
+            if not model:
+                raise ValueError("Tokenizer `huggingface` requires model name or tokenizer.json path")
+            tokenizer = Tokenizer.from_file(model) if os.path.isfile(model) else Tokenizer.from_pretrained(model)
+            counter = lambda text: len(tokenizer.encode(text, add_special_tokens=False).ids)
+    except ImportError as e:
+        raise ValueError(f"Tokenizer `{name}` is not available, install package `{name if name == 'tiktoken' else 'tokenizers'}`: {e}")
+    _token_counter, _tokenizer_name = counter, f"{name}:{model}" if model else name
+    logging.info(f"Using tokenizer {_tokenizer_name}")
+
+
+def tokenizer_name() -> str:
+    return _tokenizer_name
+
 
 def estimate_tokens(content):
-    """Estimates tokens by counting words and spaces more accurately."""
+    """Estimates tokens by counting words and spaces more accurately, or counts them by tokenizer selected with set_tokenizer."""
     if not content:
         return 0
+    if _token_counter is not None:
+        return _token_counter(content)
     tokens = 0
     words = re.findall(r'\S+', content)
     for word in words:
@@ -0,0 +1,195 @@
+# /lib/lua_block.py, created 2026-10-14 18:45 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+LONG_BRACKET = re.compile(r"\[(?P<level>=*)\[")
+OPENER_REGEX = re.compile(r"\b(?:function|do|if|repeat)\b")
+CLOSER_REGEX = re.compile(r"\b(?:end|until)\b")
+
+
+def _long_bracket_end(text: str, i: int):
+    """For long bracket `[==[` at i returns position after matching `]==]` (or len(text)) and closed flag."""
+    match = LONG_BRACKET.match(text, i)
+    close = "]" + match.group('level') + "]"
+    end = text.find(close, match.end())
+    return (len(text), False) if end < 0 else (end + len(close), True)
+
+
+def strip_lua_literals(text: str, warn=None) -> str:
+    """Blanks Lua quoted and long bracket (`[[...]]`, `[==[...]==]`) strings, drops `--` and `--[[...]]` comments, preserving line count."""
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("--", i):
+            if LONG_BRACKET.match(text, i + 2):
+                end, _ = _long_bracket_end(text, i + 2)
+                out.append("\n" * text.count("\n", i, end))
+                i = end
+            else:
+                end = text.find("\n", i)
+                i = n if end < 0 else end
+            continue
+        if ch == "[" and LONG_BRACKET.match(text, i):
+            end, closed = _long_bracket_end(text, i)
+        elif ch in "\"'":
+            end = i + 1
+            while end < n and text[end] not in (ch, "\n"):
+                end += 2 if text[end] == "\\" else 1   # backslash-newline continues the string
+            closed = end < n and text[end] == ch
+            end = end + 1 if closed else end
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class FunctionParser(EntityParser):
+    """Parser for `function a.b.c()`, `function Obj:method()` and `local function name()` statements."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)(?P<local>local\s+)?function\s+(?:(?P<receiver>[\w.]+):)?(?P<name>[\w.]+)",
+                       ["indent", "local", "receiver", "name"], 2)\
+            .add_token(r"\s*\((?P<args>[^)]*)\)", ["args"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")   # keep keywords for detect_bounds
+
+    def detect_visibility(self, match):
+        return "private" if match_value(match, 'local') else "public"
+
+    def _process_match(self, base_match):
+        line_num = self.owner.find_line(base_match.start('name'))
+        if any(e["first_line"] < line_num <= e["last_line"] for e in self.owner.entity_map.values()):
+            return False   # nested functions belong to the enclosing one
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(line_num)
+        if entity and match_value(base_match, 'receiver'):
+            entity["type"] = "method"
+            entity["parent"] = base_match.group('receiver')
+        elif entity and match_value(base_match, 'local'):
+            entity["type"] = "local " + entity["type"]
+        return True
+
+
+class AssignedFunctionParser(FunctionParser):
+    """Parser for functions assigned to table fields or variables: `M.foo = function(...)`, `local bar = function(...)`."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner)
+        self.outer_regex = IterativeRegex()
+        self.outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)(?P<local>local\s+)?(?P<name>[\w.]+)\s*=\s*function\b", ["indent", "local", "name"], 2)\
+            .add_token(r"\s*\((?P<args>[^)]*)\)", ["args"], 1)
+
+
+class DepsParserLua(DepsParser):
+    """Parser for `require("mod")` / `require 'mod'` calls, the local variable receiving module is stored as import."""
+    REQUIRE_REGEX = re.compile(r"(?:\blocal\s+(?P<alias>\w+)\s*=\s*)?\brequire\s*\(?\s*([\"'])(?P<module>[\w./-]+)\2")
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.REQUIRE_REGEX.finditer(self.owner.content_text):
+            module = match.group('module')
+            self.add_module(module)
+            if alias := match.group('alias'):
+                self.add_import(module, alias)
+        return True
+
+
+class ContentCodeLua(ContentBlock):
+    """Parser for Lua content blocks (.lua)."""
+    supported_types = [".lua"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "lua"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are dropped by strip_lua_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodeLua with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips quoted and long bracket strings with comments, using strip_lua_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_lua_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Detects bounds by counting block openers (function, do, if, repeat) against `end`/`until`."""
+        depth = 0
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num]
+            if not isinstance(line, str):
+                continue
+            depth += len(OPENER_REGEX.findall(line)) - len(CLOSER_REGEX.findall(line))
+            if depth <= 0:
+                return start_line, line_num
+        self.parse_warn(f"Incomplete entity at line {start_line} in file {self.file_name}, depth={depth}")
+        return start_line, start_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """One-line functions are regular in Lua, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_start), self.clean_lines)
+        lines = self.clean_lines[start_line:end_line + 1]
+        return "\n".join(line for line in lines if isinstance(line, str))
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Lua content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserLua(self),
+            FunctionParser("function", self),
+            AssignedFunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeLua)
@@ -0,0 +1,361 @@
+# /lib/make_block.py, created 2026-10-15 03:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+RULE_REGEX = re.compile(r"^(?P<targets>[^\s:#=][^:#=]*?)[ \t]*(?P<sep>::?|&:)(?![=])[ \t]*(?P<prereqs>[^;\n]*)(?:;.*)?$")
+MAKE_INCLUDE_REGEX = re.compile(r"^[ \t]*-?(?:s?include)[ \t]+(?P<files>[^\n]+)$", re.MULTILINE)
+CMAKE_COMMAND_REGEX = re.compile(r"^[ \t]*(?P<command>[A-Za-z_]\w*)[ \t]*\(", re.MULTILINE)
+CMAKE_ARG_REGEX = re.compile(r"\"(?:[^\"\\]|\\.)*\"|[^\s()\"]+")
+CMAKE_TARGETS = {"add_executable": "executable", "add_library": "library", "add_custom_target": "custom target"}
+CMAKE_LIBRARY_KINDS = ("STATIC", "SHARED", "MODULE", "OBJECT", "INTERFACE", "UNKNOWN")
+CMAKE_KEYWORDS = ("EXCLUDE_FROM_ALL", "WIN32", "MACOSX_BUNDLE", "IMPORTED", "GLOBAL", "ALIAS", "ALL") + CMAKE_LIBRARY_KINDS
+CMAKE_LINK_SCOPES = ("PUBLIC", "PRIVATE", "INTERFACE", "LINK_PUBLIC", "LINK_PRIVATE", "LINK_INTERFACE_LIBRARIES", "debug", "optimized", "general")
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def strip_cmake_literals(text: str, warn=None):
+    """Blanks `#` and `#[[ ]]` comments, returns (clean, decommented): clean has quoted argument contents blanked too.
+
+    Both are length-preserving, so arguments are read from decommented text by offsets of clean one.
+    """
+    clean = []
+    decommented = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            bracket = re.match(r"#\[(=*)\[", text[i:i + 16])
+            if bracket:
+                end = text.find("]" + bracket.group(1) + "]", i)
+                end = n if end < 0 else end + len(bracket.group(1)) + 2
+            else:
+                end = text.find("\n", i)
+                end = n if end < 0 else end
+            comment = blank(text[i:end])
+            clean.append(comment)
+            decommented.append(comment)
+            i = end
+            continue
+        if ch == "\"":
+            end = i + 1
+            while end < n and text[end] != "\"":
+                end += 2 if text[end] == "\\" else 1
+            closed = end < n
+            if not closed and warn:
+                warn(f"Incomplete quoted argument at line {text.count(chr(10), 0, i) + 1}")
+            end = end + 1 if closed else n
+            clean.append("\"" + blank(text[i + 1:end - (1 if closed else 0)]) + ("\"" if closed else ""))
+            decommented.append(text[i:end])
+            i = end
+            continue
+        clean.append(ch)
+        decommented.append(ch)
+        i += 1
+    return "".join(clean), "".join(decommented)
+
+
+class RuleParser(EntityParser):
+    """Parser for Makefile rules: explicit targets with prerequisites and recipe lines, pattern rules (`%.o: %.c`), `.PHONY` marks."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        lines = self.owner.logical_lines
+        phony = set()
+        rules = []
+        for index, (first_line, last_line, text) in enumerate(lines):
+            if text.startswith(self.owner.recipe_prefix):
+                continue
+            match = RULE_REGEX.match(text)
+            if not match or ("=" in match.group('prereqs') and not match.group('prereqs').startswith("$")):
+                continue   # variable assignment or target-specific variable
+            targets = match.group('targets').split()
+            prereqs = match.group('prereqs').split()
+            if targets and targets[0] == ".PHONY":
+                phony.update(prereqs)
+                continue
+            if not targets or all(t.startswith(".") and t.isupper() for t in targets):
+                continue   # special targets (.SUFFIXES, .DEFAULT_GOAL)
+            end = last_line
+            for recipe_first, recipe_last, recipe in lines[index + 1:]:
+                if not recipe.startswith(self.owner.recipe_prefix):
+                    break
+                end = recipe_last
+            rules.append((first_line, end, targets, prereqs))
+
+        for first_line, last_line, targets, prereqs in rules:
+            order_only = prereqs[prereqs.index("|") + 1:] if "|" in prereqs else []
+            prereqs = prereqs[:prereqs.index("|")] if "|" in prereqs else prereqs
+            extra = {"prerequisites": prereqs}
+            if order_only:
+                extra["order_only"] = order_only
+            if len(targets) > 1:
+                extra["targets"] = targets
+            if any(t in phony for t in targets):
+                extra["phony"] = True
+            e_type = "pattern rule" if "%" in targets[0] else self.entity_type
+            if self.owner.add_target(e_type, " ".join(targets), first_line, last_line, extra):
+                self.new_entities_lines.append(first_line)
+        return True
+
+
+class DepsParserMake(DepsParser):
+    """Parser for `include` / `-include` / `sinclude` directives, paths with variables are skipped."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in MAKE_INCLUDE_REGEX.finditer(self.owner.get_clean_content()):
+            for path in match.group('files').split():
+                if "$" in path or re.search(r"[*?\[]", path):
+                    continue
+                module = os.path.normpath(os.path.join(str(Path(self.owner.file_name).parent), path)).replace("\\", "/")
+                self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class CMakeParser(EntityParser):
+    """Parser for CMake commands: project, executable / library / custom targets with sources and linked libraries, functions and macros."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        targets = {}
+        blocks = []
+        for command, args, first_line, last_line in self.owner.commands:
+            if command in ("function", "macro") and args:
+                blocks.append((command, args, first_line))
+            elif command in ("endfunction", "endmacro") and blocks and blocks[-1][0] == command[3:]:
+                kind, block_args, block_line = blocks.pop()
+                if self.owner.add_target(kind, block_args[0], block_line, last_line, {"params": block_args[1:]}):
+                    self.new_entities_lines.append(block_line)
+            elif blocks:
+                continue   # commands inside function bodies are not targets
+            elif command == "project" and args:
+                extra = {}
+                if "VERSION" in args and args.index("VERSION") + 1 < len(args):
+                    extra["version"] = args[args.index("VERSION") + 1]
+                if self.owner.add_target("project", args[0], first_line, last_line, extra):
+                    self.new_entities_lines.append(first_line)
+            elif command in CMAKE_TARGETS and args:
+                extra = {}
+                kinds = [a for a in args[1:] if a in CMAKE_LIBRARY_KINDS]
+                if command == "add_library" and kinds:
+                    extra["kind"] = kinds[0].lower()
+                if "ALIAS" in args and args.index("ALIAS") + 1 < len(args):
+                    extra["alias"] = args[args.index("ALIAS") + 1]
+                if "IMPORTED" in args:
+                    extra["imported"] = True
+                if command == "add_custom_target":
+                    sources = args[args.index("SOURCES") + 1:] if "SOURCES" in args else []
+                elif "ALIAS" in args:
+                    sources = []
+                else:
+                    sources = [a for a in args[1:] if a not in CMAKE_KEYWORDS]
+                if sources:
+                    extra["sources"] = sources
+                if self.owner.add_target(CMAKE_TARGETS[command], args[0], first_line, last_line, extra):
+                    self.new_entities_lines.append(first_line)
+                    targets[args[0]] = self.owner.entity_map[first_line]
+            elif command == "target_link_libraries" and args and args[0] in targets:
+                links = targets[args[0]].setdefault("links", [])
+                links += [a for a in args[1:] if a not in CMAKE_LINK_SCOPES and a not in links]
+        return True
+
+
+class DepsParserCMake(DepsParser):
+    """Parser for `add_subdirectory(dir)` and `include(file.cmake)`, modules found by CMAKE_MODULE_PATH are stored as imports."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        base = Path(self.owner.file_name).parent
+        for command, args, _, _ in self.owner.commands:
+            if not args or "$" in args[0] or command not in ("add_subdirectory", "include"):
+                continue
+            if command == "include" and not args[0].endswith(".cmake"):
+                self.add_import(args[0], args[0])
+                continue
+            path = f"{args[0]}/CMakeLists.txt" if command == "add_subdirectory" else args[0]
+            module = os.path.normpath(os.path.join(str(base), path)).replace("\\", "/")
+            self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class ContentMakefile(ContentBlock):
+    """Parser for Makefiles (Makefile, GNUmakefile, *.mk): rules with prerequisites and includes."""
+    supported_types = ["Makefile", "makefile", "GNUmakefile", ".mk", ".mak"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "make"
+        self.entity_map = {}
+        self.logical_lines = []
+        self.recipe_prefix = "\t"
+        self.open_sl_comment = []   # comments are blanked by strip_strings
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentMakefile with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks comments outside recipes, joins continued lines into logical lines (first_line, last_line, text)."""
+        lines = self.content_text.split("\n")
+        clean = []
+        self.logical_lines = []
+        current = None
+        for line_num, line in enumerate(lines, 1):
+            prefix = re.match(r"^\.RECIPEPREFIX\s*:?=\s*(\S)", line)
+            if prefix:
+                self.recipe_prefix = prefix.group(1)
+            if not line.startswith(self.recipe_prefix) and current is None:
+                line = re.sub(r"(?<!\\)#.*$", lambda m: blank(m.group(0)), line)
+            clean.append(line)
+            text = line[:-1] if line.endswith("\\") else line
+            if current is None:
+                if not text.strip():
+                    continue
+                current = [line_num, line_num, text]
+                self.logical_lines.append(current)
+            else:
+                current[1] = line_num
+                current[2] += " " + text.strip()
+            if not line.endswith("\\"):
+                current = None
+        self.logical_lines = [tuple(item) for item in self.logical_lines]
+        self.clean_lines = [""] + clean
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_target(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Makefile content to extract targets and includes."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            RuleParser("target", self),
+            DepsParserMake(self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+class ContentCMake(ContentMakefile):
+    """Parser for CMake scripts (CMakeLists.txt, *.cmake): targets, functions, macros and subdirectories."""
+    supported_types = ["CMakeLists.txt", ".cmake"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "cmake"
+        self.commands = []
+        logging.debug(f"Initialized ContentCMake with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks comments and quoted arguments with strip_cmake_literals, collects commands (name, args, first_line, last_line)."""
+        if len(self.content_text) < 3:
+            return ContentBlock.strip_strings(self)
+        clean_text, decommented = strip_cmake_literals(self.content_text, warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}"))
+        self.commands = []
+        for match in CMAKE_COMMAND_REGEX.finditer(clean_text):
+            level = 0
+            end = None
+            for pos in range(match.end() - 1, len(clean_text)):
+                level += 1 if clean_text[pos] == "(" else -1 if clean_text[pos] == ")" else 0
+                if level == 0:
+                    end = pos
+                    break
+            if end is None:
+                self.parse_warn(f"Unclosed command {match.group('command')} at line {clean_text.count(chr(10), 0, match.start()) + 1} in file {self.file_name}")
+                continue
+            args = [a[1:-1] if a.startswith("\"") else a for a in CMAKE_ARG_REGEX.findall(decommented[match.end():end])]
+            self.commands.append((match.group('command').lower(), args,
+                                  clean_text.count("\n", 0, match.start('command')) + 1, clean_text.count("\n", 0, end) + 1))
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses CMake content to extract targets, functions and subdirectories."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            CMakeParser("target", self),
+            DepsParserCMake(self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentMakefile)
+SandwichPack.register_block_class(ContentCMake)
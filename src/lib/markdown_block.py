@@ -0,0 +1,227 @@
+# /lib/markdown_block.py, created 2026-10-14 23:35 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+FENCE_REGEX = re.compile(r"^[ ]{0,3}(?P<fence>`{3,}|~{3,})[ \t]*(?P<info>[^`\n]*)$")
+ATX_REGEX = re.compile(r"^[ ]{0,3}(?P<marks>#{1,6})(?:[ \t]+(?P<title>.*?))?(?:[ \t]+#+)?[ \t]*$")
+SETEXT_REGEX = re.compile(r"^[ ]{0,3}(?P<underline>=+|-+)[ \t]*$")
+LINK_REGEX = re.compile(r"(?<!!)\[(?P<text>[^\]\n]*)\]\(\s*<?(?P<target>[^\s)>]+)>?(?:\s+[\"'(][^)]*)?\)|"
+                        r"^[ ]{0,3}\[(?P<ref>[^\]\n]+)\]:\s*<?(?P<ref_target>\S+?)>?(?:\s+.*)?$|"
+                        r"<(?P<auto>[a-z][\w+.-]*:[^\s>]+)>", re.MULTILINE | re.IGNORECASE)
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def plain_title(title: str) -> str:
+    """Heading text without inline markup: links become their text, emphasis and code marks are dropped."""
+    title = re.sub(r"!?\[([^\]]*)\]\([^)]*\)", r"\1", title)
+    title = re.sub(r"[*_`]+", "", title)
+    return " ".join(title.split())
+
+
+class HeadingParser(EntityParser):
+    """Parser for ATX (`## Title`) and Setext (`Title` underlined by `===`/`---`) headings, nesting by heading level."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        clean_lines = self.owner.clean_lines
+        raw_lines = [""] + self.owner.content_text.split("\n")
+        headings = []
+        for line_num in range(1, len(clean_lines)):
+            line = clean_lines[line_num]
+            if not isinstance(line, str) or not line.strip():
+                continue
+            if match := ATX_REGEX.match(line):
+                title = ATX_REGEX.match(raw_lines[line_num]).group('title') or ""
+                headings.append((line_num, len(match.group('marks')), title))
+                continue
+            following = clean_lines[line_num + 1] if line_num + 1 < len(clean_lines) else ""
+            previous = clean_lines[line_num - 1] if line_num > 1 else ""
+            setext = SETEXT_REGEX.match(following) if isinstance(following, str) else None
+            if setext and not (headings and headings[-1][0] == line_num - 1) and not FENCE_REGEX.match(line) \
+                    and not (isinstance(previous, str) and previous.strip() and not SETEXT_REGEX.match(previous)):
+                headings.append((line_num, 1 if setext.group('underline')[0] == "=" else 2, raw_lines[line_num].strip()))
+
+        stack = []
+        for i, (line_num, level, title) in enumerate(headings):
+            while stack and stack[-1][1] >= level:
+                stack.pop()
+            ends = [h[0] for h in headings[i + 1:] if h[1] <= level]
+            last_line = self.owner.last_filled_line(ends[0] - 1 if ends else len(clean_lines) - 1, line_num)
+            extra = {"level": level}
+            if stack:
+                extra["parent"] = stack[-1][2]
+            name = self.owner.add_section(self.entity_type, plain_title(title) or f"h{level}", line_num, last_line, extra)
+            if name:
+                self.new_entities_lines.append(line_num)
+                stack.append((line_num, level, name))
+        return True
+
+
+class CodeFenceParser(EntityParser):
+    """Parser for fenced code blocks, tagged with language from the info string and named by it with line suffix."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        for first_line, last_line, language in self.owner.fences:
+            parent = self.owner.enclosing_section(first_line)
+            extra = {"language": language}
+            if parent:
+                extra["parent"] = parent
+            if self.owner.add_section(self.entity_type, f"{language or 'code'}:{first_line}", first_line, last_line, extra):
+                self.new_entities_lines.append(first_line)
+        return True
+
+
+class DepsParserMarkdown(DepsParser):
+    """Parser for links: targets are stored in the enclosing heading, local documents and sources become modules."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in LINK_REGEX.finditer(content):
+            target = next(match.group(g) for g in ('target', 'ref_target', 'auto') if match.group(g) is not None)
+            line_num = self.owner.find_line(match.start())
+            section = self.owner.enclosing_section(line_num)
+            if section:
+                entity = next(e for e in self.owner.entity_map.values() if e["name"] == section)
+                entity.setdefault("links", [])
+                if target not in entity["links"]:
+                    entity["links"].append(target)
+            if re.match(r"(?i)^(?:[a-z][\w+.-]*:|//|#)", target):
+                continue   # external or same document anchor
+            path = target.split("#")[0].split("?")[0]
+            if not path.startswith("/"):
+                path = os.path.normpath(f"{Path(self.owner.file_name).parent}/{path}").replace("\\", "/")
+            self.add_module(path if path.startswith("/") else f"/{path}")
+        return True
+
+
+class ContentMarkdown(ContentBlock):
+    """Parser for Markdown documents (.md, .markdown): headings hierarchy, fenced code blocks and links."""
+    supported_types = [".md", ".markdown"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "markdown"
+        self.entity_map = {}
+        self.fences = []
+        self.open_sl_comment = []   # HTML comments are blanked by strip_strings
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentMarkdown with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks front matter, fenced code bodies, inline code spans and HTML comments, fences are collected with language."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        lines = self.content_text.split("\n")
+        self.fences = []
+        if lines and lines[0].strip() == "---":
+            end = next((i for i in range(1, len(lines)) if lines[i].strip() in ("---", "...")), None)
+            for i in range(0, end + 1 if end is not None else 0):
+                lines[i] = blank(lines[i])
+        fence = None
+        for i, line in enumerate(lines):
+            match = FENCE_REGEX.match(line)
+            if fence is None:
+                if match and not (match.group('fence')[0] == "`" and "`" in match.group('info')):
+                    fence = (i, match.group('fence'), match.group('info').split()[0] if match.group('info').strip() else "")
+                continue
+            if match and match.group('fence')[0] == fence[1][0] and len(match.group('fence')) >= len(fence[1]) and not match.group('info').strip():
+                self.fences.append((fence[0] + 1, i + 1, fence[2].lower()))
+                fence = None
+                continue
+            lines[i] = blank(line)
+        if fence is not None:
+            self.parse_warn(f"Unclosed code fence at line {fence[0] + 1} in file {self.file_name}")
+            self.fences.append((fence[0] + 1, len(lines), fence[2].lower()))
+        clean_text = "\n".join(lines)
+        clean_text = re.sub(r"<!--.*?(?:-->|$)", lambda m: blank(m.group(0)), clean_text, flags=re.DOTALL)
+        clean_text = re.sub(r"(?P<ticks>`+)(?!`).+?(?<!`)(?P=ticks)(?!`)", lambda m: m.group(0)[0] + blank(m.group(0)[1:-1]) + m.group(0)[-1],
+                            clean_text)
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def last_filled_line(self, line_num: int, first_line: int) -> int:
+        """Last non-empty line at or before line_num, trailing blank lines do not belong to section."""
+        while line_num > first_line and not self.content_text.split("\n")[line_num - 1].strip():
+            line_num -= 1
+        return line_num
+
+    def enclosing_section(self, line_num: int):
+        """Name of the innermost heading containing line_num."""
+        sections = [e for e in self.entity_map.values() if e["type"] == "heading" and e["first_line"] <= line_num <= e["last_line"]]
+        return max(sections, key=lambda e: e["first_line"])["name"] if sections else None
+
+    def add_section(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, repeated heading titles get line suffix (Usage:42), returns stored name."""
+        if first_line in self.entity_map:
+            return None
+        if any(e["name"] == name and e["type"] == e_type for e in self.entity_map.values()):
+            name = f"{name}:{first_line}"
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return name
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Markdown content to extract headings, code blocks and links."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            HeadingParser("heading", self),
+            CodeFenceParser("code block", self),
+            DepsParserMarkdown(self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentMarkdown)
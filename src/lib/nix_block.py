@@ -0,0 +1,353 @@
+# /lib/nix_block.py, created 2026-10-15 06:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+WORD_REGEX = re.compile(r"(?<![\w'-])(?P<word>let|in|with|assert|rec|inherit)(?![\w'-])")
+LAMBDA_IDENT_REGEX = re.compile(r"[A-Za-z_][\w'-]*\s*:(?!:)")
+IMPORTS_LIST_REGEX = re.compile(r"\bimports\s*=\s*\[(?P<items>[^\]]*)\]")
+IMPORT_PATH_REGEX = re.compile(r"(?:\b(?:import|callPackage)\s+(?P<call>\.{0,2}/[\w./+-]*)|(?<![\w.])(?P<file>\.{1,2}/[\w./+-]*\.nix)\b)")
+
+
+def compact(text: str) -> str:
+    return " ".join(text.split())
+
+
+def strip_nix_literals(text: str, warn=None) -> str:
+    """Blanks strings ("..." and indented ''...''), `#` and `/* */` comments with spaces; `${...}` interpolations are blanked too.
+
+    Positions are preserved and quotes are kept, so attribute names and values are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append(re.sub(r"[^\n]", " ", text[i:end]))
+            i = end
+            continue
+        if ch == "\"" or text.startswith("''", i):
+            quote = "\"" if ch == "\"" else "''"
+            end = skip_nix_string(text, i)
+            closed = text.startswith(quote, end - len(quote)) and end - len(quote) > i
+            if not closed and warn:
+                warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+            body_end = end - len(quote) if closed else end
+            out.append(quote + re.sub(r"[^\n]", " ", text[i + len(quote):body_end]) + (quote if closed else ""))
+            i = end
+            continue
+        out.append(ch)
+        i += 1
+    return "".join(out)
+
+
+def skip_nix_string(text: str, i: int) -> int:
+    """End offset (after closing quote) of string opened at text[i]; `''` strings escape with `'''`, `''$` and `''\\`."""
+    n = len(text)
+    indented = text.startswith("''", i)
+    j = i + (2 if indented else 1)
+    while j < n:
+        if indented and text.startswith("''", j):
+            if j + 2 < n and text[j + 2] in "'$\\":
+                j += 3 if text[j + 2] != "\\" else 4
+                continue
+            return j + 2
+        if not indented and text[j] == "\"":
+            return j + 1
+        if not indented and text[j] == "\\":
+            j += 2
+            continue
+        if text.startswith("${", j):
+            depth = 0
+            while j < n:
+                if text[j] == "\"" or text.startswith("''", j):
+                    j = skip_nix_string(text, j)
+                    continue
+                depth += {"{": 1, "}": -1}.get(text[j], 0)
+                j += 1
+                if depth == 0:
+                    break
+            continue
+        j += 1
+    return n
+
+
+class ExpressionParser(EntityParser):
+    """Parser for the file expression: bindings of the resulting attribute set, top level `let` bindings and flake inputs / outputs.
+
+    Function headers (`{ pkgs, ... }:`), `with` / `assert` prefixes and applications (`pkgs.mkShell {...}`) are skipped
+    until the attribute set is found; for flakes the bindings returned by `outputs` are stored as outputs.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        self.text = self.owner.get_clean_content()
+        self.raw = self.owner.content_text
+        self.flake = Path(self.owner.file_name or "").name == "flake.nix"
+        self.expression(0, len(self.text), "attribute", "")
+        return True
+
+    def matching(self, pos: int) -> int:
+        """Offset of bracket closing the one at pos."""
+        depth = 0
+        for j in range(pos, len(self.text)):
+            ch = self.text[j]
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}":
+                depth -= 1
+                if depth == 0:
+                    return j
+        return len(self.text) - 1
+
+    def skip_space(self, pos: int, limit: int) -> int:
+        while pos < limit and self.text[pos].isspace():
+            pos += 1
+        return pos
+
+    def word_at(self, pos: int):
+        match = WORD_REGEX.match(self.text, pos)
+        return match.group('word') if match else None
+
+    def statement_end(self, pos: int, limit: int) -> int:
+        """Offset of `;` ending the binding value started at pos, nested `let ... in` and `with x;` prefixes respected."""
+        stack = []
+        depth = 0
+        j = pos
+        while j < limit:
+            ch = self.text[j]
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}":
+                depth -= 1
+                if depth < 0:
+                    return j
+            elif depth == 0 and ch == ";":
+                if stack and stack[-1] == "with":
+                    stack.pop()
+                elif not stack:
+                    return j
+            elif depth == 0 and (word := self.word_at(j)):
+                if word == "let":
+                    stack.append("let")
+                elif word in ("with", "assert"):
+                    stack.append("with")
+                elif word == "in" and "let" in stack:
+                    while stack.pop() != "let":
+                        pass
+                j += len(word)
+                continue
+            j += 1
+        return limit
+
+    def expression(self, pos: int, limit: int, kind: str, parent: str):
+        """Walks expression prefixes up to the attribute set and stores its bindings as kind entities."""
+        while True:
+            pos = self.skip_space(pos, limit)
+            if pos >= limit:
+                return
+            word = self.word_at(pos)
+            ch = self.text[pos]
+            if word == "let":
+                pos = self.bindings(pos + 3, limit, "let binding", parent, until_in=True)
+            elif word in ("with", "assert"):
+                pos = self.statement_end(pos + len(word), limit) + 1
+            elif word == "rec":
+                pos += 3
+            elif ch == "{":
+                close = self.matching(pos)
+                after = self.skip_space(close + 1, limit)
+                if after < limit and self.text[after] in ":@":   # function header `{ a, b ? x, ... }:` or `{ ... } @ args:`
+                    colon = self.text.find(":", after)
+                    pos = (colon if colon >= 0 else limit) + 1
+                    continue
+                self.bindings(pos + 1, close, kind, parent)
+                return
+            elif ch == "(":
+                self.expression(pos + 1, self.matching(pos), kind, parent)
+                return
+            elif match := LAMBDA_IDENT_REGEX.match(self.text, pos):
+                pos = match.end()
+            else:
+                token = re.match(r"[^\s({\[;]+", self.text[pos:limit])
+                pos += len(token.group(0)) if token else 1
+
+    def bindings(self, pos: int, limit: int, kind: str, parent: str, until_in: bool = False) -> int:
+        """Stores bindings `path = value;` and `inherit` names between pos and limit, returns offset after the last one (or `in`)."""
+        while True:
+            pos = self.skip_space(pos, limit)
+            if pos >= limit:
+                return limit
+            word = self.word_at(pos)
+            if until_in and word == "in":
+                return pos + 2
+            end = self.statement_end(pos, limit)
+            if word == "inherit":
+                head = self.text[pos + len(word):end]
+                source = re.match(r"\s*\((?P<src>[^)]*)\)", head)
+                names = head[source.end():] if source else head
+                extra = {"inherit": compact(self.raw[pos + len(word) + source.start('src'):pos + len(word) + source.end('src')])} if source else {}
+                for name in re.finditer(r"[A-Za-z_][\w'-]*", names):
+                    self.add(kind, name.group(0), pos, end, {**extra, **({"parent": parent} if parent else {})})
+                pos = end + 1
+                continue
+            eq = self.text.find("=", pos, end)
+            if eq < 0:
+                pos = end + 1
+                continue
+            path = compact(self.raw[pos:eq])
+            value_start = self.skip_space(eq + 1, end)
+            self.binding(path, pos, value_start, end, kind, parent)
+            pos = end + 1
+
+    def binding(self, path: str, start: int, value_start: int, end: int, kind: str, parent: str):
+        value = self.raw[value_start:end]
+        extra = {"parent": parent} if parent else {}
+        if len(value) <= 80 and "\n" not in value:
+            extra["value"] = compact(value)
+        header = re.match(r"\s*(?:{(?P<pattern>[^{}]*)}\s*(?:@\s*\w+\s*)?|(?P<ident>[A-Za-z_][\w'-]*)\s*):(?!:)", self.text[value_start:end])
+        if header:
+            extra["args"] = [a.split("?")[0].strip() for a in (header.group('pattern') or header.group('ident')).split(",") if a.strip()]
+        if self.flake and kind == "attribute" and not parent:
+            if path in ("inputs", "outputs"):
+                self.add(kind, path, start, end, extra)
+                self.expression(value_start, end, path[:-1], "" if path == "inputs" else path)
+                return
+            if path.startswith("inputs."):
+                kind, path = "input", path[len("inputs."):]
+        if kind == "input":
+            name, _, field = path.partition(".")
+            if field == "url":
+                url = value.strip().strip("\"")
+            elif not field:
+                url = next(iter(re.findall(r"\burl\s*=\s*\"([^\"]*)\"", value)), None)
+            else:
+                return   # follows / flake flags of already stored input
+            self.add(kind, name, start, end, {"url": url} if url else {})
+            return
+        self.add(kind, path, start, end, extra)
+
+    def add(self, e_type: str, name: str, start: int, end: int, extra: dict):
+        first_line = self.owner.find_line(start)
+        if self.owner.add_binding(e_type, name, first_line, self.owner.find_line(min(end, len(self.text) - 1)), extra):
+            self.new_entities_lines.append(first_line)
+
+
+class DepsParserNix(DepsParser):
+    """Parser for imported files: `import ./x.nix`, `callPackage ./pkg {}`, module `imports = [...]` lists and `.nix` path literals.
+
+    Directories mean their default.nix.
+    """
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        paths = [m.group('call') or m.group('file') for m in IMPORT_PATH_REGEX.finditer(content)]
+        for match in IMPORTS_LIST_REGEX.finditer(content):
+            paths += re.findall(r"(?<![\w.])\.{1,2}/[\w./+-]*|(?<![\w.])\.{1,2}(?=[\s\]])", match.group('items'))
+        for path in paths:
+            path = path.rstrip("/")
+            if not path.endswith(".nix"):
+                path += "/default.nix"
+            self.add_module(os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), path)).replace("\\", "/"))
+        return True
+
+
+class ContentNix(ContentBlock):
+    """Parser for Nix expressions (.nix), flakes included."""
+    supported_types = [".nix"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "nix"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentNix with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips strings and comments with strip_nix_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_nix_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_binding(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Nix content to extract attribute bindings, let bindings, flake inputs / outputs and imports."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserNix(self),
+            ExpressionParser("attribute", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentNix)
@@ -0,0 +1,164 @@
+# /lib/notebook_block.py, created 2026-10-15 09:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import json
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.html_block import RawTextParser, blank
+from lib.python_block import ContentCodePython
+
+
+CELL_MARKERS = {"code": "# %%", "markdown": "# %% [markdown]", "raw": "# %% [raw]"}
+MAGIC_REGEX = re.compile(r"^[ \t]*[%!?]")
+HEADING_REGEX = re.compile(r"^[ \t]{0,3}#{1,6}[ \t]+(?P<title>.+?)[ \t#]*$", re.MULTILINE)
+
+
+def cell_source(cell: dict) -> str:
+    source = cell.get("source", "")
+    return "".join(source) if isinstance(source, list) else str(source)
+
+
+def output_lines(output: dict) -> list:
+    """Text of cell output as list of lines: streams, plain text results and errors, rich data is replaced by its mime types."""
+    kind = output.get("output_type")
+    if kind == "stream":
+        text = cell_source({"source": output.get("text", "")})
+    elif kind in ("execute_result", "display_data"):
+        data = output.get("data", {})
+        if "text/plain" not in data:
+            return [f"[{', '.join(sorted(data))}]"] if data else []
+        text = cell_source({"source": data["text/plain"]})
+    elif kind == "error":
+        text = f"{output.get('ename', 'Error')}: {output.get('evalue', '')}"
+    else:
+        return []
+    return text.rstrip("\n").split("\n") if text.strip() else []
+
+
+def notebook_to_text(notebook: dict, keep_outputs: bool = False) -> tuple:
+    """Converts notebook to percent-format script (`# %%` cell markers), returns text and cells with their line ranges.
+
+    Outputs are dropped unless keep_outputs, then they follow the cell source as `# >> ` comment lines.
+    """
+    lines = []
+    cells = []
+    for index, cell in enumerate(notebook.get("cells", []), 1):
+        kind = cell.get("cell_type", "code")
+        lines.append(CELL_MARKERS.get(kind, CELL_MARKERS["raw"]))
+        first_line = len(lines)
+        source = cell_source(cell).rstrip("\n")
+        lines.extend(source.split("\n") if source else [])
+        if keep_outputs and kind == "code":
+            for output in cell.get("outputs", []):
+                lines.extend(f"# >> {line}" for line in output_lines(output))
+        cells.append({"index": index, "kind": kind, "first_line": first_line, "last_line": len(lines),
+                      "source": source, "execution_count": cell.get("execution_count")})
+        lines.append("")
+    return "\n".join(lines), cells
+
+
+class CellParser(RawTextParser):
+    """Parser for notebook cells in source order; code cells of a Python notebook are delegated to the Python block as one script.
+
+    Cells share the kernel namespace, so all code cells are parsed together with other lines masked, IPython magics
+    (`%time`, `!pip`) are masked too. Entities found in a cell get the cell as parent.
+    """
+    def parse(self):
+        text = self.owner.content_text
+        lines = text.split("\n")
+        code_lines = set()
+        for cell in self.owner.cells:
+            name = f"cell {cell['index']}"
+            extra = {"cell": cell["index"]}
+            if cell["kind"] == "markdown" and (heading := HEADING_REGEX.search(cell["source"])):
+                extra["title"] = heading.group('title')
+            if cell["kind"] == "code" and cell["execution_count"] is not None:
+                extra["execution_count"] = cell["execution_count"]
+            cell["name"] = name
+            self.owner.add_cell(f"{cell['kind']} cell", name, cell["first_line"], cell["last_line"], extra)
+            self.new_entities_lines.append(cell["first_line"])
+            if cell["kind"] == "code":
+                code_lines.update(ln for ln in range(cell["first_line"] + 1, cell["last_line"] + 1)
+                                  if not MAGIC_REGEX.match(lines[ln - 1]) and not lines[ln - 1].startswith("# >> "))
+        if not code_lines or self.owner.language != "python":
+            return True
+        masked = "\n".join(line if ln in code_lines else blank(line) for ln, line in enumerate(lines, 1))
+        first_new = len(self.new_entities_lines)
+        self.parse_sub_block(ContentCodePython, masked, ".py", "code")
+        for line_num in self.new_entities_lines[first_new:]:
+            entity = self.owner.entity_map[line_num]
+            cell = next((c for c in self.owner.cells if c["first_line"] < line_num <= c["last_line"]), None)
+            if cell and not entity.get("parent"):
+                entity["parent"] = cell["name"]
+        return True
+
+
+class ContentNotebook(ContentBlock):
+    """Parser for Jupyter notebooks (.ipynb): JSON is converted to percent-format text, which is packed and indexed.
+
+    Outputs are stripped by default to save tokens, `keep_outputs=True` keeps text outputs as comments.
+    Line numbers of entities refer to the converted text.
+    """
+    supported_types = [".ipynb"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        self.cells = []
+        self.language = "python"
+        self.load_error = None
+        try:
+            notebook = json.loads(content_text) if content_text.strip() else {"cells": []}
+            metadata = notebook.get("metadata", {})
+            self.language = (metadata.get("kernelspec", {}).get("language") or metadata.get("language_info", {}).get("name") or "python").lower()
+            content_text, self.cells = notebook_to_text(notebook, kwargs.get("keep_outputs", False))
+        except (ValueError, AttributeError) as e:
+            self.load_error = str(e)   # broken notebook is packed as is, without entities
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "notebook"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        self.depth = 0
+        logging.debug(f"Initialized ContentNotebook with tag={self.tag}, file_name={file_name}, language={self.language}, cells={len(self.cells)}")
+
+    def add_cell(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores cell entity without placement check, cell marker line is the first line."""
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses notebook cells and Python entities of code cells."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.depth = depth
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        if self.load_error:
+            self.parse_warn(f"Invalid notebook JSON in file {self.file_name}: {self.load_error}")
+            return {"entities": [], "dependencies": self.dependencies}
+        parser = CellParser("cell", self)
+        try:
+            parser.parse()
+            self.extend_deps(parser)
+        except Exception as e:
+            logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+            traceback.print_exc()
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentNotebook)
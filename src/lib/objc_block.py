@@ -0,0 +1,206 @@
+# /lib/objc_block.py, updated 2026-10-16 16:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+from lib.cpp_block import strip_cpp_literals
+
+
+SCOPE_REGEX = re.compile(r"^\s*@(?P<kind>interface|implementation|protocol)\s+(?P<name>\w+)"
+                         r"(?:\s*\(\s*(?P<category>\w*)\s*\))?(?:\s*:\s*(?P<parent>\w+))?(?:\s*<(?P<protocols>[^>]*)>)?(?P<tail>.*)$")
+METHOD_REGEX = re.compile(r"^\s*(?P<kind>[-+])\s*(?:\((?P<returns>[^()]*(?:\([^()]*\)[^()]*)*)\))?\s*(?P<selector>\w)")
+PROPERTY_REGEX = re.compile(r"^\s*@property\s*(?:\((?P<attrs>[^)]*)\))?\s*(?P<decl>[^;]+);")
+PARAMS_REGEX = re.compile(r"\([^()]*(?:\([^()]*\)[^()]*)*\)")
+IMPORT_REGEX = re.compile(r"^[ \t]*(?:#[ \t]*(?:import|include)\s*[<\"](?P<header>[^>\"]+)[>\"]|@import\s+(?P<module>[\w.]+)\s*;)", re.MULTILINE)
+
+
+class ScopeParser(EntityParser):
+    """Parser for `@interface` (classes, categories, extensions), `@implementation` and `@protocol` blocks ended by `@end`.
+
+    Methods are taken from implementations (with body), interfaces and categories (declarations) and protocols (requirements,
+    `@optional` flagged), properties from interfaces and protocols; methods are named by selector (`initWithName:age:`),
+    `+` methods are class methods. Declarations without body are abstract, extension members are private.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        lines = self.owner.clean_lines
+        line_num = 1
+        while line_num < len(lines):
+            line = lines[line_num] if isinstance(lines[line_num], str) else ""
+            match = SCOPE_REGEX.match(line)
+            if not match or match.group('tail').strip().startswith((";", ",")) or (match.group('kind') == "protocol" and ";" in line):
+                line_num += 1   # forward declarations `@protocol Foo;`
+                continue
+            end_line = next((ln for ln in range(line_num + 1, len(lines)) if isinstance(lines[ln], str) and re.match(r"^\s*@end\b", lines[ln])), None)
+            if end_line is None:
+                self.owner.parse_warn(f"Missing @end for @{match.group('kind')} at line {line_num} in file {self.owner.file_name}")
+                end_line = len(lines) - 1
+            self.add_scope(match, line_num, end_line)
+            line_num = end_line + 1
+        return True
+
+    def add_scope(self, match, first_line, last_line):
+        kind = match.group('kind')
+        name = match.group('name')
+        category = match.group('category')
+        extra = {}
+        if kind == "interface":
+            e_type = "class" if category is None else "category" if category else "extension"
+        else:
+            e_type = kind
+        if category:
+            extra["category"] = category
+            extra["parent"] = name
+            name = f"{name}+{category}"
+        elif category == "":
+            extra["parent"] = name
+        if match.group('parent'):
+            extra["parent"] = match.group('parent')
+        if match.group('protocols'):
+            extra["protocols"] = [p.strip() for p in match.group('protocols').split(",") if p.strip()]
+        self.add(e_type, name, first_line, last_line, extra)
+        private = e_type == "extension"
+        optional = False
+        line_num = first_line + 1
+        while line_num < last_line:
+            line = self.owner.clean_lines[line_num] if isinstance(self.owner.clean_lines[line_num], str) else ""
+            if re.match(r"^\s*@(optional|required)\b", line):
+                optional = "optional" in line
+            elif prop := PROPERTY_REGEX.match(line):
+                if kind != "implementation":
+                    self.add_property(prop, name, line_num, private, optional and kind == "protocol")
+            elif method := METHOD_REGEX.match(line):
+                header_end = next((ln for ln in range(line_num, min(line_num + 10, last_line)) if re.search(r"[{;]", self.owner.clean_lines[ln] or "")), line_num)
+                header = " ".join(self.owner.clean_lines[line_num:header_end + 1])
+                body = kind == "implementation" and "{" in header.split(";")[0]
+                if body or kind != "implementation":
+                    end_line = self.owner.detect_bounds(line_num, self.owner.clean_lines)[1] if body else header_end
+                    self.add_method(method, header, name, line_num, end_line, not body, optional, private)
+                    line_num = end_line
+            line_num += 1
+
+    def add_method(self, match, header, parent, first_line, last_line, abstract, optional, private=False):
+        signature = header[match.start('selector'):].split("{")[0].split(";")[0]
+        signature = PARAMS_REGEX.sub(" ", signature)   # parameter types dropped, `name:(int)a label:(id)b` -> `name: a label: b`
+        parts = re.findall(r"(\w*)\s*:", signature)
+        selector = "".join(p + ":" for p in parts) if parts else signature.split()[0]
+        e_type = "class method" if match.group('kind') == "+" else "method"
+        extra = {"parent": parent}
+        if match.group('returns'):
+            extra["returns"] = " ".join(match.group('returns').split())
+        if optional:
+            extra["optional"] = True
+        self.add("abstract " + e_type if abstract else e_type, selector, first_line, last_line, extra, "private" if private else None)
+
+    def add_property(self, match, parent, line_num, private, optional):
+        decl = match.group('decl').strip()
+        name = re.search(r"(\w+)\s*(?:__\w+\s*)*$", decl)
+        if not name:
+            return
+        extra = {"parent": parent, "value_type": decl[:name.start(1)].strip()}
+        if match.group('attrs'):
+            extra["attributes"] = [a.strip() for a in match.group('attrs').split(",") if a.strip()]
+        if optional:
+            extra["optional"] = True
+        self.add("property", name.group(1), line_num, line_num, extra, "private" if private else None)
+
+    def add(self, e_type, name, first_line, last_line, extra, visibility=None):
+        visibility = visibility or ("private" if name.startswith("_") else "public")
+        if self.owner.add_definition(e_type, name, first_line, last_line, extra, visibility):
+            self.new_entities_lines.append(first_line)
+
+
+class DepsParserObjC(DepsParser):
+    """Parser for `#import` / `#include` headers and `@import` modules; directives are removed from clean_lines, so original text is used."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in IMPORT_REGEX.finditer(self.owner.content_text):
+            self.add_module(match.group('header') or match.group('module'))
+        return True
+
+
+class ContentCodeObjC(ContentBlock):
+    """Parser for Objective-C and Objective-C++ content blocks (.m, .mm); `.h` headers are handled by the C/C++ parser."""
+    supported_types = [".m", ".mm"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "objc"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeObjC with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips literals (`@"..."` included) and preprocessor directives with strip_cpp_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_cpp_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": self.module_prefix + name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Objective-C content to extract interfaces, implementations, protocols, methods and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserObjC(self),
+            ScopeParser("scope", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeObjC)
@@ -0,0 +1,99 @@
+# /lib/pack_format.py, updated 2026-10-16 15:00 EEST
+# Pack format versions and index reader accepting packs of all previous versions.
+
+import json
+import logging
+
+# index layout versions:
+#   1 - no header (packer 0.6 / 0.7): flat index, packer 0.6 writes no templates
+#   2 - "format_version" header in global and deep index, structured JSON pack carries it at top level
+FORMAT_VERSION = 2
+
+DEFAULT_TEMPLATES = {
+    "filelist": "file_id,file_name,md5,tokens,timestamp",
+    "users": "user_id,username,role",
+    "entities": "vis(pub/prv),type,parent,name,file_id,start_line-end_line,tokens"
+}
+
+
+def format_version(index: dict) -> int:
+    """Layout version of index, packs without header are version 1."""
+    version = index.get("format_version", 1)
+    if not isinstance(version, int) or isinstance(version, bool) or version < 1:
+        raise ValueError(f"Invalid pack format_version {version!r}")
+    return version
+
+
+def flatten_structured(pack: dict) -> dict:
+    """Flat index from structured JSON pack (manifest, file and entity records)."""
+    index = {"packer_version": pack.get("packer_version")}
+    if pack.get("context_date"):
+        index["context_date"] = pack["context_date"]
+    index["project_name"] = pack.get("project_name")
+    index.update(pack.get("manifest", {}))
+    index["files"] = [f"{f['file_id']},{f['file_name']},{f['md5']},{f['tokens']},{f['timestamp']}" for f in pack.get("files", [])]
+    index["entities"] = [(f"{'pub' if ent.get('visibility') == 'public' else 'prv'},{ent['type']},{ent.get('parent', '')},{ent['name']}," +
+                          f"{ent['file_id']},{ent['first_line']}-{ent['last_line']},{ent.get('tokens', 0)}") for ent in pack.get("entities", [])]
+    if "format_version" in pack:
+        index["format_version"] = pack["format_version"]
+    return index
+
+
+def upgrade_index(index: dict) -> dict:
+    """Copy of index in current layout. Unknown sections, entity kinds and template columns are kept as is."""
+    if "manifest" in index and isinstance(index.get("files"), list) and all(isinstance(f, dict) for f in index["files"]):
+        index = flatten_structured(index)
+    version = format_version(index)
+    if version > FORMAT_VERSION:
+        logging.warning(f"Pack format version {version} is newer than supported {FORMAT_VERSION}, unknown sections are ignored")
+        return dict(index)
+    result = dict(index)
+    if version < 2:
+        result["templates"] = {**DEFAULT_TEMPLATES, **index.get("templates", {})}
+        for section in ("files", "entities", "users"):
+            result.setdefault(section, [])
+        result.pop("format_version", None)
+        result = {"format_version": FORMAT_VERSION, **result}
+    return result
+
+
+def parse_index(text: str) -> dict:
+    """Upgraded global index from sandwich text (INDEX_COPY), index jsl (STRUCTURE) or JSON pack."""
+    if "\nINDEX_COPY:\n" in text:
+        text = text.split("\nINDEX_COPY:\n", 1)[1]
+    elif "STRUCTURE: " in text:
+        text = text.split("STRUCTURE: ", 1)[0]
+    index = json.loads(text)
+    if not isinstance(index, dict):
+        raise ValueError("Pack index is not a JSON object")
+    return upgrade_index(index)
+
+
+def split_row(row: str, template: str) -> dict:
+    """Index row as dict by template columns. Entity parent and name may hold commas, columns over template go to `extra`."""
+    columns = [c.split("(")[0] for c in template.split(",")]
+    values = row.split(",")
+    if columns[:4] == ["vis", "type", "parent", "name"] and len(values) > len(columns):
+        tail = len(columns) - 4
+        middle = values[2:len(values) - tail]
+        values = values[:2] + [middle[0], ",".join(middle[1:])] + values[len(values) - tail:]
+    record = dict(zip(columns, values))
+    if len(values) > len(columns):
+        record["extra"] = values[len(columns):]
+    return record
+
+
+def entity_references(index: dict, name: str) -> list:
+    """Names of files referencing entity `name` (`Parent.name`, `Parent::name`) by index `refs` section, [] if not indexed."""
+    template = index.get("templates", {}).get("entities", DEFAULT_TEMPLATES["entities"])
+    files = {int(row.split(",")[0]): row.split(",")[1] for row in index.get("files", [])}
+    refs = index.get("refs", {})
+    parts = name.replace("::", ".").split(".")
+    result = set()
+    for entity_index, row in enumerate(index.get("entities", [])):
+        ent = split_row(row, template)
+        path = f"{ent.get('parent') or ''}.{ent.get('name', '')}".replace("::", ".").strip(".").split(".")
+        if path[-len(parts):] == parts:
+            result.update(files.get(file_id) for file_id in refs.get(str(entity_index), refs.get(entity_index, [])))
+    result.discard(None)
+    return sorted(result)
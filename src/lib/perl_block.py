@@ -0,0 +1,338 @@
+# /lib/perl_block.py, created 2026-10-15 07:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+PAIRED_DELIMITERS = {"(": ")", "[": "]", "{": "}", "<": ">"}
+QUOTE_LIKE_REGEX = re.compile(r"(?P<op>qq|qw|qx|qr|q|m|s|tr|y)(?:(?P<delim>[^\w\s,;)=])|\s+(?P<spaced>[^\w\s,;)=#]))")
+HEREDOC_REGEX = re.compile(r"<<(?P<indent>~?)(?:\"(?P<dq>[^\"\n]+)\"|'(?P<sq>[^'\n]+)'|(?P<bare>[A-Za-z_]\w*))")
+REGEX_KEYWORDS = ("split", "if", "unless", "and", "or", "not", "return", "grep", "map", "when", "while", "until", "x")
+PACKAGE_REGEX = re.compile(r"^[ \t]*package\s+(?P<name>[A-Za-z_][\w:]*)(?:\s+v?[\d._]+)?\s*(?P<ending>[;{])", re.MULTILINE)
+SUB_REGEX = re.compile(r"(?<![\w$@%&>:-])sub\s+(?P<name>[A-Za-z_][\w:]*)(?P<signature>\s*\([^()]*\))?"
+                       r"(?P<attrs>(?:\s*:\s*\w+(?:\([^()]*\))?)*)\s*(?P<ending>[{;])")
+USE_REGEX = re.compile(r"^[ \t]*(?P<kind>use|no|require)\s+(?P<module>[A-Za-z_][\w:]*)(?P<args>[^;]*);", re.MULTILINE)
+PARENT_REGEX = re.compile(r"^[ \t]*(?:use\s+(?:parent|base)\b(?P<use>[^;]*)|our\s+@ISA\s*=(?P<isa>[^;]*));", re.MULTILINE)
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def scan_delimited(text: str, i: int, close_ch: str, open_ch: str = None):
+    """Scans literal body from i up to close_ch (paired delimiters nest), returns (offset of closing delimiter or len, closed)."""
+    n = len(text)
+    depth = 1
+    while i < n:
+        ch = text[i]
+        if ch == "\\":
+            i += 2
+            continue
+        if open_ch and ch == open_ch:
+            depth += 1
+        elif ch == close_ch:
+            depth -= 1
+            if depth == 0:
+                return i, True
+        i += 1
+    return n, False
+
+
+def is_regex_start(text: str, i: int) -> bool:
+    """Slash starts a match (not division) after operators, openers and list keywords."""
+    j = i - 1
+    while j >= 0 and text[j] in " \t":
+        j -= 1
+    if j < 0 or text[j] in "\n(,=~!|&{};?:[":
+        return True
+    word = re.search(r"(\w+)$", text[max(0, j - 9):j + 1])
+    return bool(word) and word.group(1) in REGEX_KEYWORDS and not re.search(r"[$@%&>]\w*$", text[max(0, j - 10):j + 1])
+
+
+def strip_perl_literals(text: str, warn=None) -> str:
+    """Blanks Perl strings, quote-like operators (q, qq, qw, m, qr, s, tr, y), regexes, heredocs, POD and `#` comments with spaces.
+
+    POD sections (`=head1` ... `=cut`) and text after `__END__` / `__DATA__` are blanked like comments. Delimiters and heredoc
+    markers are kept and positions are preserved, so `use` import lists are read from the source by offsets.
+    """
+    out = []
+    pending = []   # heredocs opened on current line: (indented terminator, marker)
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        line_start = i == 0 or text[i - 1] == "\n"
+        if ch == "\n" and pending:
+            out.append(ch)
+            i += 1
+            for indented, marker in pending:
+                closing = re.compile(r"^" + (r"[ \t]*" if indented else "") + re.escape(marker) + r"[ \t]*$", re.MULTILINE).search(text, i)
+                if not closing and warn:
+                    warn(f"Unterminated heredoc {marker} at line {text.count(chr(10), 0, i)}")
+                end = closing.end() if closing else n
+                out.append(blank(text[i:end]))
+                i = end
+            pending = []
+            continue
+        if line_start and re.match(r"=[a-zA-Z]", text[i:i + 2]):
+            closing = re.compile(r"^=cut\b[^\n]*", re.MULTILINE).search(text, i)
+            end = closing.end() if closing else n
+            out.append(blank(text[i:end]))
+            i = end
+            continue
+        if line_start and re.match(r"__(?:END|DATA)__\b", text[i:i + 8]):
+            out.append(blank(text[i:]))
+            break
+        if ch == "#" and not (i > 0 and text[i - 1] == "$"):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        prev = text[i - 1] if i > 0 else "\n"
+        if ch == "<" and (heredoc := HEREDOC_REGEX.match(text, i)) and not prev.isalnum():
+            pending.append((bool(heredoc.group('indent')), heredoc.group('dq') or heredoc.group('sq') or heredoc.group('bare')))
+            out.append(heredoc.group(0))
+            i = heredoc.end()
+            continue
+        if ch in "\"'`":
+            end, closed = scan_delimited(text, i + 1, ch)
+            if not closed and warn:
+                warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+            out.append(ch + blank(text[i + 1:end]) + (ch if closed else ""))
+            i = end + 1 if closed else end
+            continue
+        quote_like = QUOTE_LIKE_REGEX.match(text, i) if not (prev.isalnum() or prev in "_$@%&>:-{") else None
+        if quote_like and re.match(r"\s*=>", text[quote_like.end('op'):]):
+            quote_like = None   # hash key like `q => 1`
+        if quote_like:
+            delim = quote_like.group('delim') or quote_like.group('spaced')
+            parts = 2 if quote_like.group('op') in ("s", "tr", "y") else 1
+            j = quote_like.end()
+            out.append(text[i:j])
+            for part in range(parts):
+                close_ch = PAIRED_DELIMITERS.get(delim, delim)
+                end, closed = scan_delimited(text, j, close_ch, delim if delim in PAIRED_DELIMITERS else None)
+                out.append(blank(text[j:end]) + (close_ch if closed else ""))
+                j = end + 1 if closed else end
+                if part + 1 < parts and delim in PAIRED_DELIMITERS:
+                    gap = re.match(r"\s*", text[j:]).end()   # `s{...} {...}` second part has own delimiters
+                    out.append(text[j:j + gap])
+                    j += gap
+                    delim = text[j] if j < n else delim
+                    if j < n:
+                        out.append(delim)
+                        j += 1
+            if not closed and warn:
+                warn(f"Incomplete {quote_like.group('op')} literal at line {text.count(chr(10), 0, i) + 1}")
+            i = j
+            continue
+        if ch == "/" and is_regex_start(text, i):
+            end, closed = scan_delimited(text, i + 1, "/")
+            if closed and "\n" not in text[i:end]:
+                out.append("/" + blank(text[i + 1:end]) + "/")
+                i = end + 1
+                continue
+        out.append(ch)
+        i += 1
+    return "".join(out)
+
+
+def word_list(raw: str) -> list:
+    """Words of `qw(a b)`, `('a', "b")` or `'a', 'b'` import / parent lists, options like -norequire dropped."""
+    raw = re.sub(r"(?<![\w$@%&])-\w+", "", re.sub(r"^\s*qw\s*", "", raw.strip()))
+    return [w for w in re.findall(r"[$@%&:]?[A-Za-z_][\w:]*", raw) if w != "qw"]
+
+
+class DefinitionParser(EntityParser):
+    """Parser for packages (statement form lasts until the next package or enclosing block end, block form by braces) and named subs.
+
+    Subs declared inside a package get it as parent, forward declarations and nested or anonymous subs are skipped;
+    parents from `use parent` / `use base` / `our @ISA` are stored for packages. Names starting with `_` are private by convention.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw = self.owner.content_text
+        depth = [0] * (len(content) + 1)
+        level = 0
+        for i, ch in enumerate(content):
+            depth[i] = level
+            level = max(level + (1 if ch == "{" else -1 if ch == "}" else 0), 0)
+        depth[len(content)] = level
+
+        packages = []
+        matches = list(PACKAGE_REGEX.finditer(content))
+        for index, match in enumerate(matches):
+            start = match.start('name')
+            if match.group('ending') == "{":
+                end = self.block_end(content, depth, match.end() - 1)
+            else:
+                outer = depth[start]
+                end = next((j for j in range(match.end(), len(content)) if content[j] == "}" and depth[j] == outer - 1), len(content)) \
+                    if outer else len(content)
+                later = [m.start() for m in matches[index + 1:] if depth[m.start('name')] == outer]
+                end = min([end] + later)
+                end = len(content[:end].rstrip()) - 1
+            packages.append({"name": match.group('name'), "first_line": self.owner.find_line(start),
+                             "last_line": self.owner.find_line(max(end, start)), "parents": []})
+        for match in PARENT_REGEX.finditer(content):
+            line_num = self.owner.find_line(match.start())
+            group = 'use' if match.group('use') is not None else 'isa'
+            owners = [p for p in packages if p["first_line"] <= line_num <= p["last_line"]]
+            if owners:
+                owners[-1]["parents"] += word_list(raw[match.start(group):match.end(group)])
+        for package in packages:
+            extra = {"parent": ", ".join(package["parents"])} if package["parents"] else {}
+            self.add("package", package["name"], package["first_line"], package["last_line"], extra)
+
+        subs = []
+        for match in SUB_REGEX.finditer(content):
+            if match.group('ending') == ";":
+                continue   # forward declaration
+            first_line = self.owner.find_line(match.start())
+            last_line = self.owner.find_line(self.block_end(content, depth, match.end() - 1))
+            if any(s[0] < first_line <= s[1] for s in subs):
+                continue   # nested named sub belongs to the enclosing one
+            subs.append((first_line, last_line))
+            name = match.group('name')
+            owners = [p for p in packages if p["first_line"] < first_line <= p["last_line"]]
+            parent = owners[-1]["name"] if owners else ""
+            if "::" in name:
+                parent, name = name.rsplit("::", 1)
+            extra = {"parent": parent}
+            if match.group('signature'):
+                extra["signature"] = " ".join(match.group('signature').split())
+            attrs = re.findall(r":\s*(\w+)", match.group('attrs') or "")
+            if attrs:
+                extra["attributes"] = attrs
+            self.add("function", name, first_line, last_line, extra)
+        return True
+
+    def block_end(self, content, depth, open_pos):
+        """Offset of brace closing the block opened at open_pos."""
+        end = next((j for j in range(open_pos + 1, len(content)) if content[j] == "}" and depth[j] == depth[open_pos] + 1), -1)
+        if end < 0:
+            self.owner.parse_warn(f"Unclosed block at line {self.owner.find_line(open_pos)} in file {self.owner.file_name}")
+            return open_pos
+        return end
+
+    def add(self, e_type, name, first_line, last_line, extra):
+        visibility = "private" if name.startswith("_") else "public"
+        if self.owner.add_definition(e_type, name, first_line, last_line, extra, visibility):
+            self.new_entities_lines.append(first_line)
+
+
+class DepsParserPerl(DepsParser):
+    """Parser for `use` / `require` of modules (lowercase pragmas skipped), import lists and `use parent` / `use base` classes."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw = self.owner.content_text
+        for match in USE_REGEX.finditer(content):
+            module = match.group('module')
+            args = raw[match.start('args'):match.end('args')]
+            if module in ("parent", "base"):
+                if "-norequire" not in args:
+                    for name in word_list(args):
+                        self.add_module(name)
+                continue
+            if match.group('kind') == "no" or module[0].islower():
+                continue   # pragmas: strict, warnings, lib, constant...
+            self.add_module(module)
+            for name in word_list(args):
+                if name[0] != ":":
+                    self.add_import(module, name)   # `:all` tags are not entities
+        return True
+
+
+class ContentCodePerl(ContentBlock):
+    """Parser for Perl content blocks (.pl, .pm, .t)."""
+    supported_types = [".pl", ".pm", ".t"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "perl"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodePerl with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips literals, heredocs, POD and comments with strip_perl_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_perl_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": self.module_prefix + name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Perl content to extract packages, subs and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserPerl(self),
+            DefinitionParser("definition", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodePerl)
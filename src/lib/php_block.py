@@ -1,4 +1,4 @@
-# /lib/php_block.py, updated 2025-08-08 18:30 EEST
+# /lib/php_block.py, updated 2026-10-14 16:50 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
@@ -17,9 +17,105 @@ PHP_CLASS_HEAD = (
     BASE_REGEX_PATTERN
     + r"(?:(?:abstract|final|readonly)\s+)*"
     + r"class\s+(?P<name>\w+)"
-    + r"(?P<parent>\s+extends\s+[\w\\]+)?"
+    + r"(?:\s+extends\s+(?P<parent>[\w\\]+))?"
+    + r"(?:\s+implements\s+(?P<implements>[\w\\]+(?:\s*,\s*[\w\\]+)*))?"
 )
-ARGS_REGEX_PATTERN = r"(?P<args>[\^)]*)"
+ARGS_REGEX_PATTERN = r"(?P<args>[^)]*)"
+OPEN_TAG_REGEX = re.compile(r"<\?(?:php\b|=|(?!xml))", re.IGNORECASE)
+HEREDOC_START = re.compile(r"<<<[ \t]*(['\"]?)([A-Za-z_]\w*)\1[ \t]*\r?\n")   # heredoc, nowdoc with single quotes
+
+
+def _skip_braces(text: str, i: int) -> int:
+    """Skips `{$...}` interpolation starting at the open brace, nested strings included."""
+    n = len(text)
+    depth = 0
+    while i < n:
+        ch = text[i]
+        if ch in "'\"":
+            i = _scan_php_string(text, i)[0]
+            continue
+        depth += {'{': 1, '}': -1}.get(ch, 0)
+        i += 1
+        if depth == 0:
+            break
+    return i
+
+
+def _scan_php_string(text: str, i: int):
+    """Scans quoted string starting at i, returns (end, closed)."""
+    quote = text[i]
+    n = len(text)
+    j = i + 1
+    while j < n and text[j] != quote:
+        if text[j] == "\\":
+            j += 2
+            continue
+        if quote != "'" and text.startswith("{$", j):
+            j = _skip_braces(text, j)
+            continue
+        j += 1
+    return (j + 1, True) if j < n else (n, False)
+
+
+def _strip_php_code(text: str, i: int, out: list, warn=None) -> int:
+    """Blanks literals of PHP code from i up to the closing `?>` tag, returns position after it."""
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("?>", i):
+            return i + 2
+        if text.startswith("//", i) or (ch == "#" and not text.startswith("#[", i)):
+            j = i
+            while j < n and text[j] != "\n" and not text.startswith("?>", j):   # closing tag ends comment too
+                j += 1
+            out.append("//")
+            i = j
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if text.startswith("<<<", i) and (match := HEREDOC_START.match(text, i)):
+            closing = re.compile(rf"^[ \t]*{match.group(2)}\b", re.MULTILINE).search(text, match.end())   # flexible syntax of PHP 7.3
+            if not closing:
+                if warn:
+                    warn(f"Incomplete heredoc {match.group(2)} at line {text.count(chr(10), 0, i) + 1}")
+                out.append('"' + "\n" * text.count("\n", i))
+                return n
+            out.append('"' + "\n" * text.count("\n", i, closing.start()) + '"')
+            i = closing.end()
+            continue
+        if ch in "'\"`":
+            end, closed = _scan_php_string(text, i)
+            if not closed and warn:
+                warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+            out.append(ch + "\n" * text.count("\n", i, end) + (ch if closed else ""))
+            i = end
+            continue
+        out.append(ch)
+        i += 1
+    return n
+
+
+def strip_php_literals(text: str, warn=None) -> str:
+    """Blanks inline HTML outside of PHP tags, string literals, heredoc and nowdoc bodies, preserving line count.
+
+    Open tags `<?php`, `<?=` and short `<?` switch to code, `?>` returns to HTML (also from a single-line comment).
+    Comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        match = OPEN_TAG_REGEX.search(text, i)
+        end = match.start() if match else n
+        out.append("\n" * text.count("\n", i, end))   # inline HTML is not code
+        if not match:
+            break
+        i = _strip_php_code(text, match.end(), out, warn)
+    return "".join(out)
 
 
 callable_regex = IterativeRegex()
@@ -29,7 +125,7 @@ callable_regex.add_token(
 ).add_token(
     ARGS_REGEX_PATTERN + r"\)", ["args"], 1
 ).add_token(
-    r"\s*(?P<ending>{|;)", ["head_end"], 1
+    r"(?:\s*:\s*\??[\w\\|]+)?\s*(?P<ending>{|;)", ["head_end"], 1
 )
 
 
@@ -39,22 +135,30 @@ class ClassParser(EntityParser):
         outer_regex = IterativeRegex()
         outer_regex.add_token(
             PHP_CLASS_HEAD,
-            ["indent", "vis", "name"], 2
+            ["indent", "vis", "name", "parent", "implements"], 2
         ).add_token(
-            r"\s*:", ["head_end"], 1
+            r"\s*{", ["head_end"], 1
         )
         super().__init__(entity_type, owner, outer_regex, r"\bclass\b", inner_regex=callable_regex, default_visibility="public")
 
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and (implements := match_value(base_match, 'implements')):
+            entity["implements"] = re.sub(r"\s+", " ", implements.strip())
+        return True
+
 
 class InterfaceParser(EntityParser):
     """Parser for PHP interfaces and their methods."""
     def __init__(self, entity_type, owner):
         outer_regex = IterativeRegex()
         outer_regex.add_token(
-            BASE_REGEX_PATTERN + r"interface\s+(?P<name>\w+)",
-            ["indent", "vis", "name"], 2
+            BASE_REGEX_PATTERN + r"interface\s+(?P<name>\w+)(?:\s+extends\s+(?P<parent>[\w\\]+(?:\s*,\s*[\w\\]+)*))?",
+            ["indent", "vis", "name", "parent"], 2
         ).add_token(
-            r"\s*:", ["head_end"], 1
+            r"\s*{", ["head_end"], 1
         )
         super().__init__(entity_type, owner, outer_regex, r"\binterface\b", inner_regex=callable_regex, default_visibility="public")
 
@@ -67,7 +171,7 @@ class TraitParser(EntityParser):
             BASE_REGEX_PATTERN + r"trait\s+(?P<name>\w+)",
             ["indent", "vis", "name"], 2
         ).add_token(
-            r"\s*:", ["head_end"], 1
+            r"\s*{", ["head_end"], 1
         )
         super().__init__(entity_type, owner, outer_regex, r"\btrait\b", inner_regex=callable_regex, default_visibility="public")
 
@@ -85,6 +189,39 @@ class EnumParser(EntityParser):
         super().__init__(entity_type, owner, outer_regex, r"\benum\b", inner_regex=None, default_visibility="public")
 
 
+class NamespaceParser(EntityParser):
+    """Parser for `namespace Name;` (scope up to next namespace) and braced `namespace Name {...}` declarations."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bnamespace\b", default_visibility="public")
+
+    def parse(self):
+        self.owner.namespaces = []
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*namespace\s+(?P<name>[\w\\]+)\s*(?P<ending>[;{])", line) if isinstance(line, str) else None
+            if not match:
+                continue
+            last_line = line_num
+            if match.group('ending') == '{':
+                last_line = self.owner.detect_bounds(line_num, self.owner.clean_lines)[1]
+            elif self.owner.namespaces and self.owner.namespaces[-1][2] is None:
+                self.owner.namespaces[-1][2] = line_num - 1
+            self.owner.namespaces.append([match.group('name'), line_num, last_line if match.group('ending') == '{' else None])
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": last_line,
+                "tokens": estimate_tokens("\n".join(self.owner.clean_lines[line_num:last_line + 1])),
+                "parent": "",
+            }
+            self.new_entities_lines.append(line_num)
+        if self.owner.namespaces and self.owner.namespaces[-1][2] is None:
+            self.owner.namespaces[-1][2] = len(self.owner.clean_lines) - 1
+        return bool(self.owner.namespaces)
+
+
 class FunctionParser(EntityParser):
     """Parser for PHP functions."""
     def __init__(self, entity_type, owner):
@@ -112,6 +249,29 @@ class DepsParserPHP(DepsParser):
         if module:
             self.add_module(module)
 
+    def parse(self):
+        result = super().parse()
+        self.parse_uses()
+        return result
+
+    def parse_uses(self):
+        """Adds `use` imports (aliases, grouped, function/const); trait `use` inside class-like bodies is skipped."""
+        bodies = [(e["first_line"], e["last_line"]) for e in self.owner.entity_map.values()
+                  if e["type"] in ("class", "interface", "trait", "enum")]
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*use\s+(?:(?:function|const)\s+)?(?P<body>[^;]+);", line) if isinstance(line, str) else None
+            if not match or any(first < line_num <= last for first, last in bodies):
+                continue
+            body = match.group('body').strip()
+            group = re.match(r"(?P<prefix>[\w\\]+)\\\{(?P<items>[^}]*)\}", body)
+            items = [f"{group.group('prefix')}\\{item.strip()}" for item in group.group('items').split(",") if item.strip()] \
+                if group else [item.strip() for item in body.split(",")]
+            for item in items:
+                path, _, alias = item.partition(" as ")
+                module, _, name = path.strip().lstrip("\\").rpartition("\\")
+                self.add_module(module or name)
+                self.add_import(module or name, alias.strip() or name)
+
 class ContentCodePHP(ContentBlock):
     """Parser for PHP content blocks."""
     supported_types = [".php"]
@@ -124,17 +284,16 @@ class ContentCodePHP(ContentBlock):
         self.open_ml_string = []
         self.close_ml_string = []
         self.open_sl_comment = ["//", "#"]
-        self.open_ml_comment.append(r"\?>")
-        self.close_ml_comment.append(r"<\?php")
         self.escape_char = "\\"
         self.entity_map = {}
+        self.namespaces = []
         self.module_prefix = kwargs.get("module_prefix", "")
         logging.debug(f"Initialized ContentCodePHP with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
 
 
 
     def strip_strings(self):
-        """Strips string literals from PHP content, preserving module names in require/include."""
+        """Strips inline HTML, string literals and heredoc/nowdoc from PHP content, preserving module names in require/include."""
         content = self.content_text
 
         #  Very matter quotes duplication for import lines
@@ -144,11 +303,34 @@ class ContentCodePHP(ContentBlock):
             content,
             flags=re.MULTILINE
         )
-        self.clean_lines = [""] + protected_content.splitlines()
+        clean_text = strip_php_literals(
+            protected_content,
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
         if len(self.clean_lines) <= 1:
             self.clean_lines.append("")
-        super().strip_strings()
+        self.get_clean_content()
+        return self.clean_lines
 
+    def check_entity_placement(self, line_num: int, name: str):
+        """Qualified names use backslash separators, only the last segment is placed on the line."""
+        return super().check_entity_placement(line_num, name.split("\\")[-1])
+
+    def apply_namespaces(self):
+        """Qualifies top-level entities with their namespace, methods follow the renamed parents."""
+        renamed = {}
+        for line_num, entity in self.entity_map.items():
+            if entity["type"] == "namespace" or "\\" in entity["name"] or "method" in entity["type"]:
+                continue
+            ns = next((name for name, first, last in self.namespaces if first < line_num <= last), None)
+            if ns:
+                renamed[(entity["name"], ns)] = f"{ns}\\{entity['name']}"
+                entity["name"] = f"{ns}\\{entity['name']}"
+        for line_num, entity in self.entity_map.items():
+            ns = next((name for name, first, last in self.namespaces if first < line_num <= last), None)
+            if "method" in entity["type"] and (entity.get("parent"), ns) in renamed:
+                entity["parent"] = renamed[(entity["parent"], ns)]
 
     def check_raw_escape(self, line: str, position: int, quote_char: str) -> bool:
         """Checks if the character at position is part of a PHP raw string escape sequence."""
@@ -170,6 +352,7 @@ class ContentCodePHP(ContentBlock):
         self.strip_comments()
 
         parsers = [
+            NamespaceParser("namespace", self),
             InterfaceParser("interface", self),
             TraitParser("trait", self),
             EnumParser("enum", self),
@@ -193,6 +376,7 @@ class ContentCodePHP(ContentBlock):
                 continue
 
         self.clean_lines = original_clean_lines
+        self.apply_namespaces()
         entities = self.sorted_entities()
         logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
         return {"entities": entities, "dependencies": self.dependencies}
@@ -0,0 +1,382 @@
+# /lib/powershell_block.py, created 2026-10-14 20:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+KEYWORDS = ("if", "elseif", "else", "foreach", "for", "while", "do", "until", "switch", "try", "catch", "finally", "trap",
+            "function", "filter", "param", "begin", "process", "end", "return", "throw", "class", "enum")
+COMMENT_PREFIX_CHARS = " \t\n;(){}|"
+MANIFEST_MODULE_KEYS = ("RootModule", "ModuleToProcess", "NestedModules", "RequiredModules")
+
+
+def _skip_subexpression(text: str, i: int) -> int:
+    """Skips `$(...)` body starting after the open paren, nested strings included."""
+    n = len(text)
+    depth = 1
+    while i < n and depth > 0:
+        if text[i] in "\"'":
+            i = _scan_quoted(text, i)[0]
+            continue
+        depth += {'(': 1, ')': -1}.get(text[i], 0)
+        i += 1
+    return i
+
+
+def _scan_quoted(text: str, i: int):
+    """Scans quoted string from i: quote doubling in both forms, backtick escapes and `$(...)` in double quoted ones."""
+    quote = text[i]
+    n = len(text)
+    j = i + 1
+    while j < n:
+        if text[j] == quote and text.startswith(quote * 2, j):
+            j += 2
+        elif text[j] == quote:
+            return j + 1, True
+        elif quote == '"' and text[j] == "`":
+            j += 2
+        elif quote == '"' and text.startswith("$(", j):
+            j = _skip_subexpression(text, j + 2)
+        else:
+            j += 1
+    return n, False
+
+
+def strip_powershell_literals(text: str, warn=None) -> str:
+    """Blanks PowerShell strings and here-strings (`@"..."@`, `@'...'@`), drops `#` and `<# #>` comments, preserving line count.
+
+    Here-string terminator must start the line, as PowerShell requires.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("<#", i):
+            end = text.find("#>", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("\n" * text.count("\n", i, end))
+            i = end
+            continue
+        if ch == "#" and (i == 0 or text[i - 1] in COMMENT_PREFIX_CHARS):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            continue
+        if ch == "`":
+            out.append(text[i:i + 2])
+            i += 2
+            continue
+        here = re.match(r"@([\"'])[ \t]*\n", text[i:i + 64])
+        if here:
+            match = re.compile(r"^" + here.group(1) + "@", re.MULTILINE).search(text, i + here.end())
+            end, closed = (match.end(), True) if match else (n, False)
+        elif ch in "\"'":
+            end, closed = _scan_quoted(text, i)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def param_names(text: str) -> list:
+    """Names of parameters declared in `param(...)` or function argument list text."""
+    return re.findall(r"\$(\w+)\s*(?==|,|\)|$)", re.sub(r"\[[^\[\]]*(?:\[[^\]]*\][^\[\]]*)*\]", "", text))
+
+
+class PowerShellEntityParser(EntityParser):
+    """Base for PowerShell parsers: with Export-ModuleMember functions not listed are private."""
+    def detect_visibility(self, match):
+        exported = self.owner.exported_functions
+        return "public" if exported is None or match.group('name').lower() in exported else "private"
+
+
+class FunctionParser(PowerShellEntityParser):
+    """Parser for `function`/`filter`/`workflow` definitions, scope modifier (`global:`) is dropped from name."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"(?im)^(?P<indent>[ \t]*)(?P<kind>function|filter|workflow)\s+(?:(?:global|script|local|private):)?(?P<name>[\w-]+)",
+                       ["indent", "kind", "name"], 2)\
+            .add_token(r"(?:\s*\((?P<args>[^)]*)\))?\s*{", ["args"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?i)\b(?:function|workflow)\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        line_num = self.owner.find_line(base_match.start('name'))
+        entity = self.owner.entity_map.get(line_num)
+        if not entity:
+            return True
+        kind = base_match.group('kind').lower()
+        if kind != "function":
+            entity["type"] = kind
+        validation = self.outer_regex.validate_match(self.content, base_match.start())
+        if args := match_value(validation['match'], 'args'):
+            entity["params"] = param_names(args)
+        elif block := self.owner.find_param_block(line_num, entity["last_line"]):
+            entity["params"] = param_names(block)
+        return True
+
+
+class TypeParser(PowerShellEntityParser):
+    """Parser for `class Name : Base, IFace` and `enum Name` definitions."""
+    def __init__(self, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"(?im)^(?P<indent>[ \t]*){keyword}\s+(?P<name>\w+)", ["indent", "name"], 2)\
+            .add_token(r"(?:\s*:\s*(?P<parent>[\w.,\s]+?))?\s*{", ["parent"], 1)
+        super().__init__(keyword, owner, outer_regex, rf"(?i)\b{keyword}\b", default_visibility="public")
+
+    def detect_visibility(self, match):
+        return "public"
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and entity.get("parent"):
+            entity["parent"] = ", ".join(p.strip() for p in entity["parent"].split(",") if p.strip())
+        return True
+
+
+class MethodParser(PowerShellEntityParser):
+    """Parser for class methods and constructors: `[static] [hidden] [Type] Name(args) {`."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"(?im)^(?P<indent>[ \t]+)(?P<mods>(?:(?:static|hidden)\s+)*)(?:\[[^\]\n]+\]\s*)?(?P<name>\w+)\s*\((?P<args>[^)]*)\)",
+                       ["indent", "mods", "name", "args"], 2)\
+            .add_token(r"\s*{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def detect_visibility(self, match):
+        return "private" if "hidden" in match_value(match, 'mods', '').lower() else "public"
+
+    def _process_match(self, base_match):
+        if base_match.group('name').lower() in KEYWORDS:
+            return False
+        line_num = self.owner.find_line(base_match.start('name'))
+        classes = [e for e in self.owner.entity_map.values() if e["type"] == "class" and e["first_line"] < line_num <= e["last_line"]]
+        if not classes or any(e["type"] not in ("class", "enum") and e["first_line"] < line_num <= e["last_line"]
+                                  for e in self.owner.entity_map.values()):
+            return False
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(line_num)
+        if entity:
+            entity["parent"] = classes[-1]["name"]
+            entity["params"] = param_names(base_match.group('args'))
+            if base_match.group('name') == classes[-1]["name"]:
+                entity["type"] = "constructor"
+            elif "static" in base_match.group('mods').lower():
+                entity["type"] = "static method"
+        return True
+
+
+class ParamBlockParser(EntityParser):
+    """Parser for script-level `param(...)` block, stored as entity with parameter names."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        match = re.search(r"(?im)^[ \t]*(?:\[CmdletBinding\([^)]*\)\]\s*)?(?P<name>param)\s*\(", self.owner.get_clean_content())
+        if not match:
+            return False
+        line_num = self.owner.find_line(match.start('name'))
+        if any(e["first_line"] <= line_num <= e["last_line"] for e in self.owner.entity_map.values()):
+            return False   # param block of a function
+        block = self.owner.find_param_block(line_num - 1, line_num)
+        self.owner.entity_map[line_num] = {
+            "type": self.entity_type,
+            "name": "param",
+            "visibility": self.default_visibility,
+            "file_id": self.owner.file_id,
+            "first_line": line_num,
+            "last_line": line_num + block.count("\n"),
+            "tokens": estimate_tokens(block),
+            "params": param_names(block)
+        }
+        self.new_entities_lines.append(line_num)
+        return True
+
+
+class ManifestParser(DepsParser):
+    """Parser for module manifest (.psd1): hashtable stored as `manifest` entity, listed modules become dependencies."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def manifest_list(self, key: str) -> list:
+        match = re.search(rf"(?im)^\s*{key}\s*=\s*(?P<value>@\([^)]*\)|'[^'\n]*'|\"[^\"\n]*\"|@{{[^}}]*}})", self.owner.content_text)
+        if not match:
+            return []
+        return [m.group(1) or m.group(2) for m in re.finditer(r"(?:ModuleName\s*=\s*)?'([^']+)'|\"([^\"]+)\"", match.group('value'))
+                if not re.search(r"(?i)ModuleVersion|GUID", match.group('value')[max(0, m.start() - 20):m.start()])]
+
+    def parse(self):
+        if self.owner.content_type != ".psd1":
+            return False
+        for key in MANIFEST_MODULE_KEYS:
+            for module in self.manifest_list(key):
+                self.add_module(module)
+        line_num = next((i for i, line in enumerate(self.owner.clean_lines[1:], 1) if isinstance(line, str) and "@{" in line), 0)
+        if line_num:
+            name = Path(self.owner.file_name).stem
+            self.owner.entity_map[line_num] = {
+                "type": "manifest",
+                "name": name,
+                "visibility": "public",
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": self.owner.detect_bounds(line_num, self.owner.clean_lines)[1],
+                "tokens": estimate_tokens(self.owner.content_text),
+                "functions": self.manifest_list("FunctionsToExport"),
+                "version": next(iter(self.manifest_list("ModuleVersion")), "")
+            }
+        return True
+
+
+class DepsParserPowerShell(DepsParser):
+    """Parser for `Import-Module`, `using module/namespace` and dot-sourced scripts (`. $PSScriptRoot\\lib.ps1`)."""
+    IMPORT_REGEX = re.compile(r"(?im)^[ \t]*(?:Import-Module\s+(?:-Name\s+)?|using\s+(?:module|namespace)\s+)['\"]?(?P<module>[\w.\\/$:-]+)")
+    SOURCE_REGEX = re.compile(r"^[ \t]*\.\s+['\"]?(?P<script>[^\s'\"]+\.ps1)")
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        lines = [""] + self.owner.content_text.splitlines()
+        for line_num, clean_line in enumerate(self.owner.clean_lines[1:], 1):
+            if not isinstance(clean_line, str) or not clean_line.strip() or line_num >= len(lines):
+                continue   # commented out and here-string lines are skipped
+            if (match := self.IMPORT_REGEX.match(lines[line_num])) and re.search(r"(?i)\.ps[dm]?1$", match.group('module')):
+                self.add_source(match.group('module').replace("\\", "/"))   # module imported by path
+            elif match:
+                self.add_module(match.group('module'))
+            elif match := self.SOURCE_REGEX.match(lines[line_num]):
+                self.add_source(match.group('script').replace("\\", "/"))
+        return True
+
+    def add_source(self, script: str):
+        if script.startswith("$"):
+            _, sep, script = script.partition("/")   # $PSScriptRoot/lib.ps1
+            if not sep:
+                return
+        script_path = os.path.normpath(f"{Path(self.owner.file_name).parent}/{script}").replace("\\", "/")
+        self.add_module(script_path if script_path.startswith("/") else f"/{script_path}")
+
+
+class ContentCodePowerShell(ContentBlock):
+    """Parser for PowerShell content blocks: scripts (.ps1), modules (.psm1) and manifests (.psd1)."""
+    supported_types = [".ps1", ".psm1", ".psd1"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "powershell"
+        self.entity_map = {}
+        self.exported_functions = None
+        self.open_sl_comment = []   # comments are dropped by strip_powershell_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodePowerShell with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips strings and here-strings with comments, using strip_powershell_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_powershell_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """One-line functions and methods are regular in PowerShell, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def find_param_block(self, first_line: int, last_line: int) -> str:
+        """Text of the first `param(...)` block starting within lines (first_line, last_line], parens balanced."""
+        content = self.get_clean_content()
+        start = self.line_offsets[first_line] if first_line < len(self.line_offsets) else 0
+        match = re.compile(r"(?i)\bparam\s*\(").search(content, start)
+        if not match or self.find_line(match.start()) > last_line:
+            return ""
+        depth = 0
+        for pos in range(match.end() - 1, len(content)):
+            depth += {'(': 1, ')': -1}.get(content[pos], 0)
+            if depth == 0:
+                return content[match.start():pos + 1]
+        return content[match.start():]
+
+    def detect_exports(self):
+        """Functions listed in Export-ModuleMember -Function (lower-cased), None when module exports everything."""
+        exported = None
+        for match in re.finditer(r"(?im)^[ \t]*Export-ModuleMember\b(?P<args>[^\n]*)", self.content_text):
+            functions = re.search(r"(?i)-Function\s+(?P<names>[\w\-*'\",\s@()]+?)(?:\s+-\w+|\s*$)", match.group('args'))
+            names = re.findall(r"[\w-]+", functions.group('names')) if functions else []
+            if exported is None:
+                exported = set()
+            exported.update(name.lower() for name in names)
+            if "*" in (functions.group('names') if functions else ""):
+                return None
+        return exported
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses PowerShell content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.exported_functions = self.detect_exports()
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            ManifestParser(self),
+            DepsParserPowerShell(self),
+            TypeParser(self, "class"),
+            TypeParser(self, "enum"),
+            FunctionParser("function", self),
+            MethodParser("method", self),
+            ParamBlockParser("param block", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodePowerShell)
@@ -0,0 +1,253 @@
+# /lib/proto_block.py, updated 2026-10-16 16:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+DECL_REGEX = re.compile(r"\b(?P<kind>message|enum|service|oneof|extend)\s+(?P<name>[\w.]+)\s*\{|"
+                        r"\brpc\s+(?P<rpc>\w+)\s*\(\s*(?P<request>(?:stream\s+)?[\w.]+)\s*\)\s*"
+                        r"returns\s*\(\s*(?P<response>(?:stream\s+)?[\w.]+)\s*\)\s*(?P<rpc_end>[{;])|"
+                        r"(?P<open>\{)|(?P<close>\})")
+FIELD_REGEX = re.compile(r"^\s*(?:(?P<label>repeated|optional|required)\s+)?(?P<type>map\s*<[^>]*>|[\w.]+)\s+(?P<name>\w+)\s*=\s*(?P<number>\d+)")
+ENUM_VALUE_REGEX = re.compile(r"^\s*(?P<name>[A-Za-z_]\w*)\s*=\s*(?P<number>-?\w+)")
+TOP_LEVEL_KINDS = ("message", "enum", "service")
+
+
+def strip_proto_literals(text: str, warn=None) -> str:
+    """Blanks Protobuf string literals (both quote styles), preserving line count and positions of code.
+
+    Quotes of blanked literals are kept, comment bodies are dropped and the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            end = text.find("*/", i + 2)
+            end = n if end < 0 else end + 2
+            out.append("/*" + "\n" * text.count("\n", i, end) + "*/")
+            i = end
+            continue
+        if ch not in "\"'":
+            out.append(ch)
+            i += 1
+            continue
+        j = i + 1
+        while j < n and text[j] not in (ch, "\n"):
+            j += 2 if text[j] == "\\" else 1
+        closed = j < n and text[j] == ch
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append(ch + (ch if closed else ""))
+        i = j + 1 if closed else min(j, n)
+    return "".join(out)
+
+
+class PackageParser(EntityParser):
+    """Parser for the `package` statement, stored as single-line entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bpackage\b", default_visibility="public")
+
+    def parse(self):
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*package\s+(?P<name>[\w.]+)\s*;", line) if isinstance(line, str) else None
+            if not match:
+                continue
+            self.owner.package = match.group('name')
+            self.owner.add_definition(self.entity_type, match.group('name'), line_num, line_num, {})
+            self.new_entities_lines.append(line_num)
+            return True
+        return False
+
+
+class DefinitionParser(EntityParser):
+    """Parser for `message`, `enum` and `service` blocks with nested definitions and `rpc` methods.
+
+    Nested definitions are named by the enclosing chain (Outer.Inner), message fields (including `oneof` members)
+    and enum values are listed in extras of the owning definition.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        stack = []
+        frames = []
+        for match in DECL_REGEX.finditer(content):
+            line_num = self.owner.find_line(match.start())
+            if match.group('close') is not None:
+                if stack:
+                    frame = stack.pop()
+                    frame["last_line"] = self.owner.find_line(match.start())
+                    frame["body_end"] = match.start()
+                    frames.append(frame)
+                continue
+            parents = [f for f in stack if f["kind"] in TOP_LEVEL_KINDS]
+            parent = parents[-1] if parents else None
+            if match.group('open') is not None:
+                stack.append({"kind": "", "name": "", "first_line": line_num, "parent": parent, "body_start": match.end()})
+            elif match.group('rpc'):
+                frame = {"kind": "rpc", "name": match.group('rpc'), "first_line": line_num, "parent": parent,
+                         "request": " ".join(match.group('request').split()), "response": " ".join(match.group('response').split())}
+                if match.group('rpc_end') == "{":
+                    frame["body_start"] = match.end()
+                    stack.append(frame)
+                else:
+                    frame["last_line"] = self.owner.find_line(match.start('rpc_end'))
+                    frames.append(frame)
+            else:
+                kind = match.group('kind')
+                name = match.group('name')
+                if kind in TOP_LEVEL_KINDS and parent:
+                    name = f"{parent['name']}.{name}"
+                stack.append({"kind": kind, "name": name, "first_line": line_num, "parent": parent, "body_start": match.end()})
+        if stack:
+            self.owner.parse_warn(f"Unclosed block at line {stack[-1]['first_line']} in file {self.owner.file_name}")
+
+        self.collect_members(content, frames)
+        for frame in sorted(frames, key=lambda f: f["first_line"]):
+            if frame["kind"] not in TOP_LEVEL_KINDS + ("rpc",):
+                continue
+            extra = {"parent": frame["parent"]["name"] if frame["parent"] else ""}
+            for field in ("fields", "values", "request", "response"):
+                if field in frame:
+                    extra[field] = frame[field]
+            if self.owner.add_definition(frame["kind"], frame["name"], frame["first_line"], frame["last_line"], extra):
+                self.new_entities_lines.append(frame["first_line"])
+        return True
+
+    def collect_members(self, content: str, frames: list):
+        """Assigns field and enum value statements to the innermost closed message or enum by body offsets, so members
+        on definition line (`enum X { A = 0; }`) count too. `oneof` members go to the enclosing message."""
+        for frame in frames:
+            if frame["kind"] == "message":
+                frame["fields"] = []
+            elif frame["kind"] == "enum":
+                frame["values"] = []
+        for statement in re.finditer(r"[^{};]*=[^{};]*", content):
+            pos = statement.start()
+            inside = [f for f in frames if f.get("body_start", 0) <= pos < f.get("body_end", -1)]
+            if not inside:
+                continue
+            frame = max(inside, key=lambda f: f["body_start"])
+            if frame["kind"] == "oneof":
+                frame = frame["parent"]
+            if not frame:
+                continue
+            line_num = self.owner.find_line(pos + len(statement.group()) - len(statement.group().lstrip()))
+            if frame["kind"] == "message" and (match := FIELD_REGEX.match(statement.group())):
+                if "fields" not in frame:
+                    self.owner.parse_warn(f"Field at line {line_num} of unclosed message {frame['name']} in file {self.owner.file_name}")
+                    continue
+                field_type = " ".join(filter(None, [match.group('label'), re.sub(r"\s+", "", match.group('type'))]))
+                frame["fields"].append(f"{match.group('name')}: {field_type} = {match.group('number')}")
+            elif frame["kind"] == "enum" and (match := ENUM_VALUE_REGEX.match(statement.group())):
+                if "values" not in frame:
+                    self.owner.parse_warn(f"Value at line {line_num} of unclosed enum {frame['name']} in file {self.owner.file_name}")
+                    continue
+                frame["values"].append(f"{match.group('name')} = {match.group('number')}")
+
+
+class DepsParserProto(DepsParser):
+    """Parser for `import "path.proto";` statements, including `public` and `weak` imports; paths are read from the original text."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*import\s+(?:(?:public|weak)\s+)?[\"'](?P<module>[^\"'\n]+)[\"']\s*;", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = re.sub(r"//[^\n]*", "", self.owner.content_text)   # import paths are strings, clean_lines lost them
+        for match in self.IMPORT_REGEX.finditer(content):
+            self.add_module(match.group('module'))
+        return True
+
+
+class ContentProtobuf(ContentBlock):
+    """Parser for Protocol Buffers definitions (.proto)."""
+    supported_types = [".proto"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "proto"
+        self.entity_map = {}
+        self.package = ""
+        logging.debug(f"Initialized ContentProtobuf with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips string literals with strip_proto_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_proto_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Protobuf content to extract messages, enums, services, rpc methods and imports."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserProto(self),
+            PackageParser("package", self),
+            DefinitionParser("message", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentProtobuf)
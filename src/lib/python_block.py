@@ -1,4 +1,4 @@
-# /lib/python_block.py, updated 2025-08-08 15:27 EEST
+# /lib/python_block.py, updated 2026-10-16 08:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
@@ -16,6 +16,90 @@ from lib.iter_regex import IterativeRegex
 BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?P<spec>@classmethod\s+|@staticmethod\s+)?(?:@[\w\s()]+)?(?P<async>async\s+)?"
 FN_REGEX_PATTERN = r"def\s+(?P<name>\w+)"
 CLASS_REGEX_PATTERN = r"class\s+(?P<name>\w+)"
+DECORATOR_REGEX = re.compile(r"^\s*@(?P<name>[\w.]+)")
+
+
+def strip_python_literals(text: str):
+    """Blanks Python string literals, including triple-quoted ones, preserving line count.
+
+    Triple-quoted literals are collapsed into an empty pair of quotes on their first line, the
+    rest of the statement is moved up to that line, and the covered lines become empty, so the
+    indentation of the literal body never affects entity bounds. `#` inside literals is removed
+    together with the literal, comment bodies are dropped up to the `#` marker.
+
+    Returns:
+        tuple: (clean_text, spans), where spans lists literals that start a statement
+        (docstring candidates) as dicts with first_line, last_line (1-based) and text.
+    """
+    out = []
+    spans = []
+    pending_lines = 0   # lines swallowed by a multi-line literal, emitted after the statement line
+    line_num = 1
+    line_start = True   # only whitespace/prefix chars seen on the current line
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "\n":
+            out.append("\n" * (pending_lines + 1))
+            line_num += pending_lines + 1
+            pending_lines = 0
+            line_start = True
+            i += 1
+            continue
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append("#")   # comment body may hold unpaired quotes, marker left for the comment stripper
+            i = end
+            continue
+        if ch in "\"'":
+            quote = text[i:i + 3] if text[i:i + 3] in ('"""', "'''") else ch
+            j = i + len(quote)
+            while j < n:
+                if text[j] == "\\":
+                    j += 2
+                    continue
+                if text.startswith(quote, j):
+                    break
+                if len(quote) == 1 and text[j] == "\n":
+                    break   # unclosed single-line literal, close it at end of line
+                j += 1
+            body = text[i + len(quote):j]
+            j = min(n, j + len(quote)) if text.startswith(quote, j) else j
+            if line_start:
+                spans.append({"first_line": line_num, "last_line": line_num + body.count("\n"), "text": body})
+            pending_lines += body.count("\n")
+            out.append(ch + ch)
+            line_start = False
+            i = j
+            continue
+        if not (ch.isspace() or (line_start and ch in "rRbBuUfF")):
+            line_start = False
+        out.append(ch)
+        i += 1
+    out.append("\n" * pending_lines)
+    return "".join(out), spans
+
+
+def collect_decorators(clean_lines: list, def_line: int) -> tuple:
+    """Collects decorator names from the contiguous `@...` lines above def_line.
+
+    Returns:
+        tuple: (decorators, first_decorator_line), decorators in source order.
+    """
+    decorators = []
+    first_line = def_line
+    for line_num in range(def_line - 1, 0, -1):
+        line = clean_lines[line_num]
+        if not isinstance(line, str):
+            break
+        dec = DECORATOR_REGEX.match(line)
+        if not dec:
+            break
+        decorators.insert(0, dec.group('name'))
+        first_line = line_num
+    return decorators, first_line
 
 
 class ClassParser(EntityParser):
@@ -45,6 +129,9 @@ class ClassParser(EntityParser):
             parent = match_value(match, 'parent', '')
             indent = match_value(match, 'indent', '')
             extra_fields = {"indent": len(indent), "parent": parent}
+            decorators, _ = collect_decorators(self.owner.clean_lines, self.owner.find_line(match.start('name')))
+            if decorators:
+                extra_fields["decorators"] = decorators
             self.make_add_entity(self.entity_type, self.owner.module_prefix + name, vis, start_line, full_text, extra_fields)
         return True
 
@@ -92,17 +179,45 @@ class FunctionParser(EntityParser):
                     elif line.startswith('def '):
                         entity_type = 'local_function'
                     break
-            if self.owner.include_decorators and spec:
-                for i in range(start_line - 1, 0, -1):
-                    if self.owner.clean_lines[i].strip().startswith('@'):
-                        start_line = i
-                        break
+            decorators, decorators_line = collect_decorators(self.owner.clean_lines, self.owner.find_line(match.start('name')))
+            if self.owner.include_decorators and decorators:
+                start_line = min(start_line, decorators_line)
             full_text = self.owner.extract_entity_text(match.start(), match.end())
             extra_fields = {"indent": indent, "parent": parent}
+            if decorators:
+                extra_fields["decorators"] = decorators
             self.make_add_entity(entity_type, self.owner.module_prefix + name, vis, start_line, full_text, extra_fields)
         return True
 
 
+class DocstringParser(EntityParser):
+    """Parser for the module-level docstring, stored as entity named after the module."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        first_line = next((i for i, line in enumerate(self.owner.clean_lines[1:], 1)
+                           if isinstance(line, str) and line.strip()), 0)
+        span = next((s for s in self.owner.literal_spans if s["first_line"] == first_line), None)
+        if not span or first_line in self.owner.entity_map:
+            return True
+        name = Path(self.owner.file_name).stem if self.owner.file_name else "module"
+        entity = {
+            "type": self.entity_type,
+            "name": self.owner.module_prefix + name,
+            "visibility": self.default_visibility,
+            "file_id": self.owner.file_id,
+            "first_line": span["first_line"],
+            "last_line": span["last_line"],
+            "tokens": estimate_tokens(span["text"]),
+            "indent": 0,
+            "parent": "",
+        }
+        self.owner.entity_map[first_line] = entity
+        logging.debug(f"Added module docstring for {entity['name']} at lines {first_line}-{span['last_line']}")
+        return True
+
+
 class DepsParserPython(DepsParser):
     """Parser for Python imports."""
     def __init__(self, owner):
@@ -135,15 +250,25 @@ class ContentCodePython(ContentBlock):
         super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
         self.tag = "python"
         self.include_decorators = include_decorators
-        self.open_ml_string = ['"""', "'''"]
-        self.close_ml_string = ['"""', "'''"]
+        # triple-quoted literals handled by strip_python_literals before the generic stripper
+        self.open_ml_string = []
+        self.close_ml_string = []
         self.open_sl_comment = ["#"]
-        self.open_ml_comment = ['"""', "'''"]
-        self.close_ml_comment = ['"""', "'''"]
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.literal_spans = []
         self.entity_map = {}
         self.module_prefix = kwargs.get("module_prefix", "")
         logging.debug(f"Initialized ContentCodePython with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}, include_decorators={include_decorators}")
 
+    def strip_strings(self):
+        """Strips Python literals with strip_python_literals, then runs the generic string stripper."""
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text, self.literal_spans = strip_python_literals("\n".join(self.clean_lines[1:]))
+        self.clean_lines = [""] + clean_text.split("\n")[:len(self.clean_lines) - 1]
+        return super().strip_strings()
+
     def detect_bounds(self, start_line, clean_lines):
         """Detects the start and end line of an entity using indentation levels.
 
@@ -202,6 +327,7 @@ class ContentCodePython(ContentBlock):
         self.strip_comments()
 
         parsers = [
+            DocstringParser("docstring", self),
             ClassParser("class", self),
             FunctionParser("function", self),
             DepsParserPython(self)
@@ -226,5 +352,16 @@ class ContentCodePython(ContentBlock):
         logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
         return {"entities": entities, "dependencies": self.dependencies}
 
+    def body_range(self, entity: dict):
+        """Lines (first, last) of function body after signature colon and docstring, None for one-liners."""
+        depth = 0
+        for num in range(entity["first_line"], min(entity["last_line"], len(self.clean_lines))):
+            line = self.clean_lines[num]
+            depth += sum(line.count(c) for c in "([{") - sum(line.count(c) for c in ")]}")
+            if depth == 0 and line.rstrip().endswith(":"):
+                first = next((span["last_line"] for span in self.literal_spans if span["first_line"] == num + 1), num) + 1
+                return (first, entity["last_line"]) if first <= entity["last_line"] else None
+        return None
+
 
 SandwichPack.register_block_class(ContentCodePython)
\ No newline at end of file
@@ -0,0 +1,318 @@
+# /lib/r_block.py, created 2026-10-15 08:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+NAME_PATTERN = r"(?:[A-Za-z.][\w.]*(?:\$[A-Za-z.][\w.]*)*|`[^`\n]*`)"
+ASSIGN_REGEX = re.compile(r"^[ \t]*(?P<name>" + NAME_PATTERN + r")\s*(?:<<?-|=(?!=))\s*(?P<kind>function\b|\\(?=\s*\()"
+                          r"|(?:R6::)?R6Class\s*\(|setRefClass\s*\()", re.MULTILINE)
+CALL_REGEX = re.compile(r"^[ \t]*(?P<call>setClass|setGeneric|setMethod)\s*\(", re.MULTILINE)
+MEMBER_REGEX = re.compile(r"(?P<name>" + NAME_PATTERN + r")\s*=\s*(?P<value>function\b|\\(?=\s*\())?")
+SECTION_REGEX = re.compile(r"\b(?P<section>public|private|active|methods)\s*=\s*list\s*\(")
+RAW_STRING_REGEX = re.compile(r"[rR](?P<quote>[\"'])(?P<dashes>-*)(?P<open>[(\[{])")
+DEPS_REGEX = re.compile(r"\b(?P<call>library|require|requireNamespace|loadNamespace|source)\s*\(\s*(?P<quote>[\"']?)(?P<name>[\w./-]+)(?P=quote)")
+IMPORT_FROM_REGEX = re.compile(r"^[ \t]*#'\s*@importFrom\s+(?P<package>[\w.]+)\s+(?P<names>[^\n]+)", re.MULTILINE)
+ROXYGEN_TAG_REGEX = re.compile(r"^@(?P<tag>\w+)")
+CONTAINS_REGEX = re.compile(r"\bcontains\s*=\s*(?P<value>\"[^\"]*\"|c\s*\([^)]*\))")
+CLASS_SYSTEMS = {"setClass": "S4", "setRefClass": "RC", "R6Class": "R6"}
+
+
+def blank(text: str) -> str:
+    """Replaces text with spaces, line breaks kept."""
+    return re.sub(r"[^\n]", " ", text)
+
+
+def strip_r_literals(text: str, warn=None) -> str:
+    """Blanks R strings (with raw strings like r"(...)" and r"---[...]---"), backtick names and `#` comments with spaces.
+
+    Delimiters are kept and positions are preserved, so names, class names in calls and roxygen blocks are read from the source by offsets.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        raw = RAW_STRING_REGEX.match(text, i) if ch in "rR" and not (i > 0 and (text[i - 1].isalnum() or text[i - 1] in "._")) else None
+        if raw:
+            closing = {"(": ")", "[": "]", "{": "}"}[raw.group('open')] + raw.group('dashes') + raw.group('quote')
+            end = text.find(closing, raw.end())
+            if end < 0 and warn:
+                warn(f"Incomplete raw string at line {text.count(chr(10), 0, i) + 1}")
+            end = n if end < 0 else end + len(closing)
+            out.append(text[i] + raw.group('quote') + blank(text[i + 2:end - 1]) + (text[end - 1] if end <= n else ""))
+            i = end
+            continue
+        if ch not in "\"'`":
+            out.append(ch)
+            i += 1
+            continue
+        j = i + 1
+        while j < n and text[j] != ch:
+            j += 2 if text[j] == "\\" and ch != "`" else 1
+        closed = j < n
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append(ch + blank(text[i + 1:min(j, n)]) + (ch if closed else ""))
+        i = j + 1 if closed else n
+    return "".join(out)
+
+
+def string_args(raw: str) -> list:
+    """Quoted values in raw call arguments, e.g. `c("A", "B")` or `signature("Circle")`."""
+    return re.findall(r"[\"']([^\"'\n]*)[\"']", raw)
+
+
+class DefinitionParser(EntityParser):
+    """Parser for function assignments (`f <- function(...)`, `f = \\(x)`), S4 classes / generics / methods, Reference and R6 classes.
+
+    Bodies end with the matching brace or, for single expressions, with the statement. Members of R6 `public` / `private` /
+    `active` lists and RC `methods` lists are stored with the class as parent. Roxygen block (`#'`) preceding a definition
+    gives doc (title line) and export flag; names starting with a dot are private by convention.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+        self.depth = []
+        self.content = ""
+
+    def parse(self):
+        self.content = content = self.owner.get_clean_content()
+        self.depth = [0] * (len(content) + 1)
+        level = 0
+        for i, ch in enumerate(content):
+            self.depth[i] = level
+            level = max(level + (1 if ch in "([{" else -1 if ch in ")]}" else 0), 0)
+        self.depth[len(content)] = level
+
+        functions = []
+        for match in ASSIGN_REGEX.finditer(content):
+            start = match.start('name')
+            if self.depth[start] != 0 and any(first < start <= last for first, last in functions):
+                continue   # closures and class members belong to the enclosing definition
+            name = self.raw_name(match)
+            kind = match.group('kind')
+            if kind.startswith(("function", "\\")):
+                end = self.function_end(match.end('kind'))
+                functions.append((start, end))
+                self.add("function", name, start, end, {})
+                continue
+            call_end = self.close_of(match.end() - 1)
+            args = self.owner.content_text[match.end():call_end]
+            system = CLASS_SYSTEMS["R6Class" if "R6Class" in kind else "setRefClass"]
+            values = string_args(args.split(",")[0]) if not args.lstrip().startswith(("public", "private", "inherit")) else []
+            extra = {"system": system}
+            if inherit := re.search(r"\binherit\s*=\s*(?P<base>[\w.]+)", content[match.end():call_end]):
+                extra["parent"] = inherit.group('base')
+            if system == "RC" and (contains := CONTAINS_REGEX.search(args)):
+                extra["parent"] = ", ".join(string_args(contains.group('value')))
+            class_name = values[0] if values else name
+            functions.append((start, call_end))
+            self.add("class", class_name, start, call_end, extra)
+            self.members(class_name, match.end(), call_end, functions)
+
+        for match in CALL_REGEX.finditer(content):
+            if self.depth[match.start('call')] != 0:
+                continue
+            call_end = self.close_of(match.end() - 1)
+            args = self.owner.content_text[match.end():call_end]
+            values = string_args(args)
+            if not values:
+                continue
+            call = match.group('call')
+            if call == "setClass":
+                extra = {"system": CLASS_SYSTEMS[call]}
+                if contains := CONTAINS_REGEX.search(args):
+                    extra["parent"] = ", ".join(string_args(contains.group('value')))
+                if slots := re.search(r"\b(?:slots\s*=\s*(?:c|list)|representation)\s*\((?P<body>[^)]*)\)", args):
+                    extra["slots"] = re.findall(r"([A-Za-z.][\w.]*)\s*=", slots.group('body'))
+                self.add("class", values[0], match.start('call'), call_end, extra)
+            elif call == "setGeneric":
+                self.add("generic", values[0], match.start('call'), call_end, {})
+            else:
+                signature = string_args(re.split(r",\s*(?:def(?:inition)?\s*=\s*)?function\b", args, maxsplit=1)[0])[1:]
+                extra = {"parent": signature[0], "signature": signature} if signature else {}
+                self.add("method", values[0], match.start('call'), call_end, extra)
+        return True
+
+    def members(self, class_name, start, end, functions):
+        """Methods of R6 `public` / `private` / `active` and RC `methods` lists inside class call."""
+        for section in SECTION_REGEX.finditer(self.content, start, end):
+            list_end = self.close_of(section.end() - 1)
+            inner = self.depth[section.end()]
+            for member in MEMBER_REGEX.finditer(self.content, section.end(), list_end):
+                if self.depth[member.start()] != inner or not member.group('value') \
+                        or self.content[:member.start()].rstrip()[-1:] not in ("(", ","):
+                    continue
+                member_end = self.function_end(member.end('value'))
+                functions.append((member.start(), member_end))
+                kind = "property" if section.group('section') == "active" else "method"
+                visibility = "private" if section.group('section') == "private" else None
+                self.add(kind, self.raw_name(member), member.start(), member_end, {"parent": class_name}, visibility)
+
+    def raw_name(self, match):
+        return self.owner.content_text[match.start('name'):match.end('name')].strip("`")
+
+    def close_of(self, open_pos):
+        """Offset of bracket closing the one at open_pos (or end of content)."""
+        level = self.depth[open_pos] + 1
+        return next((j for j in range(open_pos + 1, len(self.content)) if self.content[j] in ")]}" and self.depth[j] == level), len(self.content) - 1)
+
+    def function_end(self, pos):
+        """End offset of function with parameter list after pos: matching brace of the body or end of expression statement."""
+        params = self.content.find("(", pos)
+        if params < 0:
+            return pos
+        i = self.close_of(params) + 1
+        while i < len(self.content) and self.content[i] in " \t\n":
+            i += 1
+        if i < len(self.content) and self.content[i] == "{":
+            return self.close_of(i)
+        level = self.depth[i] if i < len(self.content) else 0
+        j = i
+        while j < len(self.content):
+            if self.content[j] == "\n" and self.depth[j] <= level and not re.search(r"(?:[-+*/^&|,=<>~]|%\w*%)\s*$", self.content[i:j]):
+                break
+            if self.content[j] in ")]}" and self.depth[j] <= level:
+                break   # anonymous function as argument
+            j += 1
+        return max(j - 1, i)
+
+    def add(self, e_type, name, start, end, extra, visibility=None):
+        first_line = self.owner.find_line(start)
+        last_line = self.owner.find_line(max(end, start))
+        extra = dict(extra)
+        lines = self.owner.content_text.split("\n")
+        doc = []
+        line_num = first_line - 1
+        while line_num >= 1 and lines[line_num - 1].lstrip().startswith("#'"):
+            doc.insert(0, lines[line_num - 1].lstrip()[2:].strip())
+            line_num -= 1
+        title = next((text for text in doc if text and not ROXYGEN_TAG_REGEX.match(text)), None)
+        if title:
+            extra["doc"] = title
+        if any(ROXYGEN_TAG_REGEX.match(text) and ROXYGEN_TAG_REGEX.match(text).group('tag') == "export" for text in doc):
+            extra["export"] = True
+        visibility = visibility or ("private" if name.split("$")[-1].startswith(".") else "public")
+        if self.owner.add_definition(e_type, name, first_line, last_line, extra, visibility):
+            self.new_entities_lines.append(first_line)
+
+
+class DepsParserR(DepsParser):
+    """Parser for `library` / `require` / `requireNamespace` packages, `source("file.R")` scripts and roxygen `@importFrom`."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw = self.owner.content_text
+        for match in DEPS_REGEX.finditer(raw):
+            if content[match.start():match.end('call')] != match.group('call'):
+                continue   # inside comment or string
+            name = match.group('name')
+            if match.group('call') == "source":
+                if not match.group('quote'):
+                    continue   # path from variable
+                name = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), name)).replace("\\", "/")
+            elif match.group('call') in ("library", "require") and "character.only" in raw[match.end():raw.find(")", match.end()) + 1]:
+                continue   # package name from variable
+            self.add_module(name)
+        for match in IMPORT_FROM_REGEX.finditer(raw):
+            self.add_module(match.group('package'))
+            for name in match.group('names').split():
+                self.add_import(match.group('package'), name.strip("`"))
+        return True
+
+
+class ContentCodeR(ContentBlock):
+    """Parser for R content blocks (.r, .R)."""
+    supported_types = [".r"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "r"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are blanked by the literal stripper
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeR with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips strings, backtick names and comments with strip_r_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_r_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def add_definition(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, visibility: str = "public"):
+        """Stores entity without placement check, one entity per line."""
+        if first_line in self.entity_map:
+            return False
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": self.module_prefix + name,
+            "visibility": visibility,
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses R content to extract functions, S4 / RC / R6 classes and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserR(self),
+            DefinitionParser("definition", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeR)
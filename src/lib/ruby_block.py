@@ -0,0 +1,396 @@
+# /lib/ruby_block.py, created 2026-10-14 16:10 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+HEREDOC_START = re.compile(r"<<([~-]?)(['\"`]?)([A-Za-z_]\w*)\2")
+PERCENT_START = re.compile(r"%([qQwWiIrsx]?)([^\w\s])")
+PAIRED_DELIMITERS = {"(": ")", "[": "]", "{": "}", "<": ">"}
+REGEX_KEYWORDS = ("if", "unless", "when", "and", "or", "not", "return", "while", "until")
+# block openers counted by detect_bounds; conditionals and loops only at statement start (not modifiers)
+OPENER_REGEX = re.compile(r"(?<![\w.:$@])(?:class|module|def|begin|case|do)\b(?![?!:])"
+                          r"|(?:^|(?<=[=(|&]))\s*(?:if|unless|while|until|for)\b(?![?!:])")
+CLOSER_REGEX = re.compile(r"(?<![\w.:$@])end\b(?![?!:])")
+ENDLESS_DEF_REGEX = re.compile(r"^\s*(?:\w+\s+)*def\s+[\w.]+[?!]?(?:\s*\([^)]*\))?\s*=(?!=)")
+SECTION_REGEX = re.compile(r"^\s*(private|protected|public)\s*$")
+
+
+def _prev_significant(text: str, i: int) -> str:
+    j = i - 1
+    while j >= 0 and text[j] in " \t":
+        j -= 1
+    return text[j] if j >= 0 else "\n"
+
+
+def _skip_interpolation(text: str, i: int) -> int:
+    """Skips `#{...}` interpolation body starting after the open brace, nested strings included."""
+    n = len(text)
+    depth = 1
+    while i < n and depth > 0:
+        ch = text[i]
+        if ch in "\"'`":
+            i, _ = _scan_quoted(text, i + 1, ch, ch, ch != "'")
+            continue
+        depth += {'{': 1, '}': -1}.get(ch, 0)
+        i += 1
+    return i
+
+
+def _scan_quoted(text: str, i: int, open_ch: str, close_ch: str, interpolate: bool):
+    """Scans literal body from i up to the closing delimiter (paired delimiters nest), returns (end, closed)."""
+    n = len(text)
+    depth = 1
+    while i < n:
+        ch = text[i]
+        if ch == "\\":
+            i += 2
+            continue
+        if interpolate and text.startswith("#{", i):
+            i = _skip_interpolation(text, i + 2)
+            continue
+        if open_ch != close_ch and ch == open_ch:
+            depth += 1
+        elif ch == close_ch:
+            depth -= 1
+            if depth == 0:
+                return i + 1, True
+        i += 1
+    return n, False
+
+
+def _skip_heredocs(text: str, i: int, pending: list, out: list, warn=None) -> int:
+    """Skips bodies of heredocs opened on the previous line, one newline per consumed line is kept."""
+    n = len(text)
+    for indented, ident in pending:
+        while True:
+            if i >= n:
+                if warn:
+                    warn(f"Incomplete heredoc {ident} at line {text.count(chr(10)) + 1}")
+                return n
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            line = text[i:end]
+            i = min(end + 1, n)
+            if end < n:
+                out.append("\n")
+            if (line.strip() if indented else line.rstrip("\r")) == ident:
+                break
+    return i
+
+
+def _is_regex_start(text: str, i: int) -> bool:
+    prev = _prev_significant(text, i)
+    if prev in "\n(,=~!|&{[;":
+        return True
+    word = re.search(r"(\w+)[ \t]*$", text[max(0, i - 16):i])
+    return bool(word) and word.group(1) in REGEX_KEYWORDS
+
+
+def _is_percent_literal(text: str, i: int, match) -> bool:
+    before = text[i - 1] if i > 0 else "\n"
+    if match.group(1):
+        return not (before.isalnum() or before == "_")
+    prev = _prev_significant(text, i)
+    if prev.isdigit() or prev in ")]}\"'":
+        return False   # modulo after an operand, e.g. `"%d" % [x]`
+    if prev.isalnum() or prev == "_":
+        # `puts %(text)` is a literal, `a % b` stays an operator
+        return before in " \t" and match.group(2) in PAIRED_DELIMITERS and text[match.end():match.end() + 1] not in " \t"
+    return True
+
+
+def strip_ruby_literals(text: str, warn=None) -> str:
+    """Blanks Ruby strings, symbols with quotes, %-literals (%q, %Q, %w, %i, %r, ...), regexes and heredocs.
+
+    Line count is preserved: heredoc bodies, `=begin`/`=end` blocks and text after `__END__` leave empty lines.
+    Interpolations `#{...}` are blanked together with the literal. Comment bodies are dropped, `#` stays for the comment stripper.
+    """
+    out = []
+    pending = []   # heredocs started on current line: (indented terminator, identifier)
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "\n":
+            out.append(ch)
+            i += 1
+            if pending:
+                i = _skip_heredocs(text, i, pending, out, warn)
+                pending = []
+            continue
+        if i == 0 or text[i - 1] == "\n":
+            if re.match(r"=begin\b", text[i:i + 7]):
+                block_end = re.compile(r"^=end\b[^\n]*", re.MULTILINE).search(text, i)
+                end = block_end.end() if block_end else n
+                out.append("\n" * text.count("\n", i, end))
+                i = end
+                continue
+            if re.match(r"__END__[ \t\r]*(?:\n|$)", text[i:i + 10]):
+                out.append("\n" * text.count("\n", i))
+                break
+        if ch == "#":
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("#")
+            continue
+        match = None
+        if ch in "\"`":
+            end, closed = _scan_quoted(text, i + 1, ch, ch, True)
+        elif ch == "'":
+            end, closed = _scan_quoted(text, i + 1, ch, ch, False)
+        elif ch == "<" and (match := HEREDOC_START.match(text, i)) and (match.group(1) or match.group(2) or match.group(3)[0].isupper()) \
+                and _prev_significant(text, i) not in ")]" and not _prev_significant(text, i).isdigit():
+            pending.append((bool(match.group(1)), match.group(3)))
+            out.append('""')
+            i = match.end()
+            continue
+        elif ch == "%" and (match := PERCENT_START.match(text, i)) and _is_percent_literal(text, i, match):
+            open_ch = match.group(2)
+            end, closed = _scan_quoted(text, match.end(), open_ch, PAIRED_DELIMITERS.get(open_ch, open_ch),
+                                       match.group(1) not in ("q", "w", "i", "s"))
+        elif ch == "/" and _is_regex_start(text, i):
+            end, closed = _scan_quoted(text, i + 1, "/", "/", True)
+        elif ch == "?" and text[i + 1:i + 2] in ("\"", "'", "`", "#") and _prev_significant(text, i) in "\n(,= ":
+            out.append("?_")   # character literal
+            i += 2
+            continue
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class RubyEntityParser(EntityParser):
+    """Base for Ruby parsers: inline `private def` is private, sections are applied in apply_scopes."""
+    def detect_visibility(self, match):
+        return "private" if match_value(match, 'mods', '').strip() == "private" else self.default_visibility
+
+
+class TypeParser(RubyEntityParser):
+    """Parser for `module Name` and `class Name < Parent` declarations (`class << self` is a scope, not an entity)."""
+    def __init__(self, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^(?P<indent>[ \t]*){keyword}\s+(?P<name>[A-Z][\w:]*)", ["indent", "name"], 2)\
+            .add_token(r"(?:[ \t]*<[ \t]*(?P<parent>[\w:.]+(?:\([^)\n]*\))?))?", ["parent"], 1)
+        super().__init__(keyword, owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+
+class MethodParser(RubyEntityParser):
+    """Parser for `def` methods, including `def self.name`, predicate/bang/setter names and endless definitions."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)(?P<mods>(?:private|protected|public|module_function)\s+)?def\s+"
+                       r"(?:(?P<receiver>self|[A-Z]\w*)\.)?(?P<name>[A-Za-z_]\w*[?!=]?)", ["indent", "mods", "receiver", "name"], 2)
+        super().__init__(entity_type, owner, outer_regex, r"\bdef\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and match_value(base_match, 'receiver'):
+            entity["receiver"] = base_match.group('receiver')
+        return True
+
+
+class AttrParser(RubyEntityParser):
+    """Parser for `attr_reader`/`attr_writer`/`attr_accessor` lines, named by the first symbol."""
+    def __init__(self, owner, kind="reader"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(rf"^(?P<indent>[ \t]*)attr_{kind}\s+:(?P<name>\w+)(?P<rest>(?:[ \t]*,[ \t]*:\w+)*)", ["indent", "name", "rest"], 1)
+        super().__init__(f"attr_{kind}", owner, outer_regex, rf"\battr_{kind}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        names = [base_match.group('name')] + re.findall(r":(\w+)", match_value(base_match, 'rest', ''))
+        if entity and len(names) > 1:
+            entity["names"] = names
+        return True
+
+
+class DepsParserRuby(DepsParser):
+    """Parser for `require`, `require_relative` and `autoload`; paths are read from the original text."""
+    REQUIRE_REGEX = re.compile(r"^[ \t]*(?P<kind>require|require_relative|load)\s*\(?\s*['\"](?P<path>[^'\"]+)['\"]", re.MULTILINE)
+    AUTOLOAD_REGEX = re.compile(r"^[ \t]*autoload\s*\(?\s*:(?P<name>\w+)\s*,\s*['\"](?P<path>[^'\"]+)['\"]", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = self.owner.content_text   # paths are strings, clean_lines lost them
+        for match in self.REQUIRE_REGEX.finditer(content):
+            path = match.group('path')
+            self.add_module("./" + path if match.group('kind') == "require_relative" and not path.startswith(".") else path)
+        for match in self.AUTOLOAD_REGEX.finditer(content):
+            self.add_module(match.group('path'))
+            self.add_import(match.group('path'), match.group('name'))
+        return True
+
+
+class ContentCodeRuby(ContentBlock):
+    """Parser for Ruby content blocks."""
+    supported_types = [".rb", ".rake", ".gemspec"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "ruby"
+        self.open_sl_comment = ["#"]
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        self.entity_map = {}
+        self.singleton_scopes = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeRuby with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips strings, %-literals, regexes and heredocs with strip_ruby_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_ruby_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Detects entity bounds by counting block openers (class, def, do, statement if/while...) against `end`."""
+        if start_line < 1 or start_line >= len(clean_lines) or not clean_lines[start_line] or not clean_lines[start_line].strip():
+            logging.error(f"Invalid start line {start_line} for file {self.file_name} module [{self.module_prefix}]")
+            return start_line, start_line
+        if ENDLESS_DEF_REGEX.match(clean_lines[start_line]):
+            return start_line, start_line
+        depth = 0
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            openers = [m.group(0).strip() for m in OPENER_REGEX.finditer(line)]
+            if openers and openers[0] in ("while", "until", "for") and "do" in openers[1:]:
+                openers.remove("do")   # `while cond do` is a single block
+            if ENDLESS_DEF_REGEX.match(line):
+                openers.remove("def")
+            depth += len(openers) - len(CLOSER_REGEX.findall(line))
+            if depth <= 0:
+                return start_line, line_num
+        self.parse_warn(f"Incomplete entity at line {start_line} in file {self.file_name}, unclosed blocks={depth}")
+        return start_line, start_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations (attr_*, endless and one-line methods) are regular, no incomplete entity warning."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def check_entity_placement(self, line_num: int, name: str):
+        """Method names may end with ?, ! or =, which are not word characters."""
+        return super().check_entity_placement(line_num, name.rstrip("?!="))
+
+    def find_singleton_scopes(self):
+        """Collects line ranges of `class << self` blocks, methods inside them are class methods."""
+        self.singleton_scopes = []
+        for line_num, line in enumerate(self.clean_lines[1:], 1):
+            if isinstance(line, str) and re.match(r"^\s*class\s*<<\s*self\b", line):
+                self.singleton_scopes.append(self.detect_bounds(line_num, self.clean_lines))
+
+    def apply_scopes(self):
+        """Qualifies nested modules/classes with `::`, binds methods to owner types and applies private sections."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in ("module", "class")]
+        for scope in scopes:
+            outer = [s for s in scopes if s is not scope and s["first_line"] < scope["first_line"] <= s["last_line"]]
+            if outer and "::" not in scope["name"]:
+                scope["qualified"] = "::".join([s["name"].split("::")[-1] for s in outer] + [scope["name"]])
+        for scope in scopes:
+            scope["name"] = scope.pop("qualified", scope["name"])
+
+        for line_num, entity in self.entity_map.items():
+            if entity["type"] in ("module", "class"):
+                continue
+            owners = [s for s in scopes if s["first_line"] < line_num <= s["last_line"]]
+            if not owners:
+                continue
+            owner = owners[-1]
+            entity["parent"] = owner["name"]
+            if entity["type"] != "function":
+                continue
+            singleton = any(first < line_num <= last for first, last in self.singleton_scopes)
+            entity["type"] = "class method" if singleton or entity.get("receiver") else "method"
+            if entity["visibility"] != "private" and self.section_visibility(owner, scopes, line_num, entity["name"]) == "private":
+                entity["visibility"] = "private"
+
+    def section_visibility(self, owner: dict, scopes: list, line_num: int, name: str) -> str:
+        """Returns visibility set by bare `private`/`protected`/`public` lines or `private :name` in owner body."""
+        nested = [(s["first_line"], s["last_line"]) for s in scopes if owner["first_line"] < s["first_line"] and s["last_line"] < line_num]
+        section = "public"
+        for ln in range(owner["first_line"] + 1, line_num):
+            line = self.clean_lines[ln]
+            if not isinstance(line, str) or any(first <= ln <= last for first, last in nested):
+                continue
+            if match := SECTION_REGEX.match(line):
+                section = match.group(1)
+        for ln in range(owner["first_line"] + 1, owner["last_line"]):
+            line = self.clean_lines[ln]
+            match = re.match(r"^\s*private(?:_class_method)?\s+(:\w.*)$", line) if isinstance(line, str) else None
+            if match and name in [s.strip().lstrip(":") for s in match.group(1).split(",")]:
+                return "private"
+        return "private" if section == "private" else "public"
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Ruby content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+        self.find_singleton_scopes()   # before masquerade, which replaces `def` keywords
+
+        parsers = [
+            DepsParserRuby(self),
+            TypeParser(self, "module"),
+            TypeParser(self, "class"),
+            MethodParser("function", self),
+            AttrParser(self, "reader"),
+            AttrParser(self, "writer"),
+            AttrParser(self, "accessor")
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeRuby)
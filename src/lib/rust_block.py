@@ -1,79 +1,528 @@
-# /lib/rust_block.py, updated 2025-08-08 12:29 EEST
+# /lib/rust_block.py, updated 2026-10-16 17:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
 import os
+import bisect
+import posixpath
 import logging
 import traceback
 from pathlib import Path
 from lib.content_block import ContentBlock, estimate_tokens
 from lib.sandwich_pack import SandwichPack
-from lib.entity_parser import EntityParser, match_value
+from lib.entity_parser import EntityParser, match_value, get_start_pos
 from lib.deps_builder import DepsParser
 from lib.iter_regex import IterativeRegex
 
 
-BASE_REGEX_PATTERN = r"^(?:#\[(?P<spec>.*)?\]\s*)?(?P<indent>[ \t]*)(?P<vis>pub\s+)?"
-FN_REGEX_PATTERN = r"(?P<async>async\s+)?fn\s+(?P<name>\w+)"
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?:#\[[^\[\]\n]*(?:\[[^\[\]\n]*\][^\[\]\n]*)*\][ \t]*)*(?P<vis>pub(?:\s*\(\s*(?:crate|self|super|in\s+[\w:]+)\s*\))?\s+)?"
+# same line attributes `#[inline] pub fn`, attributes of preceding lines are read by outer_attributes
+GENERICS_REGEX_PATTERN = r"<(?:[^<>{};]|<(?:[^<>{};]|<[^<>{};]*>)*>)*>"   # up to two nesting levels: `<T: Into<Vec<u8>>>`
+FN_REGEX_PATTERN = r"(?:const\s+)?(?P<async>async\s+)?(?:unsafe\s+)?(?:extern\s*(?:\"[^\"]*\"\s*)?)?fn\s+(?P<name>\w+)(?:\s*" + GENERICS_REGEX_PATTERN + ")?"
 ARGS_REGEX_PATTERN = r"\s*\((?P<args>([^;^\{]+)\)?)\s*"
 RET_REGEX_PATTERN = r"(?:->\s*(?P<return>[^;^\{]+))?"
+MACRO_RULES_REGEX = re.compile(r"^[ \t]*macro_rules!\s*(?P<name>\w+)\s*(?P<open>[{(\[])", re.MULTILINE)
+# char literal: exactly one (escaped) char between quotes, so lifetimes and labels (`'a`, `'static`, `'outer:`) never match
+CHAR_LITERAL_REGEX = re.compile(r"(?<![\w'])b?'(?:\\(?:u\{[0-9a-fA-F_]{1,8}\}|x[0-9a-fA-F]{2}|.)|[^\\'\n])'")
+RAW_STRING_REGEX = re.compile(r'b?r(?P<hashes>#*)"')
+SHEBANG_REGEX = re.compile(r"\ufeff?#!(?!\s*\[)[^\n]*")   # `#![...]` is inner attribute, even split over lines
+OUTER_DOC_REGEX = re.compile(r"^\s*(?:///(?!/)\s?(?P<text>.*)|#\[doc\s*=\s*\"(?P<attr>(?:[^\"\\]|\\.)*)\"\s*\])\s*$")
+INNER_DOC_REGEX = re.compile(r"^\s*(?://!\s?(?P<text>.*)|#!\[doc\s*=\s*\"(?P<attr>(?:[^\"\\]|\\.)*)\"\s*\])\s*$")
+STRING_END_REGEX = re.compile(r"[ \t]*(?:[;,)\]}?]|\.\w|=>|\n|$)")
+CLOSING_BRACKETS = {"{": "}", "(": ")", "[": "]"}
+USE_REGEX = re.compile(r"^[ \t]*(?P<vis>pub(?:\s*\([^)]*\))?\s+)?use\s+(?P<tree>[^;]+);", re.MULTILINE)
+MOD_DECL_REGEX = re.compile(r"^[ \t]*(?:pub(?:\s*\([^)]*\))?\s+)?mod\s+(?P<name>\w+)", re.MULTILINE)
+MOD_FILE_REGEX = re.compile(r"^[ \t]*(?:pub(?:\s*\([^)]*\))?\s+)?mod\s+(?P<name>\w+)\s*;", re.MULTILINE)
+ITEM_REGEX = re.compile(r"^[ \t]*(?P<vis>pub(?:\s*\([^)]*\))?\s+)?(?P<kind>const|static(?:\s+mut)?|type)\s+(?P<name>\w+)(?=\s*[:=<;])",
+                        re.MULTILINE)
+EXTERN_BLOCK_REGEX = re.compile(r"^[ \t]*(?:unsafe\s+)?(?P<keyword>extern)\s*(?:\"[^\"]*\"\s*)?\{", re.MULTILINE)
+EXTERN_ITEM_REGEX = re.compile(r"^[ \t]*(?P<vis>pub(?:\s*\([^)]*\))?\s+)?(?:(?:unsafe\s+|safe\s+)?fn\s+(?P<fn>\w+)|static\s+(?P<mut>mut\s+)?(?P<static>\w+)\s*:)",
+                               re.MULTILINE)
+ABI_REGEX = re.compile(r"\bextern\s*(?:\"(?P<abi>[^\"]*)\")?")
+ITEM_TYPES = {"const": "constant", "static": "static", "type": "type alias"}
+MAX_VALUE_LENGTH = 80
+MAX_MODULE_DEPTH = 64
+TEST_ATTRIBUTES = {"test": "test", "rstest": "test", "test_case": "test", "bench": "bench"}
+TEST_DIRS = {"tests": "test", "benches": "bench"}
+CARGO_ROLE_DIRS = {"tests": "integration test", "benches": "bench", "examples": "example"}
 
 
-class ModuleParser(EntityParser):
-    """Parser for Rust modules with recursive parsing."""
+def blank_file_header(text: str) -> str:
+    """Blanks UTF-8 BOM and shebang line (`#!/usr/bin/env rust-script`) with spaces, so offsets and columns are kept."""
+    match = SHEBANG_REGEX.match(text)
+    if match:
+        return " " * match.end() + text[match.end():]
+    return " " + text[1:] if text.startswith("\ufeff") else text
+
+
+def strip_char_literals(line: str) -> str:
+    """Blanks char literals like `'{'`, `'"'` or `'\\''` with spaces, quotes kept; lifetimes are left as is."""
+    return CHAR_LITERAL_REGEX.sub(lambda m: m.group(0)[:m.group(0).index("'") + 1] + " " * (len(m.group(0)) - m.group(0).index("'") - 2) + "'", line)
+
+
+def doc_text(match) -> str:
+    """Text of doc comment line or unescaped value of `#[doc = "..."]` attribute."""
+    if match.group('text') is not None:
+        return match.group('text')
+    return re.sub(r"\\(.)", r"\1", match.group('attr'))
+
+
+def first_paragraph(doc_lines: list) -> str:
+    """Joins doc lines up to the first blank one."""
+    paragraph = []
+    for line in doc_lines:
+        if not line.strip():
+            if paragraph:
+                break
+            continue
+        paragraph.append(line.strip())
+    return " ".join(paragraph)
+
+
+RAW_IDENT_REGEX = re.compile(r"\br#(?=[A-Za-z_])")
+RAW_IDENT_MARK = "ʳ_"   # word chars of same length as `r#`, so `\w+` name patterns match raw identifiers like `r#type`
+
+
+def restore_raw_idents(value):
+    """Restores `r#` prefix of raw identifiers marked in clean lines, in strings, lists and dicts of parse results."""
+    if isinstance(value, str):
+        return value.replace(RAW_IDENT_MARK, "r#")
+    if isinstance(value, list):
+        return [restore_raw_idents(v) for v in value]
+    if isinstance(value, dict):
+        return {k: restore_raw_idents(v) for k, v in value.items()}
+    return value
+
+
+def strip_raw_strings(text: str) -> tuple:
+    """Removes content of raw strings with any hash count (`r##"..."##` -> `r##""##`, `br#"..."#`), line breaks kept.
+
+    Regular strings are skipped (multi-line ones only if recover_strings keeps them), line comments up to the line end.
+    Quotes inside comments are blanked, so `/* "odd */` or `// "odd` doesn't open a string hiding code after it.
+    Returns text and count of unterminated raw strings.
+    """
+    out = []
+    unclosed = 0
+    depth = 0   # block comments nesting
+    i = 0
+    n = len(text)
+    while i < n:
+        if text.startswith("/*", i) or (depth and text.startswith("*/", i)):
+            depth += 1 if text[i + 1] == "*" else -1
+            out.append(text[i:i + 2])
+            i += 2
+            continue
+        if depth:
+            out.append(" " if text[i] == '"' else text[i])
+            i += 1
+            continue
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(text[i:end].replace('"', " "))
+            i = end
+            continue
+        if text[i] == '"':
+            close = i + 1
+            while close < n and text[close] != '"':
+                close += 2 if text[close] == "\\" else 1
+            line_end = text.find("\n", i)
+            line_end = n if line_end < 0 else line_end
+            multi_line = close < n and STRING_END_REGEX.match(text, close + 1)   # same rule as recover_strings
+            end = min(close + 1, n) if close < line_end or multi_line else line_end
+            out.append(text[i:end])
+            i = end
+            continue
+        match = RAW_STRING_REGEX.match(text, i) if i == 0 or not re.match(r"\w", text[i - 1]) else None
+        if not match:
+            out.append(text[i])
+            i += 1
+            continue
+        end = text.find('"' + match.group('hashes'), match.end())
+        if end < 0:
+            unclosed += 1
+            end = n
+        else:
+            end += len(match.group('hashes')) + 1
+        out.append(match.group(0) + '"' + match.group('hashes') + "\n" * text.count("\n", i, end))   # delimiters kept on one line
+        i = end
+    return "".join(out), unclosed
+
+
+def recover_strings(text: str) -> tuple:
+    """Closes regular strings: multi-line ones are emptied keeping line breaks, unclosed ones are closed at end of line.
+
+    A string continues on next lines only if its closing quote is followed by a token ending an expression (`;`, `,`, `)`,
+    `.method()`, line end), otherwise like `let s = "unclosed { string;` it is closed at end of line and the rest
+    of the file is parsed as usual. Returns text and numbers of lines with recovered strings.
+    """
+    out = []
+    recovered = []
+    depth = 0   # block comments nesting
+    i = 0
+    n = len(text)
+    while i < n:
+        if depth == 0 and text.startswith("//", i):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+        elif text.startswith("/*", i) or (depth and text.startswith("*/", i)):
+            depth += 1 if text[i + 1] == "*" else -1
+            end = i + 2
+        elif depth or text[i] != '"':
+            end = i + 1
+        else:
+            close = i + 1
+            while close < n and text[close] != '"':
+                close += 2 if text[close] == "\\" else 1
+            line_end = text.find("\n", i)
+            line_end = n if line_end < 0 else line_end
+            if close < line_end:
+                end = close + 1
+            elif close < n and STRING_END_REGEX.match(text, close + 1):
+                out.append('""' + "\n" * text.count("\n", i, close))
+                i = close + 1
+                continue
+            else:
+                recovered.append(text.count("\n", 0, i) + 1)
+                out.append(text[i:line_end] + '"')
+                i = line_end
+                continue
+        out.append(text[i:end])
+        i = end
+    return "".join(out), recovered
+
+
+def strip_nested_comments(text: str) -> tuple:
+    """Blanks block comments keeping nesting depth (`/* a /* b */ still comment */`), line breaks kept.
+
+    Line comments are left for the comment stripper, `/*` inside them doesn't open a block. Returns text and unclosed depth.
+    """
+    out = []
+    depth = 0
+    i = 0
+    n = len(text)
+    while i < n:
+        if depth == 0 and text.startswith("//", i):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(text[i:end])
+            i = end
+        elif text.startswith("/*", i):
+            depth += 1
+            out.append("  ")
+            i += 2
+        elif depth and text.startswith("*/", i):
+            depth -= 1
+            out.append("  ")
+            i += 2
+        else:
+            out.append(text[i] if depth == 0 or text[i] == "\n" else " ")
+            i += 1
+    return "".join(out), depth
+
+
+def blank_strings(line: str) -> str:
+    """Blanks content of string literals in line with spaces, so brackets inside them are not counted; length kept."""
+    return re.sub(r'"(?:[^"\\]|\\.)*"', lambda m: '"' + " " * (len(m.group(0)) - 2) + '"', line)
+
+
+def split_attributes(text: str, masked: str) -> tuple:
+    """Splits leading attributes `#[a] #[b(x)]` of text (masked is text with blanked strings), returns contents and rest offset."""
+    attrs = []
+    pos = len(masked) - len(masked.lstrip())
+    while masked.startswith("#[", pos) and (end := match_bracket(masked, pos + 1)) > 0:
+        attrs.append(" ".join(text[pos + 2:end].split()))
+        pos = end + 1 + len(masked[end + 1:]) - len(masked[end + 1:].lstrip())
+    return attrs, pos
+
+
+def outer_attributes(lines: list, first_line: int) -> list:
+    """Returns attributes of item at first_line (`#[derive(Debug)]` -> `derive(Debug)`): preceding lines, multi-line ones
+    and several in one line included, and ones on the item line itself. Doc comments between attributes are skipped,
+    `#[doc]` is not an attribute.
+    """
+    masked = [blank_strings(line) for line in lines]
+    attrs = []
+    line_num = first_line - 1
+    while line_num > 0:
+        text = masked[line_num].strip()
+        if OUTER_DOC_REGEX.match(lines[line_num]) and not text.startswith("#"):
+            line_num -= 1
+            continue
+        if not text.endswith("]"):
+            break
+        start = None
+        for ln in range(line_num, max(line_num - 20, 0), -1):
+            if not masked[ln].lstrip().startswith("#["):
+                continue
+            chunk, masked_chunk = "\n".join(lines[ln:line_num + 1]), "\n".join(masked[ln:line_num + 1])
+            found, pos = split_attributes(chunk, masked_chunk)
+            if found and pos >= len(masked_chunk.rstrip()):
+                start = ln
+                break
+        if start is None:
+            break
+        attrs[0:0] = found
+        line_num = start - 1
+    attrs.extend(split_attributes(lines[first_line], masked[first_line])[0])
+    return [a for a in attrs if not re.match(r"doc\s*=", a)]
+
+
+def match_bracket(text: str, pos: int) -> int:
+    """Returns offset of the bracket closing one at pos, -1 if unbalanced."""
+    stack = []
+    for i in range(pos, len(text)):
+        ch = text[i]
+        if ch in CLOSING_BRACKETS:
+            stack.append(CLOSING_BRACKETS[ch])
+        elif ch in ")]}":
+            if not stack or stack.pop() != ch:
+                return -1
+            if not stack:
+                return i
+    return -1
+
+
+def split_top_level(text: str, offset: int = 0, separator: str = ",") -> list:
+    """Splits text by commas (or other separator like `+` of bounds) outside of brackets, returns (offset, piece) pairs for non-empty pieces."""
+    pieces = []
+    depth = 0
+    angles = 0   # generic arguments `HashMap<K, V>`, while shifts `1 << 4` and arrows `->` are not brackets
+    start = 0
+    for i, ch in enumerate(text + separator):
+        if ch in "([{":
+            depth += 1
+        elif ch in ")]}" and depth > 0:
+            depth -= 1
+        elif ch == "<" and re.match(r"[\w:]", text[i - 1:i]) and text[i + 1:i + 2] not in ("<", "="):
+            angles += 1
+        elif ch == ">" and angles > 0 and text[i - 1:i] not in ("-", "="):
+            angles -= 1
+        elif ch == separator and depth == 0 and angles == 0:
+            if text[start:i].strip():
+                pieces.append((offset + start, text[start:i]))
+            start = i + 1
+    return pieces
+
+
+def cfg_enabled(condition: str, test=None, features=None):
+    """Evaluates cfg predicate (`test`, `feature = "x"`, `all`, `any`, `not`), returns None if unknown.
+
+    test None keeps `test` unknown, features None keeps all features unknown; target predicates (`unix`,
+    `target_os = "linux"`) are never evaluated, so an item is excluded only if its condition is surely false.
+    """
+    condition = condition.strip()
+    if match := re.match(r"(?P<op>all|any|not)\s*\((?P<args>.*)\)$", condition, re.DOTALL):
+        values = [cfg_enabled(piece, test, features) for _, piece in split_top_level(match.group('args'))]
+        if match.group('op') == "not":
+            return None if not values or values[0] is None else not values[0]
+        decisive = match.group('op') == "any"   # value deciding result: True for any, False for all
+        if decisive in values:
+            return decisive
+        return None if None in values else not decisive
+    if condition == "test":
+        return test
+    if match := re.match(r'feature\s*=\s*"(?P<name>[^"]*)"$', condition):
+        return None if features is None else match.group('name') in features
+    return None
+
+
+def crate_module_path(file_name: str) -> str:
+    """Module path of source file in crate: `src/lib.rs` -> `crate`, `src/net/mod.rs` -> `crate::net`, `src/net/tcp.rs` -> `crate::net::tcp`."""
+    parts = Path(file_name.split("&")[0]).parts if file_name else ()
+    parts = parts[len(parts) - parts[::-1].index("src"):] if "src" in parts else parts[-1:]
+    stem = Path(parts[-1]).stem if parts else "lib"
+    return "::".join(["crate", *parts[:-1]] + ([] if stem in ("lib", "main", "mod") else [stem]))
+
+
+def cargo_role(file_name: str):
+    """Cargo target role of source file by crate layout: `src/` files "library", `src/main.rs` and `src/bin/` "binary",
+    `tests/` "integration test", `benches/` "bench", `examples/` "example", `build.rs` "build script"; None if unknown.
+    """
+    parts = Path(file_name.split("&")[0]).parts if file_name else ()
+    if "src" in parts[:-1]:
+        inner = parts[len(parts) - parts[::-1].index("src"):]
+        return "binary" if inner == ("main.rs",) or (inner[0] == "bin" and len(inner) > 1) else "library"
+    role = next((CARGO_ROLE_DIRS[part] for part in reversed(parts[:-1]) if part in CARGO_ROLE_DIRS), None)
+    return role or ("build script" if parts and parts[-1] == "build.rs" else None)
+
+
+def flatten_use_tree(tree: str, prefix: str = "") -> list:
+    """Flattens use tree `a::{b, c::{d as e, self}, f::*}` to (path, alias) pairs: `a::b`, `a::c::d` as `e`, `a::c`, `a::f::*`."""
+    pairs = []
+    for _, piece in split_top_level(tree):
+        piece = re.sub(r"\s*::\s*", "::", " ".join(piece.split())).lstrip(":")
+        if match := re.match(r"^(?P<path>(?:\w+::)*)\{(?P<inner>.*)\}$", piece, re.DOTALL):
+            path = match.group('path').rstrip(":")
+            pairs.extend(flatten_use_tree(match.group('inner'), f"{prefix}::{path}" if prefix and path else prefix or path))
+        elif match := re.match(r"^(?P<path>(?:\w+::)*(?:\w+|\*))(?:\s+as\s+(?P<alias>\w+))?$", piece):
+            path = f"{prefix}::{match.group('path')}" if prefix else match.group('path')
+            pairs.append((path[:-len("::self")] if path.endswith("::self") else path, match.group('alias')))
+    return pairs
+
+
+def split_assignment(decl: str) -> tuple:
+    """Splits declaration `: Type = value` by top level `=`, generic arguments like `Iterator<Item = u8>` skipped; value None if absent."""
+    depth = 0
+    for i, ch in enumerate(decl):
+        if ch in "([{<":
+            depth += 1
+        elif ch in ")]}" or (ch == ">" and decl[i - 1:i] != "-"):
+            depth -= 1
+        elif ch == "=" and depth == 0 and decl[i + 1:i + 2] not in ("=", ">"):
+            return decl[:i], decl[i + 1:].strip()
+    return decl, None
+
+
+def rust_visibility(vis: str, default: str = "private") -> str:
+    """Normalizes visibility qualifier: `pub` -> "public", `pub(crate)`, `pub(super)`, `pub(in path)` kept, `pub(self)` -> "private"."""
+    match = re.match(r"pub\s*(?:\(\s*(?P<scope>[^)]*?)\s*\))?", vis.strip()) if vis else None
+    if not match:
+        return default
+    scope = " ".join((match.group('scope') or "").split())
+    if not scope:
+        return "public"
+    return "private" if scope == "self" else f"pub({scope})"
+
+
+class RustEntityParser(EntityParser):
+    """Base parser for Rust items with restricted visibility levels."""
+    def detect_visibility(self, match):
+        return rust_visibility(match_value(match, 'vis'), self.default_visibility)
+
+    def add_item_entity(self, e_type: str, name: str, vis: str, first_line: int, last_line: int, extra_fields: dict, anchor: str = None) -> bool:
+        """Stores item with known bounds, skipped if line is taken or anchor (name by default) is misplaced."""
+        if first_line in self.owner.entity_map or not self.owner.check_entity_placement(first_line, anchor or name):
+            return False
+        lines = self.owner.source_text.split("\n")[first_line - 1:last_line]
+        self.owner.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": vis,
+            "file_id": self.owner.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        self.new_entities_lines.append(first_line)
+        return True
+
+
+class ModuleParser(RustEntityParser):
+    """Parser for Rust inline modules, nesting is tracked by explicit stack and each module body is parsed by own sub-block."""
     def __init__(self, entity_type, owner):
         outer_regex = IterativeRegex()
         outer_regex.add_token(BASE_REGEX_PATTERN + r"mod\s+(?P<name>\w+)", ["indent", "vis", "name"], 2)
         outer_regex.add_token(r"\s*{", ["head_end"], 1)
         super().__init__(entity_type, owner, outer_regex, r"\bmod\b", default_visibility="private")
 
-    def _process_match(self, match):
-        """Process a module match and perform recursive parsing."""
-        start_pos = match.start('name')
-        start_line = self.owner.find_line(start_pos)
-        module_name = match.group('name')
-        vis = "public" if match.groupdict().get('vis') else "private"
-        name_final = f"{self.owner.module_prefix}{module_name}"
-        full_text = self.owner.extract_entity_text(match.start(), match.end())
-        logging.debug(f"Processing module {name_final} at line {start_line}, text: {full_text!r}")
-        if not self.make_add_entity(self.entity_type, name_final, vis, start_line, full_text, {"parent": ""}):
+    def parse(self):
+        """Adds inline modules with nested ones, then parses each body with lines of nested modules masked.
+
+        Module bodies are tracked by one pass over braces with explicit stack, sub-blocks don't look for modules, so deep
+        nesting costs no recursion; modules nested deeper than owner `max_depth` are left to enclosing module with a warning.
+        """
+        if self.owner.depth > 0:
+            return False
+        self.content = self.owner.get_clean_content()
+        if not self.content.strip():
             return False
+        heads = {}   # offset of module body brace: head match, declarations `mod name;` have no body
+        for match in self.outer_regex.all_matches(self.content):
+            rest = self.content[match.end('name'):]
+            if rest.lstrip().startswith("{"):
+                heads[match.end('name') + len(rest) - len(rest.lstrip())] = match
+        offsets = self.owner.line_offsets
+        modules = []
+        stack = []   # open braces, module frames {"name", "match", "first_line", "nested"} or None
+        depth = 0   # module frames in stack
+        too_deep = None
+        for brace in re.finditer(r"[{}]", self.content):
+            pos = brace.start()
+            if brace.group() == "}":
+                frame = stack.pop() if stack else None
+                if frame:
+                    frame["last_line"] = bisect.bisect_right(offsets, pos)
+                    depth -= 1
+                continue
+            frame = None
+            if match := heads.get(pos):
+                first_line = bisect.bisect_right(offsets, match.start('name'))
+                if depth >= self.owner.max_depth:
+                    too_deep = too_deep or first_line
+                else:
+                    parent = next((f for f in reversed(stack) if f), None)
+                    prefix = parent["name"] + "." if parent else self.owner.module_prefix
+                    frame = {"name": prefix + match.group('name'), "match": match, "first_line": first_line, "nested": []}
+                    (parent["nested"] if parent else modules).append(frame)
+                    depth += 1
+            stack.append(frame)
+        if too_deep:
+            self.owner.parse_warn(f"Modules nested deeper than {self.owner.max_depth} levels in file {self.owner.file_name} " +
+                                  f"from line {too_deep}, parsed as part of enclosing module")
+        pending = list(reversed(modules))
+        while pending:
+            frame = pending.pop()
+            if "last_line" not in frame:
+                self.owner.parse_warn(f"Incomplete entity at line {frame['first_line']} in file {self.owner.file_name}, module not closed")
+                frame["last_line"] = frame["first_line"]
+            if not self.add_item_entity(self.entity_type, frame["name"], self.detect_visibility(frame["match"]),
+                                        frame["first_line"], frame["last_line"], {"parent": ""}):
+                continue
+            nested = [(f["first_line"], f["last_line"]) for f in frame["nested"] if "last_line" in f]
+            self.parse_module(frame["name"], frame["first_line"], frame["last_line"], nested)
+            pending.extend(reversed(frame["nested"]))
+        return True
 
-        module_lines = self.owner.extract_entity_text(match.start(), match.end()).splitlines()
-        module_size = len(module_lines)
-        sub_clean_lines = self.owner.clean_lines.copy()
-        end_line = start_line + module_size
-        for i in range(0, len(sub_clean_lines)):
-            if i < start_line + 1 or i > end_line - 1:
-                sub_clean_lines[i] = f"// ext. line #{i}"
-        masked_content = "\n".join(sub_clean_lines[1:])
+    def parse_module(self, name: str, first_line: int, last_line: int, nested: list):
+        """Parses module body by sub-block of body lines only, each nested module is masked by one line.
+        Entities, imports and module declarations are merged to owner with line numbers of file."""
+        numbers = [0]   # file line of every sub-block line
+        line_num = first_line + 1
+        for nested_first, nested_last in nested:
+            numbers += range(line_num, nested_first)
+            numbers.append(nested_first)
+            line_num = nested_last + 1
+        numbers += range(line_num, last_line + 1)
+        if len(numbers) == 1:
+            return
+        masked = {f: f"// ext. lines #{f}-#{l}" for f, l in nested}
+        sub_clean_lines = [""] + [masked.get(n, self.owner.clean_lines[n]) for n in numbers[1:]]
         sub_parser = ContentCodeRust(
-            masked_content, self.owner.content_type,
-            f"{self.owner.file_name}&{module_name}", self.owner.timestamp,
-            module_prefix=f"{self.owner.module_prefix}{module_name}."
+            "\n".join(sub_clean_lines[1:]), self.owner.content_type,
+            f"{self.owner.file_name}&{name.replace('.', '&')}", self.owner.timestamp,
+            module_prefix=f"{name}.", crate_path=self.owner.crate_path,
+            crate_root=self.owner.crate_root, role=self.owner.role
         )
         sub_result = sub_parser.parse_content(sub_clean_lines, depth=1)
         for sub_entity in sub_result["entities"]:
             if isinstance(sub_entity, dict):
-                first_line = sub_entity['first_line']
-                if first_line in self.owner.entity_map:
-                    logging.error(f"Already exists entity {self.owner.entity_map[first_line]}, can't add {sub_entity}")
+                sub_entity["first_line"] = numbers[sub_entity["first_line"]]
+                sub_entity["last_line"] = numbers[sub_entity["last_line"]]
+                line_num = sub_entity['first_line']
+                if line_num in self.owner.entity_map:
+                    logging.error(f"Already exists entity {self.owner.entity_map[line_num]}, can't add {sub_entity}")
                     continue
-                self.owner.entity_map[first_line] = sub_entity
-                self.new_entities_lines.append(first_line)
+                self.owner.entity_map[line_num] = sub_entity
+                self.new_entities_lines.append(line_num)
 
+        for record in sub_parser.reexports + sub_parser.import_paths + sub_parser.module_decls:
+            record["line"] = numbers[record["line"]]
         self.owner.extend_deps(sub_result["dependencies"])
-        return True
+        self.owner.reexports.extend(sub_parser.reexports)
+        self.owner.import_paths.extend(sub_parser.import_paths)
+        self.owner.module_decls.extend(sub_parser.module_decls)
 
 
-class TraitParser(EntityParser):
+class TraitParser(RustEntityParser):
     def __init__(self, entity_type, owner):
         self.current_struct = ""
         outer_regex = IterativeRegex()
         outer_regex\
-            .add_token(BASE_REGEX_PATTERN + r"trait\s+(?P<name>\w+)", ["indent", "vis", "name"], 2)\
-            .add_token(r"(?:\:\s*(?P<parent>[\+\w\s]+))?", ["parent"], 1)\
-            .add_token(r"\s*{", ["head_end"], 1)
+            .add_token(BASE_REGEX_PATTERN + r"(?:unsafe\s+)?trait\s+(?P<name>\w+)(?:\s*" + GENERICS_REGEX_PATTERN + ")?", ["indent", "vis", "name"], 2)\
+            .add_token(r"(?:\:\s*(?P<parent>[^{};]+?))?", ["parent"], 1)\
+            .add_token(r"\s*(?:where\s+[^{};]+)?{", ["head_end"], 1)
         inner_regex = IterativeRegex()   # abstract method
         inner_regex\
             .add_token(BASE_REGEX_PATTERN + FN_REGEX_PATTERN, ["indent", "vis", "async", "name"], 2)\
@@ -82,17 +531,22 @@ class TraitParser(EntityParser):
             .add_token(r";", ["head_end"], 1)
         super().__init__(entity_type, owner, outer_regex, r"\btrait\b|\bfn\b", inner_regex, default_visibility="private"),
 
+    def detect_abstract(self, match):
+        """Trait methods matched by inner regex are declarations ended by `;`."""
+        return match.group(0).rstrip().endswith(";")
+
 
-class TraitImplParser(EntityParser):
+class TraitImplParser(RustEntityParser):
     """Parser for Rust trait implementations and their methods."""
     def __init__(self, entity_type, owner):
         self.current_struct = ""
         outer_regex = IterativeRegex()
         # possible very simple impl definition, without "for Struct"
         outer_regex\
-            .add_token(BASE_REGEX_PATTERN + r"impl\s+(?P<name>\w+)", ["indent", "vis", "name"], 2)\
-            .add_token(r"\s+(?:for\s+(?P<struct_name>\w+))?", ["struct_name"], 1)\
-            .add_token(r"\s*{", ["head_end"], 1)
+            .add_token(BASE_REGEX_PATTERN + r"(?:unsafe\s+)?impl(?:\s*" + GENERICS_REGEX_PATTERN + r")?\s+(?P<name>(?:\w+::)*\w+)(?:" + GENERICS_REGEX_PATTERN + ")?",
+                       ["indent", "vis", "name"], 2)\
+            .add_token(r"\s+(?:for\s+(?P<struct_name>\w+)(?:" + GENERICS_REGEX_PATTERN + ")?)?", ["struct_name"], 1)\
+            .add_token(r"(?:\s*where\s+[^{;]+)?\s*{", ["head_end"], 1)
         inner_regex = IterativeRegex()
         inner_regex\
             .add_token(BASE_REGEX_PATTERN + FN_REGEX_PATTERN, ["indent", "vis", "async", "name"], 2)\
@@ -103,23 +557,269 @@ class TraitImplParser(EntityParser):
 
     def _format_entity_name(self, match):
         name = match.group("name")
-        struct_name = match.group('struct_name')
+        struct_name = match_value(match, 'struct_name')
         if struct_name is not None:
+            self.impl_fields = {"trait": name, "target": self.owner.module_prefix + struct_name}
             return f"{self.owner.module_prefix}{name}<{struct_name}>"
+        self.impl_fields = {"target": self.owner.module_prefix + name}
         return name
 
+    def _process_match(self, base_match):
+        """Adds impl block, then marks it with implemented trait and target type for link_impls."""
+        self.impl_fields = {}
+        start_line = self.owner.find_line(get_start_pos(base_match))
+        if not super()._process_match(base_match):
+            return False
+        self.owner.entity_map[start_line].update(self.impl_fields)
+        return True
+
 
-class FunctionParser(EntityParser):
+class FunctionParser(RustEntityParser):
     """Parser for Rust functions."""
     def __init__(self, entity_type, owner):
         outer_regex = IterativeRegex()
-        outer_regex.add_token(BASE_REGEX_PATTERN + r"(?P<async>async\s+)?fn\s+(?P<name>\w+)", ["indent", "vis", "async", "name"], 2)\
+        outer_regex.add_token(BASE_REGEX_PATTERN + FN_REGEX_PATTERN, ["indent", "vis", "async", "name"], 2)\
             .add_token(ARGS_REGEX_PATTERN, ["args"], 1)\
             .add_token(RET_REGEX_PATTERN, ["return"], 1)\
             .add_token(r"{", ["head_end"], 1)
         super().__init__(entity_type, owner, outer_regex, r"\bfn\b", default_visibility="private")
 
 
+class MacroParser(RustEntityParser):
+    """Parser for declarative macros `macro_rules! name { (pattern) => { expansion }; ... }`.
+
+    Arm patterns are summarized in `arms`; macro bodies are masked (brackets kept for bounds detection), so items
+    written in templates like `struct $name { .. }` don't become entities. `#[macro_export]` macros are public.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bmacro_rules\b", default_visibility="private")
+        self.bodies = []   # (open, close) offsets of macro bodies in clean content
+
+    def parse(self):
+        self.content = self.owner.get_clean_content()
+        text = self.content
+        for match in MACRO_RULES_REGEX.finditer(text):
+            if self.bodies and match.start() < self.bodies[-1][1]:
+                continue   # macro defined by another macro
+            open_pos = match.start('open')
+            close_pos = match_bracket(text, open_pos)
+            if close_pos < 0:
+                self.owner.parse_warn(f"Unbalanced macro_rules! {match.group('name')} body in file {self.owner.file_name}")
+                continue
+            self.bodies.append((open_pos, close_pos))
+            start_line = self.owner.find_line(match.start('name'))
+            if start_line in self.owner.entity_map:
+                continue   # already added by module parsing
+            vis = "private"
+            for line in reversed(self.owner.clean_lines[1:start_line]):
+                if not line.strip().startswith("#["):
+                    break
+                if re.search(r"\bmacro_export\b", line):
+                    vis = "public"
+            full_text = "\n".join(self.owner.source_text.split("\n")[start_line - 1:self.owner.find_line(close_pos)])
+            extra_fields = {"arms": self.macro_arms(text, open_pos + 1, close_pos)}
+            if self.make_add_entity(self.entity_type, self.owner.module_prefix + match.group('name'), vis, start_line, full_text, extra_fields):
+                self.owner.entity_map[start_line]["last_line"] = self.owner.find_line(close_pos)   # body may be `( .. );`
+        return True
+
+    def macro_arms(self, text: str, start: int, end: int) -> list:
+        """Returns matcher patterns of `(pattern) => {expansion}` arms with collapsed whitespace."""
+        arms = []
+        pos = start
+        while pos < end:
+            if text[pos] not in CLOSING_BRACKETS:
+                pos += 1
+                continue
+            pattern_end = match_bracket(text, pos)
+            if pattern_end < 0 or pattern_end > end:
+                break
+            pattern = " ".join(text[pos + 1:pattern_end].split())
+            arms.append(f"({pattern[:77]}...)" if len(pattern) > 80 else f"({pattern})")
+            arrow = text.find("=>", pattern_end, end)
+            expansion = next((i for i in range(arrow + 2, end) if text[i] in CLOSING_BRACKETS), -1) if arrow >= 0 else -1
+            if expansion < 0:
+                break
+            pos = match_bracket(text, expansion) + 1
+            if pos <= 0:
+                break
+        return arms
+
+    def masquerade(self):
+        """Blanks macro bodies except brackets, then marks macro lines as usual."""
+        chars = list(self.content)
+        for open_pos, close_pos in self.bodies:
+            for i in range(open_pos + 1, close_pos):
+                if chars[i] not in "{}()[]\n":
+                    chars[i] = " "
+        self.owner.clean_lines = [""] + "".join(chars).split("\n")
+        return super().masquerade()
+
+
+class ItemParser(RustEntityParser):
+    """Parser for `const`, `static` (`mutable` flag for `static mut`) and `type` alias items, local ones in function bodies skipped.
+
+    Declared type is stored as `value_type` (aliased type for `type`), value only if short. Associated items of impl
+    blocks and traits get parent; trait declarations without value like `type Output;` are abstract.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bconst\b|\bstatic\b|\btype\b", default_visibility="private")
+
+    def parse(self):
+        self.content = self.owner.get_clean_content()
+        source = self.owner.source_text.split("\n")
+        for match in ITEM_REGEX.finditer(self.content):
+            end = self.declaration_end(match.end())
+            if end < 0:
+                continue
+            first_line = self.owner.find_line(match.start('name'))
+            last_line = self.owner.find_line(end)
+            scopes = [e for e in self.owner.entity_map.values() if e["first_line"] < first_line <= e["last_line"]]
+            if any("function" in e["type"] or "method" in e["type"] for e in scopes):
+                continue   # local item
+            parent = next((e for e in sorted(scopes, key=lambda e: -e["first_line"]) if e["type"] in ("class", "interface")), None)
+            decl = " ".join(" ".join(source[first_line - 1:last_line]).split())
+            name = restore_raw_idents(match.group('name'))   # source text has raw identifiers as is
+            decl = decl[decl.find(name, decl.find(match.group('kind').split()[0])) + len(name):]
+            self.add_item(match, parent, first_line, last_line, decl.rstrip().rstrip(";"))
+        return True
+
+    def declaration_end(self, pos: int) -> int:
+        """Offset of `;` ending declaration, brackets of values (`[1, 2]`, `{ .. }`) skipped."""
+        depth = 0
+        for i in range(pos, len(self.content)):
+            ch = self.content[i]
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}":
+                depth -= 1
+                if depth < 0:
+                    return -1
+            elif ch == ";" and depth == 0:
+                return i
+        return -1
+
+    def add_item(self, match, parent, first_line, last_line, decl):
+        kind = match.group('kind').split()[0]
+        extra = {"parent": parent["name"]} if parent else {}
+        if match.group('kind') != kind:
+            extra["mutable"] = True
+        if generics := re.match(r"\s*" + GENERICS_REGEX_PATTERN, decl):
+            extra["generics"] = generics.group(0).strip()
+            decl = decl[generics.end():]
+        head, value = split_assignment(decl)
+        head = head.strip().lstrip(":").strip()
+        if head:
+            extra["bounds" if kind == "type" else "value_type"] = head
+        if kind == "type" and value:
+            extra["value_type"] = value
+        elif value and len(value) <= MAX_VALUE_LENGTH:
+            extra["value"] = re.sub(r"([(\[{])\s+", r"\1", re.sub(r",?\s+([)\]}])", r"\1", value))
+        e_type = ITEM_TYPES[kind]
+        if not value and parent and parent["type"] == "interface":
+            e_type = "abstract " + e_type   # `type Output;`, `const ID: u32;` in trait
+        name = match.group('name') if parent else self.owner.module_prefix + match.group('name')
+        self.add_item_entity(e_type, name, self.detect_visibility(match), first_line, last_line, extra)
+
+
+class ExternParser(RustEntityParser):
+    """Parser for `extern "ABI" { .. }` blocks: foreign functions and statics are FFI imports (`ffi` = "import").
+
+    Block is named by `#[link(name = "..")]` library or by ABI (`extern "C"`), repeated names get ` #N` suffix.
+    ABI is read from source, as strings are blanked in clean content.
+    """
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bextern\b", default_visibility="private")
+
+    def parse(self):
+        self.content = self.owner.get_clean_content()
+        source = [""] + self.owner.source_text.split("\n")
+        names = {}
+        for match in EXTERN_BLOCK_REGEX.finditer(self.content):
+            close_pos = match_bracket(self.content, match.end() - 1)
+            first_line = self.owner.find_line(match.start('keyword'))
+            if close_pos < 0:
+                self.owner.parse_warn(f"Unbalanced extern block at line {first_line} in file {self.owner.file_name}")
+                continue
+            abi_match = ABI_REGEX.search(source[first_line])
+            abi = abi_match.group('abi') if abi_match and abi_match.group('abi') is not None else "C"
+            link = next((m.group(1) for a in outer_attributes(source, first_line) if (m := re.match(r'link\s*\(.*\bname\s*=\s*"([^"]+)"', a))), None)
+            name = link or f'extern "{abi}"'
+            names[name] = names.get(name, 0) + 1
+            name += f" #{names[name]}" if names[name] > 1 else ""
+            block_name = self.owner.module_prefix + name
+            items = []
+            body_start = match.end()
+            for item in EXTERN_ITEM_REGEX.finditer(self.content, body_start, close_pos):
+                end = self.content.find(";", item.end(), close_pos)
+                end = close_pos if end < 0 else end
+                item_line = self.owner.find_line(item.start('fn') if item.group('fn') else item.start('static'))
+                extra = {"parent": block_name, "abi": abi, "ffi": "import"}   # signatures of functions are added later
+                if item.group('fn'):
+                    items.append(("extern function", item.group('fn'), item, item_line, self.owner.find_line(end), extra))
+                else:
+                    extra["value_type"] = " ".join(self.content[item.end():end].split())
+                    if item.group('mut'):
+                        extra["mutable"] = True
+                    items.append(("extern static", item.group('static'), item, item_line, self.owner.find_line(end), extra))
+            vis = "public" if any(rust_visibility(i[2].group('vis')) == "public" for i in items) else "private"
+            if not self.add_item_entity("extern block", block_name, vis, first_line, self.owner.find_line(close_pos), {"abi": abi}, "extern"):
+                continue
+            for e_type, item_name, item, item_line, last_line, extra in items:
+                self.add_item_entity(e_type, self.owner.module_prefix + item_name, self.detect_visibility(item), item_line, last_line, extra)
+        return True
+
+
+class EnumParser(RustEntityParser):
+    """Parser for Rust enums; variants are added as child entities with kind (unit, tuple, struct) and fields."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"enum\s+(?P<name>\w+)", ["indent", "vis", "name"], 2)\
+            .add_token(r"(?:<.*?>)?[^{;]*{", ["head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\benum\b", default_visibility="private")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        self.parse_variants(self.owner.entity_map[self.new_entities_lines[-1]])
+        return True
+
+    def parse_variants(self, enum: dict):
+        content = self.content
+        open_pos = content.find("{", self.owner.line_offsets[enum["first_line"] - 1])
+        close_pos = match_bracket(content, open_pos) if open_pos >= 0 else -1
+        if close_pos < 0:
+            return
+        lines = self.owner.source_text.split("\n")
+        for offset, piece in split_top_level(content[open_pos + 1:close_pos], open_pos + 1):
+            match = re.match(r"\s*(?:#\[.*?\]\s*)*(?P<name>\w+)\s*(?P<rest>.*?)\s*$", piece, re.DOTALL)
+            if not match:
+                continue
+            rest = match.group('rest')
+            extra = {"parent": enum["name"], "kind": "unit"}
+            if rest.startswith(("(", "{")):
+                extra["kind"] = "tuple" if rest[0] == "(" else "struct"
+                inner = rest[1:match_bracket(rest, 0)]
+                extra["fields"] = [" ".join(field.split()) for _, field in split_top_level(inner)]
+            elif rest.startswith("="):
+                extra["value"] = " ".join(rest[1:].split())
+            first_line = self.owner.find_line(offset + match.start('name'))
+            last_line = self.owner.find_line(offset + match.end('rest') - 1) if rest else first_line
+            if first_line in self.owner.entity_map:
+                continue
+            self.owner.entity_map[first_line] = {
+                "type": "variant",
+                "name": match.group('name'),
+                "visibility": enum["visibility"],
+                "file_id": self.owner.file_id,
+                "first_line": first_line,
+                "last_line": last_line,
+                "tokens": estimate_tokens("\n".join(lines[first_line - 1:last_line])),
+                **extra
+            }
+            self.new_entities_lines.append(first_line)
+
+
 class DepsParserRust(DepsParser):
     """Parser for Rust imports."""
     def __init__(self, owner):
@@ -208,9 +908,46 @@ class ContentCodeRust(ContentBlock):
         self.raw_str_prefix = "r"
         self.open_ml_string = ["r#\""]
         self.close_ml_string = ["\"#"]
+        self.nested_comments = True
         self.module_prefix = kwargs.get("module_prefix", "")
+        self.source_text = " " + content_text[1:] if content_text.startswith("\ufeff") else content_text   # raw lines, BOM blanked
+        self.depth = 0   # 1 for module sub-blocks
+        self.max_depth = kwargs.get("max_depth", MAX_MODULE_DEPTH)   # inline modules nesting limit
+        self.crate_root = kwargs.get("crate_root", "crate")   # crate name in workspace, see rebase_crate
+        self.crate_path = kwargs.get("crate_path") or crate_module_path(file_name)
+        self.role = kwargs.get("role") or cargo_role(file_name)
         logging.debug(f"Initialized ContentCodeRust with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
 
+    def strip_strings(self):
+        """Blanks char literals, removes raw and multi-line strings and closes unclosed ones before string stripping.
+
+        So `'"'` doesn't open a string, `'{'` doesn't break bounds and `r##"struct Fake {"##` doesn't produce entities.
+        Raw identifiers (`r#type`) are marked with RAW_IDENT_MARK, names get `r#` back after parsing.
+        """
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        text = blank_file_header("\n".join(line if isinstance(line, str) else "" for line in self.clean_lines[1:]))
+        lines = [strip_char_literals(line) for line in text.split("\n")]
+        text, unclosed = strip_raw_strings("\n".join(lines))
+        if unclosed:
+            self.parse_warn(f"Unterminated raw string literal in file {self.file_name}")
+        text, recovered = recover_strings(text)
+        for line_num in recovered:
+            self.parse_warn(f"Unclosed string literal in file {self.file_name} at line {line_num}, closed at end of line")
+        text = RAW_IDENT_REGEX.sub(RAW_IDENT_MARK, text)
+        self.clean_lines = [""] + text.split("\n")
+        return super().strip_strings()
+
+    def strip_comments(self):
+        """Blanks nested block comments, then strips line comments with CodeCommentStripper."""
+        if len(self.clean_lines) <= 1:
+            raise Exception("clean_lines not filled")
+        text, depth = strip_nested_comments("\n".join(line if isinstance(line, str) else "" for line in self.clean_lines[1:]))
+        if depth:
+            self.parse_warn(f"Unterminated block comment (depth {depth}) in file {self.file_name}")
+        self.clean_lines = [""] + [line.rstrip() for line in text.split("\n")]
+        return super().strip_comments()
+
     def check_lines_match(self, offset, full_clean_lines):
         """Validates that clean_lines matches full_clean_lines at the given offset."""
         if offset < 1 or offset >= len(self.clean_lines):
@@ -237,16 +974,361 @@ class ContentCodeRust(ContentBlock):
             return 0
         return line.count(ch)
 
+    def add_signatures(self, code_lines: list):
+        """Stores full declaration of functions and methods: qualifiers, generics with bounds, lifetimes, return type and where clause.
+
+        code_lines are clean lines before masquerade, as parsers replace `fn` keywords with entity types.
+        """
+        content = "\n".join(code_lines[1:])
+        line_offsets = [0]
+        for line in code_lines[1:]:
+            line_offsets.append(line_offsets[-1] + len(line) + 1)
+        for entity in self.entity_map.values():
+            if "function" not in entity["type"] and "method" not in entity["type"]:
+                continue
+            name = re.escape(entity["name"].split(".")[-1])
+            head = re.compile(r"(?:pub(?:\s*\([^)]*\))?\s+)?(?:(?:const|async|unsafe|safe|extern(?:\s+\"[^\"]*\")?)\s+)*fn\s+" + name + r"\b")
+            match = head.search(content, line_offsets[entity["first_line"] - 1])
+            if not match or match.start() >= line_offsets[min(entity["first_line"] + 1, len(line_offsets) - 1)]:
+                continue
+            depth = 0
+            end = match.end()
+            while end < len(content) and not (depth == 0 and content[end] in "{;"):
+                depth += 1 if content[end] in "([" else -1 if content[end] in ")]" else 0
+                end += 1
+            signature = " ".join(content[match.start():end].split())
+            signature = re.sub(r"\(\s+", "(", re.sub(r",?\s+\)", ")", signature))
+            entity["signature"] = signature.rstrip(", ")
+
+    def add_qualifiers(self, code_lines: list):
+        """Flags `async` / `unsafe` functions and methods, `unsafe impl` / `unsafe trait` scopes and counts `unsafe { .. }` blocks.
+
+        Blocks are counted per function in clean lines before masquerade, bodies of nested functions are excluded.
+        """
+        functions = [e for e in self.entity_map.values() if "function" in e["type"] or "method" in e["type"]]
+        for entity in self.entity_map.values():
+            first_line, last_line = entity["first_line"], entity["last_line"]
+            if entity["type"] in ("class", "interface"):
+                if re.search(r"\bunsafe\s+(?:impl|trait)\b", code_lines[first_line]):
+                    entity["unsafe"] = True
+                continue
+            if entity not in functions:
+                continue
+            head = re.split(r"\bfn\b", entity.get("signature", ""), 1)[0]   # qualifiers, `pub(crate)` has own parentheses
+            if entity["type"].startswith("async ") or re.search(r"\basync\b", head):
+                entity["async"] = True
+            if re.search(r"\bunsafe\b", head):
+                entity["unsafe"] = True
+            nested = {ln for e in functions if e is not entity and first_line < e["first_line"] and e["last_line"] <= last_line
+                      for ln in range(e["first_line"], e["last_line"] + 1)}
+            count = sum(len(re.findall(r"\bunsafe\s*\{", code_lines[ln])) for ln in range(first_line, min(last_line, len(code_lines) - 1) + 1)
+                        if ln not in nested)
+            if count:
+                entity["unsafe_blocks"] = count
+
+    def add_docs(self):
+        """Attaches first paragraph of `///` / `#[doc = "..."]` comments above entity (or `//!` inside module) as `doc`."""
+        lines = [""] + self.source_text.split("\n")
+        for entity in self.entity_map.values():
+            doc_lines = []
+            line_num = entity["first_line"] - 1
+            while line_num > 0:
+                match = OUTER_DOC_REGEX.match(lines[line_num])
+                if match:
+                    doc_lines.insert(0, doc_text(match))
+                elif not re.match(r"^\s*#\[.*\]\s*$", lines[line_num]):
+                    break   # attributes between docs and item are skipped
+                line_num -= 1
+            if not doc_lines and entity["type"] == "module":
+                for line in lines[entity["first_line"] + 1:entity["last_line"]]:
+                    match = INNER_DOC_REGEX.match(line)
+                    if not match:
+                        break
+                    doc_lines.append(doc_text(match))
+            if doc := first_paragraph(doc_lines):
+                entity["doc"] = doc
+
+    def add_attributes(self):
+        """Stores attributes of entities as `attributes` (`tokio::main`, `async_trait`), derived traits also as `derives`."""
+        lines = [""] + self.source_text.split("\n")
+        for entity in self.entity_map.values():
+            attrs = outer_attributes(lines, entity["first_line"])
+            if not attrs:
+                continue
+            entity["attributes"] = attrs
+            conditions = [m.group(1) for a in attrs if (m := re.match(r"cfg\s*\((.*)\)$", a))]
+            if conditions:
+                entity["cfg"] = conditions[0] if len(conditions) == 1 else f"all({', '.join(conditions)})"
+            derives = []
+            for attr in attrs:
+                if match := re.match(r"derive\s*\((?P<traits>.*)\)$", attr):
+                    derives.extend(piece.strip() for _, piece in split_top_level(match.group('traits')) if piece.strip())
+            if derives:
+                entity["derives"] = derives
+
+    def add_ffi_exports(self):
+        """Marks `#[no_mangle]` / `#[export_name = ".."]` functions and statics as FFI exports (`ffi` = "export") with ABI.
+
+        ABI comes from source (`extern "C" fn`, "Rust" if omitted), blanked ABI string in signature is restored.
+        """
+        lines = [""] + self.source_text.split("\n")
+        for entity in self.entity_map.values():
+            attrs = [re.sub(r"^unsafe\s*\((.*)\)$", r"\1", a) for a in entity.get("attributes", [])]   # edition 2024 `#[unsafe(no_mangle)]`
+            export = next((a for a in attrs if a == "no_mangle" or a.startswith("export_name")), None)
+            if not export or ("function" not in entity["type"] and entity["type"] != "static"):
+                continue
+            entity["ffi"] = "export"
+            if name := re.match(r'export_name\s*=\s*"([^"]*)"', export):
+                entity["symbol"] = name.group(1)
+            head = " ".join(lines[entity["first_line"]:entity["first_line"] + 3])
+            head = head[:head.find(" fn ") + 1] if " fn " in head else head[:head.find(entity["name"].split(".")[-1])]
+            abi_match = ABI_REGEX.search(head)
+            entity["abi"] = (abi_match.group('abi') or "C") if abi_match else "Rust"
+            if abi_match and "signature" in entity:
+                entity["signature"] = entity["signature"].replace('extern ""', f'extern "{entity["abi"]}"', 1)
+
+    def inherit_cfg(self):
+        """Adds cfg conditions of enclosing items (`#[cfg(test)] mod tests`) to nested entities, outer ones first."""
+        gated = sorted([e for e in self.entity_map.values() if e.get("cfg")], key=lambda e: e["first_line"])
+        own = {id(e): e["cfg"] for e in gated}
+        for entity in self.entity_map.values():
+            conditions = [own[id(e)] for e in gated if e is not entity
+                          and e["first_line"] < entity["first_line"] and entity["last_line"] <= e["last_line"]]
+            if not conditions:
+                continue
+            conditions += [own[id(entity)]] if id(entity) in own else []
+            entity["cfg"] = conditions[0] if len(conditions) == 1 else f"all({', '.join(conditions)})"
+
+    def add_test_kinds(self):
+        """Tags tests and benches with `test` field ("test" / "bench").
+
+        Detected by `#[test]`, `#[tokio::test]`, `#[bench]` attributes, `cfg(test)` condition and `tests/`, `benches/` directories.
+        """
+        dirs = [TEST_DIRS[part] for part in Path(self.file_name.split("&")[0]).parts[:-1] if part in TEST_DIRS] if self.file_name else []
+        for entity in self.entity_map.values():
+            names = [re.split(r"[\s(]", attr, 1)[0] for attr in entity.get("attributes", [])]
+            kinds = [TEST_ATTRIBUTES.get(name, "test" if name.endswith("::test") else None) for name in names]
+            kind = next((k for k in kinds if k), None)
+            if kind is None and entity.get("cfg") and cfg_enabled(entity["cfg"], test=False) is False:
+                kind = "test"
+            kind = kind or (dirs[-1] if dirs else None)
+            if kind:
+                entity["test"] = kind
+
+    def add_roles(self):
+        """Tags entities with Cargo target `role` of file (library, binary, build script, example, bench, integration test)."""
+        if self.role:
+            for entity in self.entity_map.values():
+                entity["role"] = self.role
+
+    def add_uses(self, code_lines: list):
+        """Collects `use` imports of this module scope as resolved paths, `pub use` ones are re-exports too:
+        public path as users import it and resolved source path.
+
+        `self::`, `super::`, `crate::` and paths starting with a module declared in file are resolved to crate paths, other ones
+        are external crates. Uses inside inline modules are collected by module sub-blocks.
+        """
+        content = "\n".join(code_lines[1:])
+        scope = self.module_path()
+        local_modules = set(MOD_DECL_REGEX.findall(content))
+        modules = [e for e in self.entity_map.values() if e["type"] == "module"]
+        for match in USE_REGEX.finditer(content):
+            line_num = content.count("\n", 0, match.start()) + 1
+            if any(m["first_line"] < line_num <= m["last_line"] for m in modules):
+                continue
+            for path, alias in flatten_use_tree(match.group('tree')):
+                segments = path.split("::")
+                base = scope.split("::")
+                if segments[0] in ("self", "super"):
+                    while segments and segments[0] in ("self", "super"):
+                        if segments.pop(0) == "super" and len(base) > 1:
+                            base = base[:-1]
+                    source = "::".join(base + segments)
+                elif segments[0] in local_modules:
+                    source = "::".join(base + segments)
+                elif segments[0] == "crate":
+                    source = "::".join([self.crate_root] + segments[1:])
+                else:
+                    source = path   # external crate, workspace crates are named by their package
+                self.import_paths.append({"path": source, "alias": alias, "line": line_num})
+                if match_value(match, 'vis', '').strip() == "pub":   # `pub(crate) use` is not a part of public API
+                    self.reexports.append({"path": f"{scope}::{alias or segments[-1]}", "source": source, "line": line_num})
+
+    def module_path(self):
+        """Crate path of module scope: file module path with inline modules of sub-block."""
+        return self.crate_path + "".join(f"::{part}" for part in self.module_prefix.split(".") if part)
+
+    def add_module_decls(self, code_lines: list):
+        """Collects `mod name;` declarations of this module scope with `#[path = ".."]` file, inline modules collect own ones."""
+        content = "\n".join(code_lines[1:])
+        source = [""] + self.source_text.split("\n")
+        prefix = [part for part in self.module_prefix.split(".") if part]
+        modules = [e for e in self.entity_map.values() if e["type"] == "module"]
+        for match in MOD_FILE_REGEX.finditer(content):
+            line_num = content.count("\n", 0, match.start()) + 1
+            if any(m["first_line"] <= line_num <= m["last_line"] for m in modules):
+                continue
+            path_attr = next((m.group(1) for a in outer_attributes(source, line_num) if (m := re.match(r'path\s*=\s*"([^"]+)"', a))), None)
+            self.module_decls.append({"name": "::".join(prefix + [match.group('name')]), "line": line_num, "path_attr": path_attr})
+
+    def module_files(self) -> dict:
+        """Candidate files of declared modules: `name.rs` and `name/mod.rs` beside `lib.rs` / `main.rs` / `mod.rs`
+        (and `src/bin/*.rs` roots), in `stem/` directory for other files; `#[path]` is relative to file directory.
+        """
+        file_name = self.file_name.split("&")[0].replace("\\", "/") if self.file_name else ""
+        directory = posixpath.dirname(file_name)
+        stem = Path(file_name).stem
+        base = directory if stem in ("lib", "main", "mod") or posixpath.basename(directory) == "bin" else posixpath.join(directory, stem)
+        files = {}
+        for decl in self.module_decls:
+            *inline, name = decl["name"].split("::")
+            scope = posixpath.join(base, *inline)
+            if decl["path_attr"]:
+                candidates = [posixpath.join(scope if inline else directory, decl["path_attr"])]
+            else:
+                candidates = [posixpath.join(scope, name + ".rs"), posixpath.join(scope, name, "mod.rs")]
+            for candidate in candidates:
+                files[posixpath.normpath(candidate)] = f"{self.crate_path}::{decl['name']}"
+        return files
+
+    def rebase_crate(self, crate_name: str) -> bool:
+        """Names crate root by workspace crate (`crate::net` -> `core_lib::net`), so crates of one index don't collide."""
+        if crate_name == self.crate_root:
+            return False
+        self.crate_path = crate_name + self.crate_path[len(self.crate_root):]
+        self.crate_root = crate_name
+        return True
+
+    def rebase_module(self, module_path: str, role=None) -> bool:
+        """Sets crate path resolved from `mod` declaration, instead of path guessed from file name; role of declaring
+        file is inherited, so modules of `main.rs` are binary ones.
+        """
+        if module_path == self.crate_path and role in (None, self.role):
+            return False
+        self.crate_path = module_path
+        self.role = role or self.role
+        return True
+
+    def target_role(self):
+        """Cargo target role of file, see cargo_role."""
+        return self.role
+
+    def add_paths(self):
+        """Stores fully-qualified `path` of entities: items `crate::net::Client`, members of types and traits
+        `crate::net::Client::connect`, trait impls `<crate::net::Client as crate::io::Read>`; extern blocks have no path.
+        """
+        def local(name):
+            return self.crate_path + "::" + name.replace(".", "::")
+
+        scopes = {e["name"]: e for e in self.entity_map.values() if e["type"] in ("interface", "class", "enum", "extern block")}
+        for entity in scopes.values():
+            if entity["type"] == "class" and "target" in entity:
+                if entity.get("trait"):
+                    entity["trait_path"] = self.resolve_trait(entity["trait"])
+                    entity["path"] = f"<{local(entity['target'])} as {entity['trait_path']}>"
+                else:
+                    entity["path"] = local(entity["target"])
+            elif entity["type"] != "extern block":
+                entity["path"] = self.entity_path(entity)
+        for entity in self.entity_map.values():
+            if scopes.get(entity["name"]) is entity:
+                continue
+            scope = scopes.get(entity.get("parent"))
+            if scope is None or scope["type"] == "extern block":
+                entity["path"] = self.entity_path({**entity, "parent": None})
+            elif scope.get("path"):
+                entity["path"] = f"{scope['path']}::{entity['name']}"
+
+    def resolve_trait(self, name: str) -> str:
+        """Path of trait used in bounds or impl: trait of this module, imported by `use` (alias too) or as written for external ones."""
+        traits = {e["name"] for e in self.entity_map.values() if e["type"] == "interface"}
+        for local_name in (self.module_prefix + name, name):
+            if local_name in traits:
+                return self.crate_path + "::" + local_name.replace(".", "::")
+        first, _, rest = name.partition("::")
+        for imp in self.import_paths:
+            if first == (imp["alias"] or imp["path"].split("::")[-1]) and not imp["path"].endswith("::*"):
+                return imp["path"] + ("::" + rest if rest else "")
+        if first == "crate":
+            return self.crate_root + "::" + rest
+        return name
+
+    def add_supertraits(self):
+        """Stores `supertraits` of traits (`trait Shape: Base + Debug`) as resolved trait paths, lifetime bounds as is."""
+        for entity in self.entity_map.values():
+            if entity["type"] == "interface" and entity.get("parent"):
+                bounds = [" ".join(piece.split()) for _, piece in split_top_level(entity["parent"], separator="+")]
+                entity["supertraits"] = [b if b.startswith(("'", "?")) else self.resolve_trait(b) for b in bounds]
+
+    def entity_path(self, entity: dict):
+        """Crate path of item (`crate::net::Client`), None for members: methods, variants, impl blocks (`parent` of trait lists supertraits)."""
+        if (entity.get("parent") and entity["type"] != "interface") or entity["type"] in ("variant", "class") or "method" in entity["type"]:
+            return None
+        if entity["type"] == "macro" and entity["visibility"] == "public":
+            return self.crate_root + "::" + entity["name"].split(".")[-1]   # `#[macro_export]` places macro at crate root
+        return self.crate_path + "::" + entity["name"].replace(".", "::")
+
+    def resolve_visibility(self):
+        """Trait items are visible as their trait, impl blocks as implemented type (and trait), trait impl items as impl."""
+        scopes = {e["name"]: e for e in self.entity_map.values() if e["type"] in ("interface", "class")}
+        types = {e["name"]: e for e in self.entity_map.values() if e["type"] in ("structure", "enum")}
+        for impl in [e for e in self.entity_map.values() if e["type"] == "class" and "target" in e]:
+            levels = [types[impl["target"]]["visibility"]] if impl["target"] in types else []
+            if impl.get("trait") in scopes and scopes[impl["trait"]]["type"] == "interface":
+                levels.append(scopes[impl["trait"]]["visibility"])
+            if levels:
+                impl["visibility"] = next((level for level in reversed(levels) if level != "public"), "public")
+        for entity in self.entity_map.values():
+            scope = scopes.get(entity.get("parent"))
+            if scope and (scope["type"] == "interface" or "trait" in scope):
+                entity["visibility"] = scope["visibility"]
+
+    def entity_public(self, entity: dict) -> bool:
+        """Entity is a part of public API if it and all enclosing modules are `pub`."""
+        if entity["visibility"] != "public":
+            return False
+        return all(e["visibility"] == "public" for e in self.entity_map.values() if e["type"] == "module" and e is not entity
+                   and e["first_line"] < entity["first_line"] and entity["last_line"] <= e["last_line"])
+
+    def entity_enabled(self, entity: dict, cfg_test=None, cfg_features=None) -> bool:
+        """Excludes entity only if its cfg condition is false for given options."""
+        return not entity.get("cfg") or cfg_enabled(entity["cfg"], cfg_test, cfg_features) is not False
+
+    def link_impls(self):
+        """Attaches impl blocks to the struct/enum they implement: target gets `impls` (impl entity names) and `methods`."""
+        types = {e["name"]: e for e in self.entity_map.values() if e["type"] in ("structure", "enum")}
+        for impl in [e for e in self.entity_map.values() if "target" in e and e["type"] == "class"]:
+            target = types.get(impl["target"])
+            if target is None:
+                base_name = impl["target"].split(".")[-1]
+                candidates = [e for name, e in types.items() if name.split(".")[-1] == base_name]
+                target = candidates[0] if len(candidates) == 1 else None
+            if target is None:
+                continue
+            impl["target"] = target["name"]
+            impls = target.setdefault("impls", [])
+            methods = target.setdefault("methods", [])
+            if impl["name"] not in impls:
+                impls.append(impl["name"])
+            for method in sorted(self.entity_map.values(), key=lambda e: e["first_line"]):
+                if method.get("parent") == impl["name"] and "method" in method["type"] and method["name"] not in methods:
+                    methods.append(method["name"])
+
     def parse_content(self, clean_lines=None, depth=0):
         """Parses Rust content to extract entities and dependencies."""
         logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
         if depth >= 2:
             self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
             return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.depth = depth
         self.entity_map = {}
         self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
         self.strip_strings()
         self.strip_comments()
+        code_lines = self.clean_lines.copy()
+        self.reexports = []
+        self.import_paths = []
+        self.module_decls = []
 
         struct_regex = IterativeRegex()
         struct_regex\
@@ -256,10 +1338,14 @@ class ContentCodeRust(ContentBlock):
         parsers = [
             DepsParserRust(self),
             ModuleParser("module", self),
-            EntityParser("structure", self, struct_regex, r"\bstruct\b", default_visibility="private"),
+            MacroParser("macro", self),
+            RustEntityParser("structure", self, struct_regex, r"\bstruct\b", default_visibility="private"),
+            EnumParser("enum", self),
             TraitParser("interface", self),
             TraitImplParser("class", self),
-            FunctionParser("function", self)
+            ExternParser("extern", self),
+            FunctionParser("function", self),
+            ItemParser("item", self)
         ]
         # Initialize structure and interface regexes
 
@@ -274,6 +1360,22 @@ class ContentCodeRust(ContentBlock):
                 traceback.print_exc()
                 break
 
+        self.add_signatures(code_lines)
+        self.add_qualifiers(code_lines)
+        self.add_docs()
+        self.add_attributes()
+        self.add_ffi_exports()
+        self.inherit_cfg()
+        self.add_test_kinds()
+        self.add_roles()
+        self.link_impls()
+        self.resolve_visibility()
+        self.add_uses(code_lines)
+        self.add_module_decls(code_lines)
+        self.add_paths()
+        self.add_supertraits()
+        self.entity_map = {line: restore_raw_idents(e) for line, e in self.entity_map.items()}
+        self.reexports, self.import_paths, self.module_decls = restore_raw_idents([self.reexports, self.import_paths, self.module_decls])
         entities = self.sorted_entities()
         logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
         return {"entities": entities, "dependencies": self.dependencies}
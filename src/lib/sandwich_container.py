@@ -0,0 +1,146 @@
+# /lib/sandwich_container.py, updated 2026-10-16 17:00 EEST
+# Compressed sandwich container: framed sections per block, seekable table of contents at the end.
+
+import gzip
+import json
+import struct
+
+MAGIC = b"SWPACK01"
+TRAILER = struct.Struct("<Q8s")   # table of contents length, magic
+CODECS = ("zstd", "gzip")
+EXTENSIONS = {"zstd": ".swp.zst", "gzip": ".swp.gz"}
+
+
+def _zstd():
+    try:
+        import zstandard
+    except ImportError as e:
+        raise ValueError(f"Codec `zstd` is not available, install package `zstandard` or use `gzip` codec: {e}")
+    return zstandard
+
+
+def default_codec() -> str:
+    """`zstd` when package `zstandard` is installed, `gzip` from standard library otherwise."""
+    try:
+        _zstd()
+    except ValueError:
+        return "gzip"
+    return "zstd"
+
+
+def compress(data: bytes, codec: str) -> bytes:
+    if codec == "zstd":
+        return _zstd().ZstdCompressor().compress(data)
+    if codec == "gzip":
+        return gzip.compress(data, mtime=0)   # reproducible frames
+    raise ValueError(f"Unknown codec `{codec}`, expected one of {', '.join(CODECS)}")
+
+
+def decompress(data: bytes, codec: str) -> bytes:
+    if codec == "zstd":
+        return _zstd().ZstdDecompressor().decompress(data)
+    if codec == "gzip":
+        return gzip.decompress(data)
+    raise ValueError(f"Unknown codec `{codec}`, expected one of {', '.join(CODECS)}")
+
+
+def split_sandwich(sandwich: str, blocks: list, footer: int = None) -> list:
+    """Splits sandwich text into (block key, block text) by start lines from deep index, keys are `file_3`, `post_7`, ...
+    Text before first block and from footer line (deep index of last volume) are `header` and `footer` sections."""
+    lines = sandwich.split("\n")   # every block is followed by newline, so last item is empty
+    starts = [(next((k for k in block if k not in ("modules", "imports", "entities")), "block"), block) for block in blocks]
+    tail = footer - 1 if footer else len(lines) - 1
+    result = []
+    if starts and starts[0][1][starts[0][0]] > 1:
+        result.append(("header", "\n".join(lines[:starts[0][1][starts[0][0]] - 1])))
+    for i, (key, block) in enumerate(starts):
+        end = starts[i + 1][1][starts[i + 1][0]] - 1 if i + 1 < len(starts) else tail
+        result.append((key, "\n".join(lines[block[key] - 1:end])))
+    if footer:
+        result.append(("footer", "\n".join(lines[tail:len(lines) - 1])))
+    return result
+
+
+def write_container(stream, result: dict, codec: str = "zstd") -> list:
+    """Writes pack result (index, deep_index, sandwiches) as container, each block compressed as own frame.
+    Returns table of contents: sections with name, volume, offset and length of frame."""
+    compress(b"", codec)   # unknown or not installed codec fails before anything is written
+    deep_index = json.loads(result["deep_index"])
+    files = {}
+    for row in json.loads(result["index"]).get("files", []):
+        file_id, file_name = row.split(",", 2)[:2]
+        files[f"file_{file_id}"] = file_name
+    stream.write(MAGIC)
+    offset = len(MAGIC)
+    sections = []
+
+    def frame(name: str, kind: str, text: str, **extra):
+        nonlocal offset
+        data = compress(text.encode("utf-8"), codec)
+        stream.write(data)
+        sections.append({"name": name, "kind": kind, **extra, "offset": offset, "length": len(data)})
+        offset += len(data)
+
+    frame("index", "index", result["index"])
+    frame("deep_index", "index", result["deep_index"])
+    for volume, (sandwich, entry) in enumerate(zip(result["sandwiches"], deep_index["sandwiches"]), 1):
+        for key, text in split_sandwich(sandwich, entry["blocks"], entry.get("footer")):
+            if key.startswith("file_"):
+                frame(files.get(key, key), "file", text, file_id=int(key[5:]), volume=volume)
+            else:
+                frame(key, "post" if key.startswith("post_") else key, text, volume=volume)
+    toc = json.dumps({"codec": codec, "sections": sections}, ensure_ascii=False).encode("utf-8")
+    stream.write(toc)
+    stream.write(TRAILER.pack(len(toc), MAGIC))
+    return sections
+
+
+class SandwichContainer:
+    """Reader of container file: table of contents is loaded on open, sections are decompressed on demand."""
+
+    def __init__(self, file_path):
+        self.file_path = file_path
+        with open(file_path, "rb") as f:
+            if f.read(len(MAGIC)) != MAGIC:
+                raise ValueError(f"File {file_path} is not a sandwich container")
+            f.seek(-TRAILER.size, 2)
+            toc_length, magic = TRAILER.unpack(f.read(TRAILER.size))
+            if magic != MAGIC:
+                raise ValueError(f"Sandwich container {file_path} is truncated")
+            f.seek(-TRAILER.size - toc_length, 2)
+            toc = json.loads(f.read(toc_length).decode("utf-8"))
+        self.codec = toc["codec"]
+        self.sections = toc["sections"]
+
+    def names(self) -> list:
+        return [s["name"] for s in self.sections if s["kind"] == "file"]
+
+    def section(self, key):
+        """Section by file name, file_id (int) or name of index and post sections."""
+        for s in self.sections:
+            if s["name"] == key or (isinstance(key, int) and s.get("file_id") == key):
+                return s
+        raise KeyError(f"No section {key} in sandwich container {self.file_path}")
+
+    def read(self, key) -> str:
+        """Decompresses single section, only its frame is read from file."""
+        s = self.section(key)
+        with open(self.file_path, "rb") as f:
+            f.seek(s["offset"])
+            return decompress(f.read(s["length"]), self.codec).decode("utf-8")
+
+    def decompress(self) -> dict:
+        """Restores whole pack result: index, deep_index and sandwiches text of all volumes."""
+        sandwiches = []
+        with open(self.file_path, "rb") as f:
+            texts = []
+            for s in self.sections:
+                f.seek(s["offset"])
+                texts.append((s, decompress(f.read(s["length"]), self.codec).decode("utf-8")))
+        for s, text in texts:
+            if "volume" in s:
+                while len(sandwiches) < s["volume"]:
+                    sandwiches.append("")
+                sandwiches[s["volume"] - 1] += text + "\n"
+        index = {s["name"]: text for s, text in texts if s["kind"] == "index"}
+        return {"index": index["index"], "deep_index": index["deep_index"], "sandwiches": sandwiches}
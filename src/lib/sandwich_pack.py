@@ -1,41 +1,167 @@
-# /lib/sandwich_pack.py, updated 2025-08-05 16:00 EEST
+# /lib/sandwich_pack.py, updated 2026-10-16 17:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import hashlib
 import importlib.util
 import os
+import posixpath
+import fnmatch
 import re
+import stat
 import logging
 import datetime
+import copy
 import json
 import math
 import traceback
-from pathlib import Path
-from .content_block import ContentBlock, ContextPatchBlock, estimate_tokens
+from pathlib import Path, PurePosixPath
+from .content_block import ContentBlock, ContextPatchBlock, SummaryBlock, estimate_tokens
 from .deps_builder import organize_modules
-from .file_type_detector import DOCUMENT_EXTENSIONS, TEXT_FILE_EXTENSIONS
+from .llm_tools import tokenizer_name
+from .ignore_rules import path_regex
+from .pack_format import FORMAT_VERSION
+from .secret_scan import redact_secrets
+from .text_bytes import decode_source, split_eol, restore_source
+from .file_type_detector import DOCUMENT_EXTENSIONS, TEXT_FILE_EXTENSIONS, generated_reason
+
+# code fence info strings for markdown output, other content types use extension without dot
+MARKDOWN_LANGUAGES = {
+    ".rs": "rust", ".py": "python", ".js": "javascript", ".jsx": "jsx", ".ts": "typescript", ".tsx": "tsx", ".md": "markdown",
+    ".sh": "bash", ".yml": "yaml", ".kt": "kotlin", ".cs": "csharp", ".rb": "ruby", ".hs": "haskell", ".h": "c", ".hpp": "cpp",
+    ".cc": "cpp", ".pl": "perl", ".ps1": "powershell", ".ex": "elixir", ".exs": "elixir", ".jl": "julia", ".sol": "solidity",
+    ".m": "objectivec", ".tf": "hcl", ".proto": "protobuf", ".ipynb": "json", "Dockerfile": "dockerfile", "Makefile": "makefile",
+    "CMakeLists.txt": "cmake",
+}
+
+
+def source_date_epoch():
+    """UTC datetime from SOURCE_DATE_EPOCH environment variable (reproducible builds), None if not set or invalid."""
+    value = os.environ.get("SOURCE_DATE_EPOCH", "").strip()
+    if not value:
+        return None
+    try:
+        return datetime.datetime.fromtimestamp(int(value), datetime.timezone.utc)
+    except (ValueError, OverflowError, OSError):
+        logging.warning(f"Invalid SOURCE_DATE_EPOCH `{value}` ignored")
+        return None
 
 
 def compute_md5(content: str) -> str:
     return hashlib.md5(content.encode("utf-8")).hexdigest()
 
 
+def compute_sha256(content: str) -> str:
+    return hashlib.sha256(content.encode("utf-8")).hexdigest()
+
+
+def git_blob_hash(data: bytes) -> str:
+    """Git object id of file content, as `git hash-object` prints."""
+    return hashlib.sha1(b"blob %d\0" % len(data) + data).hexdigest()
+
+
 class SandwichPack:
     _block_classes = []
     _NON_CODE_CONTENT_TYPES = {
         ":post",
         ":context_patch",
         ":document",
+        ":binary",
+        ":symlink",
+        ":summary",
     } | set(DOCUMENT_EXTENSIONS) | set(TEXT_FILE_EXTENSIONS)
+    # jsonl records are streamed by write_jsonl, container is written from text pack by sandwich_container.write_container
+    OUTPUT_FORMATS = ("text", "json", "jsonl", "markdown", "container")
+    SPLIT_UNITS = ("module", "crate", "directory")   # files without module path or crate fall back to top-level directory
+    LINE_NUMBER_MODES = ("lines", "anchors")   # lines - `12| code` prefixes, anchors - comment line before every entity
+    OVERSIZE_MODES = ("skip", "truncate", "index")   # files over per-file cap: dropped, head + tail kept, entity index only
+    GENERATED_MODES = ("keep", "skip", "index")   # generated files (lockfiles, markers, minified): as is, dropped, entity index only
+    # input - as collected; size - smallest first; mtime - newest first; topology - roots first; relevance - highest first;
+    # complexity - highest total complexity of file functions first
+    SORT_ORDERS = ("input", "path", "size", "mtime", "topology", "relevance", "complexity")
+    REPORT_FUNCTIONS = 20   # most complex functions listed by token report
+    # header / footer template placeholders, `{{name}}` is kept literal `{name}`
+    TEMPLATE_PLACEHOLDERS = ("project_name", "file_count", "token_count", "entity_count", "volume_count", "context_date", "packer_version")
+    # extensions importing each other without extension in module names (`./view` -> `view.tsx`, `str` -> `str.h`)
+    DEPENDENCY_FAMILIES = ({".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx", ".mts", ".cts", ".vue", ".svelte"},
+                           {".c", ".h", ".cc", ".cpp", ".cxx", ".hh", ".hpp", ".hxx", ".m", ".mm"})
+    PACKAGE_STEMS = ("__init__", "index", "mod")   # package files named by directory: Python, JS / TS, Rust
+    DIRECTORY_PACKAGES = (".go", ".tf")   # imports name directory, dependency on all its files
+    # last word of callable entity types besides functions and methods (`class method`, Elixir `defp`, Rust `macro`)
+    CALLABLE_KINDS = ("constructor", "procedure", "proc", "def", "defp", "macro", "test")
+    CALL_REGEX = re.compile(r"(?:\b(\w+)\s*(?:\.|::|->|:)\s*)?\b([A-Za-z_]\w*)\s*!?\s*\(")   # qualifier, called name
+    TEST_DIRS = ("tests", "benches")   # members of these directories are test files, dropped or kept whole by tests mode
+    # why entities are excluded from pack, for the comment replacing their text
+    EXCLUSION_NOTES = {"cfg": "disabled by cfg options", "tests": "excluded by tests mode", "role": "excluded build target role",
+                       "private": "not public API"}
+    ATTACHED_LINE_REGEX = re.compile(r"^\s*(?:#\[|///|@[A-Za-z_])")   # attributes, doc comments and decorators of next item
 
-    def __init__(self, project_name: str, max_size: int = 42_000, token_limit=131_000, system_prompt=None, compression=False):
+    def __init__(self, project_name: str, max_size: int = 42_000, token_limit=131_000, system_prompt=None, compression=False,
+                 cfg_test=None, cfg_features=None, tests=None, public_api=False, roles=None, output_format="text",
+                 timestamps=True, max_file_tokens=None, max_file_size=None, oversize="truncate", sort="input",
+                 budget_tokens=None, outline=False, elide_bodies_over=None, line_numbers=None, summary=False,
+                 split_by=None, header=None, footer=None, redact=True, strip_comments=False, strip_docs=False, generated="index",
+                 slice_root=None, slice_depth=2, cross_refs=False):
+        if output_format not in self.OUTPUT_FORMATS:
+            raise ValueError(f"Unsupported output format `{output_format}`, expected one of {', '.join(self.OUTPUT_FORMATS)}")
+        if oversize not in self.OVERSIZE_MODES:
+            raise ValueError(f"Unsupported oversize mode `{oversize}`, expected one of {', '.join(self.OVERSIZE_MODES)}")
+        if generated not in self.GENERATED_MODES:
+            raise ValueError(f"Unsupported generated files mode `{generated}`, expected one of {', '.join(self.GENERATED_MODES)}")
+        if line_numbers is not None and line_numbers not in self.LINE_NUMBER_MODES:
+            raise ValueError(f"Unsupported line numbers mode `{line_numbers}`, expected one of {', '.join(self.LINE_NUMBER_MODES)}")
+        if split_by is not None and split_by not in self.SPLIT_UNITS:
+            raise ValueError(f"Unsupported split unit `{split_by}`, expected one of {', '.join(self.SPLIT_UNITS)}")
+        if sort not in self.SORT_ORDERS:
+            raise ValueError(f"Unsupported sort order `{sort}`, expected one of {', '.join(self.SORT_ORDERS)}")
+        if not isinstance(slice_depth, int) or isinstance(slice_depth, bool) or slice_depth < 0:
+            raise ValueError(f"Invalid call slice depth {slice_depth!r}, non-negative integer expected")
+        if slice_root is not None and output_format == "jsonl":
+            raise ValueError("Call slice needs whole project index, not available for streamed jsonl format")
+        if strip_docs and not strip_comments:
+            raise ValueError("Doc comments are stripped only together with comments, strip_comments expected")
+        for template in (header, footer):
+            unknown = [name for literal, name in re.findall(r"\{\{(\w+)\}\}|\{(\w+)\}", template or "")
+                       if name and name not in self.TEMPLATE_PLACEHOLDERS]
+            if unknown:
+                raise ValueError(f"Unknown template placeholder `{{{unknown[0]}}}`, expected one of {', '.join(self.TEMPLATE_PLACEHOLDERS)}")
         self.project_name = project_name
         self.max_size = max_size
         self.token_limit = token_limit
         self.entities = []   # unpacked list, with records
         self.entity_rev_map = {}
+        self.file_names = {}   # file_id: file name of packed files
+        self.file_deps = {}   # file_id: sorted file_ids imported or declared as modules by file
+        self.call_graph = {}   # entity index: sorted indices of functions called by entity
         self.system_prompt = system_prompt
         self.compression = compression
+        self.cfg_test = cfg_test   # None - test-only items are kept, True/False - evaluated like `--cfg test`
+        self.cfg_features = None if cfg_features is None else set(cfg_features)   # None - no feature filtering
+        self.tests = tests   # None - all entities, "skip" - without tests and benches, "only" - tests and benches only
+        self.public_api = public_api   # pack externally visible entities only, text of other items is omitted
+        self.roles = None if roles is None else set(roles)   # None - all, else build target roles to pack (library, binary, ...)
+        self.output_format = output_format   # "json" - pack result also contains structured `json` dump
+        self.timestamps = timestamps   # False - no wall-clock context date in index, SOURCE_DATE_EPOCH is used anyway
+        self.path_filters = []   # ordered (include, pattern, regex), last matching rule wins
+        self.max_file_tokens = max_file_tokens   # per-file cap, None - not limited
+        self.max_file_size = max_file_size   # per-file cap in bytes of text, None - not limited
+        self.oversize = oversize
+        self.sort = sort   # order of file blocks in sandwiches, posts always go first
+        self.priorities = []   # ordered (pattern, weight, regex), last matching rule wins, unmatched files have weight 0
+        self.budget_tokens = budget_tokens   # whole pack token budget, low priority files are elided over it; None - not limited
+        self.outline = outline   # signatures only: function bodies of code files are replaced by `...`
+        self.elide_bodies_over = elide_bodies_over   # function bodies longer than this lines are replaced by comment, None - kept
+        self.line_numbers = line_numbers   # original line numbers annotation of packed content, None - plain content
+        self.summary = summary   # leading manifest block: languages, file counts, tokens, entity kinds, module tree
+        self.split_by = split_by   # own sandwiches for every module / crate / directory, None - split by size only
+        self.header = header   # template of text before first sandwich block, e.g. standing LLM instructions
+        self.footer = footer   # template of text after last sandwich block
+        self.redact = redact   # API keys, private keys, passwords replaced by `[REDACTED:kind]` in packed text
+        self.strip_comments = strip_comments   # comments removed from code files, doc comments kept unless strip_docs
+        self.strip_docs = strip_docs
+        self.generated = generated
+        self.slice_root = slice_root   # function (name, `Parent.name`) packed with functions it calls, None - whole project
+        self.slice_depth = slice_depth   # call levels of slice, 0 - root function only
+        self.cross_refs = cross_refs   # index files referencing every entity name, grows index by about third
         self.datasheet = {
             "project_root": "/app",
             "backend_address": "http://localhost:8080",
@@ -47,6 +173,143 @@ class SandwichPack:
         }
         self.busy_ids = set()
 
+    def oversized(self, block) -> bool:
+        if self.max_file_tokens is not None and block.tokens > self.max_file_tokens:
+            return True
+        return self.max_file_size is not None and len(block.content_text.encode("utf-8")) > self.max_file_size
+
+    def cap_block(self, block, entities: list) -> str:
+        """Cuts file over cap: keeps head and tail within half of cap each, or replaces text by entity index.
+        Omitted lines are replaced by `[truncated N lines]` marker, entity line numbers refer to original file."""
+        lines = block.content_text.split("\n")
+        if self.oversize == "index":
+            first, last = 1, len(lines)
+            self.index_block(block, entities)
+        else:
+            tokens_cap = self.max_file_tokens if self.max_file_tokens is not None else math.inf
+            size_cap = self.max_file_size if self.max_file_size is not None else math.inf
+            head, tail = self._fit_lines(lines, tokens_cap / 2, size_cap / 2), []
+            if head < len(lines):
+                tail = self._fit_lines(list(reversed(lines[head:])), tokens_cap / 2, size_cap / 2)
+            first, last = head + 1, len(lines) - tail
+            block.content_text = "\n".join(lines[:head] + [f"[truncated {last - first + 1} lines]"] + lines[len(lines) - tail:])
+            numbers = block.original_lines()
+            block.line_map = numbers[:head] + [None] + numbers[len(lines) - tail:]
+        block.tokens = estimate_tokens(block.content_text)
+        logging.warning(f"File {block.file_name} over per-file cap, {self.oversize} mode: lines {first}-{last} omitted")
+        return f"{self.oversize},{first}-{last}"
+
+    @staticmethod
+    def index_block(block, entities: list):
+        """Replaces text by entity index rows and `[truncated N lines]` marker."""
+        lines = block.content_text.split("\n")
+        rows = [f"{'pub' if e['visibility'] == 'public' else 'prv'} {e['type']} {e['name']}: lines {e['first_line']}-{e['last_line']}"
+                for e in entities]
+        block.content_text = "\n".join(rows + [f"[truncated {len(lines)} lines]"])
+        block.line_map = [None] * (len(rows) + 1)
+        block.tokens = estimate_tokens(block.content_text)
+
+    def redact_block(self, block) -> list:
+        """Replaces secrets in text of file block before parsing, returns `kind:line` findings."""
+        if not self.redact or not block.file_name or block.content_type in (":binary", ":symlink"):
+            return []
+        text, findings = redact_secrets(block.content_text)
+        if findings:
+            block.content_text = text
+            block.clean_lines = ["Line №0"] + text.splitlines()
+            block.tokens = estimate_tokens(text)
+            logging.warning(f"Redacted {len(findings)} secrets in file {block.file_name}: {', '.join(findings)}")
+        return findings
+
+    def elide_bodies(self, block, entities: list, entry):
+        """Replaces function bodies over `elide_bodies_over` lines by comment referring to index entry of function."""
+        bodies = block.function_bodies(entities, self.elide_bodies_over + 1)
+        if bodies:
+            block.replace_bodies(bodies, lambda ent, count: f"elided, {count} lines, see index entry #{entry(ent)}")
+            logging.debug(f"Elided {len(bodies)} function bodies over {self.elide_bodies_over} lines in file {block.file_name}")
+
+    def annotate(self, block, entities: list, entry):
+        """Annotates content by original line numbers: line prefixes or entity anchors `// @entity #K name, lines F-L`."""
+        if self.line_numbers == "anchors":
+            block.anchor_entities([(e["first_line"], f"@entity #{entry(e)} {e['name']}, lines {e['first_line']}-{e['last_line']}")
+                                   for e in entities])
+        elif block.content_type not in (":binary", ":symlink"):
+            block.annotate_lines()
+
+    @staticmethod
+    def parse_annotated(text: str) -> list:
+        """Maps annotated lines (`12| code`) back to (original line number, text), None for inserted lines."""
+        result = []
+        for line in text.split("\n"):
+            match = re.match(r"^ *(\d*)\|(?: (.*))?$", line)
+            result.append((int(match.group(1)) if match.group(1) else None, match.group(2) or "") if match else (None, line))
+        return result
+
+    @staticmethod
+    def _fit_lines(lines: list, tokens_cap: float, size_cap: float) -> int:
+        count, tokens, size = 0, 0, 0
+        for line in lines:
+            tokens += estimate_tokens(line) + 1
+            size += len(line.encode("utf-8")) + 1
+            if tokens > tokens_cap or size > size_cap:
+                break
+            count += 1
+        return count
+
+    def include(self, *patterns) -> "SandwichPack":
+        """Adds include globs (`src/**/*.rs`, `Cargo.toml`), files matched by no rule are skipped when first rule is include."""
+        self.path_filters += [(True, p, path_regex(p)) for p in patterns]
+        return self
+
+    def exclude(self, *patterns) -> "SandwichPack":
+        self.path_filters += [(False, p, path_regex(p)) for p in patterns]
+        return self
+
+    def path_selected(self, file_name) -> bool:
+        """Evaluates include / exclude rules in order for file path relative to project root."""
+        if not self.path_filters or not file_name:
+            return True
+        path = str(file_name).replace("\\", "/").lstrip("/")
+        selected = not self.path_filters[0][0]
+        for include, _, regex in self.path_filters:
+            if regex.match(path):
+                selected = include
+        return selected
+
+    def priority(self, pattern: str, weight) -> "SandwichPack":
+        """Adds priority weight for glob (`src/** = 10`, `docs/** = 1`), files of lower weight are elided first over token budget."""
+        self.priorities.append((pattern, weight, path_regex(pattern)))
+        return self
+
+    def file_priority(self, file_name):
+        path = str(file_name or "").replace("\\", "/").lstrip("/")
+        weight = 0
+        for _, w, regex in self.priorities:
+            if regex.match(path):
+                weight = w
+        return weight
+
+    def elide_blocks(self, blocks: list) -> tuple:
+        """Drops whole files until pack fits token budget: lowest priority first, later ones first on equal priority.
+        Posts are never dropped. Returns kept blocks and {file_name: "priority,tokens"} of elided files."""
+        def cost(block):
+            if not self.oversized(block):
+                return block.tokens
+            return 0 if self.oversize == "skip" else min(block.tokens, self.max_file_tokens or block.tokens)
+        total = sum(cost(b) for b in blocks)
+        candidates = sorted((i for i, b in enumerate(blocks) if b.file_name and b.content_type not in (":post", ":context_patch")),
+                            key=lambda i: (self.file_priority(blocks[i].file_name), -i))
+        dropped = {}
+        for i in candidates:
+            if total <= self.budget_tokens:
+                break
+            total -= cost(blocks[i])
+            dropped[i] = f"{self.file_priority(blocks[i].file_name)},{blocks[i].tokens}"
+            logging.info(f"File {blocks[i].file_name} elided to fit token budget {self.budget_tokens}, priority {dropped[i]}")
+        if total > self.budget_tokens:
+            logging.warning(f"Pack has {total} tokens over budget {self.budget_tokens} after elision")
+        return [b for i, b in enumerate(blocks) if i not in dropped], {blocks[i].file_name: v for i, v in dropped.items()}
+
     @classmethod
     def register_block_class(cls, block_class):
         logging.debug(f"Registering block class: {block_class.__name__}")
@@ -80,6 +343,18 @@ class SandwichPack:
                 return True
         return content_type in ContentBlock.supported_types
 
+    @classmethod
+    def content_type_of(cls, file_path) -> str:
+        """Content type for file: name-based types first (CMakeLists.txt, Dockerfile, Dockerfile.prod), then lowercase extension."""
+        path = Path(file_path)
+        extension = path.suffix.lower()
+        if cls.supported_type(path.name):
+            return path.name
+        if extension and cls.supported_type(extension):
+            return extension
+        stem = path.name.split(".")[0]
+        return stem if stem and cls.supported_type(stem) else extension
+
     @classmethod
     def create_block(cls, content_text: str, content_type: str, file_name=None,
                     timestamp=None, **kwargs) -> ContentBlock:
@@ -101,6 +376,716 @@ class SandwichPack:
         key = (file_id, e_type, e_name) if file_id is not None else (None, e_type, e_name)
         return self.entity_rev_map.get(key, -1)
 
+    @staticmethod
+    def is_test_entity(ent: dict) -> bool:
+        """Tests and benches: entities tagged by parser (`#[test]`, `tests/` members) and test blocks like Zig `test "name" {}`."""
+        return bool(ent.get("test")) or ent["type"] == "test"
+
+    def entity_selected(self, ent: dict) -> bool:
+        """Applies tests packing mode and build target roles to entity, entities without role are kept."""
+        if self.roles is not None and ent.get("role") and ent["role"] not in self.roles:
+            return False
+        if self.tests is None:
+            return True
+        return self.is_test_entity(ent) == (self.tests == "only")
+
+    def is_test_file(self, file_name) -> bool:
+        """File is in `tests/` or `benches/` directory."""
+        return bool(file_name) and any(part in self.TEST_DIRS for part in PurePosixPath(self._norm_name(file_name)).parts[:-1])
+
+    def block_selected(self, block: ContentBlock, entities: list) -> bool:
+        """Applies build target roles and tests packing mode to file block: files of roles not selected are dropped, test and
+        bench files in skip mode, files without tests in tests only mode; test items of mixed files are omitted by exclusion."""
+        if self.roles is not None and block.target_role() and block.target_role() not in self.roles:
+            return False
+        if self.tests is None:
+            return True
+        if self.tests == "skip":
+            return not self.is_test_file(block.file_name)
+        return self.is_test_file(block.file_name) or any(self.is_test_entity(e) for e in entities)
+
+    def exclusion(self, block: ContentBlock, ent: dict, exported: set) -> str:
+        """Key of EXCLUSION_NOTES entity is excluded from pack by, None if packed; exported - re-exported paths kept in public API."""
+        if not block.entity_enabled(ent, self.cfg_test, self.cfg_features):
+            return "cfg"
+        if self.roles is not None and ent.get("role") and ent["role"] not in self.roles:
+            return "role"
+        if not self.entity_selected(ent):
+            return "tests"
+        if self.public_api and not block.entity_public(ent) and not self._reexported(block, ent, exported):
+            return "private"
+        return None
+
+    def omit_entities(self, block: ContentBlock, excluded: list, kept: list):
+        """Replaces text of excluded (entity, reason) items by one-line comment, with attributes and doc comments above them.
+        Items enclosing kept entities (re-exported members of private module) stay, only their excluded members are omitted."""
+        lines = dict(zip(block.original_lines(), block.content_text.split("\n")))
+        ranges = []
+        for ent, reason in excluded:
+            first, last = ent["first_line"], ent["last_line"]
+            if any(first <= e["first_line"] and e["last_line"] <= last for e in kept):
+                continue
+            while first > 1 and self.ATTACHED_LINE_REGEX.match(lines.get(first - 1) or ""):
+                first -= 1
+            ranges.append((first, last, (ent, reason)))
+        ranges.sort(key=lambda r: (r[0], -r[1]))   # enclosing items first, nested ones are dropped with them
+        block.replace_bodies(ranges, lambda item, count: f"{item[0]['type']} {item[0]['name']} omitted, {count} lines, "
+                                                         f"{self.EXCLUSION_NOTES[item[1]]}")
+
+    @staticmethod
+    def resolve_reexport(sources: dict, path: str) -> str:
+        """Follows re-export chain (`crate::Client` -> `crate::net::Client` -> `crate::net::tcp::Client`) to original path."""
+        seen = set()
+        while path in sources and path not in seen:
+            seen.add(path)
+            path = sources[path]
+        return path
+
+    @staticmethod
+    def trait_graph(edges: dict, path_map: dict) -> dict:
+        """Completes trait edges with trait entity index and inherited bounds: supertraits of supertraits, cycles are cut."""
+        graph = {}
+        for trait, edge in sorted(edges.items()):
+            inherited = []
+            pending = list(edge.get("supertraits", []))
+            while pending:
+                bound = pending.pop(0)
+                if bound not in inherited and bound != trait:
+                    inherited.append(bound)
+                    pending.extend(edges.get(bound, {}).get("supertraits", []))
+            node = {"entity": path_map.get(trait, -1), "supertraits": edge.get("supertraits", []), "implementors": edge["implementors"]}
+            if inherited != node["supertraits"]:
+                node["inherited"] = inherited
+            graph[trait] = node
+        return graph
+
+    @staticmethod
+    def resolve_import(path: str, path_map: dict, module_ids: dict) -> tuple:
+        """Finds imported item (file_id, entity_index) by path, or (file_id, -1) of closest enclosing file module; (-1, -1) if external."""
+        path = path[:-len("::*")] if path.endswith("::*") else path
+        if path in path_map:
+            return -1, path_map[path]
+        segments = path.split("::")
+        while segments:
+            if (file_id := module_ids.get("::".join(segments))) is not None:
+                return file_id, -1
+            segments.pop()
+        return -1, -1
+
+    @classmethod
+    def dependency_lookup(cls, files: dict) -> dict:
+        """Path suffixes of packed files {file_id: file_name} for module resolution, full paths are keyed with leading `/`.
+
+        Tables: "names" - file names, "stems" - names without extension (package files by directory), "dirs" - directories.
+        """
+        lookup = {"names": {}, "stems": {}, "dirs": {}}
+        for file_id, file_name in files.items():
+            stem = posixpath.splitext(file_name)[0]
+            dir_name = posixpath.dirname(file_name)
+            paths = {"names": [file_name], "stems": [stem], "dirs": [dir_name]}
+            if posixpath.basename(stem) in cls.PACKAGE_STEMS:
+                paths["stems"].append(dir_name)
+            for kind, kind_paths in paths.items():
+                for path in kind_paths:
+                    parts = [p for p in path.split("/") if p]
+                    keys = ["/" + "/".join(parts)] + ["/".join(parts[i:]) for i in range(len(parts))]
+                    for key in keys:
+                        lookup[kind].setdefault(key, []).append(file_id)
+        return lookup
+
+    @classmethod
+    def resolve_dependency(cls, module: str, importer: str, files: dict, lookup: dict, item=None) -> list:
+        """File ids of packed files providing module imported by file `importer`, [] if module is external.
+
+        Relative modules (`./util`, Python `.deps`) and paths resolved by parser are looked up from importer directory,
+        bare modules (`lib.deps`, `App::Util`, `util/str.h`) by path suffix, closest to importer wins. Domain and other
+        leading segments of Go style paths (`example.com/app/pkg/net`) are dropped until a directory matches. With `item`
+        only module member file matches (Python `from . import deps`, Java `import com.acme.Util`).
+        """
+        ext = posixpath.splitext(importer)[1].lower()
+        family = next((f for f in cls.DEPENDENCY_FAMILIES if ext in f), {ext})
+        base = posixpath.dirname(importer)
+        name = module.replace("\\", "/").replace("::", "/")
+        if name.startswith("package:"):   # Dart package URI, package files are under its `lib/`
+            name = name.split("/", 1)[1] if "/" in name else ""
+        if ext == ".py" and name.startswith("."):
+            level = len(name) - len(name.lstrip("."))
+            rest = name[level:].replace(".", "/").strip("/")
+            candidates = ["/" + posixpath.normpath(posixpath.join(base, *[".."] * (level - 1), rest)).strip("/")]
+        elif name.startswith(("./", "../", "/")):
+            candidates = ["/" + posixpath.normpath(posixpath.join(base, name)).strip("/")]
+        elif name:
+            candidates = ["/" + posixpath.normpath(posixpath.join(base, name)).strip("/"), name]
+            if "/" not in name and "." in name:
+                candidates.append(name.replace(".", "/"))   # dotted module: `lib.deps`, `com.acme.util`, `mod.sub`
+            parts = name.split("/")
+            if len(parts) > 1 and "." in parts[0]:
+                candidates += ["/".join(parts[i:]) for i in range(1, len(parts))]
+        else:
+            return []
+        if item:
+            candidates = [f"{path.rstrip('/')}/{item}" for path in candidates]
+        for path in candidates:
+            for kind in ("names", "stems", "dirs"):
+                if kind == "dirs" and ext not in cls.DIRECTORY_PACKAGES:
+                    continue
+                found = [i for i in lookup[kind].get(path, []) if files[i] != importer and
+                         (kind == "names" or posixpath.splitext(files[i])[1].lower() in family)]
+                if kind == "dirs" and found:
+                    return sorted(found)
+                if found:
+                    shared = lambda i: len(posixpath.commonpath([base, posixpath.dirname(files[i])]))
+                    return [max(found, key=lambda i: (shared(i), -len(files[i]), -i))]
+        return []
+
+    @staticmethod
+    def resolve_module_files(blocks: list) -> list:
+        """Moves files declared as modules (Rust `mod net;` -> `net.rs`, `net/mod.rs`) to module path of declaring file.
+
+        Declarations are followed until paths are stable, so nested file modules get full paths. Returns moved blocks.
+        """
+        by_file = {posixpath.normpath(b.file_name.replace("\\", "/")): b for b in blocks if b.file_name}
+        moved = {}
+        for _ in range(len(blocks)):
+            changed = False
+            for block in blocks:
+                for file_name, module_path in block.module_files().items():
+                    target = by_file.get(file_name)
+                    if target is not None and target is not block and target.rebase_module(module_path, block.target_role()):
+                        moved[id(target)] = target
+                        changed = True
+            if not changed:
+                break
+        return list(moved.values())
+
+    @staticmethod
+    def resolve_crate_roots(blocks: list) -> tuple:
+        """Names crates of Cargo workspaces, so entities of crates get own paths (`crates/core/src/net.rs` -> `core_lib::net`).
+
+        Crates are packages of manifests and workspace members (globs, excluded ones skipped) named by directory if their
+        manifest isn't packed; files belong to the closest crate directory. Returns crates {name: {"dir", "manifest",
+        "roots"}} with manifest and root (`src/lib.rs`, `src/main.rs`) blocks, and moved blocks.
+        """
+        files = {posixpath.normpath(b.file_name.split("&")[0].replace("\\", "/")): b for b in blocks if b.file_name}
+        folders = set()
+        for file_name in files:
+            while (file_name := posixpath.dirname(file_name)) not in folders and file_name not in ("", "/"):
+                folders.add(file_name)
+        manifests = [(posixpath.dirname(f), m) for f, b in files.items() if (m := b.crate_manifest())]
+        crates = {folder: {"name": m["name"], "manifest": files[posixpath.join(folder, "Cargo.toml")]}
+                  for folder, m in manifests if m["name"]}
+        def matches(member, folder, patterns):
+            return any(member.count("/") == p.count("/") and fnmatch.fnmatchcase(member, p)
+                       for p in (posixpath.normpath(posixpath.join(folder, pattern)) for pattern in patterns))
+
+        for folder, manifest in manifests:
+            for member in sorted(folders):
+                if (member not in crates and matches(member, folder, manifest["members"])
+                        and not matches(member, folder, manifest["exclude"])):
+                    crates[member] = {"name": posixpath.basename(member), "manifest": None}
+        named = {}
+        moved = {}
+        for file_name, block in files.items():
+            folder = max((f for f in crates if f == "" or file_name.startswith(f.rstrip("/") + "/")), key=len, default=None)
+            if folder is None:
+                continue
+            name = crates[folder]["name"].replace("-", "_")
+            if block.rebase_crate(name):
+                moved[id(block)] = block
+            crate = named.setdefault(name, {"dir": folder, "manifest": crates[folder]["manifest"], "roots": []})
+            if file_name in (posixpath.join(folder, "src", "lib.rs"), posixpath.join(folder, "src", "main.rs")):
+                crate["roots"].append(block)
+        return named, list(moved.values())
+
+    @staticmethod
+    def _reexported(block: ContentBlock, ent: dict, exported: set) -> bool:
+        """Checks `pub` entity is re-exported by `pub use` directly or by glob (`pub use detail::*`)."""
+        path = block.entity_path(ent)
+        if path is None or ent.get("visibility") != "public":
+            return False
+        return path in exported or path.rsplit("::", 1)[0] + "::*" in exported
+
+    @staticmethod
+    def _norm_name(file_name) -> str:
+        return posixpath.normpath("/" + str(file_name).replace("\\", "/").lstrip("/"))
+
+    def _packed_file_id(self, file):
+        if file in self.file_names:
+            return file
+        name = self._norm_name(file)
+        return next((file_id for file_id, file_name in self.file_names.items() if file_name == name), None)
+
+    def deps_of(self, file) -> list:
+        """Names of files imported or declared as modules by packed file (name or file_id), [] if not packed."""
+        file_id = self._packed_file_id(file)
+        return [self.file_names[target] for target in self.file_deps.get(file_id, [])]
+
+    def dependents_of(self, file) -> list:
+        """Names of packed files depending on packed file (name or file_id), [] if not packed."""
+        file_id = self._packed_file_id(file)
+        return [self.file_names[source] for source, targets in self.file_deps.items() if file_id is not None and file_id in targets]
+
+    @classmethod
+    def is_callable(cls, ent: dict) -> bool:
+        e_type = ent.get("type") or ""
+        return "function" in e_type or "method" in e_type or (e_type.split() or [""])[-1] in cls.CALLABLE_KINDS
+
+    @classmethod
+    def measure_block(cls, block, entities: list):
+        """Sets `sloc` and `complexity` of callable entities parsed from block, before bodies or comments are stripped."""
+        block.measure_entities([e for e in entities if cls.is_callable(e) and "first_line" in e and "last_line" in e])
+
+    @staticmethod
+    def short_name(ent: dict) -> str:
+        return re.split(r"::|\.", ent["name"])[-1]
+
+    @classmethod
+    def call_sites(cls, block, entities: list) -> dict:
+        """Called names {id(entity): [(qualifier, name)]} in stripped code of callable entities, calls of nested functions
+        belong to innermost one. Own name on first line is declaration, not a call."""
+        callables = sorted((e for e in entities if cls.is_callable(e) and "first_line" in e and "last_line" in e),
+                           key=lambda e: (e["first_line"], -e["last_line"]))
+        owner = {}
+        for ent in callables:
+            for num in range(ent["first_line"], min(ent["last_line"], len(block.clean_lines) - 1) + 1):
+                owner[num] = ent
+        sites = {id(ent): {} for ent in callables}
+        for num, ent in sorted(owner.items()):
+            for m in cls.CALL_REGEX.finditer(block.clean_lines[num]):
+                if num == ent["first_line"] and m.group(2) == cls.short_name(ent):
+                    continue
+                sites[id(ent)].setdefault((m.group(1), m.group(2)), None)
+        return {key: list(names) for key, names in sites.items() if names}
+
+    def resolve_call(self, caller: int, qualifier, name: str, callables: dict):
+        """Index of function called by name from entity `caller`, None if external or ambiguous.
+
+        Candidates are narrowed by qualifier (`Parent.name`, `self.name` - caller parent), then first unambiguous of:
+        same file, files caller file depends on, whole project. Calls by other qualifiers (`obj.name`, `module.name`) resolve
+        to files caller file depends on only, as object type is unknown.
+        """
+        candidates = callables.get(name, [])
+        caller_ent = self.entities[caller]
+        parent = caller_ent.get("parent") if qualifier in ("self", "this", "Self", "cls") else qualifier
+        owned = [i for i in candidates if parent and self.entities[i].get("parent") == parent]
+        candidates = owned or candidates
+        file_id = caller_ent["file_id"]
+        deps = self.file_deps.get(file_id, [])
+        levels = [[i for i in candidates if self.entities[i]["file_id"] == file_id],
+                  [i for i in candidates if self.entities[i]["file_id"] in deps], candidates]
+        if parent and not owned:
+            levels = levels[1:2]
+        for pick in levels:
+            if pick:
+                return pick[0] if len(pick) == 1 else None
+        return None
+
+    def find_callable(self, name) -> int:
+        """Index of packed function by name, `Parent.name` or `Parent::name`; -1 if not packed, ValueError if ambiguous."""
+        if isinstance(name, int):
+            return name if 0 <= name < len(self.entities) else -1
+        parts = re.split(r"::|\.", name)
+        parent = "::".join(parts[:-1]) if len(parts) > 1 else None
+        found = [i for i, ent in enumerate(self.entities) if self.is_callable(ent) and
+                 (ent["name"] == name or self.short_name(ent) == parts[-1] and (parent is None or ent.get("parent") in (parent, ".".join(parts[:-1]))))]
+        if len(found) > 1:
+            places = ", ".join(f"{self.entities[i].get('parent') or ''}.{self.entities[i]['name']} in {self.file_names.get(self.entities[i]['file_id'])}"
+                               for i in found[:5])
+            raise ValueError(f"Function name `{name}` is ambiguous ({places}), qualify it by parent")
+        return found[0] if found else -1
+
+    def callees_of(self, entity, depth=1) -> list:
+        """Sorted indices of packed functions called by entity (index or name) directly or through up to depth calls."""
+        root = self.find_callable(entity)
+        seen = {root}
+        level = [root]
+        for _ in range(depth):
+            level = [callee for caller in level for callee in self.call_graph.get(caller, []) if callee not in seen]
+            seen.update(level)
+        return sorted(seen - {root})
+
+    def callers_of(self, entity) -> list:
+        """Sorted indices of packed functions calling entity (index or name)."""
+        root = self.find_callable(entity)
+        return [caller for caller, callees in sorted(self.call_graph.items()) if root in callees]
+
+    def slice_keys(self, blocks) -> dict:
+        """Function slice_root and functions it calls to slice_depth as {file_name: {(type, name, first_line)}}.
+
+        Call graph comes from scout pack of block copies with same options, so sliced pack indexes files of slice only.
+        """
+        scout = copy.copy(self)
+        scout.slice_root = None
+        scout.busy_ids = set()
+        scout.output_format = "text"
+        scout.pack(copy.deepcopy(blocks))
+        root = scout.find_callable(self.slice_root)
+        if root < 0:
+            raise ValueError(f"Function `{self.slice_root}` for call slice is not packed")
+        keys = {}
+        for index in [root] + scout.callees_of(root, self.slice_depth):
+            ent = scout.entities[index]
+            keys.setdefault(scout.file_names[ent["file_id"]], set()).add((ent["type"], ent["name"], ent["first_line"]))
+        return keys
+
+    @classmethod
+    def slice_bodies(cls, block, entities: list, keep: set) -> list:
+        """Elides bodies of functions out of call slice `keep` {(type, name, first_line)} like outline mode, functions
+        nested in kept ones stay. Returns entities outside of elided bodies."""
+        kept = [e for e in entities if (e["type"], e["name"], e["first_line"]) in keep]
+        drop = [e for e in entities if cls.is_callable(e) and not any(e is k for k in kept) and
+                not any(k["first_line"] <= e["first_line"] and e["last_line"] <= k["last_line"] for k in kept)]
+        bodies = block.function_bodies(drop)
+        block.replace_bodies(bodies)
+        return [e for e in entities if not any(first <= e["first_line"] and e["last_line"] <= last for first, last, _ in bodies)]
+
+    def find_deriving(self, trait_name: str) -> list:
+        """Returns indices of packed entities deriving trait (`Serialize` matches `serde::Serialize` too)."""
+        return [index for index, ent in enumerate(self.entities)
+                if any(d == trait_name or d.split("::")[-1] == trait_name for d in ent.get("derives", []))]
+
+    def project_summary(self, parsed_blocks: list, module_ids: dict) -> dict:
+        """Orientation map of pack: languages (files, tokens), entity counts by kind, top-level directories and modules."""
+        files = [block for block, _ in parsed_blocks if block.file_name and block.content_type not in (":post", ":context_patch")]
+        names = {block.file_id: block.file_name for block in files}
+        languages = {}
+        directories = {}
+        for block in files:
+            stats = languages.setdefault(MARKDOWN_LANGUAGES.get(block.content_type, block.content_type.lstrip(".")), [0, 0])
+            stats[0] += 1
+            stats[1] += block.tokens
+            parts = block.file_name.lstrip("/").split("/", 1)
+            directory = parts[0] if len(parts) > 1 else "."
+            directories[directory] = directories.get(directory, 0) + 1
+        kinds = {}
+        for ent in self.entities:
+            kinds[ent["type"]] = kinds.get(ent["type"], 0) + 1
+        return {
+            "files": len(files),
+            "tokens": sum(block.tokens for block in files),
+            "languages": dict(sorted(languages.items(), key=lambda x: (-x[1][1], x[0]))),
+            "entities": dict(sorted(kinds.items(), key=lambda x: (-x[1], x[0]))),
+            "directories": dict(sorted(directories.items())),
+            "modules": {path: names.get(file_id, "") for path, file_id in sorted(module_ids.items()) if path.count("::") <= 1},
+        }
+
+    @staticmethod
+    def summary_text(project_name: str, summary: dict) -> str:
+        def files(count):
+            return f"{count} file{'s' if count != 1 else ''}"
+        lines = [f"Project {project_name}: {files(summary['files'])}, {summary['tokens']} tokens, "
+                 f"{sum(summary['entities'].values())} entities",
+                 "Languages: " + ", ".join(f"{lang} {files(n)} / {tokens} tokens" for lang, (n, tokens) in summary["languages"].items())]
+        if summary["entities"]:
+            lines.append("Entities: " + ", ".join(f"{kind} {count}" for kind, count in summary["entities"].items()))
+        lines.append("Directories: " + ", ".join(f"{d} {files(n)}" for d, n in summary["directories"].items()))
+        if summary["modules"]:
+            lines.append("Modules:")
+            lines += [f"{'  ' * (path.count('::') + 1)}{path}: {file_name}" for path, file_name in summary["modules"].items()]
+        return "\n".join(lines)
+
+    def token_report(self, blocks) -> dict:
+        """Token and size breakdown of files selected by include / exclude filters, without packing: files and
+        directories (cumulative, every level) sorted by tokens descending. Generated files are marked by reason,
+        code files get total complexity of their functions, most complex functions are listed with size metrics."""
+        files = []
+        functions = []
+        directories = {}
+        for block in blocks:
+            if not block.file_name or not self.path_selected(block.file_name):
+                continue
+            size = len(block.content_text.encode("utf-8")) if block.content_type != ":binary" else block.size
+            reason = (generated_reason(block.file_name, block.content_text)
+                      if self.generated != "keep" and block.content_type not in (":binary", ":symlink") else None)
+            complexity = 0
+            if self._is_code_block(block):
+                block.strip_strings()
+                block.strip_comments()
+                entities = block.parse_content()["entities"]
+                self.measure_block(block, entities)
+                measured = [e for e in entities if "complexity" in e]
+                complexity = sum(e["complexity"] for e in measured)
+                functions += [{"file_name": block.file_name, "name": e["name"], "first_line": e["first_line"], "sloc": e["sloc"],
+                               "complexity": e["complexity"]} for e in measured]
+            files.append({"file_name": block.file_name, "tokens": block.tokens, "bytes": size, "generated": reason,
+                          "complexity": complexity})
+            parts = block.file_name.strip("/").split("/")[:-1]
+            for depth in range(len(parts) + 1):
+                entry = directories.setdefault("/" + "/".join(parts[:depth]), {"files": 0, "tokens": 0, "bytes": 0})
+                entry["files"] += 1
+                entry["tokens"] += block.tokens
+                entry["bytes"] += size
+        files.sort(key=lambda f: -f["tokens"])
+        functions.sort(key=lambda f: (-f["complexity"], -f["sloc"]))
+        return {"files": files, "tokens": sum(f["tokens"] for f in files), "bytes": sum(f["bytes"] for f in files),
+                "functions": functions[:self.REPORT_FUNCTIONS],
+                "directories": [{"directory": d, **entry} for d, entry in sorted(directories.items(), key=lambda d: -d[1]["tokens"])]}
+
+    def report_text(self, report: dict) -> str:
+        """Printable token report: totals against budgets, then directories and files tables."""
+        lines = [f"Token report for {self.project_name}: {len(report['files'])} files, {report['tokens']} tokens, "
+                 f"{report['bytes']} bytes (tokenizer {tokenizer_name()})"]
+        if self.budget_tokens is not None:
+            over = report["tokens"] - self.budget_tokens
+            lines.append(f"Budget {self.budget_tokens} tokens: " + (f"{over} tokens over" if over > 0 else f"{-over} tokens left"))
+        lines.append(f"Volumes: about {max(1, math.ceil(report['tokens'] / self.token_limit))} by {self.token_limit} tokens limit")
+        lines += ["", "Directories:", f"{'tokens':>10} {'bytes':>10} {'files':>6}  directory"]
+        lines += [f"{d['tokens']:>10} {d['bytes']:>10} {d['files']:>6}  {d['directory']}" for d in report["directories"]]
+        lines += ["", "Files:", f"{'tokens':>10} {'bytes':>10} {'complexity':>10}  file"]
+        lines += [f"{f['tokens']:>10} {f['bytes']:>10} {f.get('complexity', 0):>10}  {f['file_name']}" +
+                  (f"  (generated: {f['generated']})" if f["generated"] else "") for f in report["files"]]
+        if report.get("functions"):
+            lines += ["", "Most complex functions:", f"{'complexity':>10} {'sloc':>6}  function"]
+            lines += [f"{f['complexity']:>10} {f['sloc']:>6}  {f['file_name']}:{f['first_line']} {f['name']}" for f in report["functions"]]
+        return "\n".join(lines)
+
+    @staticmethod
+    def _file_row(block) -> str:
+        return f"{block.file_id},{block.file_name},{compute_md5(block.to_sandwich_block())},{block.tokens},{block.timestamp}"
+
+    @staticmethod
+    def _entity_row(ent: dict) -> str:
+        vis_short = "pub" if ent["visibility"] == "public" else "prv"
+        return (f"{vis_short},{ent['type']},{ent.get('parent', '')},{ent['name']},{ent['file_id']}," +
+                f"{ent['first_line']}-{ent['last_line']},{ent['tokens']}")
+
+    def structured_pack(self, global_index: dict, parsed_blocks: list) -> dict:
+        """Pack as one JSON-ready dict: manifest (index sections), files with content, entities as records and posts."""
+        files = []
+        posts = []
+        for block, _ in parsed_blocks:
+            if block.content_type in (":post", ":context_patch"):
+                posts.append({"type": block.content_type[1:], "post_id": block.post_id, "user_id": block.user_id,
+                              "timestamp": block.timestamp, "content": block.content_text})
+            elif block.file_name:
+                files.append({"file_id": block.file_id, "file_name": block.file_name, "content_type": block.content_type,
+                              "md5": compute_md5(block.to_sandwich_block()), "tokens": block.tokens,
+                              "timestamp": block.timestamp, "content": block.content_text})
+        entities = [{"index": i, **ent} for i, ent in enumerate(self.entities)]
+        return {
+            "format_version": global_index["format_version"],
+            "packer_version": global_index["packer_version"],
+            "context_date": global_index.get("context_date"),
+            "project_name": self.project_name,
+            "manifest": {k: v for k, v in global_index.items() if k not in ("entities", "files", "format_version", "packer_version", "context_date", "project_name")},
+            "files": files,
+            "entities": entities,
+            "posts": posts
+        }
+
+    def order_blocks(self, parsed_blocks: list, edges: dict) -> list:
+        """Parsed blocks in pack order by sort strategy, posts stay first. Sorts are stable, ties keep input order."""
+        posts = [p for p in parsed_blocks if p[0].content_type in (":post", ":context_patch")]
+        files = [p for p in parsed_blocks if p[0].content_type not in (":post", ":context_patch")]
+        if self.sort == "path":
+            files.sort(key=lambda p: p[0].file_name or "")
+        elif self.sort == "size":
+            files.sort(key=lambda p: len(p[0].content_text.encode("utf-8")))
+        elif self.sort == "mtime":
+            files.sort(key=lambda p: p[0].timestamp or "", reverse=True)
+        elif self.sort == "relevance":
+            files.sort(key=lambda p: p[0].relevance or 0, reverse=True)
+        elif self.sort == "complexity":
+            files.sort(key=lambda p: sum(e.get("complexity", 0) for e in p[1].get("entities", [])), reverse=True)
+        elif self.sort == "topology":
+            files = self.topology_order(files, edges)
+        return posts + files
+
+    def split_unit(self, block, crates: dict) -> str:
+        """Logical unit of file block for `split_by`: top-level module path, crate name or top-level directory."""
+        path = block.module_path() if self.split_by in ("module", "crate") else None
+        if path:
+            return "::".join(path.split("::")[:2 if self.split_by == "module" else 1])
+        if self.split_by == "crate":
+            folder = posixpath.dirname(block.file_name)
+            owners = [(len(c["dir"]), name) for name, c in crates.items()
+                      if folder == c["dir"] or folder.startswith(c["dir"].rstrip("/") + "/")]
+            if owners:
+                return max(owners)[1]
+        parts = block.file_name.lstrip("/").split("/", 1)
+        return parts[0] if len(parts) > 1 else "."
+
+    @staticmethod
+    def topology_order(files: list, edges: dict) -> list:
+        """Files with no incoming dependency edges first, then files they declare or import.
+        Cycles are broken by least constrained file, input order otherwise."""
+        position = {p[0].file_id: i for i, p in enumerate(files) if p[0].file_id is not None}
+        targets = {i: {position[t] for t in edges.get(fid, ()) if t in position and position[t] != i} for fid, i in position.items()}
+        incoming = [0] * len(files)
+        for i in targets:
+            for t in targets[i]:
+                incoming[t] += 1
+        remaining = list(range(len(files)))
+        order = []
+        while remaining:
+            ready = next((i for i in remaining if incoming[i] == 0), None)
+            if ready is None:
+                ready = min(remaining, key=lambda i: incoming[i])
+                logging.debug(f"Dependency cycle at file {files[ready][0].file_name}, ordered by input")
+            remaining.remove(ready)
+            order.append(ready)
+            for t in targets.get(ready, ()):
+                incoming[t] -= 1
+        return [files[i] for i in order]
+
+    @staticmethod
+    def pack_digest(file_list: list, file_hashes: dict) -> str:
+        """Pack-level digest over file names and hashes, in files list order."""
+        names = {row.split(",", 2)[0]: row.split(",", 2)[1] for row in file_list}
+        return compute_sha256("\n".join(f"{names[str(fid)]}:{sha}" for fid, sha in file_hashes.items()))
+
+    @classmethod
+    def verify_tree(cls, index: dict, root_dir) -> dict:
+        """Checks packed files against on-disk tree: lists modified and missing files, verifies pack digest of index.
+        Files with permissions other than recorded in metadata are listed in mode_changed, not counted as drift."""
+        integrity = index.get("integrity")
+        if not integrity:
+            raise ValueError("Index has no integrity section, pack was created by older version")
+        hashes = {str(fid): sha for fid, sha in integrity["files"].items()}
+        modes = {str(fid): meta.split(",")[1] for fid, meta in index.get("metadata", {}).items()}
+        report = {"verified": [], "modified": [], "missing": [], "mode_changed": [],
+                  "digest_ok": cls.pack_digest(index["files"], hashes) == integrity["pack"]}
+        for row in index["files"]:
+            file_id, file_name = row.split(",", 2)[:2]
+            if file_id not in hashes:
+                continue
+            path = Path(root_dir) / file_name.lstrip("/")
+            if path.is_symlink() and compute_sha256(os.readlink(path).replace("\\", "/")) == hashes[file_id]:
+                report["verified"].append(file_name)   # recorded as link, target is unchanged
+                continue
+            if not path.is_file():
+                report["modified" if path.is_symlink() else "missing"].append(file_name)
+                continue
+            with open(path, "rb") as f:
+                data = f.read()
+            decoded = decode_source(data)
+            same = hashes[file_id] in (hashlib.sha256(data).hexdigest(), compute_sha256(split_eol(decoded[0])[0]) if decoded else None)
+            report["verified" if same else "modified"].append(file_name)
+            if file_id in modes and f"{stat.S_IMODE(path.stat().st_mode):o}" != modes[file_id]:
+                report["mode_changed"].append(file_name)
+        report["ok"] = report["digest_ok"] and not report["modified"] and not report["missing"]
+        return report
+
+    @staticmethod
+    def restore_file(index: dict, file_id, text: str) -> bytes:
+        """Original bytes of packed file text: line endings and encoding recorded in index are restored."""
+        file_id = str(file_id)
+        encoding = {str(k): v for k, v in index.get("encodings", {}).items()}.get(file_id)
+        eol = {str(k): v for k, v in index.get("line_endings", {}).items()}.get(file_id)
+        return restore_source(text, encoding, eol)
+
+    @staticmethod
+    def restore_metadata(index: dict, file_id, path):
+        """Applies recorded permissions and mtime to restored file. Returns True if index holds metadata of file."""
+        file_id = str(file_id)
+        meta = {str(k): v for k, v in index.get("metadata", {}).items()}.get(file_id)
+        if meta is None:
+            return False
+        os.chmod(path, int(meta.split(",")[1], 8))
+        row = next((r for r in index.get("files", []) if r.split(",", 1)[0] == file_id), None)
+        try:
+            mtime = datetime.datetime.strptime(row.rsplit(",", 1)[1], "%Y-%m-%d %H:%M:%SZ") if row else None
+        except ValueError:
+            mtime = None   # blocks created with free-form timestamps
+        if mtime is not None:
+            stamp = mtime.replace(tzinfo=datetime.timezone.utc).timestamp()
+            os.utime(path, (stamp, stamp))
+        return True
+
+    def context_date(self):
+        """Index context date: SOURCE_DATE_EPOCH if set, else current date when timestamps enabled, else None (omitted)."""
+        epoch = source_date_epoch()
+        if epoch is not None:
+            return epoch.strftime("%Y-%m-%d")
+        return datetime.datetime.now(datetime.timezone.utc).strftime("%Y-%m-%d") if self.timestamps else None
+
+    @staticmethod
+    def render_template(template: str, values: dict) -> str:
+        """Template with `{name}` placeholders substituted by values, `{{name}}` left as literal `{name}`."""
+        return re.sub(r"\{\{(\w+)\}\}|\{(\w+)\}",
+                      lambda m: "{" + m.group(1) + "}" if m.group(1) else str(values.get(m.group(2), "")), template)
+
+    @staticmethod
+    def markdown_fence(content: str) -> str:
+        """Backticks fence longer than any backticks run inside content."""
+        longest = max((len(run) for run in re.findall(r"`+", content)), default=0)
+        return "`" * max(3, longest + 1)
+
+    def markdown_pack(self, global_index: dict, parsed_blocks: list, header=None, footer=None) -> str:
+        """Human-readable pack: contents from entity index, posts, then file sections with fenced code."""
+        by_file = {}
+        for ent in self.entities:
+            by_file.setdefault(ent["file_id"], []).append(ent)
+        files = [block for block, _ in parsed_blocks if block.content_type not in (":post", ":context_patch") and block.file_name]
+        posts = [block for block, _ in parsed_blocks if block.content_type == ":post"]
+        packed = f"Packed {global_index['context_date']}" if global_index.get("context_date") else "Packed"
+        lines = [header, ""] if header else []
+        lines += [f"# {self.project_name}", "", f"{packed}: {len(files)} files, {len(self.entities)} entities.", ""]
+        if global_index.get("summary"):
+            lines += ["## Summary", "", "```", self.summary_text(self.project_name, global_index["summary"]), "```", ""]
+        lines += ["## Contents", ""]
+        for block in files:
+            lines.append(f"- `{block.file_name}` (file {block.file_id}, {block.tokens} tokens)")
+            for ent in by_file.get(block.file_id, []):
+                owner = f" in `{ent['parent']}`" if ent.get("parent") else ""
+                vis = "pub" if ent["visibility"] == "public" else "prv"
+                lines.append(f"  - {vis} {ent['type']} `{ent['name']}`{owner}, lines {ent['first_line']}-{ent['last_line']}")
+        if posts:
+            lines += ["", "## Posts"]
+            for block in posts:
+                lines += ["", f"### Post {block.post_id} (user {block.user_id}, {block.timestamp})", "", block.content_text]
+        lines += ["", "## Files"]
+        for block in files:
+            language = MARKDOWN_LANGUAGES.get(block.content_type, block.content_type.lstrip("."))
+            fence = self.markdown_fence(block.content_text)
+            lines += ["", f"### `{block.file_name}`", "", f"{fence}{language}", block.content_text.rstrip("\n"), fence]
+        if footer:
+            lines += ["", footer]
+        return "\n".join(lines) + "\n"
+
+    def stream_records(self, blocks):
+        """Yields JSON Lines records while parsing blocks one by one: file record, then records of its entities.
+        Blocks may be a lazy iterable, pack-wide passes (crate roots, re-exports, partials merging) are not applied."""
+        self.busy_ids.clear()
+        index = 0
+        for block in blocks:
+            if block.content_type in (":post", ":context_patch"):
+                yield {"record": block.content_type[1:], "post_id": block.post_id, "user_id": block.user_id,
+                       "timestamp": block.timestamp, "content": block.content_text}
+                continue
+            if not self.path_selected(block.file_name):
+                continue
+            if block.file_id is not None:
+                self.busy_ids.add(block.file_id)
+            else:
+                block.file_id = self.generate_unique_file_id()
+            findings = self.redact_block(block)
+            block.strip_strings()
+            block.strip_comments()
+            parsed = block.parse_content()
+            self.measure_block(block, parsed["entities"])
+            if not self.block_selected(block, parsed["entities"]):
+                continue
+            excluded = [(e, reason) for e in parsed["entities"] if (reason := self.exclusion(block, e, set()))]
+            entities = [e for e in parsed["entities"] if not any(e is d for d, _ in excluded)]
+            if excluded and block.content_type not in (":binary", ":symlink"):
+                self.omit_entities(block, excluded, entities)
+            record = {"record": "file", "file_id": block.file_id, "file_name": block.file_name, "content_type": block.content_type,
+                      "md5": compute_md5(block.to_sandwich_block()), "tokens": block.tokens, "timestamp": block.timestamp,
+                      "content": block.content_text}
+            yield {**record, "redacted": findings} if findings else record
+            for ent in entities:
+                yield {"record": "entity", "index": index, **ent, "file_id": block.file_id}
+                index += 1
+
+    def write_jsonl(self, blocks, stream) -> int:
+        """Writes stream_records to text stream, one JSON object per line. Returns count of records."""
+        count = 0
+        for record in self.stream_records(blocks):
+            stream.write(json.dumps(record, ensure_ascii=False) + "\n")
+            count += 1
+        return count
+
     @classmethod
     def _is_code_block(cls, block: ContentBlock) -> bool:
         ctype = str(getattr(block, "content_type", "") or "").strip().lower()
@@ -178,14 +1163,51 @@ class SandwichPack:
 
     def pack(self, blocks, users=None) -> dict:
         """Packs content blocks into sandwiches with an index including entity boundaries."""
+        slice_keys = None   # file_name: (type, name, first_line) of call slice functions, None - whole project
+        if self.slice_root is not None:
+            slice_keys = self.slice_keys(blocks)
+            blocks = [b for b in blocks if b.content_type in (":post", ":context_patch") or
+                      (b.file_name and self._norm_name(b.file_name) in slice_keys)]
         try:
             self.busy_ids.clear()
+            if self.path_filters:
+                selected = [b for b in blocks if b.content_type in (":post", ":context_patch") or self.path_selected(b.file_name)]
+                logging.debug(f"Include / exclude filters skipped {len(blocks) - len(selected)} blocks")
+                blocks = selected
+            generated = {}   # file_name: "reason,mode" of generated files dropped or packed as entity index only
+            if self.generated != "keep":
+                selected = []
+                for block in blocks:
+                    reason = (generated_reason(block.file_name, block.content_text)
+                              if block.file_name and block.content_type not in (":binary", ":symlink") else None)
+                    if reason:
+                        generated[block.file_name] = f"{reason},{self.generated}"
+                        logging.info(f"Generated file {block.file_name} ({reason}) {'skipped' if self.generated == 'skip' else 'indexed only'}")
+                    if not reason or self.generated != "skip":
+                        selected.append(block)
+                blocks = selected
+            elided = {}   # file_name: "priority,tokens" of files dropped to fit token budget
+            if self.budget_tokens is not None:
+                blocks, elided = self.elide_blocks(blocks)
             file_map = {}
             file_list = []
+            file_hashes = {}   # file_id: sha256 of file text, for drift verification
+            encodings = {}   # file_id: original encoding of files transcoded to UTF-8
+            line_endings = {}   # file_id: original crlf / cr line endings of files normalized to LF
+            metadata = {}   # file_id: "size,mode,git blob hash" of files on disk
+            truncated = {}   # file_name: "mode,first-last" of omitted lines for files over per-file cap
+            redacted = {}   # file_name: "kind:line" of secrets replaced in packed text
+            source_hashes = {}   # id(block): sha256 of file text before redaction, for drift verification
             entity_stor = {}
             self.entities = []
             entities_list = []
             name_to_locations = {}
+            partial_stor = {}
+            partials = {}
+            cfg_items = {}
+            ffi_rows = []
+            trait_edges = {}   # trait path: {"entity": index, "supertraits": [...], "implementors": [impl indices]}
+            path_map = {}
             module_map = {}
             module_list = []
             parsed_blocks = []
@@ -203,24 +1225,90 @@ class SandwichPack:
                 if block.content_type in (":post", ":context_patch"):
                     parsed_blocks.append((block, {}))
 
+            parsed_map = {}   # all blocks are parsed first, as re-exports from other files affect public API
             for block in blocks:
                 if block.content_type in (":post", ":context_patch"):
                     continue
+                original = block.content_text
+                findings = self.redact_block(block)
+                if findings:
+                    redacted[block.file_name] = findings
+                    source_hashes[id(block)] = compute_sha256(original)
+                block.strip_strings()
+                block.strip_comments()
+                parsed_map[id(block)] = block.parse_content()
+            crates, moved = self.resolve_crate_roots([b for b in blocks if id(b) in parsed_map])
+            moved = {id(b): b for b in moved + self.resolve_module_files([b for b in blocks if id(b) in parsed_map])}
+            for block in moved.values():
+                parsed_map[id(block)] = block.parse_content()   # entity paths depend on crate and module path
+            selected = [b for b in blocks if id(b) not in parsed_map or self.block_selected(b, parsed_map[id(b)]["entities"])]
+            if len(selected) < len(blocks):
+                logging.debug(f"Build target roles and tests mode skipped {len(blocks) - len(selected)} blocks")
+                blocks = selected
+            call_sites = {}   # id(entity): called (qualifier, name), collected before bodies or comments are stripped
+            for block in blocks:
+                if id(block) in parsed_map and self._is_code_block(block):
+                    self.measure_block(block, parsed_map[id(block)]["entities"])
+                    call_sites.update(self.call_sites(block, parsed_map[id(block)]["entities"]))
+            caller_sites = {}   # entity index: called (qualifier, name)
+            occurrences = {}   # entity name: {id(block): line numbers}, in stripped text of files
+            if self.cross_refs:
+                ref_names = {self.short_name(e) for block in blocks if id(block) in parsed_map for e in parsed_map[id(block)]["entities"]}
+                ref_names = {name for name in ref_names if re.fullmatch(r"[A-Za-z_]\w*", name)}
+                for block in blocks:
+                    if id(block) in parsed_map and block.file_name and block.content_type not in (":binary", ":symlink"):
+                        for num, line in enumerate(block.clean_lines[1:], 1):
+                            for word in ref_names.intersection(re.findall(r"[A-Za-z_]\w*", line)):
+                                occurrences.setdefault(word, {}).setdefault(id(block), []).append(num)
+            sources = {r["path"]: r["source"] for block in blocks if id(block) in parsed_map for r in block.reexports}
+            exported = {self.resolve_reexport(sources, path) for path in sources}
+
+            for block in blocks:
+                if block.content_type in (":post", ":context_patch"):
+                    continue
+                if block.file_name and self.oversize == "skip" and self.oversized(block):
+                    logging.warning(f"File {block.file_name} over per-file cap ({block.tokens} tokens) skipped")
+                    truncated[block.file_name] = "skip,1-" + str(block.content_text.count("\n") + 1)
+                    continue
                 if block.file_name:
                     # file_id - четко указывает на ссылку файла из БД, он привязан к блоку намертво
                     file_id = block.file_id if block.file_id is not None else self.generate_unique_file_id()
                     file_map[block.file_name] = file_id
                     block.file_id = file_id
-                    file_list.append(
-                        f"{file_id},{block.file_name},{compute_md5(block.to_sandwich_block())}," +
-                        f"{block.tokens},{block.timestamp}"
-                    )
+                    # of original text (raw bytes for binaries, target for symlinks), for drift verification
+                    file_hashes[file_id] = (block.sha256 if block.content_type == ":binary" else
+                                            source_hashes.get(id(block)) or compute_sha256(block.content_text))
                     if self._is_code_block(block):
                         code_base_file_ids.add(int(file_id))
-                block.strip_strings()
-                block.strip_comments()
-                parsed = block.parse_content()
+                    if block.source_encoding:
+                        encodings[file_id] = block.source_encoding
+                    if block.line_endings:
+                        line_endings[file_id] = block.line_endings
+                    if block.file_meta:
+                        metadata[file_id] = f"{block.file_meta['size']},{block.file_meta['mode']},{block.file_meta['git']}"
+                parsed = parsed_map[id(block)]
+                excluded = [(e, reason) for e in parsed["entities"] if (reason := self.exclusion(block, e, exported))]
+                if excluded:
+                    logging.debug(f"Excluded {len(excluded)} entities by cfg options, tests or public API mode in file {block.file_name}")
+                    parsed["entities"] = [e for e in parsed["entities"] if not any(e is d for d, _ in excluded)]
+                    if block.file_name and block.content_type not in (":binary", ":symlink"):
+                        self.omit_entities(block, excluded, parsed["entities"])
+                if block.file_name:
+                    if block.file_name in generated:
+                        self.index_block(block, parsed["entities"])
+                    elif self.strip_comments and self._is_code_block(block):
+                        count = block.strip_comment_text(keep_docs=not self.strip_docs)
+                        if count:
+                            logging.debug(f"Stripped {count} comments in file {block.file_name}")
+                    if slice_keys is not None and self._is_code_block(block) and block.file_name not in generated:
+                        parsed["entities"] = self.slice_bodies(block, parsed["entities"], slice_keys.get(self._norm_name(block.file_name), set()))
+                    if self.outline and self._is_code_block(block) and block.file_name not in generated:
+                        parsed["entities"] = block.outline(parsed["entities"])
+                    if self.oversized(block):
+                        truncated[block.file_name] = self.cap_block(block, parsed["entities"])
+                    file_list.append(self._file_row(block))
                 parsed_blocks.append((block, parsed))
+                first_entity = len(self.entities)
                 if block.file_name and parsed["entities"]:
                     for ent in parsed["entities"]:
                         name = ent['name']
@@ -231,20 +1319,54 @@ class SandwichPack:
                             )
                             continue
                         key = (block.file_name, ent["type"], name)
+                        merge_key = (ent["type"], name)
+                        if key not in entity_stor and ent.get("partial") and merge_key in partial_stor:
+                            # partial declarations (C# partial class) from several files share one index entry
+                            main_idx = partial_stor[merge_key]
+                            entity_stor[key] = main_idx
+                            ent['file_id'] = file_id
+                            main = self.entities[main_idx]
+                            main["tokens"] += ent["tokens"]
+                            partials[name].append(f"{file_id},{ent['first_line']}-{ent['last_line']}")
+                            entities_list[main_idx] = self._entity_row(main)
+                            self.entity_rev_map[(file_id, ent["type"], name)] = main_idx
+                            continue
                         if key not in entity_stor:
                             entity_stor[key] = len(entities_list)  # global index of entity
-                            vis_short = "pub" if ent["visibility"] == "public" else "prv"
                             e_type = ent["type"]
                             name_to_locations.setdefault(name, []).append((block.file_name, e_type))
-                            start_line = ent["first_line"]
-                            end_line = ent["last_line"]
-                            parent = ent.get("parent", "")
                             ent['file_id'] = file_id  # for outside using
-                            self.entities.append(ent)
-                            entities_list.append(
-                                f"{vis_short},{e_type},{parent},{name},{file_id},{start_line}-{end_line},{ent['tokens']}"
-                            )
+                            self.entities.append(dict(ent) if ent.get("partial") else ent)
+                            entities_list.append(self._entity_row(ent))
                             self.entity_rev_map[(file_id, ent["type"], name)] = len(entities_list) - 1
+                            if id(ent) in call_sites:
+                                caller_sites[len(entities_list) - 1] = call_sites[id(ent)]
+                            if ent.get("cfg"):
+                                cfg_items[len(entities_list) - 1] = ent["cfg"]
+                            if ent.get("supertraits") or ent.get("trait_path"):
+                                edge = trait_edges.setdefault(ent.get("trait_path") or ent.get("path") or name, {"implementors": []})
+                                if ent.get("trait_path"):
+                                    edge["implementors"].append(len(entities_list) - 1)
+                                else:
+                                    edge["supertraits"] = ent["supertraits"]
+                            if ent.get("ffi"):
+                                symbol = ent.get("symbol", name.split("::")[-1])
+                                ffi_rows.append(f"{ent['ffi']},{ent.get('abi', '')},{symbol},{len(entities_list) - 1}")
+                            if (path := block.entity_path(ent)) is not None:
+                                path_map.setdefault(path, len(entities_list) - 1)
+                            if ent.get("partial"):
+                                partial_stor[merge_key] = len(entities_list) - 1
+                                partials[name] = [f"{file_id},{ent['first_line']}-{ent['last_line']}"]
+                if block.file_name and (self.elide_bodies_over is not None or self.line_numbers):
+                    indices = {id(e): i for i, e in enumerate(self.entities[first_entity:], first_entity)}
+
+                    def entry(ent):
+                        return indices.get(id(ent), entity_stor.get((block.file_name, ent["type"], ent["name"]), -1))
+                    if self.elide_bodies_over is not None and self._is_code_block(block):
+                        self.elide_bodies(block, parsed["entities"], entry)
+                    if self.line_numbers:
+                        self.annotate(block, parsed["entities"], entry)
+                    file_list[-1] = self._file_row(block)
 
                 for module in parsed["dependencies"]["modules"]:
                     if module not in module_map:
@@ -261,8 +1383,9 @@ class SandwichPack:
             current_sw_index = 1
             sandwiches = []
             global_index = {
+                "format_version": FORMAT_VERSION,
                 "packer_version": "0.7",
-                "context_date": datetime.datetime.utcnow().strftime("%Y-%m-%d"),
+                "context_date": self.context_date(),
                 "templates": {
                     "filelist": "file_id,file_name,md5,tokens,timestamp",
                     "users": "user_id,username,role",
@@ -275,11 +1398,130 @@ class SandwichPack:
                 "users": users or [],
                 "code_base_files": sorted(code_base_file_ids),
             }
+            if global_index["context_date"] is None:
+                del global_index["context_date"]   # reproducible output
+            partials = {name: parts for name, parts in partials.items() if len(parts) > 1}
+            if partials:
+                global_index["templates"]["partials"] = "name: [file_id,start_line-end_line, ...]"
+                global_index["partials"] = partials
+            roles = {block.file_id: block.target_role() for block in blocks if id(block) in parsed_map and block.target_role()}
+            if roles:
+                global_index["templates"]["roles"] = "file_id: build target role (library, binary, build script, example, bench, integration test)"
+                global_index["roles"] = roles
+            if crates:
+                global_index["templates"]["crates"] = "crate: dir, manifest (file_id, -1 not packed), roots (lib.rs / main.rs file_ids)"
+                global_index["crates"] = {name: {"dir": crate["dir"], "manifest": crate["manifest"].file_id if crate["manifest"] else -1,
+                                                 "roots": [b.file_id for b in crate["roots"]]} for name, crate in sorted(crates.items())}
+            reexport_rows = []
+            for block in blocks:
+                for r in block.reexports if id(block) in parsed_map else []:
+                    source = self.resolve_reexport(sources, r["source"])
+                    reexport_rows.append(f"{r['path']},{source},{path_map.get(source, -1)},{block.file_id},{r['line']}")
+            if reexport_rows:
+                global_index["templates"]["reexports"] = "public_path,source_path,entity_index(-1 not indexed),file_id,line"
+                global_index["reexports"] = reexport_rows
+            module_ids = {block.module_path(): block.file_id for block in blocks
+                          if id(block) in parsed_map and block.module_path() is not None}
+            import_rows = []
+            import_edges = []
+            for block in blocks:
+                for imp in sorted(block.import_paths, key=lambda i: i["line"]) if id(block) in parsed_map else []:
+                    target_file, target_idx = self.resolve_import(self.resolve_reexport(sources, imp["path"]), path_map, module_ids)
+                    if target_idx >= 0:
+                        target_file = self.entities[target_idx]["file_id"]
+                    alias = f" as {imp['alias']}" if imp["alias"] else ""
+                    import_rows.append(f"{block.file_id},{imp['line']},{imp['path']}{alias},{target_file},{target_idx}")
+                    if target_file != -1:
+                        import_edges.append((block.file_id, target_file))
+            edges = {}   # file_id: files declared as modules or imported by file, for topology order
+            module_of = {file_id: path for path, file_id in module_ids.items()}
+            for path, file_id in module_ids.items():
+                parent = path
+                while "::" in parent:
+                    parent = parent.rsplit("::", 1)[0]
+                    if parent in module_ids:
+                        edges.setdefault(module_ids[parent], set()).add(file_id)
+                        break
+            for source, target in import_edges:
+                # imports from own file (inline modules) or ancestor modules (`super::`, `crate::`) would make cycles
+                if source != target and not module_of.get(source, "").startswith(module_of.get(target, "\0") + "::"):
+                    edges.setdefault(source, set()).add(target)
+            self.file_names = {block.file_id: self._norm_name(block.file_name)
+                               for block in blocks if id(block) in parsed_map and block.file_name and block.file_id is not None}
+            deps = {}   # file_id: files imported or declared as modules, all languages
+            for source, targets in edges.items():
+                deps.setdefault(source, set()).update(targets)
+            for source, target in import_edges:
+                if source != target:
+                    deps.setdefault(source, set()).add(target)
+            lookup = self.dependency_lookup(self.file_names)
+            for block in blocks:
+                if block.file_id not in self.file_names or block.module_path() is not None:
+                    continue   # Rust files are resolved by module paths above
+                dependencies = parsed_map[id(block)].get("dependencies", {})
+                importer = self.file_names[block.file_id]
+                imports = dependencies.get("imports", {})
+                requests = [(mod_name, ent_name) for ent_name, mod_name in imports.items()]
+                requests += [(mod_name, None) for mod_name in dependencies.get("modules", []) if mod_name not in imports.values()]
+                for mod_name, ent_name in requests:
+                    # imported item may be a module itself, else it belongs to module file
+                    targets = ent_name and self.resolve_dependency(mod_name, importer, self.file_names, lookup, ent_name)
+                    for target in targets or self.resolve_dependency(mod_name, importer, self.file_names, lookup):
+                        deps.setdefault(block.file_id, set()).add(target)
+            self.file_deps = {file_id: sorted(targets) for file_id, targets in sorted(deps.items()) if targets}
+            if self.file_deps:
+                global_index["templates"]["deps"] = "file_id: file_ids of files imported or declared as modules by file"
+                global_index["deps"] = self.file_deps
+            callables = {}   # short name: indices of packed callable entities
+            for index, ent in enumerate(self.entities):
+                if self.is_callable(ent):
+                    callables.setdefault(self.short_name(ent), []).append(index)
+            self.call_graph = {}
+            for caller, sites in caller_sites.items():
+                callees = {self.resolve_call(caller, qualifier, name, callables) for qualifier, name in sites} - {None, caller}
+                if callees:
+                    self.call_graph[caller] = sorted(callees)
+            if self.call_graph:
+                global_index["templates"]["calls"] = "entity_index: indices of functions called by entity (name based, best effort)"
+                global_index["calls"] = self.call_graph
+            if self.cross_refs:
+                block_files = {id(block): block.file_id for block in blocks if block.file_id is not None}
+                refs = {}   # entity index: files referencing entity name besides its declaration
+                for index, ent in enumerate(self.entities):
+                    files = set()
+                    for block_id, lines in occurrences.get(self.short_name(ent), {}).items():
+                        own = block_files.get(block_id) == ent["file_id"]
+                        if len(lines) > 1 or not own or not ent["first_line"] <= lines[0] <= ent["last_line"]:
+                            files.add(block_files.get(block_id))
+                    files.discard(None)
+                    if files:
+                        refs[index] = sorted(files)
+                global_index["templates"]["refs"] = "entity_index: file_ids referencing entity name (uses, impls, constructions) besides declaration"
+                global_index["refs"] = refs
+            if slice_keys is not None:
+                kept = sorted({index for (block_name, e_type, e_name), index in entity_stor.items()
+                               if any(key[:2] == (e_type, e_name) for key in slice_keys.get(self._norm_name(block_name), ()))})
+                global_index["templates"]["call_slice"] = "root: sliced function, depth: call levels, entities: indices of sliced functions"
+                global_index["call_slice"] = {"root": self.slice_root, "depth": self.slice_depth, "entities": kept}
+            if import_rows:
+                global_index["templates"]["imports"] = "file_id,line,import_path,target_file_id(-1 external),entity_index(-1 module or not indexed)"
+                global_index["imports"] = import_rows
+            if trait_edges:
+                global_index["templates"]["traits"] = ("trait_path: entity (index, -1 external), supertraits, inherited (all supertraits), " +
+                                                       "implementors (impl entity indices)")
+                global_index["traits"] = self.trait_graph(trait_edges, path_map)
+            if ffi_rows:
+                global_index["templates"]["ffi"] = "direction(import/export),abi,symbol,entity_index"
+                global_index["ffi"] = ffi_rows
+            if cfg_items:
+                global_index["templates"]["cfg"] = "entity_index: cfg condition"
+                global_index["cfg"] = cfg_items
             warn = self._index_redundancy_warning(file_list, entities_list)
             if warn is not None:
                 global_index["warnings"] = [warn]
 
             deep_index = {
+                "format_version": FORMAT_VERSION,
                 "templates": {
                     "entities": "vis(pub/prv),type,parent,name,file_id,start_line-end_line,tokens",
                     "modules": "module_name"
@@ -292,8 +1534,29 @@ class SandwichPack:
                 "sandwiches": []
             }
 
+            parsed_blocks = self.order_blocks(parsed_blocks, edges)
+            block_units = {}   # id(block): split unit of file blocks
+            if self.split_by:
+                unit_order = {}
+                for block, _ in parsed_blocks:
+                    if block.file_name and block.content_type not in (":post", ":context_patch"):
+                        block_units[id(block)] = self.split_unit(block, crates)
+                        unit_order.setdefault(block_units[id(block)], len(unit_order))
+                # stable, order inside of unit is kept; posts go first as own sandwich
+                parsed_blocks.sort(key=lambda p: unit_order[block_units[id(p[0])]] if id(p[0]) in block_units else -1)
+            if self.summary:
+                global_index["templates"]["summary"] = ("files, tokens; languages: name: [files, tokens]; entities: kind: count; " +
+                                                        "directories: top-level dir: files; modules: top-level module path: file_name")
+                global_index["summary"] = self.project_summary(parsed_blocks, module_ids)
+                summary_block = SummaryBlock(self.summary_text(self.project_name, global_index["summary"]), self.project_name)
+                parsed_blocks = [(summary_block, {})] + parsed_blocks
+            if self.sort != "input":
+                global_index["sort"] = self.sort
             current_line = 1
             processed = 0
+            volumes = {}   # file_id: sandwich number
+            units = {}   # split unit: sandwich numbers
+            current_unit = None
             total_blocks = len(parsed_blocks)
             for block, parsed in parsed_blocks:
                 logging.debug(f" ================= PROCESSING BLOCK type {block.content_type}, file_id {block.file_id} ==================== ")
@@ -306,8 +1569,12 @@ class SandwichPack:
                 processed += 1
                 target_size = current_size + block_size
                 target_tks = current_tokens + block_tokens
+                if block_size > self.max_size or block_tokens > self.token_limit:
+                    logging.warning(f"Block of file {block.file_name} has {block_tokens} tokens / {block_size} bytes over sandwich limits, " +
+                                    "packed whole as own volume")
 
-                if target_size > self.max_size or target_tks > self.token_limit:
+                unit = block_units.get(id(block))   # None for posts and summary, and for all blocks without split_by
+                if current_content and (target_size > self.max_size or target_tks > self.token_limit or unit != current_unit):
                     logging.debug(f"Sandwich #{current_sw_index} reached maximum size, target_size = {target_size}, target_tks = {target_tks} storing and creating new")
                     sandwiches.append("".join(current_content))
                     deep_index["sandwiches"].append({
@@ -320,6 +1587,9 @@ class SandwichPack:
                     current_index = []
                     current_tokens = 0
                     current_line = 1
+                current_unit = unit
+                if unit is not None and current_sw_index not in units.setdefault(unit, []):
+                    units[unit].append(current_sw_index)
 
                 block_data = {
                     # "start_line": current_line
@@ -328,8 +1598,11 @@ class SandwichPack:
                     block_data["post_" + str(block.post_id)] = current_line
                 elif block.content_type == ":context_patch":
                     block_data["context_patch"] = current_line
+                elif block.content_type == ":summary":
+                    block_data["summary"] = current_line
                 elif block.file_id is not None:
                     block_data["file_" + str(block.file_id)] = current_line
+                    volumes[block.file_id] = current_sw_index
                 if parsed.get('modules'):
                     block_data["modules"] = [module_map[module] for module in parsed["modules"]]  # module ids
                 if parsed.get('imports'):
@@ -358,12 +1631,76 @@ class SandwichPack:
                 })
 
             logging.debug(f" Processed {processed} / {total_blocks} blocks, packing complete")
+            if len(sandwiches) > 1:
+                global_index["templates"]["volumes"] = "file_id: sandwich volume number (sandwich_N.txt), files are never split"
+                global_index["volumes"] = volumes
+            if self.split_by:
+                global_index["templates"]["units"] = f"{self.split_by}: sandwich volume numbers (sandwich_N.txt)"
+                global_index["units"] = units
+            if encodings:
+                global_index["templates"]["encodings"] = "file_id: original encoding, text is transcoded to UTF-8"
+                global_index["encodings"] = encodings
+            if metadata:
+                global_index["templates"]["metadata"] = "file_id: size (bytes on disk),mode (octal permissions),git blob hash; mtime is files timestamp"
+                global_index["metadata"] = metadata
+            if line_endings:
+                global_index["templates"]["line_endings"] = "file_id: original line endings (crlf/cr), text is normalized to LF"
+                global_index["line_endings"] = line_endings
+            if self.line_numbers:
+                global_index["templates"]["line_numbers"] = ("lines: content lines prefixed by original line number and `|`" if self.line_numbers == "lines"
+                                                             else "anchors: comment `@entity #index name, lines first-last` before entity")
+                global_index["line_numbers"] = self.line_numbers
+            if self.strip_comments:
+                global_index["templates"]["comments"] = ("removed from code files: comments (doc comments kept) or all, " +
+                                                         "entity lines refer to original files")
+                global_index["comments"] = "all" if self.strip_docs else "comments"
+            if self.outline:
+                global_index["templates"]["outline"] = "function bodies replaced by `...`, entity lines refer to original files"
+                global_index["outline"] = True
+            if elided:
+                global_index["templates"]["elided"] = "file_name: priority,tokens of file dropped to fit pack token budget"
+                global_index["elided"] = elided
+            if generated:
+                global_index["templates"]["generated"] = "file_name: reason (lockfile/name/marker/minified),mode (skip/index)"
+                global_index["generated"] = generated
+            if redacted:
+                global_index["templates"]["redacted"] = "file_name: kind:line of secrets replaced by `[REDACTED:kind]`"
+                global_index["redacted"] = redacted
+            if truncated:
+                global_index["templates"]["truncated"] = "file_name: mode (skip/truncate/index),first_line-last_line omitted"
+                global_index["truncated"] = truncated
+            global_index["templates"]["integrity"] = "files: file_id: sha256 of file text, pack: sha256 of `file_name:sha256` lines"
+            global_index["integrity"] = {"algorithm": "sha256", "files": file_hashes,
+                                         "pack": self.pack_digest(file_list, file_hashes)}
+            global_index["tokens"] = {"tokenizer": tokenizer_name(), "total": sum(block.tokens for block, _ in parsed_blocks)}
+            values = {"project_name": self.project_name, "file_count": len(file_list), "token_count": global_index["tokens"]["total"],
+                      "entity_count": len(entities_list), "volume_count": len(sandwiches),
+                      "context_date": global_index.get("context_date", ""), "packer_version": global_index["packer_version"]}
+            header = self.render_template(self.header, values).rstrip("\n") if self.header else None
+            footer = self.render_template(self.footer, values).rstrip("\n") if self.footer else None
+            if header and sandwiches:
+                sandwiches[0] = header + "\n" + sandwiches[0]
+                shift = header.count("\n") + 1   # deep index lines of first volume follow header
+                for block_data in deep_index["sandwiches"][0]["blocks"]:
+                    for key, line in block_data.items():
+                        if key not in ("modules", "imports", "entities"):
+                            block_data[key] = line + shift
+            if footer and sandwiches:
+                deep_index["sandwiches"][-1]["footer"] = sandwiches[-1].count("\n") + 1   # first line of footer in last volume
+                sandwiches[-1] += footer + "\n"
 
-            return {
+            result = {
                 "index": json.dumps(global_index, indent=2),
                 "deep_index": json.dumps(deep_index, indent=2),
                 "sandwiches": sandwiches
             }
+            if self.output_format == "json":
+                structured = self.structured_pack(global_index, parsed_blocks)
+                structured.update({k: v for k, v in (("header", header), ("footer", footer)) if v})
+                result["json"] = json.dumps(structured, indent=2, ensure_ascii=False)
+            elif self.output_format == "markdown":
+                result["markdown"] = self.markdown_pack(global_index, parsed_blocks, header, footer)
+            return result
         except Exception as e:
             logging.error(f"#ERROR: Failed to pack blocks: {str(e)}")
             traceback.print_exc()
@@ -0,0 +1,408 @@
+# /lib/scala_block.py, created 2026-10-14 17:20 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+MODIFIERS_PATTERN = r"(?P<mods>(?:(?:private(?:\[\w+\])?|protected(?:\[\w+\])?|final|sealed|abstract|implicit|lazy|override|case|" \
+                    r"inline|opaque|transparent|open|infix|package)\s+)*)"
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?:@\w+(?:\([^)\n]*\))?[ \t]+)*" + MODIFIERS_PATTERN
+TYPE_PARAMS_PATTERN = r"(?:\s*\[[^\]\n]*(?:\[[^\]\n]*\][^\]\n]*)*\])?"
+CHAR_LITERAL = re.compile(r"'(?:\\u[0-9a-fA-F]{4}|\\.|[^\\'\n])'")
+KIND_TYPES = ("object", "trait", "class", "enum")
+SCOPE_TYPES = ("object", "case object", "package object", "implicit object", "trait", "class", "case class", "implicit class",
+               "enum", "extension", "given")
+
+
+def _skip_interpolation(text: str, i: int) -> int:
+    """Skips `${...}` splice body starting after the open brace, nested strings included."""
+    n = len(text)
+    depth = 1
+    while i < n and depth > 0:
+        if text[i] == '"':
+            i = _scan_string(text, i, text[i - 1:i].isalnum())[0]
+            continue
+        depth += {'{': 1, '}': -1}.get(text[i], 0)
+        i += 1
+    return i
+
+
+def _scan_string(text: str, i: int, interpolated: bool):
+    """Scans string or triple-quoted (raw) string starting at i, returns (end, closed)."""
+    n = len(text)
+    if text.startswith('"""', i):
+        j = i + 3
+        while j < n and not text.startswith('"""', j):
+            j = _skip_interpolation(text, j + 2) if interpolated and text.startswith("${", j) else j + 1
+        if j >= n:
+            return n, False
+        j += 3
+        while j < n and text[j] == '"':   # """a "quoted"""" ends on the last quotes
+            j += 1
+        return j, True
+    j = i + 1
+    while j < n and text[j] not in '"\n':
+        if text[j] == "\\":
+            j += 2
+        elif interpolated and text.startswith("${", j):
+            j = _skip_interpolation(text, j + 2)
+        elif interpolated and text.startswith("$$", j):
+            j += 2
+        else:
+            j += 1
+    return (j + 1, True) if j < n and text[j] == '"' else (j, False)
+
+
+def strip_scala_literals(text: str, warn=None) -> str:
+    """Blanks Scala string, triple-quoted, interpolated (s"", f"", raw"") and char literals, preserving line count.
+
+    Splices `${...}` are blanked with the literal, symbol-like quotes ('sym) stay untouched.
+    Block comments may be nested; comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("/*", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("*/", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            out.append("/*" + "\n" * text.count("\n", i, j) + "*/")
+            i = j
+            continue
+        if ch == "'" and (match := CHAR_LITERAL.match(text, i)):
+            out.append("''")
+            i = match.end()
+            continue
+        if ch != '"':
+            out.append(ch)
+            i += 1
+            continue
+        interpolated = i > 0 and (text[i - 1].isalnum() or text[i - 1] == "_")
+        end, closed = _scan_string(text, i, interpolated)
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class ScalaEntityParser(EntityParser):
+    """Base for Scala parsers: public unless declared private/protected."""
+    def detect_visibility(self, match):
+        mods = match_value(match, 'mods', '')
+        return "private" if re.search(r"\b(?:private|protected)\b", mods) else "public"
+
+    def _format_entity_name(self, match):
+        return match.group('name')   # qualified by enclosing objects in apply_scopes
+
+
+class TypeParser(ScalaEntityParser):
+    """Parser for object, trait, class and enum declarations, with braces or Scala 3 indentation."""
+    def __init__(self, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + rf"{keyword}\s+(?P<name>\w+)", ["indent", "mods", "name"], 3)\
+            .add_token(r"(?P<head>[^{\n]*)", ["head"], 1)
+        super().__init__(keyword, owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if not entity:
+            return True
+        mods = match_value(base_match, 'mods', '')
+        for spec in ("case", "implicit", "package"):
+            if re.search(rf"\b{spec}\b", mods):
+                entity["type"] = f"{spec} {entity['type']}"
+        line = self.owner.clean_lines[entity["first_line"]]
+        if parents := re.search(r"\bextends\s+([^{\n]+?)(?:\s+derives\b[^{\n]*)?\s*(?::|{.*)?$", line):
+            parents = re.sub(r"\([^()]*\)", "", parents.group(1))   # constructor arguments
+            entity["parent"] = ", ".join(p.strip() for p in re.split(r"\bwith\b|,(?![^\[]*\])", parents) if p.strip())
+        return True
+
+
+class ExtensionParser(ScalaEntityParser):
+    """Parser for Scala 3 `extension (x: Type)` blocks, named by the extended type."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)extension\s*" + TYPE_PARAMS_PATTERN + r"\s*\(\s*\w+\s*:\s*(?P<name>[\w.]+)", ["indent", "name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bextension\b", default_visibility="public")
+
+
+class GivenParser(ScalaEntityParser):
+    """Parser for Scala 3 `given` instances and Scala 2 `implicit val/var/object` definitions."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"(?:(?P<given>given)\s+(?:(?P<name>\w+)" + TYPE_PARAMS_PATTERN + r"\s*:\s*)?"
+                       r"(?P<instance>[\w.]+(?:\[[^\n=]*?\])?)|(?P<value>val|var)\s+(?P<value_name>\w+))", ["indent", "mods", "name"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\b(?:given|val|var)\b", default_visibility="public")
+
+    def _format_entity_name(self, match):
+        if match.group('given'):
+            # anonymous given gets the compiler-style name, like given_Ord_Int
+            return match.group('name') or "given_" + "_".join(re.findall(r"\w+", match.group('instance')))
+        return match.group('value_name')
+
+    def _process_match(self, base_match):
+        if not base_match.group('given') and 'implicit' not in match_value(base_match, 'mods', ''):
+            return False   # plain values are not indexed
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start()))
+        if entity and not base_match.group('given'):
+            entity["type"] = "implicit value"
+        return True
+
+
+class FunctionParser(ScalaEntityParser):
+    """Parser for `def` definitions; declarations without `=` or body are abstract."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"def\s+(?P<name>\w+)", ["indent", "mods", "name"], 2)\
+            .add_token(TYPE_PARAMS_PATTERN + r"(?:\s*\((?:[^()]|\([^()]*\))*\))*(?:\s*:\s*(?:[^={\n]|=>)+?)?[ \t]*(?P<ending>=(?!>)|{|$)",
+                       ["args", "ending"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bdef\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if not entity:
+            return True
+        validation = self.outer_regex.validate_match(self.content, base_match.start())
+        if not match_value(validation['match'], 'ending'):
+            entity["type"] = "abstract " + entity["type"]
+        if 'implicit' in match_value(base_match, 'mods', ''):
+            entity["type"] = "implicit " + entity["type"]
+        return True
+
+
+class PackageParser(EntityParser):
+    """Parser for the first `package` clause, stored as single-line entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bpackage\b", default_visibility="public")
+
+    def parse(self):
+        for line_num, line in enumerate(self.owner.clean_lines[1:], 1):
+            match = re.match(r"^\s*package\s+(?!object\b)(?P<name>[\w.]+)", line) if isinstance(line, str) else None
+            if not match:
+                continue
+            self.owner.package = match.group('name')
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": line_num,
+                "tokens": estimate_tokens(line),
+                "parent": "",
+            }
+            self.new_entities_lines.append(line_num)
+            return True
+        return False
+
+
+class DepsParserScala(DepsParser):
+    """Parser for Scala imports: wildcards (`_`, `*`), selectors `{A, B => C}`, Scala 3 `as` renames and several clauses per line."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*import\s+(?P<clauses>[^\n]+)", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def _add_clause(self, clause: str):
+        clause = clause.strip()
+        selectors = re.match(r"(?P<path>[\w.]+)\.\{(?P<items>[^}]*)\}$", clause)
+        if selectors:
+            module = selectors.group('path')
+            self.add_module(module)
+            for item in selectors.group('items').split(","):
+                parts = re.split(r"\s*(?:=>|\bas\b)\s*", item.strip())
+                name, alias = parts[0], parts[-1] if len(parts) > 1 else ""
+                if name and name not in ("_", "*", "given") and alias != "_":   # `A => _` hides A
+                    self.add_import(module, alias or name)
+            return
+        match = re.match(r"(?P<path>[\w.]+?)(?:\.(?P<name>\w+|_|\*))(?:\s+as\s+(?P<alias>\w+))?$", clause)
+        if not match:
+            return
+        module = match.group('path')
+        self.add_module(module)
+        if match.group('name') not in ("_", "*", "given"):
+            self.add_import(module, match.group('alias') or match.group('name'))
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.get_clean_content()):
+            for clause in re.split(r",(?![^{]*\})", match.group('clauses')):
+                self._add_clause(clause)
+        return True
+
+
+class ContentCodeScala(ContentBlock):
+    """Parser for Scala content blocks (.scala, .sc), Scala 2 braces and Scala 3 indentation syntax."""
+    supported_types = [".scala", ".sc"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "scala"
+        self.entity_map = {}
+        self.package = ""
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeScala with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string, triple-quoted, interpolated and char literals with strip_scala_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_scala_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def indented_bounds(self, start_line, header_line, clean_lines):
+        """Bounds of Scala 3 indented body: deeper lines after header, with optional `end Name` marker."""
+        line = clean_lines[start_line]
+        indent = len(line) - len(line.lstrip())
+        last_line = header_line
+        for line_num in range(header_line + 1, len(clean_lines)):
+            line = clean_lines[line_num]
+            if not isinstance(line, str) or not line.strip():
+                continue
+            line_indent = len(line) - len(line.lstrip())
+            if line_indent > indent:
+                last_line = line_num
+                continue
+            if line_indent == indent and re.match(r"end\b", line.strip()):
+                last_line = line_num
+            break
+        return start_line, last_line
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Detects bounds by braces, by indentation after `:`/`=`/`with` header end, or as single statement."""
+        depth = 0
+        for line_num in range(start_line, min(len(clean_lines), start_line + 8)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for ch in line:
+                if ch == '{' and depth == 0:
+                    return super().detect_bounds(start_line, clean_lines)
+                depth += {'(': 1, ')': -1, '[': 1, ']': -1}.get(ch, 0)
+            stripped = line.rstrip()
+            if depth > 0:
+                continue
+            if stripped.endswith((':', '=', '=>')) or re.search(r"\bwith$|^\s*extension\b", stripped):
+                return self.indented_bounds(start_line, line_num, clean_lines)
+            following = next((ln.strip() for ln in clean_lines[line_num + 1:] if isinstance(ln, str) and ln.strip()), "")
+            if re.match(r"(?:extends|with|derives)\b|[:=]", following):
+                continue
+            return start_line, line_num
+        return super().detect_bounds(start_line, clean_lines)
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line definitions are regular in Scala, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def check_entity_placement(self, line_num: int, name: str):
+        """Anonymous given instances get a synthesized name, absent in the source line."""
+        line = self.clean_lines[line_num] if 0 < line_num < len(self.clean_lines) else None
+        if name.startswith("given_") and isinstance(line, str) and re.search(r"\bgiven\b", line):
+            return True
+        return super().check_entity_placement(line_num, name)
+
+    def apply_scopes(self):
+        """Builds the hierarchy: entities inside objects are qualified like Rust module items (`Outer.Inner.name`),
+        defs in classes/traits/extensions become methods with the qualified owner as parent."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in SCOPE_TYPES]
+        short = {id(e): e["name"] for e in self.entity_map.values()}
+
+        def chain(entity):
+            return [s for s in scopes if s is not entity and s["first_line"] < entity["first_line"] <= s["last_line"]]
+
+        qualified = {}
+        for entity in self.entity_map.values():
+            owners = [s for s in chain(entity) if s["type"] != "extension"]
+            if entity["type"] == "package":
+                qualified[id(entity)] = entity["name"]
+                continue
+            qualified[id(entity)] = self.module_prefix + ".".join([short[id(s)] for s in owners] + [short[id(entity)]])
+        for entity in self.entity_map.values():
+            owners = chain(entity)
+            owner = owners[-1] if owners else None
+            if "function" in entity["type"] and owner and "object" not in owner["type"]:
+                entity["type"] = entity["type"].replace("function", "method")
+                entity["parent"] = qualified[id(owner)]
+                continue
+            entity["name"] = qualified[id(entity)]
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Scala content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserScala(self),
+            PackageParser("package", self),
+            *[TypeParser(self, keyword) for keyword in KIND_TYPES],
+            ExtensionParser("extension", self),
+            GivenParser("given", self),
+            FunctionParser("function", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeScala)
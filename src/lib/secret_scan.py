@@ -0,0 +1,61 @@
+# /lib/secret_scan.py, created 2026-10-16 11:30 EEST
+# Secrets detection in packed text: pattern rules for known key formats, entropy rule for other quoted tokens.
+
+import math
+import re
+from collections import Counter
+
+# kind, regex; rules with group redact only the group (password of connection string, value of assignment)
+SECRET_RULES = (
+    ("private_key", re.compile(r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----[\s\S]*?-----END (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----")),
+    ("aws_key", re.compile(r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b")),
+    ("github_token", re.compile(r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{60,})\b")),
+    ("slack_token", re.compile(r"\bxox[abprs]-[A-Za-z0-9-]{10,}")),
+    ("google_api_key", re.compile(r"\bAIza[0-9A-Za-z_-]{35}")),
+    ("stripe_key", re.compile(r"\b[sr]k_live_[0-9A-Za-z]{24,}\b")),
+    ("api_key", re.compile(r"\bsk-(?:[A-Za-z]+-)?[A-Za-z0-9_-]{32,}")),
+    ("jwt", re.compile(r"\beyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}")),
+    ("connection_string", re.compile(r"\b[a-z][a-z0-9+.-]*://[^\s:/@'\"]+:([^\s@'\"/]+)@")),
+    ("password", re.compile(r"(?i)(?:password|passwd|pwd|secret|api_?key|access_?token|auth_?token|client_?secret)\w*[\"']?\s*[:=]\s*" +
+                            r"[\"']([^\"'\s$%{}<>]{6,})[\"']")),
+)
+TOKEN_REGEX = re.compile(r"[\"'`]([A-Za-z0-9+/=_-]{32,})[\"'`]")   # quoted tokens checked by entropy rule
+MIN_ENTROPY = 4.5   # bits per char; hex digests (4 bits max) and words stay below, random base64 above
+
+
+def shannon_entropy(token: str) -> float:
+    counts = Counter(token)
+    return -sum(n / len(token) * math.log2(n / len(token)) for n in counts.values()) if token else 0.0
+
+
+def find_secrets(text: str) -> list:
+    """Secrets as (start, end, kind) spans, sorted and not overlapping, earlier rules win."""
+    spans = []
+    for kind, regex in SECRET_RULES:
+        for m in regex.finditer(text):
+            group = 1 if regex.groups else 0
+            spans.append((m.start(group), m.end(group), kind))
+    for m in TOKEN_REGEX.finditer(text):
+        token = m.group(1)
+        if re.search(r"\d", token) and re.search(r"[A-Za-z]", token) and shannon_entropy(token) >= MIN_ENTROPY:
+            spans.append((m.start(1), m.end(1), "high_entropy"))
+    result = []
+    for start, end, kind in sorted(spans, key=lambda s: s[0]):
+        if not result or start >= result[-1][1]:
+            result.append((start, end, kind))
+    return result
+
+
+def redact_secrets(text: str) -> tuple:
+    """Text with secrets replaced by `[REDACTED:kind]` and list of `kind:line` findings. Lines count is kept."""
+    findings = []
+    out = []
+    pos = 0
+    for start, end, kind in find_secrets(text):
+        out.append(text[pos:start])
+        out.append(f"[REDACTED:{kind}]" + "\n" * text.count("\n", start, end))
+        line = text.count("\n", 0, start) + 1
+        findings.append(f"{kind}:{line}")
+        pos = end
+    out.append(text[pos:])
+    return "".join(out), findings
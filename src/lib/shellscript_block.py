@@ -1,4 +1,4 @@
-# /lib/shellscript_block.py, updated 2025-08-08 18:30 EEST
+# /lib/shellscript_block.py, updated 2026-10-14 19:20 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
@@ -13,14 +13,115 @@ from lib.deps_builder import DepsParser
 from lib.iter_regex import IterativeRegex
 
 
+HEREDOC_REGEX = re.compile(r"<<(?P<strip>-)?[ \t]*(?P<quote>['\"\\]?)(?P<word>[A-Za-z_][\w-]*)(?P=quote)?")
+COMMENT_PREFIX_CHARS = " \t\n;&|()"
+
+
+def _skip_subshell(text: str, i: int) -> int:
+    """Skips `$(...)` body starting after the open paren, quotes inside included."""
+    n = len(text)
+    depth = 1
+    while i < n and depth > 0:
+        ch = text[i]
+        if ch == '"':
+            i = _scan_double(text, i)[0]
+            continue
+        if ch == "'":
+            end = text.find("'", i + 1)
+            i = n if end < 0 else end + 1
+            continue
+        if ch == "\\":
+            i += 2
+            continue
+        depth += {'(': 1, ')': -1}.get(ch, 0)
+        i += 1
+    return i
+
+
+def _scan_double(text: str, i: int):
+    """Scans double quoted string from i, nested command substitutions included, returns (end, closed)."""
+    n = len(text)
+    j = i + 1
+    while j < n and text[j] != '"':
+        if text[j] == "\\":
+            j += 2
+        elif text.startswith("$(", j):
+            j = _skip_subshell(text, j + 2)
+        else:
+            j += 1
+    return (j + 1, True) if j < n else (n, False)
+
+
+def strip_shell_literals(text: str, warn=None) -> str:
+    """Blanks shell quoted strings ('...', "...", $'...') and heredoc bodies, drops `#` comments, preserving line count.
+
+    Heredoc delimiter may be quoted, `<<-` allows tab indented terminator, `<<<` here-string is not a heredoc.
+    Hash inside words (`$#`, `${#arr[@]}`) does not start a comment.
+    """
+    out = []
+    pending = []   # heredocs waiting for the end of current line: (word, strip_tabs)
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "\n" and pending:
+            out.append(ch)
+            i += 1
+            while pending and i < n:
+                word, strip_tabs = pending.pop(0)
+                start = i
+                while i < n:
+                    end = text.find("\n", i)
+                    end = n if end < 0 else end
+                    line = text[i:end]
+                    i = min(end + 1, n)
+                    if (line.lstrip("\t") if strip_tabs else line) == word:
+                        out.append("\n" * text.count("\n", start, i - 1) + line + ("\n" if end < n else ""))
+                        break
+                else:
+                    if warn:
+                        warn(f"Incomplete heredoc {word} at line {text.count(chr(10), 0, start) + 1}")
+                    out.append("\n" * text.count("\n", start, n))
+            continue
+        if ch == "#" and (i == 0 or text[i - 1] in COMMENT_PREFIX_CHARS):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            continue
+        if ch == "\\":
+            out.append(text[i:i + 2])
+            i += 2
+            continue
+        if text.startswith("<<", i) and not text.startswith("<<<", i) and (match := HEREDOC_REGEX.match(text, i)):
+            pending.append((match.group('word'), bool(match.group('strip'))))
+            out.append(match.group(0))
+            i = match.end()
+            continue
+        if ch == "'" or (text.startswith("$'", i)):
+            j = i + (2 if ch == "$" else 1)
+            while j < n and text[j] != "'":
+                j += 2 if ch == "$" and text[j] == "\\" else 1   # escapes only in ANSI-C $'...'
+            end, closed = (j + 1, True) if j < n else (n, False)
+        elif ch == '"':
+            end, closed = _scan_double(text, i)
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
 class FunctionParser(EntityParser):
-    """Parser for Shell script functions."""
+    """Parser for Shell script functions, both `name() {` and `function name {` forms."""
     def __init__(self, entity_type, owner):
         outer_regex = IterativeRegex()
         outer_regex.add_token(
-            r"^(?P<indent>\s*)(?:function\s+)?(?P<name>\w+)\s*\(\)", ["indent", "name"], 2
+            r"^(?P<indent>[ \t]*)(?:function\s+(?P<kw_name>[\w:.-]+)(?:\s*\(\))?|(?P<name>[\w:.-]+)\s*\(\))", ["indent", "name"], 2
         ).add_token(
-            r"\s*{", ["head_end"], 1
+            r"\s*[{(]", ["head_end"], 1
         )
         super().__init__(entity_type, owner, outer_regex, r"\bfunction\b", default_visibility="private")
 
@@ -36,42 +137,118 @@ class FunctionParser(EntityParser):
                 exported_functions.add(validation['match'].group('name'))
 
         for match in matches:
-            fn_name = match.group('name')
+            validation = self.outer_regex.validate_match(content, match.start())
+            if validation['hit_rate'] < 1.0:
+                continue   # no body follows, e.g. `function` word in command arguments
+            fn_name = match.group('name') or match.group('kw_name')
             vis = "public" if fn_name in exported_functions else self.default_visibility
-            start_pos = match.start('name')
+            start_pos = match.start('name') if match.group('name') else match.start('kw_name')
             start_line = self.owner.find_line(start_pos)
             full_text = self.owner.extract_entity_text(start_pos, match.end())
             self.make_add_entity(self.entity_type, fn_name, vis, start_line, full_text)
         return True
 
 
+class ExportParser(EntityParser):
+    """Parser for exported variables (`export NAME=...`, `declare -x NAME`), stored as single-line public entities."""
+    EXPORT_REGEX = re.compile(r"^[ \t]*(?:export|declare\s+-[a-zA-Z]*x[a-zA-Z]*|typeset\s+-x)\s+(?P<name>[A-Za-z_]\w*)", re.MULTILINE)
+
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\bexport\b", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in self.EXPORT_REGEX.finditer(content):
+            line_num = self.owner.find_line(match.start('name'))
+            if line_num in self.owner.entity_map:
+                continue
+            line = self.owner.clean_lines[line_num]
+            self.owner.entity_map[line_num] = {
+                "type": self.entity_type,
+                "name": match.group('name'),
+                "visibility": self.default_visibility,
+                "file_id": self.owner.file_id,
+                "first_line": line_num,
+                "last_line": line_num,
+                "tokens": estimate_tokens(line)
+            }
+            self.new_entities_lines.append(line_num)
+        return True
+
+
 class DepsParserShell(DepsParser):
-    """Parser for Shell script dependencies."""
+    """Parser for Shell script dependencies: files sourced with `.` or `source`, quoted paths and directory variables resolved relative to script."""
+    SOURCE_REGEX = re.compile(r"^\s*(?:\.|source)\s+(?P<script>\"[^\"]+\"|'[^']+'|[^\s;&|]+)")
+
     def __init__(self, owner):
-        outer_regex = IterativeRegex()
-        outer_regex.add_token(r"^\s*(?:\.|source|\./)\s+(?P<script>[^\s]+)", ["script"], 1)
-        super().__init__(owner, outer_regex)
+        super().__init__(owner, None)
 
-    def _process_match(self, match):
-        script = match.group('script')
-        if script:
-            script_path = f"{Path(self.owner.file_name).parent}/{script}".replace("\\", "/")
-            if not script_path.startswith("/"):
-                script_path = f"/{script_path}"
-            self.add_module(script_path)
+    def parse(self):
+        lines = [""] + self.owner.content_text.splitlines()
+        for line_num, clean_line in enumerate(self.owner.clean_lines[1:], 1):
+            if not isinstance(clean_line, str) or not re.match(r"^\s*(?:\.|source)\s", clean_line) or line_num >= len(lines):
+                continue   # source commands in comments and heredocs are skipped
+            match = self.SOURCE_REGEX.match(lines[line_num])
+            if match:
+                self.add_source(match.group('script').strip("\"'"))
+        return True
+
+    def add_source(self, script: str):
+        if script.startswith("$"):
+            _, sep, script = script.partition("/")   # $DIR/lib.sh, $(dirname "$0")/lib.sh, ${BASH_SOURCE%/*}/lib.sh
+            if not sep or "$" in script:
+                return
+        if script.startswith("/"):
+            self.add_module(script)
+            return
+        script_path = f"{Path(self.owner.file_name).parent}/{script}".replace("\\", "/")
+        if not script_path.startswith("/"):
+            script_path = f"/{script_path}"
+        self.add_module(os.path.normpath(script_path).replace("\\", "/"))
 
 
 class ContentShellScript(ContentBlock):
     """Parser for Shell script content blocks."""
-    supported_types = ['.sh', '.bashrc', '.profile']
+    supported_types = ['.sh', '.bash', '.bashrc', '.profile']
 
     def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
         super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
         self.tag = "shell"
         self.entity_map = {}
-        self.open_sl_comment = ["#"]
+        self.open_sl_comment = []   # comments are dropped by strip_shell_literals, `$#` is not a comment
+        self.open_ml_comment = []
+        self.close_ml_comment = []
         logging.debug(f"Initialized ContentShellScript with tag={self.tag}, file_name={file_name}")
 
+    def strip_strings(self):
+        """Strips quoted strings and heredoc bodies with comments, using strip_shell_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_shell_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Function with subshell body `name() ( ... )` ends by parens balance, others by braces."""
+        line = clean_lines[start_line] if 0 < start_line < len(clean_lines) else None
+        head = re.match(r"^\s*(?:function\s+)?[\w:.-]+\s*(?:\(\)\s*)?(?=\((?!\)))", line) if isinstance(line, str) else None
+        if not head:
+            return super().detect_bounds(start_line, clean_lines)
+        depth = 0
+        for line_num in range(start_line, len(clean_lines)):
+            text = clean_lines[line_num][head.end():] if line_num == start_line else clean_lines[line_num]
+            depth += text.count("(") - text.count(")")
+            if depth <= 0:
+                return start_line, line_num
+        self.parse_warn(f"Incomplete entity at line {start_line} in file {self.file_name}, paren_count={depth}")
+        return start_line, start_line
+
     def parse_content(self, clean_lines=None, depth=0):
         """Parses shell script content to extract entities and dependencies."""
         logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
@@ -84,7 +261,7 @@ class ContentShellScript(ContentBlock):
         self.strip_strings()
         self.strip_comments()
 
-        parsers = [FunctionParser("function", self), DepsParserShell(self)]
+        parsers = [FunctionParser("function", self), ExportParser("variable", self), DepsParserShell(self)]
         original_clean_lines = self.clean_lines.copy()
         for parser in parsers:
             try:
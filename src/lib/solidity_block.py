@@ -0,0 +1,250 @@
+# /lib/solidity_block.py, created 2026-10-15 05:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+from lib.java_block import strip_java_literals
+
+
+ACCESS_KEYWORDS = ("public", "external", "internal", "private")
+MUTABILITY_KEYWORDS = ("pure", "view", "payable", "nonpayable")
+SPECIAL_FUNCTIONS = ("constructor", "fallback", "receive")
+SCOPE_TYPES = ("contract", "abstract contract", "interface", "library")
+# attributes between parameters and returns/body: visibility, mutability, virtual/override and modifier invocations
+ATTRS_PATTERN = r"(?P<attrs>(?:\s+(?!returns\b)\w+(?:\s*\([^()]*\))?)*)"
+
+
+class SolidityEntityParser(EntityParser):
+    """Base for Solidity parsers: extras are filled by update_entity from the full match, tokens are counted on source lines."""
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        line_num = self.owner.find_line(base_match.start('name'))
+        entity = self.owner.entity_map.get(line_num)
+        if entity:
+            self.update_entity(entity, self.outer_regex.validate_match(self.content, base_match.start())['match'])
+            entity["tokens"] = estimate_tokens("\n".join(self.owner.content_text.split("\n")[entity["first_line"] - 1:entity["last_line"]]))
+        return True
+
+    def update_entity(self, entity, match):
+        pass
+
+
+class ContractParser(SolidityEntityParser):
+    """Parser for `contract`, `abstract contract`, `interface` and `library` declarations, inherited contracts are stored as parent."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)(?P<abstract>abstract\s+)?" + entity_type + r"\s+(?P<name>\w+)", ["indent", "abstract", "name"], 1)\
+            .add_token(r"(?:\s+is\s+(?P<parent>[^{]+?))?", ["parent"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)
+        super().__init__(entity_type, owner, outer_regex, rf"\b{entity_type}\b", default_visibility="public")
+
+    def update_entity(self, entity, match):
+        if match_value(match, 'abstract'):
+            entity["type"] = "abstract " + entity["type"]
+        if entity.get("parent"):
+            entity["parent"] = re.sub(r"\s+", " ", re.sub(r"\([^()]*\)", "", entity["parent"])).strip()   # base constructor arguments dropped
+
+
+class FunctionParser(SolidityEntityParser):
+    """Parser for functions, constructors, `fallback` / `receive` and modifiers (with or without parameter list).
+
+    Visibility keyword is stored as access (public / external count as public), mutability, custom modifiers and returns as extras.
+    """
+    def __init__(self, entity_type, owner):
+        if entity_type == "modifier":
+            head = r"^(?P<indent>[ \t]*)modifier\s+(?P<name>\w+)"
+        else:
+            head = r"^(?P<indent>[ \t]*)(?:function\s+|(?=(?:" + "|".join(SPECIAL_FUNCTIONS) + r")\s*\())(?P<name>\w+)"
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(head, ["indent", "name"], 2)\
+            .add_token(r"(?:\s*\((?P<args>[^()]*(?:\([^()]*\)[^()]*)*)\))?" + ATTRS_PATTERN + r"(?:\s+returns\s*\((?P<returns>[^;{]*?)\))?",
+                       ["args", "attrs", "returns"], 1)\
+            .add_token(r"\s*(?P<ending>[{;])", ["ending"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\b(?:function|modifier)\b", default_visibility="public")
+
+    def detect_visibility(self, match):
+        attrs = match_value(match, 'attrs', '').split()
+        return "private" if "internal" in attrs or "private" in attrs else "public"
+
+    def update_entity(self, entity, match):
+        attrs = re.findall(r"(\w+)(?:\s*\([^()]*\))?", match_value(match, 'attrs', ''))
+        if entity["type"] == "function" and match.group('name') in SPECIAL_FUNCTIONS and not match.group(0).lstrip().startswith("function"):
+            entity["type"] = match.group('name')
+        if match_value(match, 'ending') == ";":
+            entity["type"] = "abstract " + entity["type"]
+        access = next((a for a in attrs if a in ACCESS_KEYWORDS), None)
+        if access:
+            entity["access"] = access
+        mutability = next((a for a in attrs if a in MUTABILITY_KEYWORDS), None)
+        if mutability:
+            entity["mutability"] = mutability
+        modifiers = [a for a in attrs if a not in ACCESS_KEYWORDS + MUTABILITY_KEYWORDS + ("virtual", "override")]
+        if modifiers:
+            entity["modifiers"] = modifiers
+        if "virtual" in attrs:
+            entity["virtual"] = True
+        if "override" in attrs:
+            entity["override"] = True
+        if returns := match_value(match, 'returns'):
+            entity["returns"] = re.sub(r"\s+", " ", returns.strip())
+
+
+class DeclarationParser(SolidityEntityParser):
+    """Parser for `event`, `error`, `struct` and `enum` declarations; events and errors keep their parameter lists."""
+    KINDS = {"event": "event", "error": "error", "struct": "structure", "enum": "enum"}
+
+    def __init__(self, keyword, owner):
+        self.keyword = keyword
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(r"^(?P<indent>[ \t]*)" + keyword + r"\s+(?P<name>\w+)", ["indent", "name"], 2)\
+            .add_token(r"\s*(?:\((?P<params>[^;]*?)\)(?:\s*anonymous)?\s*;|{)", ["params"], 1)
+        super().__init__(self.KINDS[keyword], owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+    def update_entity(self, entity, match):
+        if self.keyword in ("event", "error"):
+            params = re.sub(r"\s+", " ", match_value(match, 'params', '')).strip()
+            entity["params"] = [p.strip() for p in params.split(",") if p.strip()]
+
+
+class DepsParserSolidity(DepsParser):
+    """Parser for `import` directives: plain, `* as X`, `{A, B as C} from` forms; relative paths resolved, package paths kept."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*import\s+(?:(?P<star>\*\s+as\s+\w+)\s+from\s+|{(?P<names>[^}]*)}\s*from\s+)?"
+                              r"(?P<quote>['\"])(?P<path>[^'\"\n]+)(?P=quote)(?:\s+as\s+(?P<alias>\w+))?\s*;", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = re.sub(r"//[^\n]*|/\*.*?\*/", "", self.owner.content_text, flags=re.DOTALL)   # paths are strings, clean_lines lost them
+        for match in self.IMPORT_REGEX.finditer(content):
+            path = match.group('path')
+            if path.startswith("."):
+                path = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), path)).replace("\\", "/")
+            self.add_module(path)
+            if match.group('star'):
+                self.add_import(path, match.group('star').split()[-1])
+            if match.group('alias'):
+                self.add_import(path, match.group('alias'))
+            for item in (match.group('names') or "").split(","):
+                words = item.split()
+                if words:
+                    self.add_import(path, words[-1])   # `A as B` imports B
+        return True
+
+
+class ContentCodeSolidity(ContentBlock):
+    """Parser for Solidity content blocks."""
+    supported_types = [".sol"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "solidity"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeSolidity with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string literals (including unicode"..." and hex"...") with strip_java_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_java_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Bodies end with the matching brace, declarations without body (events, prototypes) with `;`."""
+        depth = 0
+        body = False
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for ch in line:
+                if ch == "{" and depth == 0:
+                    body = True
+                if ch in "([{":
+                    depth += 1
+                elif ch in ")]}":
+                    depth = max(depth - 1, 0)
+                    if body and depth == 0:
+                        return start_line, line_num
+                elif ch == ";" and depth == 0 and not body:
+                    return start_line, line_num
+        return start_line, start_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations (events, prototypes) are regular in Solidity, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def apply_scopes(self):
+        """Sets parent of members declared inside contracts, interfaces and libraries; contract functions become methods."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in SCOPE_TYPES]
+        for line_num, entity in self.entity_map.items():
+            if entity["type"] in SCOPE_TYPES:
+                continue   # for contracts parent holds inherited contracts
+            owners = [s for s in scopes if s["first_line"] < line_num <= s["last_line"]]
+            if owners:
+                entity["parent"] = owners[-1]["name"]
+                entity["type"] = entity["type"].replace("function", "method")
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Solidity content to extract contracts, functions, modifiers, events and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserSolidity(self),
+            ContractParser("contract", self),
+            ContractParser("interface", self),
+            ContractParser("library", self),
+            FunctionParser("modifier", self),
+            FunctionParser("function", self),
+            DeclarationParser("event", self),
+            DeclarationParser("error", self),
+            DeclarationParser("struct", self),
+            DeclarationParser("enum", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeSolidity)
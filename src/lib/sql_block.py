@@ -0,0 +1,287 @@
+# /lib/sql_block.py, created 2026-10-14 20:40 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+DOLLAR_QUOTE = re.compile(r"\$(?P<tag>[A-Za-z_]\w*)?\$")
+NAME_PATTERN = r"(?P<name>(?:[\w$]+|\"[^\"\n]+\"|`[^`\n]+`|\[[^\]\n]+\])(?:\s*\.\s*(?:[\w$]+|\"[^\"\n]+\"|`[^`\n]+`|\[[^\]\n]+\]))*)"
+CREATE_PATTERN = r"(?im)^[ \t]*CREATE\s+(?:OR\s+(?:REPLACE|ALTER)\s+)?(?:DEFINER\s*=\s*\S+\s+)?" \
+                 r"(?P<mods>(?:(?:TEMP|TEMPORARY|UNLOGGED|GLOBAL|LOCAL|MATERIALIZED|UNIQUE|CLUSTERED|NONCLUSTERED|RECURSIVE)\s+)*)"
+STATEMENT_START = re.compile(r"(?i)^(?:CREATE|ALTER|DROP|INSERT|UPDATE|DELETE|GRANT|REVOKE|COMMENT)\b")   # unindented
+BOUNDS_TOKEN = re.compile(r"(?i)[();]|\bBEGIN\b(?!\s+(?:TRAN|TRANSACTION|WORK)\b)|\bCASE\b|\bEND\b(?!\s+(?:IF|LOOP|WHILE|REPEAT)\b)")
+CONSTRAINT_WORDS = ("CONSTRAINT", "PRIMARY", "FOREIGN", "UNIQUE", "CHECK", "INDEX", "KEY", "EXCLUDE", "LIKE", "FULLTEXT", "SPATIAL", "PERIOD")
+ARG_MODES = ("IN", "OUT", "INOUT", "VARIADIC")
+
+
+def strip_sql_literals(text: str, warn=None) -> str:
+    """Blanks SQL string literals ('...', E'...') and dollar-quoted bodies ($$...$$, $fn$...$fn$), drops `--` and nested `/* */` comments.
+
+    Quoted identifiers ("name", `name`, [name]) are kept, they are entity names.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("--", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            continue
+        if text.startswith("/*", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("/*", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("*/", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            out.append("\n" * text.count("\n", i, j))
+            i = j
+            continue
+        if ch == "$" and (i == 0 or not (text[i - 1].isalnum() or text[i - 1] == "_")) and (match := DOLLAR_QUOTE.match(text, i)):
+            end = text.find(match.group(0), match.end())
+            closed = end >= 0
+            end = end + len(match.group(0)) if closed else n
+        elif ch == "'":
+            escapes = i > 0 and text[i - 1] in "eE" and (i == 1 or not text[i - 2].isalnum())
+            j = i + 1
+            while j < n:
+                if escapes and text[j] == "\\":
+                    j += 2
+                elif text.startswith("''", j):
+                    j += 2
+                elif text[j] == "'":
+                    break
+                else:
+                    j += 1
+            closed = j < n
+            end = j + 1 if closed else n
+        else:
+            out.append(ch)
+            i += 1
+            continue
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append("''" if "\n" not in text[i:end] else "'" + "\n" * text.count("\n", i, end) + "'")
+        i = end
+    return "".join(out)
+
+
+def normalize_name(name: str) -> str:
+    """Removes identifier quoting and spaces around dots: "public" . "users" -> public.users."""
+    return ".".join(part.strip().strip('"`[]') for part in re.split(r"\s*\.\s*", name))
+
+
+def split_items(body: str) -> list:
+    """Splits parenthesized list body by commas outside nested parens."""
+    items, depth, current = [], 0, ""
+    for ch in body:
+        depth += {'(': 1, ')': -1}.get(ch, 0)
+        if ch == "," and depth == 0:
+            items.append(current.strip())
+            current = ""
+            continue
+        current += ch
+    if current.strip():
+        items.append(current.strip())
+    return items
+
+
+class CreateParser(EntityParser):
+    """Parser for `CREATE [OR REPLACE] <kind> name` statements; table columns, function params and index columns are stored."""
+    def __init__(self, owner, kind="TABLE"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(CREATE_PATTERN + rf"{kind}\s+(?:CONCURRENTLY\s+)?(?:IF\s+NOT\s+EXISTS\s+)?" + NAME_PATTERN,
+                       ["mods", "name"], 1)
+        super().__init__(kind.lower(), owner, outer_regex, r"(?!)", default_visibility="public")
+
+    def _format_entity_name(self, match):
+        return normalize_name(match.group('name'))
+
+    def _process_match(self, base_match):
+        line_num = self.owner.find_line(base_match.start('name'))
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(line_num)
+        if not entity:
+            return True
+        mods = base_match.group('mods').lower().split()
+        if "materialized" in mods:
+            entity["type"] = "materialized " + entity["type"]
+        elif "temp" in mods or "temporary" in mods:
+            entity["type"] = "temporary " + entity["type"]
+        statement = self.owner.statement_text(line_num, entity["last_line"])
+        tail = statement[statement.find(base_match.group('name')) + len(base_match.group('name')):]
+        if entity["type"].endswith("table"):
+            self.table_details(entity, tail)
+        elif entity["type"] in ("function", "procedure"):
+            head = re.split(r"(?i)\b(?:AS|RETURNS|BEGIN)\b", tail, maxsplit=1)[0]
+            args = self.owner.paren_body(head) if "(" in head else head   # T-SQL params may go without parens
+            entity["params"] = [(words[1] if words[0].upper() in ARG_MODES else words[0]).lstrip("@")
+                                for words in (item.split() for item in split_items(args)) if len(words) > 1]
+        elif entity["type"].endswith("index"):
+            table = re.match(r"(?is)\s+ON\s+(?:ONLY\s+)?" + NAME_PATTERN, tail)
+            if table:
+                entity["parent"] = normalize_name(table.group('name'))
+                entity["columns"] = [normalize_name(item.split()[0]) for item in split_items(self.owner.paren_body(tail) or "") if item]
+            if "unique" in mods:
+                entity["type"] = "unique index"
+        return True
+
+    def table_details(self, entity, tail: str):
+        if body := self.owner.paren_body(tail):
+            items = split_items(body)
+            entity["columns"] = [normalize_name(item.split()[0]) for item in items
+                                 if item and item.split()[0].upper() not in CONSTRAINT_WORDS]
+            references = sorted({normalize_name(m.group('name')) for item in items
+                                 for m in re.finditer(r"(?i)\bREFERENCES\s+" + NAME_PATTERN, item)})
+            if references:
+                entity["references"] = references
+
+
+class DepsParserSQL(DepsParser):
+    """Parser for included scripts: psql `\\i`/`\\ir`/`\\include` and MySQL `SOURCE`, resolved relative to the current file."""
+    INCLUDE_REGEX = re.compile(r"(?im)^[ \t]*(?:\\(?:i|ir|include|include_relative)|SOURCE)\s+['\"]?(?P<script>[^\s'\";]+\.sql)\b")
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.INCLUDE_REGEX.finditer(self.owner.get_clean_content()):
+            script = match.group('script').replace("\\", "/")
+            if not script.startswith("/"):
+                script = os.path.normpath(f"{Path(self.owner.file_name).parent}/{script}").replace("\\", "/")
+            self.add_module(script if script.startswith("/") else f"/{script}")
+        return True
+
+
+class ContentCodeSQL(ContentBlock):
+    """Parser for SQL schema and migration scripts (.sql)."""
+    supported_types = [".sql"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "sql"
+        self.entity_map = {}
+        self.open_sl_comment = []   # comments are dropped by strip_sql_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentCodeSQL with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Strips string literals, dollar-quoted bodies and comments, using strip_sql_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_sql_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def statement_text(self, first_line: int, last_line: int) -> str:
+        return "\n".join(line for line in self.clean_lines[first_line:last_line + 1] if isinstance(line, str))
+
+    @staticmethod
+    def paren_body(text: str):
+        """Body of the first parenthesized group in text, None without parens."""
+        start = text.find("(")
+        if start < 0:
+            return None
+        depth = 0
+        for pos in range(start, len(text)):
+            depth += {'(': 1, ')': -1}.get(text[pos], 0)
+            if depth == 0:
+                return text[start + 1:pos]
+        return text[start + 1:]
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Statement ends with `;` outside parens and BEGIN/END blocks, `GO` batch separator or before the next unindented statement."""
+        depth = 0
+        blocks = 0
+        last_line = start_line
+        for line_num in range(start_line, len(clean_lines)):
+            line = clean_lines[line_num]
+            if not isinstance(line, str) or not line.strip():
+                continue
+            if line_num > start_line and depth == 0 and blocks == 0 and (re.match(r"(?i)^\s*GO\s*$", line) or STATEMENT_START.match(line)):
+                return start_line, last_line
+            for token in BOUNDS_TOKEN.finditer(line):
+                token = token.group(0).upper()
+                if token in "()":
+                    depth += 1 if token == "(" else -1
+                elif token == ";":
+                    if depth <= 0 and blocks <= 0:
+                        return start_line, line_num
+                else:
+                    blocks += -1 if token == "END" else 1
+            last_line = line_num
+        return start_line, last_line
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Statement text by detected bounds, single-line statements are regular."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_start), self.clean_lines)
+        return self.statement_text(start_line, end_line)
+
+    def check_entity_placement(self, line_num: int, name: str):
+        """Qualified names (schema.table) may be quoted in source, checked by the last part."""
+        line = self.clean_lines[line_num] if 0 < line_num < len(self.clean_lines) else None
+        return isinstance(line, str) and name.split(".")[-1].lower() in line.lower()
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses SQL content to extract schema entities and included scripts."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserSQL(self),
+            CreateParser(self, "TABLE"),
+            CreateParser(self, "VIEW"),
+            CreateParser(self, "FUNCTION"),
+            CreateParser(self, "PROCEDURE"),
+            CreateParser(self, "INDEX")
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeSQL)
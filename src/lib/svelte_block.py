@@ -0,0 +1,203 @@
+# /lib/svelte_block.py, created 2026-10-14 23:10 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.iter_regex import IterativeRegex
+from lib.js_block import ContentCodeJs, ContentCodeTypeScript, strip_js_literals
+from lib.css_block import ContentCodeCSS
+from lib.html_block import RawTextParser, RAW_TEXT_REGEX, attr_value, blank
+
+
+PROP_REGEX = re.compile(r"^[ \t]*export\s+(?:let|var)\s+(?P<names>[\w$]+(?:\s*(?::[^=;,\n]+)?(?:=[^,;\n]+)?,\s*[\w$]+)*)", re.MULTILINE)
+RUNE_PROPS_REGEX = re.compile(r"^[ \t]*let\s*\{(?P<names>[^}]*)\}\s*(?::[^=]+)?=\s*\$props\s*\(", re.MULTILINE)
+REACTIVE_REGEX = re.compile(r"^[ \t]*(?:\$:\s*(?:(?P<name>[\w$]+)\s*=(?!=))?|(?:let|const)\s+(?P<rune_name>[\w$]+)\s*(?::[^=\n]+)?=\s*\$derived\b|(?P<effect>\$effect)\b)",
+                            re.MULTILINE)
+
+
+def is_module_script(attrs: str) -> bool:
+    """Module level script: `context="module"` (Svelte 4) or `module` attribute (Svelte 5)."""
+    return attr_value(attrs, "context") == "module" or bool(re.search(r"(?:^|\s)module(?:\s|$)", attrs))
+
+
+class ComponentParser(RawTextParser):
+    """Parser for the component entity spanning the file, instance script and styles are delegated to JS/TS and CSS blocks."""
+    def parse(self):
+        text = self.owner.content_text
+        lines = [line_num for line_num, line in enumerate(text.split("\n"), 1) if line.strip()]
+        if not lines:
+            return False
+        name = self.owner.component
+        self.owner.add_member(self.entity_type, name, lines[0], lines[-1], {"tokens": estimate_tokens(text)})
+        self.new_entities_lines.append(lines[0])
+        for match in RAW_TEXT_REGEX.finditer(text):
+            tag, attrs = match.group('tag').lower(), match.group('attrs')
+            if not match.group('body').strip() or attr_value(attrs, "src"):
+                continue
+            start, end = match.start('body'), match.end('body')
+            first_line = text.count("\n", 0, start) + 1
+            masked = blank(text[:start]) + match.group('body') + blank(text[end:])
+            lang = (attr_value(attrs, "lang") or "").lower()
+            known = set(self.new_entities_lines)
+            if tag == "style":
+                if lang not in ("", "css", "scss", "postcss"):
+                    continue
+                self.parse_sub_block(ContentCodeCSS, masked, ".scss" if lang == "scss" else ".css", f"style{first_line}")
+            else:
+                typescript = lang in ("ts", "typescript")
+                self.parse_sub_block(ContentCodeTypeScript if typescript else ContentCodeJs, masked, ".ts" if typescript else ".js", f"script{first_line}")
+                if is_module_script(attrs):
+                    continue   # module level functions are shared by all instances
+                self.owner.instance_lines.update(range(first_line, text.count("\n", 0, end) + 2))
+            for line_num in set(self.new_entities_lines) - known:
+                entity = self.owner.entity_map[line_num]
+                if not entity.get("parent"):
+                    entity["parent"] = name
+        return True
+
+
+class PropParser(EntityParser):
+    """Parser for component props: `export let name` declarations and destructured `$props()` rune."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in list(PROP_REGEX.finditer(content)) + list(RUNE_PROPS_REGEX.finditer(content)):
+            line_num = self.owner.find_line(match.start('names'))
+            if line_num not in self.owner.instance_lines:
+                continue
+            names = re.sub(r"\{[^{}]*\}|\[[^\[\]]*\]|\([^()]*\)", "", match.group('names'))   # default values
+            for item in names.split(","):
+                prop = re.match(r"\s*(?:\.\.\.)?(?P<name>[\w$]+)", item)
+                if not prop:
+                    continue
+                item_line = self.owner.find_line(match.start('names') + match.group('names').find(prop.group('name')))
+                last_line = self.owner.statement_end(item_line)
+                if self.owner.add_member(self.entity_type, prop.group('name'), item_line, last_line, {"parent": self.owner.component}):
+                    self.new_entities_lines.append(item_line)
+        return True
+
+
+class ReactiveParser(EntityParser):
+    """Parser for reactive statements `$: name = ...`, `$: { ... }` and runes `$derived` / `$effect`."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        for match in REACTIVE_REGEX.finditer(content):
+            line_num = self.owner.find_line(match.end())
+            if line_num not in self.owner.instance_lines:
+                continue
+            name = match.group('name') or match.group('rune_name') or ("effect" if match.group('effect') else "reactive")
+            if self.owner.add_member(self.entity_type, name, line_num, self.owner.statement_end(line_num), {"parent": self.owner.component}):
+                self.new_entities_lines.append(line_num)
+        return True
+
+
+class ContentCodeSvelte(ContentBlock):
+    """Parser for Svelte components (.svelte)."""
+    supported_types = [".svelte"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "svelte"
+        self.open_ml_string = ["`"]
+        self.close_ml_string = ["`"]
+        self.entity_map = {}
+        self.depth = 0
+        self.instance_lines = set()
+        self.module_prefix = kwargs.get("module_prefix", "")
+        self.component = self.module_prefix + Path(file_name or "").stem
+        logging.debug(f"Initialized ContentCodeSvelte with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Blanks markup and styles, script contents are stripped with strip_js_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        text = "\n".join(self.clean_lines[1:])
+        masked = []
+        pos = 0
+        for match in RAW_TEXT_REGEX.finditer(text):
+            if match.group('tag').lower() == "script":
+                masked += [blank(text[pos:match.start('body')]), match.group('body')]
+                pos = match.end('body')
+        masked.append(blank(text[pos:]))
+        clean_text = strip_js_literals("".join(masked), warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}"))
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def statement_end(self, start_line: int) -> int:
+        """Last line of statement started at start_line, brackets are balanced."""
+        depth = 0
+        for line_num in range(start_line, len(self.clean_lines)):
+            line = self.clean_lines[line_num] if isinstance(self.clean_lines[line_num], str) else ""
+            depth += sum(line.count(c) for c in "{[(") - sum(line.count(c) for c in "}])")
+            if depth <= 0:
+                return line_num
+        return start_line
+
+    def add_member(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, unnamed reactive statements get line suffix (reactive:12)."""
+        if first_line in self.entity_map:
+            return False
+        if name in ("reactive", "effect"):
+            name = f"{name}:{first_line}"
+        lines = self.clean_lines[first_line:last_line + 1]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(line for line in lines if isinstance(line, str))),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Svelte component to extract props, reactive statements, script and style entities."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.depth = depth
+        self.entity_map = {}
+        self.instance_lines = set()
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            ComponentParser("component", self),
+            PropParser("prop", self),
+            ReactiveParser("reactive", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeSvelte)
@@ -0,0 +1,317 @@
+# /lib/swift_block.py, created 2026-10-14 15:40 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+MODIFIERS_PATTERN = r"(?P<mods>(?:(?:public|private|fileprivate|internal|open|final|static|class(?=\s+(?:func|var|let|subscript)\b)|override|" \
+                    r"mutating|nonmutating|lazy|weak|unowned|convenience|required|dynamic|optional|indirect|nonisolated|" \
+                    r"distributed|package)\s+)*)"
+ATTRIBUTES_PATTERN = r"(?P<attributes>(?:@\w+(?:\([^)\n]*\))?[ \t]+)*)"   # same line only, lines above are collected
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)" + ATTRIBUTES_PATTERN + MODIFIERS_PATTERN
+PARENS_PATTERN = r"\((?:[^()]|\((?:[^()]|\([^()]*\))*\))*\)"
+TYPE_KINDS = {"class": "class", "struct": "structure", "enum": "enum", "protocol": "protocol", "actor": "actor", "extension": "extension"}
+STRING_START = re.compile(r'(#*)("""|")')
+ATTRIBUTES_LINE = re.compile(r"^\s*(?:@\w+(?:\([^)\n]*\))?\s*)+$")
+
+
+def _skip_interpolation(text: str, i: int) -> int:
+    """Skips `\\(...)` interpolation body starting after the open paren, nested strings included."""
+    n = len(text)
+    depth = 1
+    while i < n and depth > 0:
+        match = STRING_START.match(text, i)
+        if match:
+            i, _ = _scan_string(text, match.end(), match.group(1), match.group(2))
+            continue
+        depth += {'(': 1, ')': -1}.get(text[i], 0)
+        i += 1
+    return i
+
+
+def _scan_string(text: str, i: int, hashes: str, quote: str):
+    """Scans string body from i up to the closing quote with the same count of `#`, returns (end, closed)."""
+    close = quote + hashes
+    escape = "\\" + hashes
+    n = len(text)
+    while i < n:
+        if text.startswith(close, i):
+            return i + len(close), True
+        if quote == '"' and text[i] == "\n":
+            return i, False
+        if text.startswith(escape, i):
+            j = i + len(escape)
+            if j < n and text[j] == "(":
+                i = _skip_interpolation(text, j + 1)
+            else:
+                i = j + 1
+            continue
+        i += 1
+    return n, False
+
+
+def strip_swift_literals(text: str, warn=None) -> str:
+    """Blanks Swift string literals: regular, multiline (\"\"\"...\"\"\") and raw (#"..."#), preserving line count.
+
+    Interpolations `\\(...)` are blanked together with the literal, strings nested inside them are skipped correctly.
+    Block comments may be nested in Swift; comment bodies are dropped, the markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i):
+            end = text.find("\n", i)
+            i = n if end < 0 else end
+            out.append("//")
+            continue
+        if text.startswith("/*", i):
+            depth = 0
+            j = i
+            while j < n:
+                if text.startswith("/*", j):
+                    depth += 1
+                    j += 2
+                elif text.startswith("*/", j):
+                    depth -= 1
+                    j += 2
+                    if depth == 0:
+                        break
+                else:
+                    j += 1
+            out.append("/*" + "\n" * text.count("\n", i, j) + "*/")
+            i = j
+            continue
+        match = STRING_START.match(text, i) if ch in '#"' else None
+        if not match:
+            out.append(ch)
+            i += 1
+            continue
+        end, closed = _scan_string(text, match.end(), match.group(1), match.group(2))
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        out.append('"' + "\n" * text.count("\n", i, end) + ('"' if closed else ""))
+        i = end
+    return "".join(out)
+
+
+def collect_attributes(clean_lines: list, line_num: int, head: str = "") -> list:
+    """Returns attribute names from attribute-only lines above line_num and from head of the declaration line."""
+    block = [head]
+    i = line_num - 1
+    while i > 0 and isinstance(clean_lines[i], str) and ATTRIBUTES_LINE.match(clean_lines[i]):
+        block.insert(0, clean_lines[i])
+        i -= 1
+    return re.findall(r"@(\w+)", " ".join(block))
+
+
+class SwiftEntityParser(EntityParser):
+    """Base for Swift parsers: internal and wider access are public, private/fileprivate are private."""
+    def detect_visibility(self, match):
+        return "private" if 'private' in match_value(match, 'mods', '') else "public"
+
+    def make_entity(self, e_type, name, vis, first_line, full_text, extra_fields=None):
+        entity = super().make_entity(e_type, name, vis, first_line, full_text, extra_fields)
+        line = self.owner.clean_lines[first_line]
+        head = line[:line.find(name.split('.')[-1])] if isinstance(line, str) else ""
+        attributes = collect_attributes(self.owner.clean_lines, first_line, head)
+        if attributes:
+            entity["attributes"] = attributes
+        return entity
+
+
+class TypeParser(SwiftEntityParser):
+    """Parser for class, struct, enum, protocol, actor and extension declarations."""
+    def __init__(self, owner, keyword="class"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + rf"{keyword}\s+(?!(?:func|var|let|subscript|init)\b)(?P<name>[\w.]+)",
+                       ["indent", "attributes", "mods", "name"], 1)\
+            .add_token(r"(?:\s*<[^{\n]*?>)?(?:\s*:\s*(?P<parent>[^{\n]+?))?(?:\s+where\s+[^{\n]+?)?", ["parent"], 1)\
+            .add_token(r"\s*{", ["head_end"], 3)
+        super().__init__(TYPE_KINDS[keyword], owner, outer_regex, rf"\b{keyword}\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and entity.get("parent"):
+            entity["parent"] = re.sub(r"\s+", " ", entity["parent"]).strip()
+        if entity and "propertyWrapper" in entity.get("attributes", []):
+            entity["type"] = "property wrapper"
+        return True
+
+
+class FunctionParser(SwiftEntityParser):
+    """Parser for functions and initializers; declarations without body (protocol requirements) are abstract."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"(?:func\s+|(?=init[?!]?\s*[(<]))(?P<name>\w+)[?!]?(?:\s*<[^>(\n]*>)?",
+                       ["indent", "attributes", "mods", "name"], 2)\
+            .add_token(r"\s*" + PARENS_PATTERN, ["args"], 1)\
+            .add_token(r"(?:\s*(?P<async>async)\b)?(?:\s*(?:throws|rethrows)\b(?:\s*\([^)]*\))?)?(?:\s*->\s*[^{\n]+?)?"
+                       r"(?:\s+where\s+[^{\n]+?)?\s*{", ["async", "head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\b(?:func|init)\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and entity.get("hit_rate", 1.0) < 1.0:   # no body: protocol requirement
+            entity["type"] = "abstract " + entity["type"]
+        return True
+
+
+class PropertyParser(SwiftEntityParser):
+    """Parser for properties declared with property wrappers (`@State var x`) and computed properties (`var x: T {`)."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"(?:var|let)\s+(?P<name>\w+)", ["indent", "attributes", "mods", "name"], 2)\
+            .add_token(r"(?:\s*:\s*[^={\n]+?)?\s*(?P<ending>{|=|$)", ["ending"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\b(?:var|let)\b", default_visibility="public")
+
+    def _process_match(self, base_match):
+        wrappers = re.findall(r"@(\w+)", match_value(base_match, 'attributes', ''))
+        line = self.content[base_match.end('name'):].split("\n", 1)[0]
+        if not wrappers and not re.match(r"\s*:[^={]+{", line):
+            return False   # plain stored properties and locals are not indexed
+        if not super()._process_match(base_match):
+            return False
+        entity = self.owner.entity_map.get(self.owner.find_line(base_match.start('name')))
+        if entity and wrappers:
+            entity["wrappers"] = wrappers
+        return True
+
+
+class DepsParserSwift(DepsParser):
+    """Parser for Swift imports, including `@testable` and kind-qualified (`import struct Module.Name`) forms."""
+    IMPORT_REGEX = re.compile(r"^[ \t]*(?:@\w+\s+)*import\s+(?:(?P<kind>typealias|struct|class|enum|protocol|let|var|func)\s+)?"
+                              r"(?P<path>[\w.]+)", re.MULTILINE)
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        for match in self.IMPORT_REGEX.finditer(self.owner.get_clean_content()):
+            path = match.group('path')
+            if match.group('kind'):
+                module, _, name = path.rpartition('.')
+                self.add_module(module)
+                self.add_import(module, name)
+            else:
+                self.add_module(path)
+        return True
+
+
+class ContentCodeSwift(ContentBlock):
+    """Parser for Swift content blocks."""
+    supported_types = [".swift"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "swift"
+        self.entity_map = {}
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeSwift with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips regular, multiline and raw string literals with strip_swift_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_swift_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def detect_bounds(self, start_line, clean_lines):
+        """Declarations without body (protocol requirements, wrapped stored properties) end with their statement."""
+        depth = 0
+        for line_num in range(start_line, min(len(clean_lines), start_line + 8)):
+            line = clean_lines[line_num] if isinstance(clean_lines[line_num], str) else ""
+            for ch in line:
+                if ch == '{' and depth == 0:
+                    return super().detect_bounds(start_line, clean_lines)
+                depth += {'(': 1, ')': -1, '[': 1, ']': -1}.get(ch, 0)
+            stripped = line.rstrip()
+            if depth > 0 or stripped.endswith(('=', ',', ':', '(', '->')):
+                continue
+            following = next((ln.strip() for ln in clean_lines[line_num + 1:] if isinstance(ln, str) and ln.strip()), "")
+            if following.startswith(('{', '->', 'where', 'throws', 'rethrows', 'async')):
+                continue
+            return start_line, line_num
+        return super().detect_bounds(start_line, clean_lines)
+
+    def extract_entity_text(self, def_start: int, def_end: int) -> str:
+        """Single-line declarations are regular in Swift, no incomplete entity warning for them."""
+        start_line, end_line = self.detect_bounds(self.find_line(def_end), self.clean_lines)
+        if start_line == end_line:
+            return self.get_clean_content()[def_start:].splitlines()[0]
+        return super().extract_entity_text(def_start, def_end)
+
+    def apply_scopes(self):
+        """Sets parent of members to the enclosing type chain (functions become methods); extension members belong to the extended type."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in TYPE_KINDS.values() or e["type"] == "property wrapper"]
+        for line_num, entity in self.entity_map.items():
+            if "function" not in entity["type"] and entity["type"] != "property":
+                continue   # for types parent holds conformances
+            owners = [s for s in scopes if s is not entity and s["first_line"] < line_num <= s["last_line"]]
+            if owners:
+                entity["parent"] = ".".join(s["name"] for s in owners)
+                entity["type"] = entity["type"].replace("function", "method")
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Swift content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserSwift(self),
+            TypeParser(self, "protocol"),
+            TypeParser(self, "extension"),
+            TypeParser(self, "struct"),
+            TypeParser(self, "enum"),
+            TypeParser(self, "actor"),
+            TypeParser(self, "class"),
+            FunctionParser("function", self),
+            PropertyParser("property", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeSwift)
@@ -0,0 +1,29 @@
+# /lib/symlink_block.py, created 2026-10-16 06:30 EEST
+# Symlinks recorded in pack as links: target path only, linked content is not packed.
+import html
+import logging
+import os
+from lib.content_block import ContentBlock
+from lib.sandwich_pack import SandwichPack
+
+
+class SymlinkBlock(ContentBlock):
+    supported_types = [":symlink"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "symlink"
+        self.target = content_text   # link target as stored in file system, relative or absolute
+        logging.debug(f"Initialized SymlinkBlock for {file_name} -> {self.target}")
+
+    @classmethod
+    def from_link(cls, file_path, file_name: str, timestamp: str, **kwargs):
+        return cls(os.readlink(file_path).replace("\\", "/"), ":symlink", file_name, timestamp, **kwargs)
+
+    def to_sandwich_block(self):
+        attrs = [f'file_id="{self.file_id}"'] if self.file_id is not None else []
+        attrs += [f'mod_time="{self.timestamp}"', f'target="{html.escape(self.target)}"']
+        return f"<{self.tag} {' '.join(attrs)} />"
+
+
+SandwichPack.register_block_class(SymlinkBlock)
@@ -1,3 +1,4 @@
+# /lib/text_bytes.py, updated 2026-10-16 06:00 EEST
 # Декодирование текстовых файлов: UTF-8 строго, затем детект и fallback (не «латиница на всё»).
 from __future__ import annotations
 
@@ -53,6 +54,23 @@ def _utf16_bom(data: bytes) -> Optional[Tuple[str, str]]:
     return None
 
 
+def _utf16_nobom(data: bytes) -> Optional[Tuple[str, str]]:
+    """UTF-16 без BOM: нули почти во всех нечётных (LE) или чётных (BE) байтах ASCII-текста."""
+    sample = data[:65536]
+    if len(sample) < 4 or len(sample) % 2:
+        return None
+    half = len(sample) // 2
+    even_zeros = sample[0::2].count(0) / half
+    odd_zeros = sample[1::2].count(0) / half
+    enc = "utf-16-le" if odd_zeros > 0.4 and even_zeros < 0.05 else "utf-16-be" if even_zeros > 0.4 and odd_zeros < 0.05 else None
+    if enc is None:
+        return None
+    try:
+        return data.decode(enc), enc
+    except UnicodeDecodeError:
+        return None
+
+
 def _try_charset_normalizer(data: bytes) -> Optional[Tuple[str, str]]:
     try:
         from charset_normalizer import from_bytes
@@ -158,7 +176,7 @@ def decode_file_bytes(data: bytes) -> Optional[Tuple[str, str, str]]:
     Декодирует содержимое файла в str. Возвращает (text, encoding_name, eol) или None, если похоже на бинарник.
 
     Политика:
-    - Сначала UTF-8 (strict), UTF-16 по BOM, UTF-8-sig.
+    - Сначала UTF-16 по BOM или раскладке нулей, UTF-8-sig, UTF-8 (strict).
     - Если UTF-8 не подошёл: при отсутствии текстовой эвристики (bytes_txt) — None.
     - Иначе charset-normalizer при наличии; иначе ручная цепочка (+ CQDS_TEXT_DECODE_FALLBACK).
     """
@@ -166,19 +184,16 @@ def decode_file_bytes(data: bytes) -> Optional[Tuple[str, str, str]]:
         return "", "utf-8", "lf"
 
     sample = data if len(data) <= 65536 else data[:65536]
-    if b"\x00" in sample:
-        return None
-
+    # UTF-16 содержит нули, поэтому BOM и раскладка проверяются до отсечения бинарников
     try:
-        text, enc = data.decode("utf-8"), "utf-8"
-        return text, enc, detect_eol_from_bytes(data, enc)
+        bom16 = _utf16_bom(data) or (_utf16_nobom(data) if b"\x00" in sample else None)
     except UnicodeDecodeError:
-        pass
-
-    bom16 = _utf16_bom(data)
+        bom16 = None
     if bom16 is not None:
         text, enc = bom16
         return text, enc, detect_eol_from_bytes(data, enc)
+    if b"\x00" in sample:
+        return None
 
     if data.startswith(b"\xef\xbb\xbf"):
         try:
@@ -187,6 +202,12 @@ def decode_file_bytes(data: bytes) -> Optional[Tuple[str, str, str]]:
         except UnicodeDecodeError:
             pass
 
+    try:
+        text, enc = data.decode("utf-8"), "utf-8"
+        return text, enc, detect_eol_from_bytes(data, enc)
+    except UnicodeDecodeError:
+        pass
+
     if not bytes_txt(sample):
         return None
 
@@ -208,3 +229,33 @@ def decode_known_text_bytes(data: bytes) -> Tuple[str, str, str]:
         return r
     eol = detect_eol_from_bytes(data, "utf-8")
     return data.decode("utf-8", errors="replace"), "utf-8-replace", eol
+
+
+def decode_source(data: bytes) -> Optional[Tuple[str, str]]:
+    """Текст исходника для пака: (text без BOM, исходная кодировка) или None для бинарника."""
+    r = decode_file_bytes(data)
+    if r is None:
+        return None
+    text = r[0][1:] if r[0].startswith("\ufeff") else r[0]
+    return text, normalize_codec_name(r[1])
+
+
+def split_eol(text: str) -> Tuple[str, str]:
+    """Текст с переводами строк LF и исходный стиль; mixed оставляется как есть, чтобы восстановить байт в байт."""
+    crlf = text.count("\r\n")
+    lone_cr = text.count("\r") - crlf
+    lone_lf = text.count("\n") - crlf
+    if crlf and not lone_cr and not lone_lf:
+        return text.replace("\r\n", "\n"), "crlf"
+    if lone_cr and not crlf and not lone_lf:
+        return text.replace("\r", "\n"), "cr"
+    return text, "mixed" if crlf or lone_cr else "lf"
+
+
+def restore_source(text: str, encoding: str | None = None, eol: str | None = None) -> bytes:
+    """Байты исходного файла из текста пака: обратная подстановка переводов строк crlf / cr и исходная кодировка."""
+    if eol == "crlf":
+        text = text.replace("\n", "\r\n")
+    elif eol == "cr":
+        text = text.replace("\n", "\r")
+    return text.encode(normalize_codec_name(encoding or "utf-8"))
@@ -0,0 +1,292 @@
+# /lib/toml_block.py, updated 2026-10-15 21:30 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+try:
+    import tomllib
+except ImportError:   # Python < 3.11, values are kept as raw text
+    tomllib = None
+
+
+HEADER_REGEX = re.compile(r"^[ \t]*(?P<open>\[\[?)(?P<name>[^\[\]]+)(?P<close>\]\]?)[ \t]*$")
+KEY_REGEX = re.compile(r"^[ \t]*(?P<key>[^=\s][^=]*?)[ \t]*=")
+KEY_PART_REGEX = re.compile(r"\"(?:[^\"\\]|\\.)*\"|'[^']*'|[^.\s]+")
+CARGO_DEPS_REGEX = re.compile(r"^(?:target\.(?P<target>.+)\.|workspace\.)?(?P<kind>dependencies|dev-dependencies|build-dependencies)$")
+DEPENDENCY_FIELDS = ("version", "path", "git", "branch", "tag", "features", "optional", "workspace", "package", "default-features")
+
+
+def strip_toml_literals(text: str, warn=None) -> str:
+    """Blanks basic, literal and multi-line strings with comments by spaces, keeping quotes; positions match source."""
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if ch == "#":
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(" " * (end - i))
+            i = end
+            continue
+        if ch not in "\"'":
+            out.append(ch)
+            i += 1
+            continue
+        quote = text[i:i + 3] if text.startswith(ch * 3, i) else ch
+        end = i + len(quote)
+        while end < n and not text.startswith(quote, end) and (len(quote) == 3 or text[end] != "\n"):
+            end += 2 if text[end] == "\\" and ch == "\"" else 1
+        closed = text.startswith(quote, end)
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        body_end = min(end, n)
+        out.append(quote + re.sub(r"[^\n]", " ", text[i + len(quote):body_end]) + (quote if closed else ""))
+        i = body_end + (len(quote) if closed else 0)
+    return "".join(out)
+
+
+def key_parts(key: str) -> list:
+    """Dotted key split into parts, quotes removed: `target."cfg(unix)".dependencies` -> [target, cfg(unix), dependencies]."""
+    return [part[1:-1] if part[:1] in "\"'" else part for part in KEY_PART_REGEX.findall(key)]
+
+
+def toml_value(text: str):
+    """Parsed value of TOML expression, raw text when it can't be parsed."""
+    if tomllib:
+        try:
+            return tomllib.loads(f"v = {text}")["v"]
+        except Exception:
+            pass
+    return text.strip()
+
+
+class TableParser(EntityParser):
+    """Parser for table headers `[a.b]`, arrays of tables `[[bin]]` and key/value pairs qualified by the table name."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        structured = [e for e in self.owner.entity_map.values() if e["type"] not in (self.entity_type, "array table")]
+        for stmt in self.owner.statements:
+            if any(e["first_line"] <= stmt["line"] <= e["last_line"] for e in structured):
+                continue   # described by Cargo manifest entity
+            table = ".".join(stmt["table"])
+            if stmt["kind"] == "table":
+                e_type = "array table" if stmt["array"] else self.entity_type
+                name = self.owner.add_item(e_type, table, stmt["line"], stmt["last_line"], {})
+            else:
+                extra = {"parent": table} if table else {}
+                name = self.owner.add_item("key", ".".join(stmt["table"] + stmt["key"]), stmt["line"], stmt["last_line"], extra)
+            if name:
+                self.new_entities_lines.append(stmt["line"])
+        return True
+
+
+class CargoParser(EntityParser):
+    """Parser for Cargo.toml manifest: package, dependencies with their specs, features and workspace members."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        if Path(self.owner.file_name or "").name != "Cargo.toml":
+            return False
+        tables = {}
+        for stmt in self.owner.statements:
+            if stmt["kind"] == "table":
+                tables.setdefault(".".join(stmt["table"]), stmt)
+                continue
+            table = ".".join(stmt["table"])
+            deps = CARGO_DEPS_REGEX.match(table)
+            if deps:
+                self.add_dependency(deps, stmt["key"][0], stmt["line"], stmt["last_line"], toml_value(stmt["value"]), stmt["key"][1:])
+            elif table == "features":
+                requires = toml_value(stmt["value"])
+                self.add(stmt, "feature", stmt["key"][0], {"requires": requires if isinstance(requires, list) else []})
+        for name, header in tables.items():
+            parts = header["table"]
+            values = {".".join(s["key"]): toml_value(s["value"]) for s in self.owner.statements
+                      if s["kind"] == "key" and s["table"] == parts}
+            last_line = max([header["line"]] + [s["last_line"] for s in self.owner.statements if s["kind"] == "key" and s["table"] == parts])
+            if name == "package" and isinstance(values.get("name"), str):
+                fields = {k: values[k] for k in ("version", "edition") if isinstance(values.get(k), str)}
+                self.add(header, "package", values["name"], fields, last_line)
+            elif name == "workspace":
+                fields = {k: values[k] for k in ("members", "exclude") if isinstance(values.get(k), list)}
+                self.add(header, "workspace", Path(self.owner.file_name).parent.name or "workspace", fields, last_line)
+            elif len(parts) >= 2 and CARGO_DEPS_REGEX.match(".".join(parts[:-1])):
+                self.add_dependency(CARGO_DEPS_REGEX.match(".".join(parts[:-1])), parts[-1], header["line"], last_line, values, [])
+        return True
+
+    def add(self, stmt, e_type, name, extra, last_line=None):
+        if self.owner.add_item(e_type, name, stmt["line"], last_line or stmt["last_line"], extra, suffix_dups=False):
+            self.new_entities_lines.append(stmt["line"])
+
+    def add_dependency(self, deps_match, crate, line_num, last_line, spec, sub_key):
+        """Dependency entity with version/path/features fields, `serde.workspace = true` style dotted keys are supported."""
+        if sub_key:
+            spec = {".".join(sub_key): spec}
+        spec = {"version": spec} if isinstance(spec, str) else spec if isinstance(spec, dict) else {}
+        extra = {k: v for k, v in spec.items() if k in DEPENDENCY_FIELDS}
+        if deps_match.group('target'):
+            extra["target"] = ".".join(key_parts(deps_match.group('target')))
+        kind = deps_match.group('kind').replace("-dependencies", " dependency").replace("dependencies", "dependency")
+        e_type = "workspace dependency" if deps_match.group(0).startswith("workspace.") else kind
+        if self.owner.add_item(e_type, crate, line_num, last_line, extra, suffix_dups=False):
+            self.new_entities_lines.append(line_num)
+
+
+class DepsParserCargo(DepsParser):
+    """Parser for local crates of Cargo.toml: `path` dependencies and workspace members without glob patterns."""
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        if Path(self.owner.file_name or "").name != "Cargo.toml":
+            return False
+        base = Path(self.owner.file_name).parent
+        paths = [e["path"] for e in self.owner.entity_map.values() if "dependency" in e["type"] and isinstance(e.get("path"), str)]
+        paths += [m for e in self.owner.entity_map.values() if e["type"] == "workspace" for m in e.get("members", [])
+                  if isinstance(m, str) and not re.search(r"[*?\[]", m)]
+        for path in paths:
+            module = os.path.normpath(f"{base}/{path}/Cargo.toml").replace("\\", "/")
+            self.add_module(module if module.startswith("/") else f"/{module}")
+        return True
+
+
+class ContentTOML(ContentBlock):
+    """Parser for TOML configs (.toml), Cargo.toml manifests get structured entities."""
+    supported_types = [".toml"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "toml"
+        self.entity_map = {}
+        self.statements = []
+        self.open_sl_comment = []   # comments are blanked by strip_toml_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentTOML with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks strings and comments with strip_toml_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_toml_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def scan_statements(self) -> list:
+        """Table headers and key/value pairs with line bounds, multi-line arrays and inline tables are balanced by brackets."""
+        raw_lines = [""] + self.content_text.split("\n")
+        statements = []
+        table = []
+        line_num = 1
+        while line_num < len(self.clean_lines):
+            line = self.clean_lines[line_num]
+            raw = raw_lines[line_num] if line_num < len(raw_lines) else ""
+            if header := HEADER_REGEX.match(line):
+                table = key_parts(raw[header.start('name'):header.end('name')])
+                statements.append({"kind": "table", "table": table, "array": header.group('open') == "[[",
+                                   "line": line_num, "last_line": line_num})
+            elif key := KEY_REGEX.match(line):
+                depth = 0
+                open_string = False
+                value = [raw[key.end():]]
+                for last_line in range(line_num, len(self.clean_lines)):
+                    text = self.clean_lines[last_line][key.end():] if last_line == line_num else self.clean_lines[last_line]
+                    if last_line > line_num:
+                        value.append(raw_lines[last_line])
+                    depth += text.count("[") + text.count("{") - text.count("]") - text.count("}")
+                    open_string ^= (text.count('"""') + text.count("'''")) % 2 == 1
+                    if depth <= 0 and not open_string:
+                        break
+                statements.append({"kind": "key", "table": table, "key": key_parts(raw[key.start('key'):key.end('key')]),
+                                   "value": "\n".join(value), "line": line_num, "last_line": last_line})
+                line_num = last_line
+            line_num += 1
+        for i, stmt in enumerate(statements):
+            if stmt["kind"] == "table":
+                following = next((s["line"] for s in statements[i + 1:] if s["kind"] == "table"), len(self.clean_lines))
+                body = [s["last_line"] for s in statements[i + 1:] if s["kind"] == "key" and s["line"] < following]
+                stmt["last_line"] = max(body) if body else stmt["line"]
+        return statements
+
+    def add_item(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict, suffix_dups: bool = True):
+        """Stores entity without placement check, repeated names get line suffix (bin:12), returns stored name."""
+        if first_line in self.entity_map:
+            return None
+        if suffix_dups and any(e["name"] == name and e["type"] == e_type for e in self.entity_map.values()):
+            name = f"{name}:{first_line}"
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return name
+
+    def crate_manifest(self):
+        """Package name, workspace members and excluded paths of parsed Cargo.toml, None for other TOML files."""
+        if Path(self.file_name or "").name != "Cargo.toml":
+            return None
+        package = next((e["name"] for e in self.entity_map.values() if e["type"] == "package"), None)
+        workspace = next((e for e in self.entity_map.values() if e["type"] == "workspace"), {})
+        members, exclude = ([m for m in workspace.get(key, []) if isinstance(m, str)] for key in ("members", "exclude"))
+        return {"name": package, "members": members, "exclude": exclude}
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses TOML content to extract tables, keys and Cargo manifest entities."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+        self.statements = self.scan_statements()
+
+        parsers = [
+            CargoParser("dependency", self),
+            DepsParserCargo(self),
+            TableParser("table", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentTOML)
@@ -1,18 +1,43 @@
-# /lib/vue_block.py, updated 2025-08-08 18:45 EEST
+# /lib/vue_block.py, updated 2026-10-14 22:50 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import re
 import os
 import logging
 import traceback
+from pathlib import Path
 from lib.content_block import ContentBlock, estimate_tokens
 from lib.sandwich_pack import SandwichPack
 from lib.entity_parser import EntityParser
 from lib.deps_builder import DepsParser
 from lib.iter_regex import IterativeRegex
-from lib.js_block import MethodParser, FunctionParser, DepsParserJs
+from lib.js_block import MethodParser, FunctionParser, DepsParserJs, ContentCodeJs, ContentCodeTypeScript
+from lib.css_block import ContentCodeCSS
+from lib.html_block import RawTextParser, attr_value, blank, is_local_ref
 
 METHODS_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?:methods|computed|watch)\s*:"
+SECTION_REGEX = re.compile(r"<!--.*?-->|<(?P<tag>template|script|style)\b(?P<attrs>[^>]*?)(?P<self>/)?>", re.IGNORECASE | re.DOTALL)
+CSS_LANGS = ("", "css", "scss", "postcss")
+
+
+def macro_keys(script: str, macro: str) -> list:
+    """Names declared by `defineProps`/`defineEmits` macro: keys of object or type literal argument, items of array argument."""
+    match = re.search(rf"\b{macro}\s*(?:<\s*(?P<type>\{{)|\(\s*(?P<obj>\{{)|\(\s*\[(?P<list>[^\]]*)\])", script)
+    if not match:
+        return []
+    if match.group('list') is not None:
+        return re.findall(r"[\"'`]([\w:-]+)[\"'`]", match.group('list'))
+    start = match.end() - 1
+    depth = 0
+    for i in range(start, len(script)):
+        depth += {"{": 1, "}": -1}.get(script[i], 0)
+        if depth == 0:
+            break
+    body = script[start + 1:i]
+    while re.search(r"\{[^{}]*\}", body):
+        body = re.sub(r"\{[^{}]*\}", "", body)   # only top level keys
+    return re.findall(r"(?:^|[,;\n])\s*(\w+)\??\s*[:(]", body)
+
 
 class ComponentParser(EntityParser):
     """Parser for Vue components."""
@@ -39,6 +64,59 @@ class ComponentParser(EntityParser):
         return True
 
 
+class SectionParser(RawTextParser):
+    """Parser for single-file components: the component entity spans all sections, script and style contents are delegated."""
+    def parse(self):
+        sections = self.owner.sections
+        text = self.owner.content_text
+        name = self.owner.module_prefix + Path(self.owner.file_name).stem
+        labels = []
+        props, emits = [], []
+        first_line = sections[0]["first_line"]
+        self.owner.entity_map[first_line] = {
+            "type": self.entity_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.owner.file_id,
+            "first_line": first_line,
+            "last_line": sections[-1]["last_line"],
+            "tokens": estimate_tokens(text),
+            "sections": labels
+        }
+        self.new_entities_lines.append(first_line)
+        for section in sections:
+            tag, attrs = section["tag"], section["attrs"]
+            lang = (attr_value(attrs, "lang") or "").lower()
+            flags = [flag for flag in ("setup", "scoped", "module") if re.search(rf"(?:^|\s){flag}\b", attrs)]
+            labels.append(" ".join([tag] + flags))
+            if src := attr_value(attrs, "src"):
+                if is_local_ref(src) and not src.startswith("/"):
+                    src = os.path.normpath(f"{Path(self.owner.file_name).parent}/{src}").replace("\\", "/")
+                if is_local_ref(src):
+                    self.modules.append(src if src.startswith("/") else f"/{src}")
+                continue
+            body = text[section["start"]:section["end"]]
+            masked = blank(text[:section["start"]]) + body + blank(text[section["end"]:])
+            suffix = f"{tag}{section['first_line']}"
+            if tag == "script":
+                typescript = lang in ("ts", "tsx", "typescript")
+                self.parse_sub_block(ContentCodeTypeScript if typescript else ContentCodeJs, masked, ".ts" if typescript else ".js", suffix)
+                if "setup" in flags:
+                    props += macro_keys(body, "defineProps")
+                    emits += macro_keys(body, "defineEmits")
+            elif tag == "style" and lang in CSS_LANGS:
+                self.parse_sub_block(ContentCodeCSS, masked, ".scss" if lang == "scss" else ".css", suffix)
+        for line_num in self.new_entities_lines:
+            entity = self.owner.entity_map[line_num]
+            if line_num != first_line and not entity.get("parent"):
+                entity["parent"] = name
+        if props:
+            self.owner.entity_map[first_line]["props"] = props
+        if emits:
+            self.owner.entity_map[first_line]["emits"] = emits
+        return True
+
+
 class ContentCodeVue(ContentBlock):
     """Parser for Vue content blocks."""
     supported_types = [".vue"]
@@ -51,17 +129,68 @@ class ContentCodeVue(ContentBlock):
         self.close_ml_string = ["`"]
         self.entity_map = {}
         self.module_prefix = kwargs.get("module_prefix", "")
+        self.sections = []
+        self.depth = 0
         logging.debug(f"Initialized ContentCodeVue with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
 
+    def split_sections(self) -> list:
+        """Top level `<template>`, `<script>` and `<style>` blocks with body offsets and tag lines, nested templates are skipped."""
+        text = self.content_text
+        sections = []
+        pos = 0
+        while match := SECTION_REGEX.search(text, pos):
+            pos = match.end()
+            if not match.group('tag') or match.group('self'):
+                continue
+            tag = match.group('tag').lower()
+            depth = 1
+            close_regex = re.compile(rf"<(?P<close>/)?{tag}\b[^>]*?(?P<self>/)?>", re.IGNORECASE)
+            end = len(text)
+            for close in close_regex.finditer(text, match.end()):
+                if tag == "template" and not close.group('close'):
+                    depth += 0 if close.group('self') else 1
+                    continue
+                depth -= 1 if close.group('close') else 0
+                if depth == 0:
+                    end = close.start()
+                    pos = close.end()
+                    break
+            if end == len(text):
+                self.parse_warn(f"Unclosed <{tag}> section at line {text.count(chr(10), 0, match.start()) + 1} in file {self.file_name}")
+                pos = end
+            sections.append({
+                "tag": tag,
+                "attrs": match.group('attrs'),
+                "start": match.end(),
+                "end": end,
+                "first_line": text.count("\n", 0, match.start()) + 1,
+                "last_line": text.count("\n", 0, pos) + 1
+            })
+        return sections
+
     def parse_content(self, clean_lines=None, depth=0):
-        """Parses Vue content to extract entities and dependencies."""
+        """Parses Vue content to extract entities and dependencies, single-file components are split into sections."""
         logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
         if depth >= 2:
             self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
             return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.depth = depth
         self.entity_map = {}
         self.dependencies = {"modules": [], "imports": {}}
         self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.sections = self.split_sections()
+        if self.sections:
+            parser = SectionParser("component", self)
+            try:
+                parser.parse()
+                self.extend_deps(parser)
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+            entities = self.sorted_entities()
+            logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+            return {"entities": entities, "dependencies": self.dependencies}
+
         self.strip_strings()
         self.strip_comments()
 
@@ -0,0 +1,308 @@
+# /lib/yaml_block.py, created 2026-10-15 00:50 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser
+from lib.iter_regex import IterativeRegex
+
+
+KEY_REGEX = re.compile(r"^(?P<indent>[ ]*)(?P<key>\"[^\"\n]*\"|'[^'\n]*'|[^\s#'\"\-?:][^:\n]*?|-[^\s:][^:\n]*?)[ ]*:(?=[ ]|$)")
+MARKER_REGEX = re.compile(r"^(?:---|\.\.\.)(?:[ ]|$)")
+BLOCK_SCALAR_REGEX = re.compile(r"(?:^|[:\-][ ]+|[ ])[|>][+-]?[1-9]?[+-]?[ ]*$")
+ANCHOR_REGEX = re.compile(r"(?:^|(?<=[\s\[{,:-]))&(?P<name>[^\s\[\]{},]+)")
+ALIAS_REGEX = re.compile(r"(?:^|(?<=[\s\[{,:-]))\*(?P<name>[^\s\[\]{},]+)")
+GITLAB_RESERVED = ("stages", "variables", "default", "include", "workflow", "image", "services", "cache",
+                   "before_script", "after_script", "types", "pages")
+
+
+def indent_of(line: str) -> int:
+    return len(line) - len(line.lstrip(" "))
+
+
+def strip_yaml_literals(text: str, warn=None) -> str:
+    """Blanks quoted scalars, block scalar bodies (`|`, `>`) and comments by spaces, keeping quotes; positions match source."""
+    out = []
+    block_indent = None
+    for line_num, line in enumerate(text.split("\n"), 1):
+        if block_indent is not None:
+            if not line.strip() or indent_of(line) > block_indent:
+                out.append(" " * len(line))
+                continue
+            block_indent = None
+        chars = list(line)
+        i = 0
+        while i < len(line):
+            ch = line[i]
+            if ch == "#" and (i == 0 or line[i - 1] in " \t"):
+                chars[i:] = " " * (len(line) - i)
+                break
+            if ch in "\"'" and (i == 0 or line[i - 1] in " \t:-[{,"):
+                end = i + 1
+                while end < len(line):
+                    if ch == "'" and line.startswith("''", end):
+                        end += 2
+                        continue
+                    if line[end] == ch:
+                        break
+                    end += 2 if ch == "\"" and line[end] == "\\" else 1
+                if end >= len(line):
+                    if warn:
+                        warn(f"Incomplete string literal at line {line_num}")   # multi-line flow scalars are not supported
+                    end = len(line) - 1
+                chars[i + 1:end] = " " * (end - i - 1)
+                i = end + 1
+                continue
+            i += 1
+        line = "".join(chars)
+        if BLOCK_SCALAR_REGEX.search(line.rstrip()):
+            block_indent = indent_of(line)
+        out.append(line)
+    return "\n".join(out)
+
+
+def unquote(value: str) -> str:
+    value = value.strip()
+    if len(value) >= 2 and value[0] == value[-1] and value[0] in "\"'":
+        return value[1:-1]
+    return value
+
+
+class DocumentParser(EntityParser):
+    """Parser for documents split by `---`: Kubernetes manifests become `Kind/name` resources, other documents index top level keys."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        stem = Path(self.owner.file_name or "document").stem
+        documents = self.owner.documents
+        for index, (start_line, first_line, last_line) in enumerate(documents, 1):
+            keys = self.owner.child_keys(first_line, last_line, -1)
+            values = {key: self.owner.key_value(line_num) for line_num, key, _ in keys}
+            metadata = next((k for k in keys if k[1] == "metadata"), None)
+            meta = {key: self.owner.key_value(line_num) for line_num, key, _ in
+                    self.owner.child_keys(metadata[0] + 1, metadata[2], 0)} if metadata else {}
+            if values.get("apiVersion") and values.get("kind") and meta.get("name"):
+                extra = {"kind": values["kind"], "api_version": values["apiVersion"]}
+                if meta.get("namespace"):
+                    extra["namespace"] = meta["namespace"]
+                if self.owner.add_node("resource", f"{values['kind']}/{meta['name']}", start_line, last_line, extra):
+                    self.new_entities_lines.append(start_line)
+                continue
+            extra = {}
+            if len(documents) > 1:   # single document is the file itself
+                extra["parent"] = f"{stem}:{index}"
+                if self.owner.add_node(self.entity_type, extra["parent"], start_line, last_line, {}):
+                    self.new_entities_lines.append(start_line)
+            for line_num, key, key_last in keys:
+                if self.owner.add_node("key", key, line_num, key_last, dict(extra)):
+                    self.new_entities_lines.append(line_num)
+        return True
+
+
+class JobParser(EntityParser):
+    """Parser for CI jobs: children of `jobs` in GitHub workflows and top level jobs of `.gitlab-ci.yml`."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        gitlab = Path(self.owner.file_name or "").name in (".gitlab-ci.yml", ".gitlab-ci.yaml")
+        for _, first_line, last_line in self.owner.documents:
+            keys = self.owner.child_keys(first_line, last_line, -1)
+            names = [k[1] for k in keys]
+            jobs = []
+            if gitlab:
+                jobs = [(k, "job template" if k[1].startswith(".") else self.entity_type) for k in keys if k[1] not in GITLAB_RESERVED]
+                parent = "pipeline"
+            elif "jobs" in names and "on" in names:
+                jobs_key = keys[names.index("jobs")]
+                jobs = [(k, self.entity_type) for k in self.owner.child_keys(jobs_key[0] + 1, jobs_key[2], 0)]
+                parent = self.owner.key_value(keys[names.index("name")][0]) if "name" in names else "workflow"
+            for (line_num, name, job_last), e_type in jobs:
+                indent = indent_of(self.owner.clean_lines[line_num])
+                fields = {key: self.owner.key_value(child) for child, key, _ in self.owner.child_keys(line_num + 1, job_last, indent)}
+                extra = {"parent": parent}
+                for field in ("stage", "runs-on", "needs", "extends"):
+                    if fields.get(field):
+                        extra[field.replace("-", "_")] = fields[field]
+                if self.owner.add_node(e_type, name, line_num, job_last, extra):
+                    self.new_entities_lines.append(line_num)
+        return True
+
+
+class AnchorParser(EntityParser):
+    """Parser for anchors `&name` with lines of their aliases `*name`, anchor on indexed key line is stored in that entity."""
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"(?!)", default_visibility="public")
+
+    def parse(self):
+        clean_lines = self.owner.clean_lines
+        aliases = {}
+        for line_num in range(1, len(clean_lines)):
+            for match in ALIAS_REGEX.finditer(clean_lines[line_num]):
+                aliases.setdefault(match.group('name'), []).append(line_num)
+        for line_num in range(1, len(clean_lines)):
+            for match in ANCHOR_REGEX.finditer(clean_lines[line_num]):
+                name = match.group('name')
+                entity = self.owner.entity_map.get(line_num)
+                if entity:
+                    entity["anchor"] = name
+                    entity["aliases"] = aliases.get(name, [])
+                    continue
+                last_line = self.owner.node_end(line_num)
+                if self.owner.add_node(self.entity_type, name, line_num, last_line, {"aliases": aliases.get(name, [])}):
+                    self.new_entities_lines.append(line_num)
+        return True
+
+
+class ContentYAML(ContentBlock):
+    """Parser for YAML documents (.yml, .yaml): Kubernetes manifests, CI pipelines, anchors and top level keys."""
+    supported_types = [".yml", ".yaml"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "yaml"
+        self.entity_map = {}
+        self.documents = []
+        self.open_sl_comment = []   # comments are blanked by strip_yaml_literals
+        self.open_ml_comment = []
+        self.close_ml_comment = []
+        logging.debug(f"Initialized ContentYAML with tag={self.tag}, file_name={file_name}")
+
+    def strip_strings(self):
+        """Blanks quoted scalars, block scalars and comments with strip_yaml_literals, offsets of clean content match the source."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_yaml_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def split_documents(self) -> list:
+        """Documents separated by `---` / `...` markers as (start line, first content line, last line), start is the `---` marker if any."""
+        documents = []
+        start_line = None
+        first_line = None
+        last_line = None
+        for line_num in range(1, len(self.clean_lines) + 1):
+            line = self.clean_lines[line_num] if line_num < len(self.clean_lines) else "---"
+            if MARKER_REGEX.match(line) or line.startswith("%"):
+                if first_line:
+                    documents.append((start_line or first_line, first_line, last_line))
+                start_line = line_num if line.startswith("---") else None
+                first_line = None
+                continue
+            if line.strip():
+                first_line = first_line or line_num
+                last_line = line_num
+        return documents
+
+    def node_end(self, line_num: int, last_line: int = None) -> int:
+        """Last line of node started at line_num: following lines are more indented or list items of the same key."""
+        indent = indent_of(self.clean_lines[line_num])
+        raw_lines = [""] + self.content_text.split("\n")
+        end = line_num
+        limit = min(last_line or len(self.clean_lines) - 1, len(self.clean_lines) - 1)
+        for i in range(line_num + 1, limit + 1):
+            line = self.clean_lines[i]
+            if not line.strip():
+                if i < len(raw_lines) and raw_lines[i].strip() and indent_of(raw_lines[i]) > indent:
+                    end = i   # block scalar body
+                continue
+            if MARKER_REGEX.match(line) or indent_of(line) < indent or \
+                    indent_of(line) == indent and not line[indent:].startswith("- ") or \
+                    indent_of(line) == indent and self.clean_lines[line_num][indent:].startswith("- "):
+                break
+            end = i
+        return end
+
+    def child_keys(self, first_line: int, last_line: int, parent_indent: int) -> list:
+        """Mapping keys at the first indent deeper than parent_indent, as (line, key, last line) tuples."""
+        keys = []
+        child_indent = None
+        for line_num in range(first_line, last_line + 1):
+            line = self.clean_lines[line_num]
+            if not line.strip():
+                continue
+            indent = indent_of(line)
+            if indent <= parent_indent:
+                break
+            child_indent = indent if child_indent is None else child_indent
+            match = KEY_REGEX.match(line)
+            if indent == child_indent and match:
+                raw = self.content_text.split("\n")[line_num - 1]
+                keys.append((line_num, unquote(raw[match.start('key'):match.end('key')]), self.node_end(line_num, last_line)))
+        return keys
+
+    def key_value(self, line_num: int):
+        """Scalar value of key at line_num, without quotes and comment; None for nested nodes."""
+        clean = self.clean_lines[line_num]
+        match = KEY_REGEX.match(clean)
+        raw = self.content_text.split("\n")[line_num - 1]
+        value = raw[match.end() + 1:len(clean.rstrip())].strip() if match else ""
+        value = re.sub(r"^&[^\s]+\s*", "", value)
+        return unquote(value) if value and value[0] not in "|>" else None
+
+    def add_node(self, e_type: str, name: str, first_line: int, last_line: int, extra_fields: dict):
+        """Stores entity without placement check, repeated names get line suffix (key:12)."""
+        if first_line in self.entity_map:
+            return False
+        if any(e["name"] == name and e["type"] == e_type for e in self.entity_map.values()):
+            name = f"{name}:{first_line}"
+        lines = self.content_text.split("\n")[first_line - 1:last_line]
+        self.entity_map[first_line] = {
+            "type": e_type,
+            "name": name,
+            "visibility": "public",
+            "file_id": self.file_id,
+            "first_line": first_line,
+            "last_line": last_line,
+            "tokens": estimate_tokens("\n".join(lines)),
+            **extra_fields
+        }
+        return True
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses YAML content to extract documents, manifests, CI jobs, keys and anchors."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+        self.documents = self.split_documents()
+
+        parsers = [
+            JobParser("job", self),
+            DocumentParser("document", self),
+            AnchorParser("anchor", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentYAML)
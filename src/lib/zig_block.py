@@ -0,0 +1,221 @@
+# /lib/zig_block.py, created 2026-10-15 04:00 EEST
+# Formatted with proper line breaks and indentation for project compliance.
+
+import re
+import os
+import logging
+import traceback
+from pathlib import Path
+from lib.content_block import ContentBlock, estimate_tokens
+from lib.sandwich_pack import SandwichPack
+from lib.entity_parser import EntityParser, match_value
+from lib.deps_builder import DepsParser
+from lib.iter_regex import IterativeRegex
+
+
+BASE_REGEX_PATTERN = r"^(?P<indent>[ \t]*)(?P<vis>pub\s+)?"
+FN_MODIFIERS_PATTERN = r"(?P<mods>(?:(?:export|extern(?:\s+\"[^\"\n]*\")?|inline|noinline)\s+)*)"
+TYPE_KINDS = {"struct": "structure", "enum": "enum", "union": "union", "opaque": "opaque", "error": "error set"}
+SCOPE_TYPES = ("structure", "enum", "union", "opaque")
+
+
+def strip_zig_literals(text: str, warn=None) -> str:
+    """Blanks Zig string, character and multiline (`\\\\` lines) literals with spaces, preserving positions of code.
+
+    Quotes of blanked literals are kept, comment bodies are blanked too and the `//` markers stay for the comment stripper.
+    """
+    out = []
+    i = 0
+    n = len(text)
+    while i < n:
+        ch = text[i]
+        if text.startswith("//", i) or text.startswith("\\\\", i):
+            end = text.find("\n", i)
+            end = n if end < 0 else end
+            out.append(text[i:i + 2] + " " * (end - i - 2))
+            i = end
+            continue
+        if ch not in "\"'":
+            out.append(ch)
+            i += 1
+            continue
+        j = i + 1
+        while j < n and text[j] not in (ch, "\n"):
+            j += 2 if text[j] == "\\" else 1
+        closed = j < n and text[j] == ch
+        if not closed and warn:
+            warn(f"Incomplete string literal at line {text.count(chr(10), 0, i) + 1}")
+        end = j + 1 if closed else min(j, n)
+        out.append(ch + " " * (end - i - (2 if closed else 1)) + (ch if closed else ""))
+        i = end
+    return "".join(out)
+
+
+class ZigEntityParser(EntityParser):
+    """Base for Zig parsers: declarations are private unless marked `pub` (export functions are public too)."""
+    def detect_visibility(self, match):
+        if match_value(match, 'vis') or "export" in match_value(match, 'mods', ''):
+            return "public"
+        return "private"
+
+
+class TypeParser(ZigEntityParser):
+    """Parser for container declarations: `const Name = [extern|packed] struct|enum|union|opaque {...}` and error sets."""
+    def __init__(self, owner, keyword="struct"):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + r"const\s+(?P<name>\w+)\s*(?::\s*type\s*)?=\s*(?:extern\s+|packed\s+)?" + keyword + r"\b",
+                       ["indent", "vis", "name"], 2)\
+            .add_token(r"(?:\s*\([^)\n]*\))?\s*{", ["head_end"], 1)
+        super().__init__(TYPE_KINDS[keyword], owner, outer_regex, r"\bconst\b", default_visibility="private")
+
+
+class FunctionParser(ZigEntityParser):
+    """Parser for functions, including `export`, `inline` and generic ones; prototypes without body (`extern fn`) are abstract."""
+    def __init__(self, entity_type, owner):
+        outer_regex = IterativeRegex()
+        outer_regex\
+            .add_token(BASE_REGEX_PATTERN + FN_MODIFIERS_PATTERN + r"fn\s+(?P<name>\w+)", ["indent", "vis", "mods", "name"], 2)\
+            .add_token(r"\s*\((?P<args>(?:[^()]|\([^()]*\))*)\)", ["args"], 1)\
+            .add_token(r"(?P<return>[^{;\n]*){", ["return", "head_end"], 1)
+        super().__init__(entity_type, owner, outer_regex, r"\bfn\b", default_visibility="private")
+
+    def _process_match(self, base_match):
+        if not super()._process_match(base_match):
+            return False
+        line_num = self.owner.find_line(base_match.start('name'))
+        entity = self.owner.entity_map.get(line_num)
+        if entity and entity.get("hit_rate", 1.0) < 1.0:   # no body: `extern fn name(...) T;` prototype
+            end = self.content.find(";", base_match.end('name'))
+            entity["type"] = "abstract " + entity["type"]
+            entity["last_line"] = self.owner.find_line(end) if end >= 0 else line_num
+            entity["tokens"] = estimate_tokens("\n".join(self.owner.content_text.split("\n")[line_num - 1:entity["last_line"]]))
+        return True
+
+
+class BlockParser(EntityParser):
+    """Parser for `test "name" {...}` and `comptime {...}` blocks; test names are read from the source lines, comptime blocks get line suffix."""
+    BLOCK_REGEX = re.compile(r"^[ \t]*(?:(?P<test>test)(?:\s+(?P<title>\"[^\"\n]*\"|\w+))?|(?P<comptime>comptime))\s*{", re.MULTILINE)
+
+    def __init__(self, entity_type, owner):
+        super().__init__(entity_type, owner, IterativeRegex(), r"\b(?:test|comptime)\b", default_visibility="public")
+
+    def parse(self):
+        content = self.owner.get_clean_content()
+        raw_lines = [""] + self.owner.content_text.split("\n")
+        for match in self.BLOCK_REGEX.finditer(content):
+            line_num = self.owner.find_line(match.start())
+            if line_num in self.owner.entity_map:
+                continue
+            if match.group('test'):
+                e_type = "test"
+                title = re.match(r"\s*test\s+(?:\"(?P<text>[^\"\n]*)\"|(?P<ident>\w+))", raw_lines[line_num])
+                name = (title.group('text') or title.group('ident')) if title else None
+                name = name or f"test:{line_num}"
+            else:
+                e_type, name = "comptime", f"comptime:{line_num}"
+            first_line, last_line = self.owner.detect_bounds(line_num, self.owner.clean_lines)
+            lines = self.owner.clean_lines[first_line:last_line + 1]
+            entity = self.make_entity(e_type, name, self.default_visibility, line_num, "\n".join(lines))
+            self.owner.entity_map[line_num] = entity   # names are not present in clean line, no placement check
+            self.new_entities_lines.append(line_num)
+        return True
+
+
+class DepsParserZig(DepsParser):
+    """Parser for `@import("...")`: package names (std, builtin) and local `.zig` / `.zon` files, aliases from `const x = @import(...)`."""
+    IMPORT_REGEX = re.compile(r"(?:\bconst\s+(?P<alias>\w+)\s*=\s*)?@import\(\s*\"(?P<module>[^\"\n]+)\"\s*\)")
+
+    def __init__(self, owner):
+        super().__init__(owner, None)
+
+    def parse(self):
+        content = re.sub(r"//[^\n]*", "", self.owner.content_text)   # import paths are strings, clean_lines lost them
+        for match in self.IMPORT_REGEX.finditer(content):
+            module = match.group('module')
+            if module.endswith((".zig", ".zon")):
+                module = os.path.normpath(os.path.join(str(Path(self.owner.file_name or "/").parent), module)).replace("\\", "/")
+            self.add_module(module)
+            if match.group('alias'):
+                self.add_import(module, match.group('alias'))
+        return True
+
+
+class ContentCodeZig(ContentBlock):
+    """Parser for Zig content blocks."""
+    supported_types = [".zig"]
+
+    def __init__(self, content_text: str, content_type: str, file_name: str, timestamp: str, **kwargs):
+        super().__init__(content_text, content_type, file_name, timestamp, **kwargs)
+        self.tag = "zig"
+        self.entity_map = {}
+        self.open_ml_comment = []   # Zig has line comments only
+        self.close_ml_comment = []
+        self.module_prefix = kwargs.get("module_prefix", "")
+        logging.debug(f"Initialized ContentCodeZig with tag={self.tag}, file_name={file_name}, module_prefix={self.module_prefix}")
+
+    def strip_strings(self):
+        """Strips string, character and multiline string literals with strip_zig_literals."""
+        if len(self.content_text) < 3:
+            return super().strip_strings()
+        if len(self.clean_lines) <= 1:
+            self.clean_lines = [''] + self.content_text.splitlines()
+        clean_text = strip_zig_literals(
+            "\n".join(self.clean_lines[1:]),
+            warn=lambda msg: self.parse_warn(f"{msg} in file {self.file_name}")
+        )
+        self.clean_lines = [""] + clean_text.split("\n")
+        self.get_clean_content()
+        return self.clean_lines
+
+    def apply_scopes(self):
+        """Sets parent of functions declared inside containers to the container chain, such functions become methods."""
+        scopes = [e for e in self.sorted_entities() if e["type"] in SCOPE_TYPES]
+        for line_num, entity in self.entity_map.items():
+            if "function" not in entity["type"]:
+                continue
+            owners = [s for s in scopes if s["first_line"] < line_num <= s["last_line"]]
+            if owners:
+                entity["parent"] = ".".join(s["name"] for s in owners)
+                entity["type"] = entity["type"].replace("function", "method")
+
+    def parse_content(self, clean_lines=None, depth=0):
+        """Parses Zig content to extract entities and dependencies."""
+        logging.debug(f"Parsing content at depth {depth} for file {self.file_name}")
+        if depth >= 2:
+            self.parse_warn(f"Maximum recursion depth reached for file {self.file_name}")
+            return {"entities": [], "dependencies": {"modules": [], "imports": {}}}
+        self.entity_map = {}
+        self.dependencies = {"modules": [], "imports": {}}
+        self.clean_lines = clean_lines if clean_lines is not None else ([""] + self.content_text.splitlines())
+        self.strip_strings()
+        self.strip_comments()
+
+        parsers = [
+            DepsParserZig(self),
+            TypeParser(self, "struct"),
+            TypeParser(self, "enum"),
+            TypeParser(self, "union"),
+            TypeParser(self, "opaque"),
+            TypeParser(self, "error"),
+            FunctionParser("function", self),
+            BlockParser("block", self)
+        ]
+
+        for parser in parsers:
+            try:
+                if parser.parse():
+                    self.extend_deps(parser)
+                    self.clean_lines = parser.masquerade()
+            except Exception as e:
+                logging.error(f"Error in {parser.__class__.__name__} parser for {self.file_name}: {str(e)}")
+                traceback.print_exc()
+                break
+
+        self.apply_scopes()
+        entities = self.sorted_entities()
+        logging.debug(f"Parsed {len(entities)} entities in {self.file_name}")
+        return {"entities": entities, "dependencies": self.dependencies}
+
+
+SandwichPack.register_block_class(ContentCodeZig)
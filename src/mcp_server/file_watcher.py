@@ -16,10 +16,29 @@ log = logging.getLogger(__name__)
 # Extensions that should trigger stale marking
 WATCHED_EXTENSIONS = {
     ".ts", ".tsx", ".js", ".jsx",
-    ".vue", ".py", ".php", ".rs",
-    ".sh", ".toml", ".md",
+    ".vue", ".svelte", ".py", ".php", ".rs", ".go",
+    ".c", ".h", ".cc", ".cpp", ".cxx", ".hh", ".hpp", ".hxx", ".java",
+    ".kt", ".kts",
+    ".cs",
+    ".m", ".mm",
+    ".swift", ".dart",
+    ".rb", ".rake", ".gemspec", ".pl", ".pm", ".t",
+    ".scala", ".sc",
+    ".hs",
+    ".ex", ".exs", ".erl", ".hrl",
+    ".lua", ".zig", ".jl", ".r",
+    ".ps1", ".psm1", ".psd1",
+    ".sql", ".sol", ".proto", ".graphql", ".gql", ".ipynb",
+    ".html", ".htm",
+    ".css", ".scss",
+    ".sh", ".bash", ".toml", ".tf", ".hcl", ".nix", ".yml", ".yaml", ".json", ".md", ".markdown",
+    ".s", ".asm",
+    ".dockerfile", ".mk", ".mak", ".cmake",
 }
 
+# File names parsed by name, compared without extension (Dockerfile.prod, CMakeLists.txt)
+WATCHED_FILE_NAMES = {"Dockerfile", "Containerfile", "Makefile", "makefile", "GNUmakefile", "CMakeLists"}
+
 # Directories to ignore completely (watchdog sees all events, we filter)
 IGNORED_DIRS = {
     "node_modules", ".nuxt", ".output", ".git",
@@ -58,7 +77,7 @@ class _ChangeHandler(FileSystemEventHandler):
             return
 
         ext = path.suffix.lower()
-        if ext not in WATCHED_EXTENSIONS:
+        if ext not in WATCHED_EXTENSIONS and path.name.split(".")[0] not in WATCHED_FILE_NAMES:
             return
 
         log.debug(f"FileWatcher: {event.event_type} {path.name}")
@@ -1,95 +1,128 @@
-# /spack.py, updated 2025-08-07 11:36 EEST
+# /spack.py, updated 2026-10-16 17:00 EEST
 import os
+import stat
+import sys
 import datetime
 import logging
 import argparse
+import json
+import tomllib
 from pathlib import Path
-from lib.sandwich_pack import SandwichPack
+from lib.sandwich_pack import SandwichPack, git_blob_hash, source_date_epoch
+from lib.llm_tools import TOKENIZERS, set_tokenizer
+from lib.ignore_rules import SYMLINK_POLICIES, walk_files
+from lib.binary_block import BINARY_POLICIES, BinaryBlock
+from lib.symlink_block import SymlinkBlock
+from lib.file_type_detector import bhead, binary_extension, is_binary_data
+from lib.text_bytes import decode_source, split_eol
+from lib.pack_format import entity_references, parse_index, upgrade_index
+from lib.sandwich_container import CODECS, EXTENSIONS, SandwichContainer, compress, default_codec, write_container
 
 logging.basicConfig(level=logging.DEBUG, format='%(asctime)s #%(levelname)s: %(message)s')
 
-def get_file_mod_time(file_path):
-    mtime = os.path.getmtime(file_path)
+def get_file_mod_time(file_path, follow_symlinks=True):
+    mtime = os.stat(file_path, follow_symlinks=follow_symlinks).st_mtime
+    epoch = source_date_epoch()
+    if epoch is not None:
+        mtime = min(mtime, epoch.timestamp())   # clamped like reproducible builds do
     mod_time = datetime.datetime.fromtimestamp(mtime, datetime.UTC)
     return mod_time.strftime("%Y-%m-%d %H:%M:%SZ")
 
+def file_metadata(file_path, data):
+    """Size, permissions (octal) and git blob hash of file, for staleness checks and restoring modes on unpack."""
+    return {"size": len(data), "mode": f"{stat.S_IMODE(os.stat(file_path).st_mode):o}", "git": git_blob_hash(data)}
+
 def is_hidden_file(filepath):
     return any(part.startswith(".") for part in filepath.parts)
 
-def collect_files(root_dir):
-    content = []
+def read_source(file_path):
+    """Raw bytes and (text, original encoding, line endings) decoded to UTF-8 with LF, None instead of decoded for binary content."""
+    try:
+        with open(file_path, "rb") as f:
+            data = f.read()
+    except OSError as e:
+        logging.warning(f"File {file_path} not readable: {e}")
+        return None, None
+    decoded = None if binary_extension(file_path.name) else decode_source(data)
+    if decoded and decoded[1] not in ("utf-8", "utf-8-sig"):
+        logging.info(f"Transcoded {file_path} from {decoded[1]} to UTF-8")
+    if decoded:
+        text, eol = split_eol(decoded[0])
+        if eol == "mixed":
+            logging.debug(f"File {file_path} has mixed line endings, packed as is")
+        decoded = (text, decoded[1], eol)
+    return data, decoded
+
+def collect_files(root_dir, use_ignore=True, binary="skip", embed_limit=65536, symlinks="skip"):
+    return list(iter_files(root_dir, use_ignore, binary, embed_limit, symlinks))
+
+def iter_files(root_dir, use_ignore=True, binary="skip", embed_limit=65536, symlinks="skip"):
     root_path = Path(root_dir).parent
     logging.debug(f"Scanning directory: {root_dir}")
     if not os.path.exists(root_dir):
         logging.error(f"Directory {root_dir} does not exist")
-        return content
-    for file_path in Path(root_dir).rglob("*"):
-        if file_path.is_file() and not is_hidden_file(file_path):
+        return
+    seen = set()   # relative paths yielded by walker, root manifests are in walked tree when packing from project root
+    for file_path in walk_files(root_dir, use_ignore, symlinks=symlinks):   # stable order, .gitignore / .ignore rules unless disabled
+        if symlinks == "link" and file_path.is_symlink() and not is_hidden_file(file_path):
             relative_path = f"/{file_path.relative_to(root_path)}".replace("\\", "/")
-            extension = Path(file_path).suffix.lower()
-            content_type = extension if extension else ""
-            if not content_type or not SandwichPack.supported_type(content_type):
+            mod_time = get_file_mod_time(file_path, follow_symlinks=False)
+            logging.debug(f"Collected symlink: {relative_path}")
+            yield SymlinkBlock.from_link(file_path, relative_path, mod_time)
+        elif file_path.is_file() and not is_hidden_file(file_path):
+            relative_path = f"/{file_path.relative_to(root_path)}".replace("\\", "/")
+            seen.add(relative_path)
+            content_type = SandwichPack.content_type_of(file_path)
+            supported = content_type and SandwichPack.supported_type(content_type)
+            if not supported and (binary == "skip" or not is_binary_data(bhead(file_path, 8192) or b"", file_path.name)):
                 logging.debug(f"Skipping unsupported content_type: {content_type} for {relative_path}")
                 continue
-            try:
-                with open(file_path, "r", encoding="utf-8-sig", errors="replace") as f:
-                    text = f.read()
-                logging.debug(f"Read {file_path} with encoding: utf-8-sig")
-            except UnicodeDecodeError as e:
-                logging.warning(f"Non-UTF-8 characters in {file_path}, replaced with �: {e}")
+            data, decoded = read_source(file_path)
+            if data is None:
                 continue
             mod_time = get_file_mod_time(file_path)
+            if decoded is None:
+                if binary == "skip":
+                    logging.debug(f"Skipping binary file {relative_path}")
+                    continue
+                logging.debug(f"Collected binary file: {relative_path}, {len(data)} bytes")
+                yield BinaryBlock.from_bytes(data, relative_path, mod_time, binary, embed_limit, file_meta=file_metadata(file_path, data))
+                continue
             logging.debug(f"Collected file: {relative_path} with content_type: {content_type}")
-            content.append(SandwichPack.create_block(
-                content_text=text,
+            yield SandwichPack.create_block(
+                content_text=decoded[0],
                 content_type=content_type,
                 file_name=relative_path,
-                timestamp=mod_time
-            ))
-    for file_path in root_path.glob("*.toml"):
+                timestamp=mod_time,
+                source_encoding=decoded[1] if decoded[1] not in ("utf-8", "utf-8-sig") else None,
+                line_endings=decoded[2] if decoded[2] in ("crlf", "cr") else None,
+                file_meta=file_metadata(file_path, data)
+            )
+    for file_path in sorted(root_path.glob("*.toml")):
         if not is_hidden_file(file_path):
             relative_path = f"/{file_path.name}".replace("\\", "/")
+            if relative_path in seen:
+                continue
             content_type = ".toml"
             if not SandwichPack.supported_type(content_type):
                 logging.debug(f"Skipping unsupported content_type: {content_type} for {relative_path}")
                 continue
-            try:
-                with open(file_path, "r", encoding="utf-8-sig", errors="replace") as f:
-                    text = f.read()
-                logging.debug(f"Read {file_path} with encoding: utf-8-sig")
-            except UnicodeDecodeError as e:
-                logging.warning(f"Non-UTF-8 characters in {file_path}, replaced with �: {e}")
+            data, decoded = read_source(file_path)
+            if not decoded:
                 continue
             mod_time = get_file_mod_time(file_path)
             logging.debug(f"Collected file: {relative_path} with content_type: {content_type}")
-            content.append(SandwichPack.create_block(
-                content_text=text,
+            yield SandwichPack.create_block(
+                content_text=decoded[0],
                 content_type=content_type,
                 file_name=relative_path,
-                timestamp=mod_time
-            ))
-    return content
-
-def main():
-    logging.info("Starting spack CLI")
-    SandwichPack.load_block_classes()
-    project_dir = "."
-    output_dir = "./sandwiches"
-    files_content = collect_files(project_dir)
-    if not files_content:
-        logging.error("No files collected, exiting")
-        raise SystemExit("Error: No files found in the specified directory")
-    logging.info(f"Collected {len(files_content)} files")
-    parser = argparse.ArgumentParser(
-        prog='Sandwich Packer',
-        description='Combining all project sources files into sandwich structured several text files',
-        epilog='Best for using with chatbots like Grok or ChatGPT, with expert level')
+                timestamp=mod_time,
+                source_encoding=decoded[1] if decoded[1] not in ("utf-8", "utf-8-sig") else None,
+                line_endings=decoded[2] if decoded[2] in ("crlf", "cr") else None,
+                file_meta=file_metadata(file_path, data)
+            )
 
-    parser.add_argument('project_name')
-    args = parser.parse_args()
-    packer = SandwichPack(args.project_name)
-    result = packer.pack(files_content)
-    os.makedirs(output_dir, exist_ok=True)
+def write_sandwiches(result, output_dir):
     for i, sandwich in enumerate(result["sandwiches"], 1):
         output_file = Path(output_dir) / f"sandwich_{i}.txt"
         with open(output_file, "w", encoding="utf-8") as f:
@@ -105,5 +138,244 @@ def main():
 
     logging.info(f"Created {global_index_file}")
 
+def read_index(pack_path):
+    """Global index in current format from sandwich_N.txt (INDEX_COPY), sandwiches_index.jsl, index json or compressed container."""
+    if str(pack_path).endswith(tuple(EXTENSIONS.values())):
+        return upgrade_index(json.loads(SandwichContainer(pack_path).read("index")))
+    with open(pack_path, "r", encoding="utf-8") as f:
+        return parse_index(f.read())
+
+def verify(pack_path, root_dir):
+    try:
+        report = SandwichPack.verify_tree(read_index(pack_path), root_dir)
+    except (OSError, ValueError) as e:
+        raise SystemExit(f"Error: {e}")
+    for file_name in report["modified"]:
+        logging.warning(f"Modified since packing: {file_name}")
+    for file_name in report["missing"]:
+        logging.warning(f"Missing on disk: {file_name}")
+    for file_name in report["mode_changed"]:
+        logging.warning(f"Permissions changed since packing: {file_name}")
+    if not report["digest_ok"]:
+        logging.warning("Pack digest mismatch, index was altered")
+    logging.info(f"Verified {len(report['verified'])} files, {len(report['modified'])} modified, {len(report['missing'])} missing")
+    if not report["ok"]:
+        raise SystemExit(1)
+
+def load_config(config_path):
+    """Packer config (TOML), empty if default file is absent. Section [priority] maps globs to weights: "src/**" = 10,
+       section [template] holds header / footer texts of pack."""
+    if config_path is None:
+        return {}
+    try:
+        with open(config_path, "rb") as f:
+            config = tomllib.load(f)
+    except (OSError, tomllib.TOMLDecodeError) as e:
+        raise SystemExit(f"Error: config {config_path} not loaded: {e}")
+    for pattern, weight in config.get("priority", {}).items():
+        if not isinstance(weight, (int, float)) or isinstance(weight, bool):
+            raise SystemExit(f"Error: priority of `{pattern}` in {config_path} must be a number")
+    for key, text in config.get("template", {}).items():
+        if key not in ("header", "footer") or not isinstance(text, str):
+            raise SystemExit(f"Error: template `{key}` in {config_path} must be header or footer text")
+    logging.info(f"Loaded config {config_path}")
+    return config
+
+def main():
+    logging.info("Starting spack CLI")
+    SandwichPack.load_block_classes()
+    project_dir = "."
+    output_dir = "./sandwiches"
+    parser = argparse.ArgumentParser(
+        prog='Sandwich Packer',
+        description='Combining all project sources files into sandwich structured several text files',
+        epilog='Best for using with chatbots like Grok or ChatGPT, with expert level')
+
+    parser.add_argument('project_name', nargs='?')
+    parser.add_argument('--no-test-code', action='store_true', help='exclude cfg(test) items from index and packed text')
+    parser.add_argument('--features', default=None,
+                        help='comma separated enabled cargo features, items gated by other features are excluded from index and packed text')
+    tests_mode = parser.add_mutually_exclusive_group()
+    tests_mode.add_argument('--skip-tests', dest='tests', action='store_const', const='skip', help='exclude test and bench files and items from pack')
+    tests_mode.add_argument('--tests-only', dest='tests', action='store_const', const='only', help='pack test and bench files and items only')
+    parser.add_argument('--public-api-only', action='store_true', help='pack externally visible (pub) entities only, other items are omitted from text')
+    parser.add_argument('--roles', default=None,
+                        help='comma separated cargo target roles to pack: library, binary, build script, example, bench, integration test')
+    parser.add_argument('--format', default='text', choices=SandwichPack.OUTPUT_FORMATS,
+                        help='text - sandwich files with index, json - single structured file with files, entities and manifest, ' +
+                             'jsonl - file and entity records streamed while scanning, markdown - readable pack for chat UIs, ' +
+                             'container - compressed file with frame per block')
+    parser.add_argument('--codec', default=None, choices=CODECS,
+                        help='compression of container format (default: zstd when `zstandard` is installed, gzip otherwise)')
+    parser.add_argument('--decompress', default=None, metavar='CONTAINER',
+                        help='restore sandwich files from container into sandwiches directory')
+    parser.add_argument('--include', dest='path_filters', action='append', type=lambda p: (True, p), metavar='GLOB',
+                        help='pack only matching files, like src/**/*.rs or Cargo.toml; with --exclude evaluated in order, last match wins')
+    parser.add_argument('--exclude', dest='path_filters', action='append', type=lambda p: (False, p), metavar='GLOB',
+                        help='skip matching files or directories, like tests/ or *.generated.rs')
+    parser.add_argument('--binary', default='skip', choices=BINARY_POLICIES,
+                        help='binary files: skip, list (path, size and sha256) or embed as base64 up to --embed-limit bytes')
+    parser.add_argument('--embed-limit', type=int, default=65536, help='max size of embedded binary file, larger ones are listed')
+    parser.add_argument('--symlinks', default='skip', choices=SYMLINK_POLICIES,
+                        help='symlinks: skip, follow (looping links are detected and skipped) or link (record target path only)')
+    parser.add_argument('--no-ignore', action='store_true',
+                        help='pack files excluded by .gitignore, .ignore and global git excludes too')
+    parser.add_argument('--report', action='store_true',
+                        help='print per-directory and per-file tokens breakdown, sorted descending, without producing pack')
+    parser.add_argument('--verify', default=None, metavar='PACK',
+                        help='check sandwich, index or container against files on disk, exit code 1 on drift')
+    parser.add_argument('--cross-refs', action='store_true',
+                        help='index files referencing every entity name (uses, impls, constructions), see --who-uses')
+    parser.add_argument('--who-uses', default=None, metavar='ENTITY',
+                        help='list files referencing entity (name, Parent.name) by index of pack in ./sandwiches packed with --cross-refs')
+    parser.add_argument('--tokenizer', default='estimate', choices=TOKENIZERS,
+                        help='token counter for files and entities: estimate (words and spaces), tiktoken BPE or huggingface')
+    parser.add_argument('--tokenizer-model', default=None,
+                        help='tiktoken encoding name (cl100k_base by default) or huggingface model name / tokenizer.json path')
+    parser.add_argument('--max-tokens', type=int, default=None,
+                        help='tokens budget of one sandwich volume, files are never split across volumes')
+    parser.add_argument('--max-size', type=int, default=None,
+                        help='bytes limit of one sandwich volume, 42000 by default, not limited when only --max-tokens given')
+    parser.add_argument('--max-file-tokens', type=int, default=None, help='per-file tokens cap, see --oversize')
+    parser.add_argument('--max-file-size', type=int, default=None, help='per-file bytes cap, see --oversize')
+    parser.add_argument('--oversize', default='truncate', choices=SandwichPack.OVERSIZE_MODES,
+                        help='files over per-file cap: skip, truncate (head and tail kept) or index (entity list only)')
+    parser.add_argument('--generated', default='index', choices=SandwichPack.GENERATED_MODES,
+                        help='generated files (lockfiles, `@generated` / DO NOT EDIT markers, *.pb.rs, minified JS/CSS): ' +
+                             'keep, skip or index (entity list only, default)')
+    parser.add_argument('--sort', default='input', choices=SandwichPack.SORT_ORDERS,
+                        help='file order in sandwiches: input (scan order), path, size (smallest first), mtime (newest first), ' +
+                             'topology (crate roots and declaring modules first), relevance or complexity (most branchy code first)')
+    parser.add_argument('--outline', action='store_true',
+                        help='signatures only: function bodies are stripped, doc comments and type definitions with fields kept')
+    parser.add_argument('--elide-bodies-over', type=int, default=None, metavar='N',
+                        help='replace function bodies longer than N lines by comment with lines count and index entry, short ones kept')
+    parser.add_argument('--line-numbers', default=None, choices=SandwichPack.LINE_NUMBER_MODES,
+                        help='annotate packed content by original line numbers: lines (`12| code` prefixes) or anchors before entities')
+    parser.add_argument('--strip-comments', action='store_true',
+                        help='remove comments from code files to save tokens, doc comments are kept, markers in strings untouched')
+    parser.add_argument('--strip-docs', action='store_true', help='with --strip-comments, remove doc comments too')
+    parser.add_argument('--summary', action='store_true',
+                        help='lead pack with manifest: languages, file counts, tokens, entity kinds and top-level module tree')
+    parser.add_argument('--split-by', default=None, choices=SandwichPack.SPLIT_UNITS,
+                        help='own sandwich for every top-level module, crate or top-level directory, listed in index units')
+    parser.add_argument('--call-slice', default=None, metavar='FUNCTION',
+                        help='pack function (name or Parent.name) with functions it calls, only their files, other bodies elided')
+    parser.add_argument('--call-depth', type=int, default=2, metavar='N', help='call levels of --call-slice, 0 - function only')
+    parser.add_argument('--no-redact', action='store_true',
+                        help='keep secrets (API keys, private keys, passwords, connection strings) instead of `[REDACTED:kind]`')
+    parser.add_argument('--header', default=None, metavar='FILE',
+                        help='template file put before pack content, placeholders: ' +
+                             ', '.join(f'{{{name}}}' for name in SandwichPack.TEMPLATE_PLACEHOLDERS))
+    parser.add_argument('--footer', default=None, metavar='FILE', help='template file put after pack content, see --header')
+    parser.add_argument('--config', default=None, metavar='TOML',
+                        help='packer config, .spack.toml of project by default; [priority] section assigns weights to globs, ' +
+                             '[template] header and footer texts')
+    parser.add_argument('--budget-tokens', type=int, default=None,
+                        help='whole pack token budget, files of lowest [priority] weight are elided first and listed in index')
+    parser.add_argument('--timestamps', action='store_true',
+                        help='include current date into index, by default output is reproducible (SOURCE_DATE_EPOCH is honored)')
+    args = parser.parse_args()
+    if args.decompress:
+        os.makedirs(output_dir, exist_ok=True)
+        try:
+            write_sandwiches(SandwichContainer(args.decompress).decompress(), output_dir)
+        except ValueError as e:
+            raise SystemExit(f"Error: {e}")
+        return
+    if args.verify:
+        verify(args.verify, project_dir)
+        return
+    if args.who_uses:
+        try:
+            index = read_index(Path(output_dir) / "sandwiches_index.jsl")
+        except (OSError, ValueError) as e:
+            raise SystemExit(f"Error: {e}")
+        if "refs" not in index:
+            raise SystemExit("Error: pack has no cross-reference index, pack it with --cross-refs")
+        files = entity_references(index, args.who_uses)
+        print("\n".join(files) if files else f"No files reference `{args.who_uses}`")
+        return
+    if not args.project_name:
+        parser.error("project_name is required")
+    try:
+        set_tokenizer(args.tokenizer, args.tokenizer_model)
+    except ValueError as e:
+        raise SystemExit(f"Error: {e}")
+    features = None if args.features is None else [f.strip() for f in args.features.split(",") if f.strip()]
+    roles = None if args.roles is None else [r.strip() for r in args.roles.split(",") if r.strip()]
+    max_size = args.max_size or (sys.maxsize if args.max_tokens else 42_000)
+    default_config = Path(project_dir) / ".spack.toml"
+    config = load_config(args.config or (default_config if default_config.is_file() else None))
+    templates = dict(config.get("template", {}))
+    for key, template_path in (("header", args.header), ("footer", args.footer)):
+        if template_path:
+            try:
+                templates[key] = Path(template_path).read_text(encoding="utf-8")
+            except OSError as e:
+                raise SystemExit(f"Error: {key} template {template_path} not loaded: {e}")
+    try:
+        packer = SandwichPack(args.project_name, max_size=max_size, token_limit=args.max_tokens or 131_000,
+                              cfg_test=False if args.no_test_code else None, cfg_features=features, tests=args.tests,
+                              public_api=args.public_api_only, roles=roles, output_format=args.format,
+                              timestamps=args.timestamps, max_file_tokens=args.max_file_tokens, max_file_size=args.max_file_size,
+                              oversize=args.oversize, sort=args.sort, budget_tokens=args.budget_tokens,
+                              outline=args.outline, elide_bodies_over=args.elide_bodies_over,
+                              line_numbers=args.line_numbers, summary=args.summary, split_by=args.split_by,
+                              header=templates.get("header"), footer=templates.get("footer"), redact=not args.no_redact,
+                              strip_comments=args.strip_comments, strip_docs=args.strip_docs, generated=args.generated,
+                              slice_root=args.call_slice, slice_depth=args.call_depth, cross_refs=args.cross_refs)
+    except ValueError as e:
+        raise SystemExit(f"Error: {e}")
+    for include, pattern in args.path_filters or []:
+        if include:
+            packer.include(pattern)
+        else:
+            packer.exclude(pattern)
+    for pattern, weight in config.get("priority", {}).items():
+        packer.priority(pattern, weight)
+    if args.report:
+        files_content = collect_files(project_dir, not args.no_ignore, args.binary, args.embed_limit, args.symlinks)
+        print(packer.report_text(packer.token_report(files_content)))
+        return
+    if args.format == "jsonl":
+        os.makedirs(output_dir, exist_ok=True)
+        output_file = Path(output_dir) / "sandwich_pack.jsonl"
+        with open(output_file, "w", encoding="utf-8") as f:
+            count = packer.write_jsonl(iter_files(project_dir, not args.no_ignore, args.binary, args.embed_limit, args.symlinks), f)
+        if not count:
+            logging.error("No files collected, exiting")
+            raise SystemExit("Error: No files found in the specified directory")
+        logging.info(f"Created {output_file} ({count} records)")
+        return
+    files_content = collect_files(project_dir, not args.no_ignore, args.binary, args.embed_limit, args.symlinks)
+    if not files_content:
+        logging.error("No files collected, exiting")
+        raise SystemExit("Error: No files found in the specified directory")
+    logging.info(f"Collected {len(files_content)} files")
+    try:
+        result = packer.pack(files_content)
+    except ValueError as e:
+        raise SystemExit(f"Error: {e}")
+    os.makedirs(output_dir, exist_ok=True)
+    if args.format in ("json", "markdown"):
+        output_file = Path(output_dir) / ("sandwich_pack.json" if args.format == "json" else "sandwich_pack.md")
+        with open(output_file, "w", encoding="utf-8") as f:
+            f.write(result[args.format])
+        logging.info(f"Created {output_file}")
+        return
+    if args.format == "container":
+        codec = args.codec or default_codec()
+        output_file = Path(output_dir) / f"sandwich_pack{EXTENSIONS[codec]}"
+        try:
+            compress(b"", codec)
+        except ValueError as e:
+            raise SystemExit(f"Error: {e}")
+        with open(output_file, "wb") as f:
+            sections = write_container(f, result, codec)
+        logging.info(f"Created {output_file} ({len(sections)} sections)")
+        return
+    write_sandwiches(result, output_dir)
+
 if __name__ == "__main__":
     main()
@@ -62,7 +62,7 @@ def run_pack(project_root: Path, project_name: str) -> str:
         if any(p.startswith(".") or p in SKIP_DIRS for p in rel_parts):
             continue
 
-        ext = file_path.suffix.lower()
+        ext = SandwichPack.content_type_of(file_path)
         if not ext or not SandwichPack.supported_type(ext):
             continue
 
@@ -1,4 +1,4 @@
-// /tests/test.rs, updated 2025-07-31 16:59 EEST
+// /tests/test.rs, updated 2026-10-15 13:00 EEST
 // Однострочная строка с фигурными скобками
 pub fn simple_function() {
     let s = "";
@@ -73,6 +73,29 @@ impl Inner {
      }         
 }
 
+// Лайфтаймы и символьные литералы (ловушка)
+pub fn lifetime_trap<'a>(x: &'a str) -> char {
+    let c = '{';
+    let q = '"';
+    let e = '\'';
+    if x.is_empty() { c } else { q }
+}
+
+// Вложенный блочный комментарий (ловушка)
+pub fn nested_comment_function() {
+    /* outer { /* inner } */ still comment { */
+    println!("");
+}
+
+// RAW-строки с произвольным числом решёток (ловушка)
+pub fn raw_hashes_function() {
+    let a = r##"struct Fake { "# }"##;
+    let b = br###"
+        fn ghost() { "## }
+    "###;
+    println!("{:?} {:?}", a, b);
+}
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Timelike};
 
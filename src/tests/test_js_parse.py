@@ -1,4 +1,4 @@
-# /tests/test_js_parse.py, updated 2025-08-01 23:45 EEST
+# /tests/test_js_parse.py, updated 2026-10-14 12:10 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import unittest
@@ -138,5 +138,39 @@ class TestJsParse(unittest.TestCase):
         print("TypeScript strip log:\n\t", "\n\t".join(_b.strip_log))
         print("----------------------- TEST PASSED ---------------------------------------")
 
+    def test_typescript_exports_and_literals(self):
+        print("------------------------- TypeScript exports and literals ----------------------")
+        test_content = '''import Default, { a, b as c } from './mod';
+import * as ns from "lib/ns";
+const re = /function fake() {/g;
+const tpl = `
+function inTemplate() {
+`;
+export type Alias<T> = {
+    a: T;
+};
+export enum Color { Red, Green }
+export default class Foo extends Bar {
+    method() {
+        return 1 / 2;
+    }
+}
+'''
+        _b = ContentCodeTypeScript(test_content, ".ts", "/src/exports.ts", "2025-07-18T12:00:00Z")
+        result = _b.parse_content()
+        entities = {e["name"]: e for e in result["entities"]}
+        print("Detected entities:", result["entities"])
+        self.assertNotIn("fake", entities)
+        self.assertNotIn("inTemplate", entities)
+        self.assertEqual((entities["Alias"]["type"], entities["Alias"]["last_line"]), ("type", 9))
+        self.assertEqual(entities["Alias"]["export"], "named")
+        self.assertEqual(entities["Color"]["type"], "enum")
+        self.assertEqual(entities["Foo"]["export"], "default")
+        self.assertEqual(entities["method"]["last_line"], 14)
+        deps = result["dependencies"]
+        self.assertEqual(sorted(deps["modules"]), ["./mod", "lib/ns"])
+        self.assertEqual(deps["imports"], {"Default": "./mod", "a": "./mod", "b": "./mod"})
+        print("----------------------- TEST PASSED ---------------------------------------")
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file
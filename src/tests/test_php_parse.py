@@ -1,4 +1,4 @@
-# /tests/test_php_parse.py, updated 2025-08-02 14:05 EEST
+# /tests/test_php_parse.py, updated 2026-10-14 17:05 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import unittest
@@ -67,5 +67,42 @@ class TestPhpParse(unittest.TestCase):
         logging.debug(f"Expected dependencies: {expected_deps}")
         self.assertEqual(found_deps, expected_deps, f"Dependencies mismatch: expected {expected_deps}, got {found_deps}")
 
+    def test_namespace_html_heredoc(self):
+        """Test namespaces, use imports, inline HTML before the open tag, heredoc and nowdoc bodies."""
+        content = """<html>
+<script>function htmlFake() { }</script>
+<?php
+namespace App\\Http;
+
+use App\\Models\\{User, Post as Article};
+
+class Controller extends Base implements \\Countable {
+    public function render(): string {
+        $html = <<<HTML
+            function inHeredoc() {
+            HTML;
+        $raw = <<<'NOW'
+        class InNowdoc {
+        NOW;
+        return $html . $raw;
+    }
+}
+?>
+<p>class HtmlClass { }</p>
+"""
+        block = ContentCodePHP(content, ".php", "Controller.php", "2025-08-02 12:42:00Z", file_id=0)
+        result = block.parse_content()
+        found = [(e["type"], e["name"]) for e in result["entities"]]
+        logging.debug(f"Found entities:\n" + entity_dump(result["entities"]))
+        self.assertEqual(found, [
+            ("namespace", "App\\Http"),
+            ("class", "App\\Http\\Controller"),
+            ("method", "render"),
+        ])
+        self.assertEqual(result["entities"][1]["implements"], "\\Countable")
+        self.assertEqual(result["entities"][2]["parent"], "App\\Http\\Controller")
+        self.assertEqual(result["entities"][2]["last_line"], 17)
+        self.assertEqual(result["dependencies"]["imports"], {"User": "App\\Models", "Article": "App\\Models"})
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file
@@ -91,5 +91,31 @@ class TestPyParse(unittest.TestCase):
         self.assertEqual(dependencies, expected_deps, f"Dependencies mismatch: expected {expected_deps}, got {dependencies}")
         print("----------------------- TEST PASSED ---------------------------------------")
 
+    def test_docstring_and_decorators(self):
+        print("------------------------- docstring and decorators ----------------------")
+        test_content = '''"""Module docstring.
+
+def fake_function():
+"""
+import os  # def not_a_function():
+
+@app.route("/x")
+@property
+def real_one(a, b):
+    s = """
+def inside_string():
+    """
+    return s
+'''
+        _b = ContentCodePython(test_content, ".py", "/lib/sample.py", "2025-08-03T14:00:00Z")
+        entities = _b.parse_content()["entities"]
+        print("Detected entities:", entities)
+        self.assertEqual([e["name"] for e in entities], ["sample", "real_one"])
+        self.assertEqual(entities[0]["type"], "docstring")
+        self.assertEqual((entities[0]["first_line"], entities[0]["last_line"]), (1, 4))
+        self.assertEqual(entities[1]["decorators"], ["app.route", "property"])
+        self.assertEqual(entities[1]["last_line"], 13, "Triple-quoted string must not break indentation bounds")
+        print("----------------------- TEST PASSED ---------------------------------------")
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file
@@ -1,4 +1,4 @@
-# /tests/test_rust_parse.py, updated 2025-08-01 09:08 EEST
+# /tests/test_rust_parse.py, updated 2026-10-16 16:00 EEST
 # Formatted with proper line breaks and indentation for project compliance.
 
 import unittest
@@ -90,6 +90,20 @@ class TestRustParse(unittest.TestCase):
         self.assertEqual(len(fails), 0, f"Not found entities:   \n" + "\n".join(fails))
         print("Parse content strip log:\n\t", "\n\t".join(_b.strip_log))
 
+    def test_incomplete_string_recovery(self):
+        warnings = [w for w in self.block.warnings if "Unclosed string literal" in w]   # block is stripped twice, warned once
+        self.assertEqual(warnings, ["Unclosed string literal in file test.rs at line 37, closed at end of line"])
+        entities = {e["name"]: e for e in self.block.sorted_entities()}
+        self.assertEqual((entities["incomplete_string"]["first_line"], entities["incomplete_string"]["last_line"]), (36, 39))
+        self.assertEqual(entities["ExampleTrait"]["last_line"], 44)
+        self.assertEqual(entities["ExampleTrait<Outer>"]["last_line"], 50)
+
+    def test_async_unsafe_flags(self):
+        entities = {e["name"]: e for e in self.block.sorted_entities()}
+        self.assertTrue(entities["new"]["async"])
+        self.assertNotIn("unsafe", entities["new"])
+        self.assertNotIn("async", entities["lifetime_trap"])
+
     def test_rust_raw_string_no_escape(self):
         os.environ['LOGLEVEL'] = 'WARNING'  # Suppress DEBUG logs for stable test
         test_content = '''
@@ -107,5 +121,60 @@ class TestRustParse(unittest.TestCase):
         self.assertEqual(clean_lines[4].strip(), 'let t = r#""#;')  # r#...#
         # print("Raw string strip log:\n\t", "\n\t".join(_b.strip_log))
 
+    def test_rust_raw_string_hashes(self):
+        test_content = '''
+        fn test_hashes() {
+            let s = r##"struct Fake { "# }"##;
+            let t = br###"
+                fn ghost() { "## }
+            "###;
+        }
+        '''
+        _b = ContentCodeRust(test_content, ".rs", "test_hashes.rs", "2025-07-29T18:00:00Z")
+        _b.strip_strings()
+        _b.strip_comments()
+        clean_lines = _b.clean_lines
+        self.assertEqual(clean_lines[3].strip(), 'let s = r##""##;')  # r##...##
+        self.assertEqual(clean_lines[4].strip(), 'let t = br###""###')  # br###...###, multi-line
+        self.assertEqual(clean_lines[5].strip(), '')
+        self.assertEqual(clean_lines[6].strip(), ';')
+
+    def test_rust_exotic_literals(self):
+        test_content = '''
+        pub fn bytes() -> &'static [u8] {
+            let a = b"{ \\" } fn fake() {";
+            let c = [b'{', b'"', b'\\'', b'\\\\', b'\\x7B'];
+            /* see "odd */ let r = br##"{ "# fn ghost() {"##;
+            let q = ['\\'', '\\\\', '"', '}']; // it's "odd {
+            let u = '\\u{1F4A9}' == '\\u{7B}';
+            let s = "line one
+                /* not a comment { ";
+            c"{ fn trap() {"
+        }
+
+        pub fn numbers<'a>(s: &'a str) -> u64 {
+            'outer: loop { break 'outer; }
+            1_000_000u64 + 0xFF_u8 as u64 + 0b1010_1010
+        }
+
+        pub const OPEN: char = '{';
+        pub static LIMIT: u64 = 1_000_000_u64;
+        '''
+        _b = ContentCodeRust(test_content, ".rs", "test_literals.rs", "2025-07-29T18:00:00Z")
+        entities = {e["name"]: e for e in _b.parse_content()["entities"]}
+        self.assertEqual(sorted(entities), ["LIMIT", "OPEN", "bytes", "numbers"])
+        self.assertEqual((entities["bytes"]["first_line"], entities["bytes"]["last_line"]), (2, 11))
+        self.assertEqual((entities["numbers"]["first_line"], entities["numbers"]["last_line"]), (13, 16))
+        self.assertEqual(entities["OPEN"]["value"], "'{'")
+        self.assertEqual(entities["LIMIT"]["value"], "1_000_000_u64")
+        self.assertEqual(_b.warnings, [])
+        clean_lines = _b.clean_lines
+        self.assertEqual(clean_lines[3].strip(), 'let a = b"";')
+        self.assertEqual(clean_lines[4].strip(), "let c = [b' ', b' ', b'  ', b'  ', b'    '];")
+        self.assertEqual(clean_lines[6].strip(), "let q = ['  ', '  ', ' ', ' '];")
+        self.assertEqual(clean_lines[7].strip(), "let u = '         ' == '      ';")
+        self.assertEqual(clean_lines[14].strip(), "'outer: loop { break 'outer; }")
+
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file